@@ -179,7 +179,12 @@ impl MatchGroup<Ignore> {
             patterns: vec![PatternList::<Ignore>::from_overrides(patterns)],
         }
     }
+}
 
+impl<T> MatchGroup<T>
+where
+    T: Pattern,
+{
     /// Add the given file at `source` if it exists, otherwise do nothing. If a `root` is provided, it's not considered a global file anymore.
     /// Returns true if the file was added, or false if it didn't exist.
     pub fn add_patterns_file(
@@ -190,18 +195,51 @@ impl MatchGroup<Ignore> {
         buf: &mut Vec<u8>,
     ) -> std::io::Result<bool> {
         let previous_len = self.patterns.len();
-        self.patterns.extend(PatternList::<Ignore>::from_file(
-            source.into(),
-            root,
-            follow_symlinks,
-            buf,
-        )?);
+        self.patterns
+            .extend(PatternList::<T>::from_file(source.into(), root, follow_symlinks, buf)?);
         Ok(self.patterns.len() != previous_len)
     }
 
     pub fn add_patterns_buffer(&mut self, bytes: &[u8], source: impl Into<PathBuf>, root: Option<&Path>) {
         self.patterns
-            .push(PatternList::<Ignore>::from_bytes(bytes, source.into(), root));
+            .push(PatternList::<T>::from_bytes(bytes, source.into(), root));
+    }
+}
+
+impl MatchGroup<Attributes> {
+    /// Return the state of the attribute `name` for `relative_path`, a path relative to the repository containing all patterns,
+    /// as assigned by the pattern with the highest precedence that mentions it, or `None` if it isn't mentioned at all.
+    ///
+    /// Note that macro attributes aren't expanded.
+    pub fn attribute_matching_relative_path<'a>(
+        &self,
+        relative_path: impl Into<&'a BStr>,
+        name: &str,
+        is_dir: Option<bool>,
+        case: git_glob::pattern::Case,
+    ) -> Option<&State> {
+        let relative_path = relative_path.into();
+        let basename_pos = relative_path.rfind(b"/").map(|p| p + 1);
+        self.patterns.iter().rev().find_map(|pl| {
+            let (relative_path, basename_start_pos) =
+                pl.strip_base_handle_recompute_basename_pos(relative_path, basename_pos)?;
+            pl.patterns
+                .iter()
+                .rev()
+                .filter(|pm| Attributes::use_pattern(&pm.pattern))
+                .filter(|pm| {
+                    pm.pattern
+                        .matches_repo_relative_path(relative_path, basename_start_pos, is_dir, case)
+                })
+                .find_map(|pm| match &pm.value {
+                    Value::Attributes(assignments) => assignments
+                        .iter()
+                        .rev()
+                        .find(|assignment| assignment.name == name)
+                        .map(|assignment| &assignment.state),
+                    Value::MacroAttributes(_) => None,
+                })
+        })
     }
 }
 
@@ -116,3 +116,41 @@ mod ignore {
         }
     }
 }
+
+mod attributes {
+    use git_attributes::{Attributes, MatchGroup, State};
+    use git_glob::pattern::Case;
+
+    #[test]
+    fn attribute_matching_relative_path_respects_precedence() {
+        let mut group = MatchGroup::<Attributes>::default();
+        group.add_patterns_buffer(b"* text\n*.bin -text filter=lfs\n", "global", None);
+        group.add_patterns_buffer(
+            b"*.txt eol=crlf\nno-lfs.bin !filter\n",
+            "/repo/dir/.gitattributes",
+            Some("/repo".as_ref()),
+        );
+
+        let text = |path: &str| group.attribute_matching_relative_path(path, "text", Some(false), Case::Sensitive);
+        assert_eq!(text("a.txt"), Some(&State::Set));
+        assert_eq!(text("a.bin"), Some(&State::Unset), "later patterns win");
+
+        let filter = |path: &str| group.attribute_matching_relative_path(path, "filter", Some(false), Case::Sensitive);
+        assert_eq!(filter("a.bin"), Some(&State::Value("lfs".into())));
+        assert_eq!(
+            filter("dir/no-lfs.bin"),
+            Some(&State::Unspecified),
+            "patterns of higher precedence can unspecify attributes"
+        );
+        assert_eq!(
+            filter("no-lfs.bin"),
+            Some(&State::Value("lfs".into())),
+            "the pattern only applies within its base"
+        );
+        assert_eq!(filter("a.txt"), None, "unmentioned attributes aren't found");
+
+        let eol = |path: &str| group.attribute_matching_relative_path(path, "eol", Some(false), Case::Sensitive);
+        assert_eq!(eol("dir/sub/a.txt"), Some(&State::Value("crlf".into())));
+        assert_eq!(eol("a.txt"), None);
+    }
+}
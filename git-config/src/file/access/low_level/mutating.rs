@@ -113,6 +113,7 @@ impl<'a> File<'a> {
                 .position(|v| *v == id)
                 .expect("Section order does not contain section that we were trying to remove"),
         );
+        self.section_sources.remove(&id);
         self.sections.remove(&id)
     }
 
@@ -1,6 +1,7 @@
-use crate::file::SectionBody;
-use crate::parser::ParsedSectionHeader;
+use crate::file::{ConfigSource, Origin, RawSection, SectionBody};
+use crate::parser::{Event, Key, ParsedSectionHeader};
 use crate::{lookup, File};
+use bstr::BString;
 use std::borrow::Cow;
 use std::convert::TryFrom;
 
@@ -254,6 +255,50 @@ impl<'a> File<'a> {
             .collect()
     }
 
+    /// Get the raw bytes of the header and body of all sections that match
+    /// `section_name`, in the order they appear in the file.
+    ///
+    /// This is useful to implement custom config dialects on top of this
+    /// parser. As the parser is lossless, the bytes are exactly those of the
+    /// parsed input, but they are reconstructed from its events as sections
+    /// don't retain the buffer they were parsed from.
+    ///
+    /// An empty iterator is returned if there is no section with `section_name`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::convert::TryFrom;
+    /// let config = "[core]\n\ta = b\n[remote \"origin\"]\n\turl = x\n";
+    /// let git_config = git_config::File::try_from(config).unwrap();
+    /// let section = git_config.raw_sections_by_name("remote").next().unwrap();
+    /// assert_eq!(section.header_bytes, b"[remote \"origin\"]");
+    /// assert_eq!(section.body_bytes, b"\n\turl = x\n");
+    /// ```
+    pub fn raw_sections_by_name<'lookup>(&self, section_name: &'lookup str) -> impl Iterator<Item = RawSection> {
+        let ids = self.section_ids_by_name(section_name).unwrap_or_default();
+        self.section_order
+            .iter()
+            .filter(|id| ids.contains(*id))
+            .map(|id| RawSection {
+                header_bytes: self
+                    .section_headers
+                    .get(id)
+                    .expect("section doesn't have a section header??")
+                    .to_vec(),
+                body_bytes: self
+                    .sections
+                    .get(id)
+                    .expect("section doesn't have id from from lookup")
+                    .as_ref()
+                    .iter()
+                    .flat_map(Event::to_vec)
+                    .collect(),
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
     /// Returns the number of values in the config, no matter in which section.
     ///
     /// For example, a config with multiple empty sections will return 0.
@@ -271,4 +316,45 @@ impl<'a> File<'a> {
     pub fn is_empty(&self) -> bool {
         self.sections.values().all(SectionBody::is_empty)
     }
+
+    /// Returns the source of the section which provides the value of `key` in the given section and subsection, following
+    /// the "last one wins" resolution of [`raw_value()`][File::raw_value()], or `None` if the value doesn't exist.
+    ///
+    /// This is useful to learn whether a value of a configuration loaded with [`from_env_paths()`][File::from_env_paths()]
+    /// came from the system, global or repository configuration.
+    #[must_use]
+    pub fn value_source<'lookup>(
+        &self,
+        section_name: &'lookup str,
+        subsection_name: Option<&'lookup str>,
+        key: &'lookup str,
+    ) -> Option<ConfigSource> {
+        let key = Key(key.into());
+        let section_id = self
+            .section_ids_by_name_and_subname(section_name, subsection_name)
+            .ok()?
+            .into_iter()
+            .rev()
+            .find(|id| {
+                self.sections
+                    .get(id)
+                    .expect("sections does not have section id from section ids")
+                    .value(&key)
+                    .is_some()
+            })?;
+        Some(self.section_sources.get(&section_id).cloned().unwrap_or(ConfigSource {
+            path: None,
+            origin: Origin::Unknown,
+        }))
+    }
+
+    /// Serialize this config into bytes, in the original order of its sections and with all comments, whitespace
+    /// and the quoting of values preserved, making it suitable for writing it back to disk after modifying it.
+    ///
+    /// Values changed in place, for example with [`set_raw_value()`][File::set_raw_value()], appear where they were
+    /// originally, while new values are appended to the end of their section.
+    #[must_use]
+    pub fn serialize(&self) -> BString {
+        Vec::<u8>::from(self).into()
+    }
 }
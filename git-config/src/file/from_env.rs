@@ -1,4 +1,4 @@
-use crate::file::{from_paths, resolve_includes};
+use crate::file::{from_paths, resolve_includes, Origin};
 use crate::values::path::interpolate;
 use crate::File;
 use std::borrow::Cow;
@@ -34,35 +34,35 @@ impl<'a> File<'a> {
 
         if env::var("GIT_CONFIG_NO_SYSTEM").is_err() {
             if let Some(git_config_system) = env::var_os("GIT_CONFIG_SYSTEM") {
-                paths.push(PathBuf::from(git_config_system))
+                paths.push((PathBuf::from(git_config_system), Origin::System))
             } else {
                 // In git the fallback is set to a build time macro which defaults to /etc/gitconfig
-                paths.push(PathBuf::from("/etc/gitconfig"));
+                paths.push((PathBuf::from("/etc/gitconfig"), Origin::System));
             }
         }
 
         if let Some(git_config_global) = env::var_os("GIT_CONFIG_GLOBAL") {
-            paths.push(PathBuf::from(git_config_global));
+            paths.push((PathBuf::from(git_config_global), Origin::Global));
         } else {
             // Divergence from git-config(1)
             // These two are supposed to share the same scope and override
             // rather than append according to git-config(1) documentation.
             if let Some(xdg_config_home) = env::var_os("XDG_CONFIG_HOME") {
-                paths.push(PathBuf::from(xdg_config_home).join("git/config"));
+                paths.push((PathBuf::from(xdg_config_home).join("git/config"), Origin::User));
             } else if let Some(home) = env::var_os("HOME") {
-                paths.push(PathBuf::from(home).join(".config/git/config"));
+                paths.push((PathBuf::from(home).join(".config/git/config"), Origin::User));
             }
 
             if let Some(home) = env::var_os("HOME") {
-                paths.push(PathBuf::from(home).join(".gitconfig"));
+                paths.push((PathBuf::from(home).join(".gitconfig"), Origin::Global));
             }
         }
 
         if let Some(git_dir) = env::var_os("GIT_DIR") {
-            paths.push(PathBuf::from(git_dir).join("config"));
+            paths.push((PathBuf::from(git_dir).join("config"), Origin::Repository));
         }
 
-        File::from_paths(paths, options)
+        File::from_paths_with_origins(paths, options)
     }
 
     /// Generates a config from the environment variables. This is neither
@@ -121,6 +121,7 @@ impl<'a> File<'a> {
             Ok(None)
         } else {
             resolve_includes(&mut config, None, options)?;
+            config.set_origin(Origin::Env);
             Ok(Some(config))
         }
     }
@@ -86,7 +86,7 @@ impl<'a> From<Parser<'a>> for File<'a> {
 
 impl From<File<'_>> for Vec<u8> {
     fn from(c: File<'_>) -> Self {
-        c.into()
+        (&c).into()
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::file::{from_paths, resolve_includes};
+use crate::file::{from_paths, resolve_includes, ConfigSource, Origin};
 use crate::parser::parse_from_path;
 use crate::{parser, File};
 use std::path::Path;
@@ -17,7 +17,14 @@ impl<'a> File<'a> {
     /// Returns an error if there was an IO error or if the file wasn't a valid
     /// git-config file.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, parser::ParserOrIoError<'static>> {
-        parse_from_path(path).map(Self::from)
+        let path = path.as_ref();
+        let mut config = Self::from(parse_from_path(path)?);
+        let source = ConfigSource {
+            path: Some(path.to_owned()),
+            origin: Origin::Unknown,
+        };
+        config.section_sources = config.sections.keys().map(|id| (*id, source.clone())).collect();
+        Ok(config)
     }
 
     /// Constructs a `git-config` file from the provided paths in the order provided.
@@ -32,12 +39,22 @@ impl<'a> File<'a> {
     pub fn from_paths(
         paths: impl IntoIterator<Item = impl AsRef<Path>>,
         options: from_paths::Options<'_>,
+    ) -> Result<Self, from_paths::Error> {
+        Self::from_paths_with_origins(paths.into_iter().map(|path| (path, Origin::Unknown)), options)
+    }
+
+    /// Like [`from_paths()`][File::from_paths()], but with the [`Origin`] of each path for use by
+    /// [`value_source()`][File::value_source()].
+    pub(crate) fn from_paths_with_origins(
+        paths: impl IntoIterator<Item = (impl AsRef<Path>, Origin)>,
+        options: from_paths::Options<'_>,
     ) -> Result<Self, from_paths::Error> {
         let mut target = Self::new();
-        for path in paths {
+        for (path, origin) in paths {
             let path = path.as_ref();
             let mut config = Self::open(path)?;
             resolve_includes(&mut config, Some(path), options)?;
+            config.set_origin(origin);
             target.append(config);
         }
         Ok(target)
@@ -11,6 +11,9 @@ pub use section::*;
 mod value;
 pub use value::*;
 
+mod source;
+pub use source::*;
+
 /// Newtype to represent an index into some range. This is to differentiate
 /// between raw usizes when multiple are present.
 #[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Clone, Copy)]
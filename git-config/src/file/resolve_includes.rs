@@ -10,6 +10,24 @@ use std::path::{Path, PathBuf};
 
 const DOT: &[u8] = b".";
 
+impl File<'_> {
+    /// Return the paths of all files which would be loaded due to `include.path` and matching `includeIf.<condition>.path`
+    /// directives in this file and, recursively, in the files it includes, without merging them.
+    ///
+    /// `config_path` is the path this file was loaded from, which is needed to resolve relative include paths and
+    /// `gitdir` conditions relative to it. The `git_dir` and `branch_name` fields of `options` are used to evaluate
+    /// conditional includes. Include paths pointing to files that don't exist are ignored, just like git does.
+    pub fn included_files(
+        &self,
+        config_path: Option<&Path>,
+        options: from_paths::Options<'_>,
+    ) -> Result<Vec<PathBuf>, from_paths::Error> {
+        let mut out = Vec::new();
+        included_files_recursive(self, config_path, 0, options, &mut out)?;
+        Ok(out)
+    }
+}
+
 pub(crate) fn resolve_includes(
     conf: &mut File<'_>,
     config_path: Option<&std::path::Path>,
@@ -34,6 +52,47 @@ fn resolve_includes_recursive(
         };
     }
 
+    let paths_to_include = include_paths(target_config, target_config_path, options)?;
+
+    for config_path in paths_to_include {
+        let mut include_config = File::open(&config_path)?;
+        resolve_includes_recursive(&mut include_config, Some(&config_path), depth + 1, options)?;
+        target_config.append(include_config);
+    }
+    Ok(())
+}
+
+fn included_files_recursive(
+    config: &File<'_>,
+    config_path: Option<&Path>,
+    depth: u8,
+    options: from_paths::Options<'_>,
+    out: &mut Vec<PathBuf>,
+) -> Result<(), from_paths::Error> {
+    if depth == options.max_depth {
+        return if options.error_on_max_depth_exceeded {
+            Err(from_paths::Error::IncludeDepthExceeded {
+                max_depth: options.max_depth,
+            })
+        } else {
+            Ok(())
+        };
+    }
+
+    for path in include_paths(config, config_path, options)? {
+        let include_config = File::open(&path)?;
+        out.push(path.clone());
+        included_files_recursive(&include_config, Some(&path), depth + 1, options, out)?;
+    }
+    Ok(())
+}
+
+/// Return the paths of all existing files that `target_config` includes directly, in the order they are to be loaded.
+fn include_paths(
+    target_config: &File<'_>,
+    target_config_path: Option<&Path>,
+    options: from_paths::Options<'_>,
+) -> Result<Vec<PathBuf>, from_paths::Error> {
     let mut paths_to_include = Vec::new();
 
     let mut include_paths = target_config
@@ -57,14 +116,7 @@ fn resolve_includes_recursive(
             paths_to_include.push(path);
         }
     }
-
-    dbg!(&paths_to_include);
-    for config_path in paths_to_include {
-        let mut include_config = File::open(&config_path)?;
-        resolve_includes_recursive(&mut include_config, Some(&config_path), depth + 1, options)?;
-        target_config.append(include_config);
-    }
-    Ok(())
+    Ok(paths_to_include)
 }
 
 fn include_condition_match(
@@ -96,7 +148,6 @@ fn include_condition_match(
                 condition = Cow::Owned(format!("{}**", condition));
             }
             let pattern = condition.as_bytes().as_bstr();
-            dbg!(&branch_name, &pattern);
             git_glob::wildmatch(pattern, branch_name, git_glob::wildmatch::Mode::empty()).then(|| ())
         }
         _ => None,
@@ -117,7 +168,6 @@ fn is_match(
         let mut condition_path = git_path::into_bstr(condition_path).as_bstr().to_owned();
         condition_path = BString::from(condition_path.replace("\\", "/"));
 
-        dbg!(&target_config_path);
         if condition_path.starts_with(DOT) {
             if let Some(parent_dir_path) = target_config_path {
                 if let Some(parent_path) = parent_dir_path.parent() {
@@ -142,8 +192,6 @@ fn is_match(
 
         let git_dir_value = git_path::into_bstr(git_dir).to_mut().replace("\\", "/");
 
-        println!();
-        dbg!(&condition_path.as_bstr(), &git_dir_value.as_bstr());
         let mut result = git_glob::wildmatch(
             condition_path.as_bstr(),
             git_dir_value.as_bstr(),
@@ -151,11 +199,12 @@ fn is_match(
         );
         if !result {
             if let Some(target_config_path) = target_config_path {
-                if let Ok(expanded_git_dir_value) =
-                    git_path::realpath(git_path::from_byte_slice(&git_dir_value), target_config_path)
-                {
+                if let Ok(expanded_git_dir_value) = git_path::realpath_opts(
+                    git_path::from_byte_slice(&git_dir_value),
+                    target_config_path,
+                    git_path::realpath::MAX_SYMLINKS,
+                ) {
                     let git_dir_value = git_path::into_bstr(expanded_git_dir_value).replace("\\", "/");
-                    dbg!(&condition_path.as_bstr(), git_dir_value.as_bstr(),);
                     result = git_glob::wildmatch(
                         condition_path.as_bstr(),
                         git_dir_value.as_bstr(),
@@ -164,7 +213,6 @@ fn is_match(
                 }
             }
         }
-        dbg!(&result);
         return result;
     }
     false
@@ -445,3 +445,15 @@ impl<'event> From<Vec<Event<'event>>> for SectionBody<'event> {
         Self(e)
     }
 }
+
+/// The bytes of a section as they would be written to disk, obtained with
+/// [`File::raw_sections_by_name()`][crate::File::raw_sections_by_name()].
+#[allow(clippy::module_name_repetitions)]
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Debug, Default)]
+pub struct RawSection {
+    /// The section header including its brackets, like `[remote "origin"]`.
+    pub header_bytes: Vec<u8>,
+    /// Everything following the header up to the next section, including
+    /// whitespace, comments and newlines.
+    pub body_bytes: Vec<u8>,
+}
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+/// The kind of configuration a section was loaded from, from lowest to highest precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Origin {
+    /// The origin isn't known, as the configuration was loaded directly from a file or created in memory.
+    Unknown,
+    /// The system-wide configuration, usually `/etc/gitconfig`.
+    System,
+    /// The user-specific configuration in `$XDG_CONFIG_HOME/git/config`.
+    User,
+    /// The global configuration of the user, usually `~/.gitconfig`.
+    Global,
+    /// The configuration of a repository in `$GIT_DIR/config`.
+    Repository,
+    /// Configuration provided by `GIT_CONFIG_COUNT`, `GIT_CONFIG_KEY_<n>` and `GIT_CONFIG_VALUE_<n>` environment variables.
+    Env,
+    /// Configuration passed on the command-line, like with `git -c section.key=value`.
+    CommandLine,
+}
+
+/// Identifies where a section, and thus the values within it, came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConfigSource {
+    /// The file the section was loaded from, or `None` if it didn't come from a file.
+    ///
+    /// For sections in included files, this is the path of the included file.
+    pub path: Option<PathBuf>,
+    /// The kind of configuration the section belongs to, which for included files is the one of the including file.
+    pub origin: Origin,
+}
@@ -1,4 +1,4 @@
-use crate::file::{LookupTreeNode, MutableSection, SectionBody, SectionId};
+use crate::file::{ConfigSource, LookupTreeNode, MutableSection, Origin, SectionBody, SectionId};
 use crate::parser::{ParsedSectionHeader, SectionHeaderName};
 use crate::{lookup, File};
 use std::collections::HashMap;
@@ -105,15 +105,33 @@ impl<'event> File<'event> {
             .ok_or(lookup::existing::Error::SectionMissing)
     }
 
+    /// Set the origin of all sections to `origin` as reported by [`value_source()`][File::value_source()], keeping
+    /// the path they were loaded from, if any.
+    pub fn set_origin(&mut self, origin: Origin) {
+        for id in self.sections.keys() {
+            self.section_sources
+                .entry(*id)
+                .or_insert(ConfigSource { path: None, origin })
+                .origin = origin;
+        }
+    }
+
     // TODO: add note indicating that probably a lot if not all information about the original files is currently lost,
     //       so can't be written back. This will probably change a lot during refactor, so it's not too important now.
-    pub(crate) fn append(&mut self, mut other: Self) {
+    /// Append all sections of `other` to this instance along with their sources, giving their values precedence over
+    /// the ones already present.
+    pub fn append(&mut self, mut other: Self) {
         let mut section_indices: Vec<_> = other.section_headers.keys().cloned().collect();
         // header keys are numeric and ascend in insertion order, hence sorting them gives the order
         // in which they appear in the config file.
         section_indices.sort();
         for section_index in section_indices {
             let section_header = other.section_headers.remove(&section_index).expect("present");
+            // the id the section is about to receive when pushed.
+            let new_section_id = SectionId(self.section_id_counter);
+            if let Some(source) = other.section_sources.remove(&section_index) {
+                self.section_sources.insert(new_section_id, source);
+            }
             self.push_section(
                 section_header.name.0,
                 section_header.subsection_name,
@@ -1,4 +1,4 @@
-use crate::file::{LookupTreeNode, SectionBody, SectionId};
+use crate::file::{ConfigSource, LookupTreeNode, SectionBody, SectionId};
 use crate::parser::{ParsedSectionHeader, SectionHeaderName};
 use std::collections::{HashMap, VecDeque};
 
@@ -72,4 +72,6 @@ pub struct File<'event> {
     pub(crate) section_id_counter: usize,
     /// Section order for output ordering.
     pub(crate) section_order: VecDeque<SectionId>,
+    /// The source of each section, if known. Sections created in memory typically have none.
+    pub(crate) section_sources: HashMap<SectionId, ConfigSource>,
 }
@@ -609,6 +609,20 @@ impl Integer {
             },
         }
     }
+
+    /// Parse the value of the environment variable `env_var` as integer and return its [decimal value][Integer::to_decimal()],
+    /// or parse `config_val` instead if `env_var` isn't set, similar to how git allows overriding sizes in configuration
+    /// with environment variables like `GIT_HTTP_MAX_REQUEST_BUFFER`.
+    ///
+    /// Returns `None` if the value overflows after applying its suffix.
+    pub fn from_bytes_with_env_override(config_val: &[u8], env_var: &str) -> Result<Option<i64>, value::parse::Error> {
+        let int = match std::env::var(env_var) {
+            Ok(value) => Self::try_from(value.as_bytes())?,
+            Err(std::env::VarError::NotPresent) => Self::try_from(config_val)?,
+            Err(std::env::VarError::NotUnicode(value)) => return Err(int_err(value.to_string_lossy().into_owned())),
+        };
+        Ok(int.to_decimal())
+    }
 }
 
 impl Display for Integer {
@@ -804,6 +818,39 @@ impl Color {
     }
 }
 
+#[cfg(feature = "termcolor")]
+impl Color {
+    /// Convert this color into a specification for use with the `termcolor` crate.
+    ///
+    /// Bright colors are mapped to their counterparts in the 256-color palette. The `blink`, `reverse` and `strike`
+    /// attributes and their negations are ignored as `termcolor` has no support for them.
+    #[must_use]
+    pub fn to_write_color_spec(&self) -> termcolor::ColorSpec {
+        let mut spec = termcolor::ColorSpec::new();
+        spec.set_fg(self.foreground.and_then(ColorValue::to_termcolor))
+            .set_bg(self.background.and_then(ColorValue::to_termcolor));
+        for attribute in &self.attributes {
+            match attribute {
+                ColorAttribute::Bold => spec.set_bold(true),
+                ColorAttribute::NoBold => spec.set_bold(false),
+                ColorAttribute::Dim => spec.set_dimmed(true),
+                ColorAttribute::NoDim => spec.set_dimmed(false),
+                ColorAttribute::Ul => spec.set_underline(true),
+                ColorAttribute::NoUl => spec.set_underline(false),
+                ColorAttribute::Italic => spec.set_italic(true),
+                ColorAttribute::NoItalic => spec.set_italic(false),
+                ColorAttribute::Blink
+                | ColorAttribute::NoBlink
+                | ColorAttribute::Reverse
+                | ColorAttribute::NoReverse
+                | ColorAttribute::Strike
+                | ColorAttribute::NoStrike => &mut spec,
+            };
+        }
+        spec
+    }
+}
+
 impl Display for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(fg) = self.foreground {
@@ -944,6 +991,37 @@ pub enum ColorValue {
     Rgb(u8, u8, u8),
 }
 
+#[cfg(feature = "termcolor")]
+impl ColorValue {
+    /// Convert this color into its counterpart in the `termcolor` crate, or `None` if it is [`ColorValue::Normal`],
+    /// i.e. the terminal's default color.
+    #[must_use]
+    pub fn to_termcolor(self) -> Option<termcolor::Color> {
+        use termcolor::Color;
+        Some(match self {
+            Self::Normal => return None,
+            Self::Black => Color::Black,
+            Self::Red => Color::Red,
+            Self::Green => Color::Green,
+            Self::Yellow => Color::Yellow,
+            Self::Blue => Color::Blue,
+            Self::Magenta => Color::Magenta,
+            Self::Cyan => Color::Cyan,
+            Self::White => Color::White,
+            Self::BrightBlack => Color::Ansi256(8),
+            Self::BrightRed => Color::Ansi256(9),
+            Self::BrightGreen => Color::Ansi256(10),
+            Self::BrightYellow => Color::Ansi256(11),
+            Self::BrightBlue => Color::Ansi256(12),
+            Self::BrightMagenta => Color::Ansi256(13),
+            Self::BrightCyan => Color::Ansi256(14),
+            Self::BrightWhite => Color::Ansi256(15),
+            Self::Ansi(code) => Color::Ansi256(code),
+            Self::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        })
+    }
+}
+
 impl Display for ColorValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
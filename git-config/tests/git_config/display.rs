@@ -70,3 +70,21 @@ fn can_reconstruct_configs_without_whitespace_in_middle() {
 
     assert_eq!(File::try_from(config).unwrap().to_string(), config);
 }
+
+#[test]
+fn serialize_updates_values_in_place_and_keeps_comments() {
+    let config = r#"# leading comment
+[core]
+    ; a comment
+    a = "quoted value" # trailing comment
+    b = 1
+
+[remote "origin"]
+    url = https://example.com
+"#;
+    let mut file = File::try_from(config).unwrap();
+    assert_eq!(file.serialize(), config, "unmodified files are reproduced exactly");
+
+    file.set_raw_value("core", None, "b", b"2".to_vec()).unwrap();
+    assert_eq!(file.serialize(), config.replace("b = 1", "b = 2"));
+}
@@ -1,6 +1,6 @@
 use std::{borrow::Cow, env, fs};
 
-use git_config::file::{from_env, from_paths, from_paths::Options};
+use git_config::file::{from_env, from_paths, from_paths::Options, ConfigSource, Origin};
 use git_config::File;
 use serial_test::serial;
 use tempfile::tempdir;
@@ -143,4 +143,21 @@ fn follow_include_paths() {
         Cow::<[u8]>::Borrowed(b"changed")
     );
     assert_eq!(config.num_values(), 5);
+
+    assert_eq!(
+        config.value_source("core", None, "key"),
+        Some(ConfigSource {
+            path: Some(a_path.clone()),
+            origin: Origin::Env
+        }),
+        "included files are reported with the origin of the including configuration"
+    );
+    assert_eq!(
+        config.value_source("other", None, "path"),
+        Some(ConfigSource {
+            path: None,
+            origin: Origin::Env
+        })
+    );
+    assert_eq!(config.value_source("core", None, "missing"), None);
 }
@@ -300,3 +300,33 @@ fn nested() -> crate::Result {
     assert_eq!(config.boolean("core", None, "a"), Some(Ok(false)));
     Ok(())
 }
+
+#[test]
+fn included_files_are_listed_recursively_without_missing_ones() -> crate::Result {
+    let dir = tempdir()?;
+
+    let a_path = dir.path().join("a");
+    fs::write(a_path.as_path(), "[core]\n  a = false")?;
+
+    let b_path = dir.path().join("b");
+    fs::write(b_path.as_path(), "[core]\n  b = true\n[include]\n  path = a")?;
+
+    let c_path = dir.path().join("c");
+    fs::write(
+        c_path.as_path(),
+        format!(
+            "
+[include]
+  path = {}
+  path = /dfgwfsghfdsfs",
+            escape_backslashes(&b_path)
+        ),
+    )?;
+
+    let config = File::open(&c_path)?;
+    assert_eq!(
+        config.included_files(Some(&c_path), Default::default())?,
+        vec![b_path, dir.path().join("a")]
+    );
+    Ok(())
+}
@@ -21,5 +21,6 @@ mod from_paths;
 mod mutable_multi_value;
 mod mutable_value;
 mod raw_multi_value;
+mod raw_sections_by_name;
 mod raw_value;
 mod value;
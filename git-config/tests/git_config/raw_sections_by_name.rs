@@ -0,0 +1,40 @@
+use std::convert::TryFrom;
+
+use git_config::{file::RawSection, File};
+
+#[test]
+fn sections_are_returned_verbatim_in_order() {
+    let input = "; frontmatter\n[core]\n\ta = b # comment\n[remote \"origin\"]\n  url = x\n[core.sub]\nc\n[core]\n";
+    let config = File::try_from(input).unwrap();
+    let sections: Vec<_> = config.raw_sections_by_name("core").collect();
+    assert_eq!(
+        sections,
+        vec![
+            RawSection {
+                header_bytes: b"[core]".to_vec(),
+                body_bytes: b"\n\ta = b # comment\n".to_vec(),
+            },
+            RawSection {
+                header_bytes: b"[core.sub]".to_vec(),
+                body_bytes: b"\nc\n".to_vec(),
+            },
+            RawSection {
+                header_bytes: b"[core]".to_vec(),
+                body_bytes: b"\n".to_vec(),
+            },
+        ],
+        "subsections match, too"
+    );
+
+    let reconstructed: Vec<u8> = config
+        .raw_sections_by_name("remote")
+        .flat_map(|section| section.header_bytes.into_iter().chain(section.body_bytes))
+        .collect();
+    assert_eq!(reconstructed, b"[remote \"origin\"]\n  url = x\n");
+}
+
+#[test]
+fn unknown_sections_yield_nothing() {
+    let config = File::try_from("[core]\na=b").unwrap();
+    assert_eq!(config.raw_sections_by_name("remote").count(), 0);
+}
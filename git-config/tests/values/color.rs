@@ -0,0 +1,27 @@
+use std::convert::TryFrom;
+
+use git_config::values::{Color, ColorValue};
+use termcolor::ColorSpec;
+
+#[test]
+fn colors_and_attributes_are_mapped() {
+    let color = Color::try_from(b"brightred #ff0000 bold noitalic ul blink".as_ref()).unwrap();
+    let mut expected = ColorSpec::new();
+    expected
+        .set_fg(Some(termcolor::Color::Ansi256(9)))
+        .set_bg(Some(termcolor::Color::Rgb(255, 0, 0)))
+        .set_bold(true)
+        .set_italic(false)
+        .set_underline(true);
+    assert_eq!(color.to_write_color_spec(), expected);
+}
+
+#[test]
+fn normal_is_the_default_color() {
+    assert_eq!(ColorValue::Normal.to_termcolor(), None);
+    assert_eq!(ColorValue::Ansi(42).to_termcolor(), Some(termcolor::Color::Ansi256(42)));
+    assert_eq!(
+        Color::try_from(b"normal".as_ref()).unwrap().to_write_color_spec(),
+        ColorSpec::new()
+    );
+}
@@ -72,3 +72,22 @@ fn as_decimal() {
     assert_eq!(decimal(&format!("{}g", i64::MAX)), None, "overflow results in None");
     assert_eq!(decimal(&format!("{}g", i64::MIN)), None, "underflow results in None");
 }
+
+#[test]
+fn from_bytes_with_env_override() {
+    let var = "GIT_CONFIG_TEST_INTEGER_ENV_OVERRIDE";
+    assert_eq!(
+        Integer::from_bytes_with_env_override(b("2k"), var).unwrap(),
+        Some(2048),
+        "the config value is used if the variable isn't set"
+    );
+
+    std::env::set_var(var, "3m");
+    assert_eq!(
+        Integer::from_bytes_with_env_override(b("2k"), var).unwrap(),
+        Some(3 * 1024 * 1024)
+    );
+    std::env::set_var(var, "invalid");
+    assert!(Integer::from_bytes_with_env_override(b("2k"), var).is_err());
+    std::env::remove_var(var);
+}
@@ -146,7 +146,7 @@ pub fn encode_message(url: &str, mut out: impl io::Write) -> io::Result<()> {
     writeln!(out, "url={}\n", url)
 }
 
-fn validate(url: &str) -> io::Result<()> {
+pub(crate) fn validate(url: &str) -> io::Result<()> {
     if url.contains('\u{0}') || url.contains('\n') {
         return Err(io::Error::new(
             io::ErrorKind::Other,
@@ -5,3 +5,7 @@
 ///
 pub mod helper;
 pub use helper::action as helper;
+
+///
+pub mod program;
+pub use program::Helper;
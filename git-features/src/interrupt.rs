@@ -1,7 +1,10 @@
 //! Utilities to cause interruptions in common traits, like Read/Write and Iterator.
 use std::{
     io,
-    sync::atomic::{AtomicBool, Ordering},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
 };
 
 /// A wrapper for an inner iterator which will check for interruptions on each iteration, stopping the iteration when
@@ -123,3 +126,65 @@ where
         self.inner.consume(amt)
     }
 }
+
+/// A token to cancel individual operations, for instance one per request in a server, without affecting other operations
+/// or the global interrupt state.
+///
+/// Cancelling a token also cancels all [child tokens][CancellationToken::child_token()] created from it, recursively,
+/// whereas cancelling a child token leaves its parent untouched.
+/// Clones of a token share its state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Node>,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    cancelled: AtomicBool,
+    children: Mutex<Vec<Weak<Node>>>,
+}
+
+impl CancellationToken {
+    /// Create a new token which isn't cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel this token and all of its children.
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Return true if this token or any of its parents were cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Create a new token which is cancelled along with this one, but which can also be cancelled on its own.
+    pub fn child_token(&self) -> CancellationToken {
+        let mut children = self.inner.children.lock().unwrap_or_else(|err| err.into_inner());
+        children.retain(|child| child.strong_count() > 0);
+        let child = Arc::new(Node {
+            cancelled: AtomicBool::new(self.is_cancelled()),
+            children: Default::default(),
+        });
+        children.push(Arc::downgrade(&child));
+        CancellationToken { inner: child }
+    }
+
+    /// Return the flag which is set once this token is cancelled, for use with all functions taking a `should_interrupt` flag.
+    pub fn should_interrupt(&self) -> &AtomicBool {
+        &self.inner.cancelled
+    }
+}
+
+impl Node {
+    fn cancel(&self) {
+        // set the flag before locking so children created concurrently observe it.
+        self.cancelled.store(true, Ordering::SeqCst);
+        let children = self.children.lock().unwrap_or_else(|err| err.into_inner());
+        for child in children.iter().filter_map(Weak::upgrade) {
+            child.cancel();
+        }
+    }
+}
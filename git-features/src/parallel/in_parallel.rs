@@ -84,6 +84,38 @@ where
     .expect("no panic")
 }
 
+/// Split `input` into one chunk of equal size per thread and call `f` on each item of a chunk in parallel, returning
+/// all results in the order of their respective items in `input`.
+///
+/// * if `thread_limit` is `Some`, at most the given amount of threads will be used. If `None`, all logical cores will be used.
+///
+/// This works best if `f` takes about the same time for each item, which is the case for looking up a sorted list of objects.
+pub fn in_parallel_with_slice_chunks<T, F, R>(input: &[T], thread_limit: Option<usize>, f: F) -> impl Iterator<Item = R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let num_threads = num_threads(thread_limit);
+    if num_threads < 2 || input.len() < 2 {
+        return input.iter().map(f).collect::<Vec<_>>().into_iter();
+    }
+    let chunk_size = (input.len() + num_threads - 1) / num_threads;
+    let f = &f;
+    crossbeam_utils::thread::scope(|s| {
+        let threads: Vec<_> = input
+            .chunks(chunk_size)
+            .map(|chunk| s.spawn(move |_| chunk.iter().map(f).collect::<Vec<_>>()))
+            .collect();
+        threads
+            .into_iter()
+            .flat_map(|thread| thread.join().expect("no panic"))
+            .collect::<Vec<_>>()
+    })
+    .expect("no panic")
+    .into_iter()
+}
+
 /// An experiment to have fine-grained per-item parallelization with built-in aggregation via thread state.
 /// This is only good for operations where near-random access isn't detremental, so it's not usually great
 /// for file-io as it won't make use of sorted inputs well.
@@ -86,6 +86,24 @@ mod not_parallel {
 #[cfg(not(feature = "parallel"))]
 pub use not_parallel::{in_parallel_with_slice, join, threads, Scope, ScopedJoinHandle};
 
+/// Call `f` on each item of `input` and return all results in order.
+///
+/// * `thread_limit` has no effect as everything is run on the main thread, but is present to keep the signature
+///   similar to the parallel version.
+///
+/// **This serial version performing all calculations on the current thread.**
+#[cfg(not(feature = "parallel"))]
+pub fn in_parallel_with_slice_chunks<T, F, R>(
+    input: &[T],
+    _thread_limit: Option<usize>,
+    f: F,
+) -> impl Iterator<Item = R>
+where
+    F: Fn(&T) -> R,
+{
+    input.iter().map(f).collect::<Vec<_>>().into_iter()
+}
+
 /// Read items from `input` and `consume` them in a single thread, producing an output to be collected by a `reducer`,
 /// whose task is to aggregate these outputs into the final result returned by this function.
 ///
@@ -1,5 +1,13 @@
 //! Various `prodash` types along with various utilities for comfort.
-use std::io;
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
 
 pub use prodash::{
     messages::MessageLevel,
@@ -94,3 +102,248 @@ where
         self.inner.flush()
     }
 }
+
+/// A [`Progress`] implementation which can be shared among threads by cloning it, with all clones reporting to the same
+/// underlying `Progress` instance.
+///
+/// Increments are counted in an atomic counter without locking, and the count is passed on to the underlying progress
+/// only every [`flush_interval`][AtomicProgress::with_flush_interval()] steps, as well as when a clone is dropped.
+/// All other calls lock the underlying progress.
+pub struct AtomicProgress<P: Progress> {
+    inner: Arc<Mutex<P>>,
+    step: Arc<AtomicUsize>,
+    flush_interval: usize,
+}
+
+impl<P: Progress> Clone for AtomicProgress<P> {
+    fn clone(&self) -> Self {
+        AtomicProgress {
+            inner: Arc::clone(&self.inner),
+            step: Arc::clone(&self.step),
+            flush_interval: self.flush_interval,
+        }
+    }
+}
+
+impl<P: Progress> AtomicProgress<P> {
+    /// Wrap `progress` to make it shareable, starting out at its current step.
+    pub fn new(progress: P) -> Self {
+        AtomicProgress {
+            step: Arc::new(AtomicUsize::new(progress.step())),
+            inner: Arc::new(Mutex::new(progress)),
+            flush_interval: 64,
+        }
+    }
+
+    /// Pass the step counter to the underlying progress each time it was incremented by at least `steps`, which must
+    /// not be zero. The default is 64.
+    pub fn with_flush_interval(mut self, steps: usize) -> Self {
+        assert_ne!(steps, 0, "the flush interval must not be zero");
+        self.flush_interval = steps;
+        self
+    }
+
+    fn lock(&self) -> MutexGuard<'_, P> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn flush(&self) {
+        let mut inner = self.lock();
+        inner.set(self.step.load(Ordering::SeqCst));
+    }
+}
+
+impl<P: Progress> Drop for AtomicProgress<P> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl<P: Progress> Progress for AtomicProgress<P> {
+    type SubProgress = AtomicProgress<P::SubProgress>;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        AtomicProgress::new(self.lock().add_child(name)).with_flush_interval(self.flush_interval)
+    }
+
+    fn init(&mut self, max: Option<usize>, unit: Option<Unit>) {
+        let mut inner = self.lock();
+        self.step.store(0, Ordering::SeqCst);
+        inner.init(max, unit);
+    }
+
+    fn set(&mut self, step: usize) {
+        let mut inner = self.lock();
+        self.step.store(step, Ordering::SeqCst);
+        inner.set(step);
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        self.lock().unit()
+    }
+
+    fn max(&self) -> Option<usize> {
+        self.lock().max()
+    }
+
+    fn step(&self) -> usize {
+        self.step.load(Ordering::SeqCst)
+    }
+
+    fn inc_by(&mut self, step: usize) {
+        let previous = self.step.fetch_add(step, Ordering::SeqCst);
+        if previous / self.flush_interval != (previous + step) / self.flush_interval {
+            self.flush();
+        }
+    }
+
+    fn set_name(&mut self, name: impl Into<String>) {
+        self.lock().set_name(name)
+    }
+
+    fn name(&self) -> Option<String> {
+        self.lock().name()
+    }
+
+    fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
+        self.lock().message(level, message)
+    }
+}
+
+/// A [`Progress`] implementation which appends the rate at which bytes are transferred to the name of the underlying
+/// `Progress` instance, like `receiving pack, 1.2 MiB/s`.
+///
+/// The rate is averaged over the [`report_throughput()`][ThroughputTracker::report_throughput()] calls of the last second
+/// and the name is updated at most every 100ms, keeping the overhead of formatting it low.
+pub struct ThroughputTracker<P: Progress> {
+    inner: P,
+    name: String,
+    start: Instant,
+    /// The time at which bytes were reported along with their amount, oldest first.
+    samples: VecDeque<(Instant, u64)>,
+    last_update: Option<Instant>,
+}
+
+impl<P: Progress> ThroughputTracker<P> {
+    /// The time span over which the rate is averaged.
+    const WINDOW: Duration = Duration::from_secs(1);
+    /// The least amount of time between two updates of the name.
+    const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Wrap `progress` to report throughput, using its current name as prefix.
+    pub fn new(progress: P) -> Self {
+        Self::new_at(progress, Instant::now())
+    }
+
+    /// Like [`new()`][ThroughputTracker::new()], but start tracking at the given `start` time.
+    pub fn new_at(progress: P, start: Instant) -> Self {
+        ThroughputTracker {
+            name: progress.name().unwrap_or_default(),
+            inner: progress,
+            start,
+            samples: VecDeque::new(),
+            last_update: None,
+        }
+    }
+
+    /// Record that `bytes` were transferred since the previous call, and update the name of the underlying progress with
+    /// the average rate of the last second unless it was updated less than 100ms ago.
+    pub fn report_throughput(&mut self, bytes: u64) {
+        self.report_throughput_at(bytes, Instant::now())
+    }
+
+    /// Like [`report_throughput()`][ThroughputTracker::report_throughput()], but record the `bytes` as transferred at
+    /// the given time `now`, which must not be earlier than the time of previous calls.
+    pub fn report_throughput_at(&mut self, bytes: u64, now: Instant) {
+        self.samples.push_back((now, bytes));
+        while let Some((time, _)) = self.samples.front() {
+            if now.duration_since(*time) <= Self::WINDOW {
+                break;
+            }
+            self.samples.pop_front();
+        }
+        if matches!(self.last_update, Some(last_update) if now.duration_since(last_update) < Self::UPDATE_INTERVAL) {
+            return;
+        }
+        self.last_update = Some(now);
+
+        // until a full window passed, the rate is relative to the time we started tracking.
+        let window = now
+            .duration_since(self.start)
+            .clamp(Self::UPDATE_INTERVAL, Self::WINDOW);
+        let bytes_in_window: u64 = self.samples.iter().map(|(_, bytes)| bytes).sum();
+        let rate = format_rate(bytes_in_window as f64 / window.as_secs_f64());
+        self.inner.set_name(if self.name.is_empty() {
+            rate
+        } else {
+            format!("{}, {}", self.name, rate)
+        });
+    }
+
+    /// Return the underlying progress instance.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+/// Format `bytes_per_second` with a binary unit, like `1.2 MiB/s`.
+fn format_rate(bytes_per_second: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut rate = bytes_per_second;
+    let mut unit = 0;
+    while rate >= 1024.0 && unit + 1 < UNITS.len() {
+        rate /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{:.0} {}/s", rate, UNITS[unit])
+    } else {
+        format!("{:.1} {}/s", rate, UNITS[unit])
+    }
+}
+
+impl<P: Progress> Progress for ThroughputTracker<P> {
+    type SubProgress = P::SubProgress;
+
+    fn add_child(&mut self, name: impl Into<String>) -> Self::SubProgress {
+        self.inner.add_child(name)
+    }
+
+    fn init(&mut self, max: Option<usize>, unit: Option<Unit>) {
+        self.inner.init(max, unit)
+    }
+
+    fn set(&mut self, step: usize) {
+        self.inner.set(step)
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        self.inner.unit()
+    }
+
+    fn max(&self) -> Option<usize> {
+        self.inner.max()
+    }
+
+    fn step(&self) -> usize {
+        self.inner.step()
+    }
+
+    fn inc_by(&mut self, step: usize) {
+        self.inner.inc_by(step)
+    }
+
+    fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+        self.last_update = None;
+        self.inner.set_name(self.name.clone())
+    }
+
+    fn name(&self) -> Option<String> {
+        self.inner.name()
+    }
+
+    fn message(&mut self, level: MessageLevel, message: impl Into<String>) {
+        self.inner.message(level, message)
+    }
+}
@@ -0,0 +1,26 @@
+mod cancellation_token {
+    use std::sync::atomic::Ordering;
+
+    use git_features::interrupt::CancellationToken;
+
+    #[test]
+    fn cancelling_a_parent_cancels_all_children_but_not_the_other_way_around() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let grand_child = child.child_token();
+        let sibling = parent.child_token();
+
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(grand_child.is_cancelled());
+        assert!(!parent.is_cancelled(), "parents are unaffected");
+        assert!(!sibling.is_cancelled());
+
+        parent.cancel();
+        assert!(sibling.should_interrupt().load(Ordering::Relaxed));
+        assert!(
+            parent.child_token().is_cancelled(),
+            "children of cancelled tokens start out cancelled"
+        );
+    }
+}
@@ -58,6 +58,17 @@ fn in_parallel_with_mut_slice_in_chunks() {
     );
 }
 
+#[test]
+fn in_parallel_with_slice_chunks_preserves_order() {
+    let input: Vec<_> = (0..101usize).collect();
+    let output: Vec<_> = parallel::in_parallel_with_slice_chunks(&input, Some(4), |item| item * 2).collect();
+    assert_eq!(output, input.iter().map(|item| item * 2).collect::<Vec<_>>());
+    assert_eq!(
+        parallel::in_parallel_with_slice_chunks(&[] as &[usize], None, |item| *item).count(),
+        0
+    );
+}
+
 #[test]
 fn stepped_reduce_next() {
     let mut iter = parallel::reduce::Stepwise::new(
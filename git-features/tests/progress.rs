@@ -0,0 +1,199 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+use git_features::progress::{MessageLevel, Progress, Unit};
+
+/// A progress implementation whose state can be observed through its clones.
+#[derive(Clone, Default)]
+struct Observed {
+    max: Arc<AtomicUsize>,
+    step: Arc<AtomicUsize>,
+    /// The amount of times the step was set or incremented.
+    updates: Arc<AtomicUsize>,
+    name: Arc<Mutex<Option<String>>>,
+}
+
+impl Progress for Observed {
+    type SubProgress = Observed;
+
+    fn add_child(&mut self, _name: impl Into<String>) -> Self::SubProgress {
+        Observed::default()
+    }
+
+    fn init(&mut self, max: Option<usize>, _unit: Option<Unit>) {
+        self.max.store(max.unwrap_or_default(), Ordering::SeqCst);
+        self.step.store(0, Ordering::SeqCst);
+    }
+
+    fn set(&mut self, step: usize) {
+        self.step.store(step, Ordering::SeqCst);
+        self.updates.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn unit(&self) -> Option<Unit> {
+        None
+    }
+
+    fn max(&self) -> Option<usize> {
+        Some(self.max.load(Ordering::SeqCst))
+    }
+
+    fn step(&self) -> usize {
+        self.step.load(Ordering::SeqCst)
+    }
+
+    fn inc_by(&mut self, step: usize) {
+        self.step.fetch_add(step, Ordering::SeqCst);
+        self.updates.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn set_name(&mut self, name: impl Into<String>) {
+        *self.name.lock().unwrap() = Some(name.into());
+    }
+
+    fn name(&self) -> Option<String> {
+        self.name.lock().unwrap().clone()
+    }
+
+    fn message(&mut self, _level: MessageLevel, _message: impl Into<String>) {}
+}
+
+mod atomic_progress {
+    use git_features::progress::{AtomicProgress, Progress};
+
+    use crate::Observed;
+
+    #[test]
+    fn increments_of_all_threads_arrive_once_all_clones_are_dropped() {
+        let inner = Observed::default();
+        let mut progress = AtomicProgress::new(inner.clone()).with_flush_interval(7);
+        let (num_threads, steps_per_thread) = (8, 1000);
+        progress.init(Some(num_threads * steps_per_thread), None);
+
+        let threads: Vec<_> = (0..num_threads)
+            .map(|_| {
+                let mut progress = progress.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..steps_per_thread {
+                        progress.inc();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().expect("no panic");
+        }
+
+        assert_eq!(
+            progress.step(),
+            num_threads * steps_per_thread,
+            "the shared counter sees all increments"
+        );
+        drop(progress);
+        assert_eq!(inner.step(), num_threads * steps_per_thread);
+        assert_eq!(inner.max(), Some(num_threads * steps_per_thread));
+        assert!(
+            inner.updates.load(std::sync::atomic::Ordering::SeqCst) < num_threads * steps_per_thread,
+            "the underlying progress isn't updated on every increment"
+        );
+    }
+
+    #[test]
+    fn the_underlying_progress_is_updated_each_time_the_flush_interval_is_crossed() {
+        let inner = Observed::default();
+        let mut progress = AtomicProgress::new(inner.clone()).with_flush_interval(10);
+        for _ in 0..9 {
+            progress.inc();
+        }
+        assert_eq!(inner.step(), 0, "nothing was passed on yet");
+        progress.inc();
+        assert_eq!(inner.step(), 10);
+        progress.inc_by(25);
+        assert_eq!(inner.step(), 35, "crossing multiple intervals at once flushes as well");
+        progress.inc();
+        assert_eq!(inner.step(), 35);
+
+        progress.set(3);
+        assert_eq!(inner.step(), 3, "setting the step is passed on right away");
+        progress.clone().inc();
+        assert_eq!(inner.step(), 4, "dropping a clone flushes");
+    }
+
+    #[test]
+    #[should_panic]
+    fn a_flush_interval_of_zero_is_invalid() {
+        let _ = AtomicProgress::new(Observed::default()).with_flush_interval(0);
+    }
+}
+
+mod throughput_tracker {
+    use std::time::{Duration, Instant};
+
+    use git_features::progress::{Progress, ThroughputTracker};
+
+    use crate::Observed;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn the_rate_is_averaged_over_the_last_second_and_updated_at_most_every_100ms() {
+        let mut inner = Observed::default();
+        inner.set_name("receiving pack");
+        let start = Instant::now();
+        let mut progress = ThroughputTracker::new_at(inner.clone(), start);
+
+        progress.report_throughput_at(1024, start + ms(100));
+        assert_eq!(
+            inner.name().as_deref(),
+            Some("receiving pack, 10.0 KiB/s"),
+            "the rate is relative to the start until a full second passed"
+        );
+
+        progress.report_throughput_at(1024, start + ms(150));
+        assert_eq!(
+            inner.name().as_deref(),
+            Some("receiving pack, 10.0 KiB/s"),
+            "the name isn't updated within 100ms of the previous update"
+        );
+
+        progress.report_throughput_at(1024, start + ms(200));
+        assert_eq!(
+            inner.name().as_deref(),
+            Some("receiving pack, 15.0 KiB/s"),
+            "the bytes of skipped updates still count"
+        );
+
+        progress.report_throughput_at(0, start + ms(2200));
+        assert_eq!(
+            inner.name().as_deref(),
+            Some("receiving pack, 0 B/s"),
+            "samples older than a second are discarded"
+        );
+
+        progress.report_throughput_at(2 * 1024 * 1024, start + ms(2700));
+        assert_eq!(inner.name().as_deref(), Some("receiving pack, 2.0 MiB/s"));
+    }
+
+    #[test]
+    fn setting_the_name_changes_the_prefix_and_allows_an_immediate_update() {
+        let inner = Observed::default();
+        let start = Instant::now();
+        let mut progress = ThroughputTracker::new_at(inner.clone(), start);
+
+        progress.report_throughput_at(100, start + ms(500));
+        assert_eq!(
+            inner.name().as_deref(),
+            Some("200 B/s"),
+            "without a name, only the rate is shown"
+        );
+
+        progress.set_name("indexing");
+        assert_eq!(inner.name().as_deref(), Some("indexing"));
+        progress.report_throughput_at(100, start + ms(510));
+        assert_eq!(inner.name().as_deref(), Some("indexing, 392 B/s"));
+    }
+}
@@ -1,6 +1,8 @@
+use std::ops::Range;
+
 use bstr::{BStr, ByteSlice};
 
-use crate::{extension, Entry, PathStorage, State, Version};
+use crate::{entry, extension, Entry, PathStorage, State, Version};
 
 impl State {
     pub fn version(&self) -> Version {
@@ -10,6 +12,72 @@ impl State {
     pub fn entries(&self) -> &[Entry] {
         &self.entries
     }
+
+    /// Find the entry at `path` and `stage` with a binary search, as entries are sorted by path and then by stage.
+    pub fn entry_by_path(&self, path: &BStr, stage: u32) -> Option<&Entry> {
+        self.entry_index_by_path_and_stage(path, stage)
+            .ok()
+            .map(|idx| &self.entries[idx])
+    }
+
+    /// Like [`entry_by_path()`][State::entry_by_path()], but returns the entry for modification.
+    pub fn entry_mut_by_path(&mut self, path: &BStr, stage: u32) -> Option<&mut Entry> {
+        self.entry_index_by_path_and_stage(path, stage)
+            .ok()
+            .map(move |idx| &mut self.entries[idx])
+    }
+
+    /// Return all entries at `path` ordered by stage, which is more than one if `path` is conflicted.
+    pub fn entries_for_path(&self, path: &BStr) -> &[Entry] {
+        &self.entries[self.entry_range_by_path(path)]
+    }
+
+    /// Return true if there are entries at stages other than 0, i.e. paths with unresolved conflicts.
+    pub fn has_conflicts(&self) -> bool {
+        self.entries.iter().any(|entry| entry.stage() != 0)
+    }
+
+    /// Return all paths with unresolved conflicts along with their entries at stage 1, 2 and 3, in order.
+    pub fn conflicts(&self) -> impl Iterator<Item = entry::Conflict<'_>> + '_ {
+        let mut entries = self.entries.iter().peekable();
+        std::iter::from_fn(move || loop {
+            let first = entries.next()?;
+            if first.stage() == 0 {
+                continue;
+            }
+            let path = first.path(self);
+            let mut conflict = entry::Conflict {
+                path,
+                ancestor: None,
+                ours: None,
+                theirs: None,
+            };
+            let mut next = Some(first);
+            while let Some(entry) = next {
+                match entry.stage() {
+                    1 => conflict.ancestor = Some(entry),
+                    2 => conflict.ours = Some(entry),
+                    3 => conflict.theirs = Some(entry),
+                    _ => {}
+                }
+                next = entries.next_if(|entry| entry.path(self) == path);
+            }
+            return Some(conflict);
+        })
+    }
+
+    /// Return the index of the entry at `path` and `stage`, or the index at which it would have to be inserted.
+    pub(crate) fn entry_index_by_path_and_stage(&self, path: &BStr, stage: u32) -> Result<usize, usize> {
+        self.entries
+            .binary_search_by(|entry| entry.path(self).cmp(path).then_with(|| entry.stage().cmp(&stage)))
+    }
+
+    /// Return the range of entries at `path`, in all of their stages.
+    pub(crate) fn entry_range_by_path(&self, path: &BStr) -> Range<usize> {
+        let start = self.entries.partition_point(|entry| entry.path(self) < path);
+        let len = self.entries[start..].partition_point(|entry| entry.path(self) == path);
+        start..start + len
+    }
     pub fn path_backing(&self) -> &PathStorage {
         &self.path_backing
     }
@@ -126,13 +126,13 @@ fn load_one<'a>(
 
         data
     } else {
-        let (path, data) = if flags.contains(entry::Flags::PATH_LEN) {
-            split_at_byte_exclusive(data, 0)?
+        let path_len = if flags.contains(entry::Flags::PATH_LEN) {
+            data.iter().position(|b| *b == 0)?
         } else {
-            let path_len = (flags.bits() & entry::Flags::PATH_LEN.bits()) as usize;
-            let (path, data) = split_at_pos(data, path_len)?;
-            (path, skip_padding(data, first_byte_of_entry))
+            (flags.bits() & entry::Flags::PATH_LEN.bits()) as usize
         };
+        let (path, data) = split_at_pos(data, path_len)?;
+        let data = skip_padding(data, first_byte_of_entry);
 
         path_backing.extend_from_slice(path);
         data
@@ -0,0 +1,141 @@
+use std::ops::Range;
+
+use bstr::{BStr, ByteSlice};
+
+use filetime::FileTime;
+
+use crate::{Entry, State, Version};
+
+pub mod add_entry {
+    use bstr::BString;
+    use quick_error::quick_error;
+
+    quick_error! {
+        #[derive(Debug)]
+        pub enum Error {
+            InvalidPath { path: BString } {
+                display("The path '{}' is empty or has components that are empty, '.', '..' or '.git'", path)
+            }
+            AlreadyExists { path: BString, stage: u32 } {
+                display("An entry at '{}' (stage = {}) already exists", path, stage)
+            }
+            DirectoryFileConflict { path: BString, existing_path: BString } {
+                display("The entry at '{}' conflicts with the existing entry at '{}' as one would have to be a directory", path, existing_path)
+            }
+        }
+    }
+}
+
+impl State {
+    /// Create a state in the given `version` without any entries or extensions, for example to build an index from scratch.
+    pub fn new(version: Version) -> Self {
+        State {
+            timestamp: FileTime::now(),
+            version,
+            entries: Vec::new(),
+            path_backing: Vec::new(),
+            is_sparse: false,
+            tree: None,
+            link: None,
+            resolve_undo: None,
+            untracked: None,
+            fs_monitor: None,
+        }
+    }
+
+    /// Add `entry` at `path`, keeping all entries sorted by path and stage, for example to stage a file like `git add` does.
+    ///
+    /// The path `entry` had before is ignored. If an entry with the same path and stage exists or if one of the leading
+    /// directories of `path` is a file or `path` is a directory with entries in it, [`add_entry::Error::AlreadyExists`] or
+    /// [`add_entry::Error::DirectoryFileConflict`] is returned, unless `replace` is true, in which case these entries are
+    /// replaced by `entry`. Adding an entry at stage 0 always removes all entries at other stages of the same path, which
+    /// marks a conflict as resolved.
+    ///
+    /// Note that the tree extension is removed as it doesn't match the entries anymore.
+    pub fn add_entry(&mut self, mut entry: Entry, path: &BStr, replace: bool) -> Result<(), add_entry::Error> {
+        if !is_valid_path(path) {
+            return Err(add_entry::Error::InvalidPath { path: path.into() });
+        }
+        let stage = entry.stage();
+        let existing = self.entry_index_by_path_and_stage(path, stage);
+        if existing.is_ok() && !replace {
+            return Err(add_entry::Error::AlreadyExists {
+                path: path.into(),
+                stage,
+            });
+        }
+
+        let mut conflicts: Vec<usize> = path
+            .find_iter("/")
+            .flat_map(|pos| self.entry_range_by_path(path[..pos].as_bstr()))
+            .chain(self.entry_range_by_directory(path))
+            .collect();
+        if let Some(&idx) = conflicts.first() {
+            if !replace {
+                return Err(add_entry::Error::DirectoryFileConflict {
+                    path: path.into(),
+                    existing_path: self.entries[idx].path(self).into(),
+                });
+            }
+        }
+        if stage == 0 {
+            conflicts.extend(
+                self.entry_range_by_path(path)
+                    .filter(|idx| self.entries[*idx].stage() != 0),
+            );
+        }
+
+        entry.path = match existing {
+            Ok(idx) => self.entries[idx].path.clone(),
+            Err(_) => {
+                let start = self.path_backing.len();
+                self.path_backing.extend_from_slice(path);
+                start..self.path_backing.len()
+            }
+        };
+        if !conflicts.is_empty() {
+            conflicts.sort_unstable();
+            let mut conflicts = conflicts.into_iter().peekable();
+            let mut idx = 0;
+            self.entries.retain(|_| {
+                let is_conflict = conflicts.next_if_eq(&idx).is_some();
+                idx += 1;
+                !is_conflict
+            });
+        }
+        match self.entry_index_by_path_and_stage(path, stage) {
+            Ok(idx) => self.entries[idx] = entry,
+            Err(idx) => self.entries.insert(idx, entry),
+        }
+        self.tree = None;
+        Ok(())
+    }
+
+    /// Remove the entry at `path` and `stage` and return it, or `None` if there is no such entry, for example to unstage
+    /// a file like `git rm --cached` does.
+    ///
+    /// The path of the removed entry can still be obtained from this instance.
+    /// Note that the tree extension is removed as it doesn't match the entries anymore.
+    pub fn remove_entry(&mut self, path: &BStr, stage: u32) -> Option<Entry> {
+        let idx = self.entry_index_by_path_and_stage(path, stage).ok()?;
+        self.tree = None;
+        Some(self.entries.remove(idx))
+    }
+
+    /// Return the range of entries in the directory at `path`, recursively.
+    fn entry_range_by_directory(&self, path: &BStr) -> Range<usize> {
+        let mut dir = path.to_owned();
+        dir.push(b'/');
+        let start = self.entries.partition_point(|entry| entry.path(self) < dir.as_bstr());
+        let len = self.entries[start..].partition_point(|entry| entry.path(self).starts_with(&dir));
+        start..start + len
+    }
+}
+
+fn is_valid_path(path: &BStr) -> bool {
+    !path.is_empty()
+        && !path.contains(&0)
+        && path.split_str("/").all(|component| {
+            !component.is_empty() && component != b"." && component != b".." && !component.eq_ignore_ascii_case(b".git")
+        })
+}
@@ -51,6 +51,12 @@ pub(crate) mod at_rest {
         pub fn to_flags(self) -> Option<super::Flags> {
             super::Flags::from_bits((self.bits as u32) << 16)
         }
+
+        pub fn from_flags(flags: super::Flags) -> Self {
+            FlagsExtended::from_bits_truncate(
+                ((flags & (super::Flags::INTENT_TO_ADD | super::Flags::SKIP_WORKTREE)).bits() >> 16) as u16,
+            )
+        }
     }
 
     impl Flags {
@@ -58,6 +64,11 @@ pub(crate) mod at_rest {
             super::Flags::from_bits((self & (Flags::PATH_LEN | Flags::STAGE_MASK | Flags::ASSUME_VALID)).bits as u32)
                 .expect("PATHLEN is part of memory representation")
         }
+
+        /// The inverse of [`to_memory()`][Flags::to_memory()], without the path length.
+        pub fn from_memory(flags: super::Flags) -> Self {
+            Flags::from_bits_truncate((flags & (super::Flags::STAGE_MASK | super::Flags::ASSUME_VALID)).bits() as u16)
+        }
     }
 
     #[cfg(test)]
@@ -111,6 +122,19 @@ impl Flags {
     }
 }
 
+/// The entries of a path with unresolved conflicts, as returned by [`State::conflicts()`][crate::State::conflicts()].
+#[derive(Clone, Copy)]
+pub struct Conflict<'a> {
+    /// The path of all entries.
+    pub path: &'a bstr::BStr,
+    /// The entry at stage 1, the common ancestor, or `None` if `path` didn't exist in it.
+    pub ancestor: Option<&'a crate::Entry>,
+    /// The entry at stage 2, the version of the current branch, or `None` if `path` was deleted there.
+    pub ours: Option<&'a crate::Entry>,
+    /// The entry at stage 3, the version being merged, or `None` if `path` was deleted there.
+    pub theirs: Option<&'a crate::Entry>,
+}
+
 #[derive(PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Time {
@@ -139,6 +163,17 @@ mod access {
     use crate::{Entry, State};
 
     impl Entry {
+        /// Create an entry without a path, which is set once it's added with [`State::add_entry()`].
+        pub fn new(stat: super::Stat, id: git_hash::ObjectId, flags: super::Flags, mode: super::Mode) -> Self {
+            Entry {
+                stat,
+                id,
+                flags,
+                mode,
+                path: 0..0,
+            }
+        }
+
         pub fn path<'a>(&self, state: &'a State) -> &'a BStr {
             (&state.path_backing[self.path.clone()]).as_bstr()
         }
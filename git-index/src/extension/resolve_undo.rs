@@ -8,7 +8,6 @@ use crate::{
 
 pub type Paths = Vec<ResolvePath>;
 
-#[allow(dead_code)]
 pub struct ResolvePath {
     /// relative to the root of the repository, or what would be stored in the index
     name: BString,
@@ -17,7 +16,6 @@ pub struct ResolvePath {
     stages: [Option<Stage>; 3],
 }
 
-#[allow(dead_code)]
 pub struct Stage {
     mode: u32,
     id: ObjectId,
@@ -25,6 +23,22 @@ pub struct Stage {
 
 pub const SIGNATURE: Signature = *b"REUC";
 
+/// Append the serialized form of all `paths` to `out`, without the extension header.
+pub fn encode(paths: &Paths, out: &mut Vec<u8>) {
+    for path in paths {
+        out.extend_from_slice(&path.name);
+        out.push(0);
+        for stage in &path.stages {
+            let mode = stage.as_ref().map_or(0, |stage| stage.mode);
+            out.extend_from_slice(format!("{:o}", mode).as_bytes());
+            out.push(0);
+        }
+        for stage in path.stages.iter().flatten() {
+            out.extend_from_slice(stage.id.as_bytes());
+        }
+    }
+}
+
 pub fn decode(mut data: &[u8], object_hash: git_hash::Kind) -> Option<Paths> {
     let hash_len = object_hash.len_in_bytes();
     let mut out = Vec::new();
@@ -133,6 +133,17 @@ impl Tree {
     }
 }
 
+/// Append the serialized form of `tree` and all of its children to `out`, without the extension header.
+pub(crate) fn encode(tree: &Tree, out: &mut Vec<u8>) {
+    out.extend_from_slice(&tree.name);
+    out.push(0);
+    out.extend_from_slice(format!("{} {}\n", tree.num_entries, tree.children.len()).as_bytes());
+    out.extend_from_slice(tree.id.as_bytes());
+    for child in &tree.children {
+        encode(child, out);
+    }
+}
+
 /// A recursive data structure
 pub fn decode(data: &[u8], object_hash: git_hash::Kind) -> Option<Tree> {
     let (tree, data) = one_recursive(data, object_hash.len_in_bytes())?;
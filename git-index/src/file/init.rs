@@ -42,4 +42,15 @@ impl File {
         let (state, checksum) = State::from_bytes(&data, mtime, options)?;
         Ok(File { state, path, checksum })
     }
+
+    /// Create an index file at `path` that isn't written yet, with an empty [`State`] in the given `version`.
+    ///
+    /// Its checksum is null until it's [written][File::write()].
+    pub fn new(path: impl Into<PathBuf>, version: crate::Version, object_hash: git_hash::Kind) -> Self {
+        File {
+            state: State::new(version),
+            path: path.into(),
+            checksum: git_hash::ObjectId::null(object_hash),
+        }
+    }
 }
@@ -0,0 +1,205 @@
+use std::{convert::TryInto, io::Write};
+
+use bstr::BStr;
+
+use crate::{entry, extension, util::leb64_encode, Entry, File, Version};
+
+mod error {
+    use quick_error::quick_error;
+
+    quick_error! {
+        #[derive(Debug)]
+        pub enum Error {
+            Io(err: std::io::Error) {
+                display("An IO error occurred while writing the index")
+                source(err)
+                from()
+            }
+            AcquireLock(err: git_lock::acquire::Error) {
+                display("Could not acquire the lock to write the index")
+                source(err)
+                from()
+            }
+            SplitIndex {
+                display("Split indices can't be written as the entries of the shared index would be lost")
+            }
+        }
+    }
+}
+
+pub use error::Error;
+
+/// Options for use in [`File::write_to()`] and [`File::write()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The version of the index file to write.
+    ///
+    /// If it's [`Version::V2`] but entries have flags that can only be stored in extended entries, [`Version::V3`] is written instead,
+    /// like `git` does.
+    pub version: Version,
+}
+
+/// Information about the index written by [`File::write_to()`] and [`File::write()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// The version that was actually written.
+    pub version: Version,
+    /// The amount of entries written.
+    pub num_entries: u32,
+    /// The checksum of all bytes prior to the checksum itself, which is written last.
+    pub checksum: git_hash::ObjectId,
+}
+
+/// The in-memory flags that can only be stored in extended entries, available as of [`Version::V3`].
+const EXTENDED_FLAGS: entry::Flags =
+    entry::Flags::from_bits_truncate(entry::Flags::INTENT_TO_ADD.bits() | entry::Flags::SKIP_WORKTREE.bits());
+
+impl File {
+    /// Serialize the header and all entries in their canonical order, sorted by path and then by stage, to `out`, followed by
+    /// the checksum of all written bytes using the hash kind of our own checksum.
+    ///
+    /// Entries flagged with [`entry::Flags::REMOVE`] are skipped. [`Version::V4`] writes prefix-compressed paths, whereas all
+    /// other versions pad entries with null bytes to a multiple of 8 bytes.
+    ///
+    /// The cache tree and the resolve-undo information are written as extensions, along with the marker of sparse indices.
+    /// The untracked cache and the file system monitor data are dropped deliberately as we don't keep them up to date
+    /// when entries change, which is valid as both are optional and recomputed by `git` if enabled.
+    /// Split indices can't be written and cause [`Error::SplitIndex`], as we only know the entries of the split index itself.
+    pub fn write_to(&self, out: impl std::io::Write, Options { version }: Options) -> Result<Stats, Error> {
+        if self.link().is_some() {
+            return Err(Error::SplitIndex);
+        }
+        let mut entries: Vec<_> = self
+            .entries()
+            .iter()
+            .filter(|entry| !entry.flags.contains(entry::Flags::REMOVE))
+            .collect();
+        entries.sort_by(|lhs, rhs| Entry::cmp(lhs, rhs, &self.state));
+        let version = if version == Version::V2 && entries.iter().any(|entry| entry.flags.intersects(EXTENDED_FLAGS)) {
+            Version::V3
+        } else {
+            version
+        };
+        let num_entries: u32 = entries
+            .len()
+            .try_into()
+            .expect("BUG: an index can't have more than u32::MAX entries");
+
+        let mut out = git_features::hash::Write::new(out, self.checksum.kind());
+        out.write_all(b"DIRC")?;
+        out.write_all(&(version as u32).to_be_bytes())?;
+        out.write_all(&num_entries.to_be_bytes())?;
+        let mut prev_path: &BStr = "".into();
+        let mut is_sparse = false;
+        for entry in entries {
+            let path = entry.path(&self.state);
+            write_entry(&mut out, entry, path, prev_path, version)?;
+            prev_path = path;
+            is_sparse |= entry.mode.is_sparse();
+        }
+
+        let mut buf = Vec::new();
+        if let Some(tree) = self.tree() {
+            extension::tree::encode(tree, &mut buf);
+            write_extension(&mut out, extension::tree::SIGNATURE, &buf)?;
+        }
+        if let Some(paths) = self.resolve_undo() {
+            buf.clear();
+            extension::resolve_undo::encode(paths, &mut buf);
+            write_extension(&mut out, extension::resolve_undo::SIGNATURE, &buf)?;
+        }
+        if is_sparse {
+            write_extension(&mut out, extension::sparse::SIGNATURE, &[])?;
+        }
+
+        let checksum = git_hash::ObjectId::from(out.hash.digest());
+        out.inner.write_all(checksum.as_bytes())?;
+        Ok(Stats {
+            version,
+            num_entries,
+            checksum,
+        })
+    }
+
+    /// Write this index to its [`path`][File::path] like [`write_to()`][File::write_to()] does, and update our checksum
+    /// and version accordingly.
+    ///
+    /// The index is written to a `.lock` file next to it first, which then atomically replaces the index. It fails if the lock
+    /// file already exists, as another process is likely writing the index at the same time.
+    /// The extensions that [aren't written][File::write_to()] are removed from this instance as well.
+    pub fn write(&mut self, options: Options) -> Result<Stats, Error> {
+        let mut lock =
+            git_lock::File::acquire_to_update_resource(&self.path, git_lock::acquire::Fail::Immediately, None)?;
+        let stats = self.write_to(&mut lock, options)?;
+        lock.commit().map_err(|err| err.error)?;
+
+        self.checksum = stats.checksum;
+        self.state.version = stats.version;
+        self.state.timestamp = filetime::FileTime::from_last_modification_time(&std::fs::metadata(&self.path)?);
+        self.state.untracked = None;
+        self.state.fs_monitor = None;
+        Ok(stats)
+    }
+}
+
+fn write_extension(out: &mut impl std::io::Write, signature: extension::Signature, data: &[u8]) -> std::io::Result<()> {
+    let size: u32 = data
+        .len()
+        .try_into()
+        .expect("BUG: extensions can't be larger than u32::MAX bytes");
+    out.write_all(&signature)?;
+    out.write_all(&size.to_be_bytes())?;
+    out.write_all(data)
+}
+
+fn write_entry(
+    out: &mut impl std::io::Write,
+    entry: &Entry,
+    path: &BStr,
+    prev_path: &BStr,
+    version: Version,
+) -> std::io::Result<()> {
+    let stat = &entry.stat;
+    for num in [
+        stat.ctime.secs,
+        stat.ctime.nsecs,
+        stat.mtime.secs,
+        stat.mtime.nsecs,
+        stat.dev,
+        stat.ino,
+        entry.mode.bits(),
+        stat.uid,
+        stat.gid,
+        stat.size,
+    ] {
+        out.write_all(&num.to_be_bytes())?;
+    }
+    out.write_all(entry.id.as_bytes())?;
+
+    let path_len = path.len().min(entry::at_rest::Flags::PATH_LEN.bits() as usize) as u16;
+    let mut flags =
+        entry::at_rest::Flags::from_memory(entry.flags) | entry::at_rest::Flags::from_bits_truncate(path_len);
+    let extended_flags = entry::at_rest::FlagsExtended::from_flags(entry.flags);
+    let is_extended = !extended_flags.is_empty();
+    if is_extended {
+        flags |= entry::at_rest::Flags::EXTENDED;
+    }
+    out.write_all(&flags.bits().to_be_bytes())?;
+    if is_extended {
+        out.write_all(&extended_flags.bits().to_be_bytes())?;
+    }
+
+    if version == Version::V4 {
+        let common_len = path.iter().zip(prev_path.iter()).take_while(|(a, b)| a == b).count();
+        let mut buf = [0u8; 10];
+        out.write_all(leb64_encode((prev_path.len() - common_len) as u64, &mut buf))?;
+        out.write_all(&path[common_len..])?;
+        out.write_all(b"\0")?;
+    } else {
+        let len = 4 * 10 + entry.id.as_bytes().len() + 2 + if is_extended { 2 } else { 0 } + path.len();
+        let padding_len = ((len + 8) & !7) - len;
+        out.write_all(path)?;
+        out.write_all(&[0u8; 8][..padding_len])?;
+    }
+    Ok(())
+}
@@ -22,6 +22,8 @@ pub mod decode;
 
 pub mod verify;
 
+pub mod edit;
+
 /// All known versions of a git index file.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
@@ -93,6 +95,24 @@ pub(crate) mod util {
         (num, data).into()
     }
 
+    /// Encode `n` like [`var_int()`] decodes it, returning the used portion of `buf`.
+    #[inline]
+    pub fn leb64_encode(mut n: u64, buf: &mut [u8; 10]) -> &[u8] {
+        let mut bytes_written = 1;
+        buf[buf.len() - 1] = n as u8 & 0b0111_1111;
+        for out in buf.iter_mut().rev().skip(1) {
+            n >>= 7;
+            if n == 0 {
+                break;
+            }
+            n -= 1;
+            *out = 0b1000_0000 | (n as u8 & 0b0111_1111);
+            bytes_written += 1;
+        }
+        debug_assert_eq!(n, 0, "BUG: buffer must be large enough to hold a 64 bit integer");
+        &buf[buf.len() - bytes_written..]
+    }
+
     #[inline]
     pub fn read_u32(data: &[u8]) -> Option<(u32, &[u8])> {
         split_at_pos(data, 4).map(|(num, data)| (u32::from_be_bytes(num.try_into().unwrap()), data))
@@ -210,3 +210,418 @@ mod init {
         }
     }
 }
+
+mod write {
+    use std::path::Path;
+
+    use filetime::FileTime;
+    use git_index::{decode, file::write::Options, Version};
+
+    fn fixtures() -> Vec<(&'static str, git_index::File)> {
+        let mut out = Vec::new();
+        for name in ["V2_empty", "v2", "v2_more_files", "v4_more_files_IEOT"] {
+            out.push((
+                name,
+                git_index::File::at(crate::fixture_path(name), decode::Options::default()).unwrap(),
+            ));
+        }
+        for name in [
+            "conflicting-file",
+            "extended-flags",
+            "very-long-path",
+            "REUC",
+            "FSMN",
+            "UNTR",
+        ] {
+            let path = git_testtools::fixture_path(Path::new("loose_index").join(name).with_extension("git-index"));
+            out.push((name, git_index::File::at(path, decode::Options::default()).unwrap()));
+        }
+        out
+    }
+
+    fn assert_entries_eq(actual: &git_index::State, expected: &git_index::State, name: &str) {
+        assert_eq!(actual.entries().len(), expected.entries().len(), "{}", name);
+        for (actual_entry, expected_entry) in actual.entries().iter().zip(expected.entries()) {
+            assert_eq!(actual_entry.path(actual), expected_entry.path(expected), "{}", name);
+            assert_eq!(actual_entry.id, expected_entry.id, "{}", name);
+            assert_eq!(actual_entry.flags, expected_entry.flags, "{}", name);
+            assert_eq!(actual_entry.mode, expected_entry.mode, "{}", name);
+            assert!(actual_entry.stat == expected_entry.stat, "{}", name);
+        }
+    }
+
+    #[test]
+    fn entries_are_written_like_git_does_in_the_same_version() {
+        for (name, file) in fixtures() {
+            let mut buf = Vec::new();
+            let stats = file
+                .write_to(
+                    &mut buf,
+                    Options {
+                        version: file.version(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(stats.version, file.version(), "{}", name);
+            assert_eq!(stats.num_entries as usize, file.entries().len(), "{}", name);
+
+            // git writes the IEOT extension before all others, which we don't write at all.
+            if name != "v4_more_files_IEOT" {
+                let original = std::fs::read(&file.path).unwrap();
+                let hash_len = file.checksum.as_bytes().len();
+                assert_eq!(
+                    &buf[..buf.len() - hash_len],
+                    &original[..buf.len() - hash_len],
+                    "{}: everything but the dropped extensions and the checksum is the same",
+                    name
+                );
+            }
+
+            let (state, checksum) =
+                git_index::State::from_bytes(&buf, FileTime::now(), decode::Options::default()).unwrap();
+            assert_eq!(checksum, stats.checksum, "{}", name);
+            assert_eq!(state.version(), file.version(), "{}", name);
+            assert_entries_eq(&state, &file, name);
+        }
+    }
+
+    #[test]
+    fn entries_can_be_written_in_any_version() {
+        for (name, file) in fixtures() {
+            for version in [Version::V2, Version::V3, Version::V4] {
+                let mut buf = Vec::new();
+                let stats = file.write_to(&mut buf, Options { version }).unwrap();
+                let (state, _) =
+                    git_index::State::from_bytes(&buf, FileTime::now(), decode::Options::default()).unwrap();
+                assert_eq!(state.version(), stats.version, "{}", name);
+                assert_entries_eq(&state, &file, name);
+            }
+        }
+    }
+
+    #[test]
+    fn tree_and_resolve_undo_extensions_are_kept() {
+        for (name, file) in fixtures() {
+            let mut buf = Vec::new();
+            file.write_to(&mut buf, Options { version: Version::V2 }).unwrap();
+            let (state, _) = git_index::State::from_bytes(&buf, FileTime::now(), decode::Options::default()).unwrap();
+
+            assert_eq!(
+                state
+                    .tree()
+                    .map(|tree| (tree.id, tree.num_entries, tree.children.len())),
+                file.tree().map(|tree| (tree.id, tree.num_entries, tree.children.len())),
+                "{}",
+                name
+            );
+            assert_eq!(
+                state.resolve_undo().map(|paths| paths.len()),
+                file.resolve_undo().map(|paths| paths.len()),
+                "{}",
+                name
+            );
+            assert!(state.untracked().is_none(), "{}: the untracked cache is dropped", name);
+            assert!(state.fs_monitor().is_none(), "{}: the fs-monitor data is dropped", name);
+        }
+    }
+
+    #[test]
+    fn split_indices_cannot_be_written() {
+        let file = git_index::File::at(crate::fixture_path("v2_split_index"), decode::Options::default()).unwrap();
+        assert!(matches!(
+            file.write_to(Vec::new(), Options { version: Version::V2 }),
+            Err(git_index::file::write::Error::SplitIndex)
+        ));
+    }
+
+    #[test]
+    fn v2_is_upgraded_to_v3_if_extended_flags_are_used() {
+        let (_, file) = fixtures()
+            .into_iter()
+            .find(|(name, _)| *name == "extended-flags")
+            .unwrap();
+        let stats = file.write_to(Vec::new(), Options { version: Version::V2 }).unwrap();
+        assert_eq!(stats.version, Version::V3);
+    }
+
+    #[test]
+    fn write_replaces_the_file_on_disk() {
+        let dir = git_testtools::scripted_fixture_repo_writable("make_index/v2_more_files.sh").unwrap();
+        let path = dir.path().join(".git").join("index");
+        let mut file = git_index::File::at(&path, decode::Options::default()).unwrap();
+
+        let stats = file.write(Options { version: Version::V4 }).unwrap();
+        assert_eq!(file.version(), Version::V4);
+        assert_eq!(file.checksum, stats.checksum);
+        assert!(!path.with_extension("lock").exists(), "the lock file is gone");
+
+        let written = git_index::File::at(&path, decode::Options::default()).unwrap();
+        written.verify_integrity().unwrap();
+        assert_eq!(written.version(), Version::V4);
+        assert_eq!(written.checksum, stats.checksum);
+        assert_entries_eq(&written, &file, "v2_more_files");
+    }
+
+    #[test]
+    fn new_files_can_be_filled_and_written() {
+        let dir = git_testtools::scripted_fixture_repo_writable("make_index/v2_more_files.sh").unwrap();
+        let path = dir.path().join(".git").join("index");
+        let expected = git_index::File::at(&path, decode::Options::default()).unwrap();
+
+        let mut file = git_index::File::new(&path, Version::V2, git_hash::Kind::Sha1);
+        assert!(file.entries().is_empty());
+        for entry in expected.entries() {
+            let new_entry = git_index::Entry::new(entry.stat, entry.id, entry.flags, entry.mode);
+            file.add_entry(new_entry, entry.path(&expected), false).unwrap();
+        }
+        let stats = file.write(Options { version: Version::V2 }).unwrap();
+        assert_eq!(file.checksum, stats.checksum);
+
+        let written = git_index::File::at(&path, decode::Options::default()).unwrap();
+        written.verify_integrity().unwrap();
+        assert_entries_eq(&written, &expected, "v2_more_files");
+    }
+}
+
+mod edit {
+    use std::path::Path;
+
+    use bstr::ByteSlice;
+    use git_index::{decode, edit::add_entry, entry, Entry};
+    use git_testtools::hex_to_id;
+
+    fn file(name: &str) -> git_index::File {
+        git_index::File::at(crate::fixture_path(name), decode::Options::default()).unwrap()
+    }
+
+    fn paths(file: &git_index::File) -> Vec<String> {
+        file.entries()
+            .iter()
+            .map(|entry| format!("{}:{}", entry.path(file), entry.stage()))
+            .collect()
+    }
+
+    fn entry_with_id(hex: &str, stage: u32) -> Entry {
+        let time = entry::Time { secs: 0, nsecs: 0 };
+        let stat = entry::Stat {
+            mtime: time,
+            ctime: time,
+            dev: 0,
+            ino: 0,
+            uid: 0,
+            gid: 0,
+            size: 0,
+        };
+        Entry::new(
+            stat,
+            hex_to_id(hex),
+            entry::Flags::from_bits(stage << 12).unwrap(),
+            entry::Mode::FILE,
+        )
+    }
+
+    const ID: &str = "d95f3ad14dee633a758d2e331151e950dd13e4ed";
+
+    #[test]
+    fn added_entries_are_inserted_in_order() {
+        let mut file = file("v2_more_files");
+        file.add_entry(entry_with_id(ID, 0), "ca".into(), false).unwrap();
+        file.add_entry(entry_with_id(ID, 0), "0".into(), false).unwrap();
+        file.add_entry(entry_with_id(ID, 0), "d/b.txt".into(), false).unwrap();
+        assert_eq!(
+            paths(&file),
+            [
+                "0:0",
+                "a:0",
+                "b:0",
+                "c:0",
+                "ca:0",
+                "d/a:0",
+                "d/b:0",
+                "d/b.txt:0",
+                "d/c:0"
+            ]
+        );
+        file.verify_entries().unwrap();
+        assert!(file.tree().is_none(), "the tree extension is outdated and removed");
+    }
+
+    #[test]
+    fn existing_entries_are_only_updated_if_they_may_be_replaced() {
+        let mut file = file("v2_more_files");
+        assert!(matches!(
+            file.add_entry(entry_with_id(ID, 0), "d/a".into(), false),
+            Err(add_entry::Error::AlreadyExists { path, stage: 0 }) if path == "d/a"
+        ));
+        file.add_entry(entry_with_id(ID, 0), "d/a".into(), true).unwrap();
+        assert_eq!(file.entries().len(), 6);
+        assert_eq!(file.entries()[3].path(&file), "d/a");
+        assert_eq!(file.entries()[3].id, hex_to_id(ID));
+    }
+
+    #[test]
+    fn directory_file_conflicts_are_resolved_only_if_entries_may_be_replaced() {
+        let mut file = file("v2_more_files");
+        for (path, existing) in [("a/b", "a"), ("d", "d/a")] {
+            assert!(matches!(
+                file.add_entry(entry_with_id(ID, 0), path.into(), false),
+                Err(add_entry::Error::DirectoryFileConflict { existing_path, .. }) if existing_path == existing
+            ));
+        }
+
+        file.add_entry(entry_with_id(ID, 0), "a/b".into(), true).unwrap();
+        file.add_entry(entry_with_id(ID, 0), "d".into(), true).unwrap();
+        assert_eq!(paths(&file), ["a/b:0", "b:0", "c:0", "d:0"]);
+    }
+
+    #[test]
+    fn invalid_paths_are_rejected() {
+        let mut file = file("v2");
+        for path in ["", "/a", "a/", "a//b", "./a", "a/../b", ".git/config", "a/.GIT"] {
+            assert!(
+                matches!(
+                    file.add_entry(entry_with_id(ID, 0), path.into(), true),
+                    Err(add_entry::Error::InvalidPath { .. })
+                ),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn adding_an_entry_at_stage_0_removes_other_stages() {
+        let path = git_testtools::fixture_path(Path::new("loose_index").join("conflicting-file.git-index"));
+        let mut file = git_index::File::at(path, decode::Options::default()).unwrap();
+        let conflicted_path = file.entries()[0].path(&file).to_owned();
+        assert_eq!(file.entries().len(), 3);
+
+        file.add_entry(entry_with_id(ID, 0), conflicted_path.as_bstr(), false)
+            .unwrap();
+        assert_eq!(paths(&file), [format!("{}:0", conflicted_path)]);
+    }
+
+    #[test]
+    fn removed_entries_are_returned_with_their_path() {
+        let mut file = file("v2_more_files");
+        let entry = file.remove_entry("d/b".into(), 0).expect("present");
+        assert_eq!(entry.path(&file), "d/b");
+        assert!(file.remove_entry("d/b".into(), 0).is_none());
+        assert!(file.remove_entry("d/a".into(), 1).is_none(), "the stage has to match");
+        assert_eq!(paths(&file), ["a:0", "b:0", "c:0", "d/a:0", "d/c:0"]);
+
+        let mut buf = Vec::new();
+        file.write_to(
+            &mut buf,
+            git_index::file::write::Options {
+                version: file.version(),
+            },
+        )
+        .unwrap();
+        let (state, _) =
+            git_index::State::from_bytes(&buf, filetime::FileTime::now(), decode::Options::default()).unwrap();
+        assert_eq!(state.entries().len(), 5);
+    }
+}
+
+mod entry_by_path {
+    use std::path::Path;
+
+    use git_index::decode;
+
+    #[test]
+    fn entries_are_found_by_path_and_stage() {
+        let file = git_index::File::at(crate::fixture_path("v4_more_files_IEOT"), decode::Options::default()).unwrap();
+        for entry in file.entries() {
+            let path = entry.path(&file);
+            let found = file.entry_by_path(path, 0).expect("present");
+            assert_eq!(found.path(&file), path);
+            assert!(std::ptr::eq(found, entry));
+            assert_eq!(file.entries_for_path(path).len(), 1);
+        }
+        assert!(file.entry_by_path("d/a".into(), 1).is_none());
+        assert!(file.entry_by_path("d".into(), 0).is_none());
+        assert!(file.entry_by_path("d/last/1".into(), 0).is_none());
+        assert!(file.entries_for_path("d".into()).is_empty());
+    }
+
+    #[test]
+    fn entries_can_be_modified_by_path_and_stage() {
+        let mut file =
+            git_index::File::at(crate::fixture_path("v4_more_files_IEOT"), decode::Options::default()).unwrap();
+        let path = file.entries()[1].path(&file).to_owned();
+        file.entry_mut_by_path(&path, 0)
+            .expect("present")
+            .flags
+            .insert(git_index::entry::Flags::SKIP_WORKTREE);
+        assert!(file.entries()[1].flags.contains(git_index::entry::Flags::SKIP_WORKTREE));
+        assert!(file.entry_mut_by_path(&path, 2).is_none());
+    }
+
+    #[test]
+    fn all_stages_of_conflicted_paths_are_returned() {
+        let path = git_testtools::fixture_path(Path::new("loose_index").join("conflicting-file.git-index"));
+        let file = git_index::File::at(path, decode::Options::default()).unwrap();
+        let entries = file.entries_for_path("file".into());
+        assert_eq!(entries.iter().map(|entry| entry.stage()).collect::<Vec<_>>(), [1, 2, 3]);
+        assert!(file.entry_by_path("file".into(), 0).is_none());
+        assert_eq!(file.entry_by_path("file".into(), 2).map(|entry| entry.stage()), Some(2));
+    }
+}
+
+mod conflicts {
+    use std::path::Path;
+
+    use git_index::decode;
+
+    #[test]
+    fn indices_without_conflicts_have_none() {
+        let file = git_index::File::at(crate::fixture_path("v2_more_files"), decode::Options::default()).unwrap();
+        assert!(!file.has_conflicts());
+        assert_eq!(file.conflicts().count(), 0);
+    }
+
+    #[test]
+    fn all_stages_of_a_conflicted_path_are_grouped() {
+        let path = git_testtools::fixture_path(Path::new("loose_index").join("conflicting-file.git-index"));
+        let file = git_index::File::at(path, decode::Options::default()).unwrap();
+        assert!(file.has_conflicts());
+
+        let conflicts: Vec<_> = file.conflicts().collect();
+        assert_eq!(conflicts.len(), 1);
+        let conflict = conflicts[0];
+        assert_eq!(conflict.path, "file");
+        for (entry, stage) in [(conflict.ancestor, 1), (conflict.ours, 2), (conflict.theirs, 3)] {
+            let entry = entry.expect("all stages are present");
+            assert_eq!(entry.stage(), stage);
+            assert_eq!(entry.path(&file), "file");
+        }
+    }
+
+    #[test]
+    fn missing_stages_are_none() {
+        let path = git_testtools::fixture_path(Path::new("loose_index").join("conflicting-file.git-index"));
+        let mut file = git_index::File::at(path, decode::Options::default()).unwrap();
+        let ancestor = file.remove_entry("file".into(), 1).expect("present");
+        file.add_entry(ancestor, "a".into(), false).unwrap();
+
+        let conflicts: Vec<_> = file
+            .conflicts()
+            .map(|c| {
+                (
+                    c.path.to_string(),
+                    c.ancestor.is_some(),
+                    c.ours.is_some(),
+                    c.theirs.is_some(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            conflicts,
+            [
+                ("a".to_string(), true, false, false),
+                ("file".into(), false, true, true)
+            ]
+        );
+    }
+}
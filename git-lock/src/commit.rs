@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::{File, Marker};
 
@@ -63,10 +63,19 @@ impl Marker {
 impl File {
     /// Commit the changes written to this lock file and overwrite the original file atomically, returning the resource path
     /// and an open file handle on success.
-    pub fn commit(mut self) -> Result<(PathBuf, Option<std::fs::File>), Error<Self>> {
+    pub fn commit(self) -> Result<(PathBuf, Option<std::fs::File>), Error<Self>> {
         let resource_path = self.resource_path();
-        match self.inner.persist(&resource_path) {
-            Ok(possibly_file) => Ok((resource_path, possibly_file)),
+        self.persist_to_and_rename(&resource_path)
+            .map(|possibly_file| (resource_path, possibly_file))
+    }
+
+    /// Like [`commit()`][File::commit()], but atomically move the lock file to `final_path` instead of the locked resource,
+    /// which stays unchanged, and return the open file handle on success.
+    ///
+    /// This is useful to write the new state of a locked resource to a different location, like a backup of it.
+    pub fn persist_to_and_rename(mut self, final_path: impl AsRef<Path>) -> Result<Option<std::fs::File>, Error<Self>> {
+        match self.inner.persist(final_path) {
+            Ok(possibly_file) => Ok(possibly_file),
             Err(err) => Err(Error {
                 error: err.error,
                 instance: {
@@ -35,6 +35,25 @@ impl File {
     pub fn resource_path(&self) -> PathBuf {
         strip_lock_suffix(&self.lock_path)
     }
+
+    /// Copy everything written to this lock file so far to `path`, atomically replacing the file there, while keeping the
+    /// lock and its content for further writes or a later [commit][File::commit()].
+    ///
+    /// This is useful to create backup files next to the locked resource.
+    pub fn persist_copy(&mut self, path: &Path) -> std::io::Result<()> {
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut copy = git_tempfile::new(
+            directory,
+            git_tempfile::ContainingDirectory::Exists,
+            git_tempfile::AutoRemove::Tempfile,
+        )?;
+        std::io::copy(&mut std::fs::File::open(&self.lock_path)?, &mut copy)?;
+        copy.persist(path).map_err(|err| err.error)?;
+        Ok(())
+    }
 }
 
 mod io_impls {
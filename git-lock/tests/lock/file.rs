@@ -86,6 +86,50 @@ mod commit {
     }
 }
 
+mod persist {
+    use std::io::Write;
+
+    use git_lock::acquire::Fail;
+
+    #[test]
+    fn to_a_different_path_leaves_the_resource_unchanged() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("config");
+        std::fs::write(&resource, b"old state")?;
+        let mut file = git_lock::File::acquire_to_update_resource(&resource, Fail::Immediately, None)?;
+        let lock_path = file.lock_path().to_owned();
+        file.write_all(b"new state")?;
+
+        let backup = dir.path().join("config.bak");
+        file.persist_to_and_rename(&backup)?;
+        assert_eq!(std::fs::read(backup)?, b"new state");
+        assert_eq!(std::fs::read(resource)?, b"old state");
+        assert!(!lock_path.is_file(), "the lock file was moved");
+        Ok(())
+    }
+
+    #[test]
+    fn copies_keep_the_lock_for_committing() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("config");
+        std::fs::write(&resource, b"old state")?;
+        let mut file = git_lock::File::acquire_to_update_resource(&resource, Fail::Immediately, None)?;
+        file.write_all(b"new state")?;
+
+        let backup = dir.path().join("config.bak");
+        std::fs::write(&backup, b"previous backup")?;
+        file.persist_copy(&backup)?;
+        assert_eq!(std::fs::read(&backup)?, b"new state", "existing files are replaced");
+        assert!(file.lock_path().is_file(), "the lock is still held");
+
+        file.write_all(b" and more")?;
+        file.commit()?;
+        assert_eq!(std::fs::read(resource)?, b"new state and more");
+        assert_eq!(std::fs::read(backup)?, b"new state", "the copy is independent");
+        Ok(())
+    }
+}
+
 mod acquire {
     use std::io::{ErrorKind, Write};
 
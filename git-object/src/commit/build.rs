@@ -0,0 +1,81 @@
+use bstr::BString;
+use git_hash::ObjectId;
+use quick_error::quick_error;
+
+use crate::{commit::CommitBuilder, encode, Commit, WriteTo};
+
+quick_error! {
+    /// The error returned by [`CommitBuilder::build()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        MissingTree {
+            display("A commit needs a tree, but the null id was given")
+        }
+        EmptyMessage {
+            display("A commit needs a non-empty message")
+        }
+        HashKindMismatch { id: ObjectId, expected: git_hash::Kind } {
+            display("The id {} is not of the kind {} used to compute the commit id", id, expected)
+        }
+        Encode(err: std::io::Error) {
+            display("The commit could not be serialized")
+            source(err)
+            from()
+        }
+    }
+}
+
+impl From<CommitBuilder> for Commit {
+    fn from(builder: CommitBuilder) -> Self {
+        let CommitBuilder {
+            tree,
+            parents,
+            author,
+            committer,
+            encoding,
+            message,
+            extra_headers,
+        } = builder;
+        Commit {
+            tree,
+            parents: parents.into(),
+            author,
+            committer,
+            encoding,
+            message,
+            extra_headers,
+        }
+    }
+}
+
+impl CommitBuilder {
+    /// Serialize the commit and compute its id with the `hash` kind, returning both the serialized commit without the loose
+    /// object header and its id.
+    ///
+    /// Fail if there is no tree or message, or if any of the ids doesn't match the `hash` kind.
+    pub fn build(self, hash: git_hash::Kind) -> Result<(BString, ObjectId), Error> {
+        if self.tree.is_null() {
+            return Err(Error::MissingTree);
+        }
+        if self.message.is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+        if let Some(id) = std::iter::once(&self.tree)
+            .chain(&self.parents)
+            .find(|id| id.kind() != hash)
+        {
+            return Err(Error::HashKindMismatch {
+                id: *id,
+                expected: hash,
+            });
+        }
+
+        let commit = Commit::from(self);
+        let mut data = Vec::with_capacity(commit.size());
+        commit.write_to(&mut data)?;
+
+        let id = encode::object_id(commit.kind(), &data, hash);
+        Ok((data.into(), id))
+    }
+}
@@ -21,6 +21,29 @@ pub struct MessageRef<'a> {
     pub body: Option<&'a BStr>,
 }
 
+///
+pub mod build;
+
+/// A way to assemble a new commit which can be [built][CommitBuilder::build()] into its serialized form along with its id,
+/// without writing the git commit format by hand.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct CommitBuilder {
+    /// The id of the tree representing the working tree state.
+    pub tree: git_hash::ObjectId,
+    /// The ids of the parent commits, empty for the first commit in a repository.
+    pub parents: Vec<git_hash::ObjectId>,
+    /// Who wrote the commit.
+    pub author: git_actor::Signature,
+    /// Who committed the commit.
+    pub committer: git_actor::Signature,
+    /// The name of the message encoding, or `None` for UTF-8.
+    pub encoding: Option<bstr::BString>,
+    /// The commit message, which must not be empty.
+    pub message: bstr::BString,
+    /// Extra header fields like `gpgsig`, written in order after all other headers.
+    pub extra_headers: Vec<(bstr::BString, bstr::BString)>,
+}
+
 ///
 pub mod ref_iter;
 
@@ -48,6 +71,21 @@ impl<'a> CommitRef<'a> {
         crate::commit::ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (*k, v.as_ref())))
     }
 
+    /// Return the cryptographic signature of this commit verbatim, as found in the `gpgsig` header or, for commits in
+    /// repositories using SHA-256, in the `gpgsig-sha256` header.
+    pub fn gpg_signature(&self) -> Option<&BStr> {
+        self.extra_headers()
+            .find("gpgsig")
+            .or_else(|| self.extra_headers().find("gpgsig-sha256"))
+    }
+
+    /// Return the first tag embedded in a `mergetag` header, as written when merging an annotated tag.
+    ///
+    /// Use [`extra_headers().mergetags()`][ExtraHeaders::mergetags()] to obtain all of them.
+    pub fn mergetag(&self) -> Option<Result<TagRef<'_>, crate::decode::Error>> {
+        self.extra_headers().mergetags().next()
+    }
+
     /// Return the author, with whitespace trimmed.
     ///
     /// This is different from the `author` field which may contain whitespace.
@@ -85,6 +123,17 @@ pub struct ExtraHeaders<I> {
     inner: I,
 }
 
+impl<'a, I> Iterator for ExtraHeaders<I>
+where
+    I: Iterator<Item = (&'a BStr, &'a BStr)>,
+{
+    type Item = (&'a BStr, &'a BStr);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
 /// Instantiation and convenience.
 impl<'a, I> ExtraHeaders<I>
 where
@@ -31,6 +31,14 @@ pub fn loose_header(kind: crate::Kind, size: usize) -> smallvec::SmallVec<[u8; 2
     v
 }
 
+/// Compute the id of an object of `kind` serialized as `data`, using the `hash` kind.
+pub(crate) fn object_id(kind: crate::Kind, data: &[u8], hash: git_hash::Kind) -> git_hash::ObjectId {
+    let mut hasher = git_features::hash::hasher(hash);
+    hasher.update(&loose_header(kind, data.len()));
+    hasher.update(data);
+    git_hash::ObjectId::from(hasher.digest())
+}
+
 impl From<Error> for io::Error {
     fn from(other: Error) -> io::Error {
         io::Error::new(io::ErrorKind::Other, other)
@@ -227,7 +227,8 @@ pub struct TreeRefIter<'a> {
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tree {
-    /// The directories and files contained in this tree. They must be and remain sorted by [`filename`][tree::Entry::filename].
+    /// The directories and files contained in this tree. They must be and remain sorted by [`filename`][tree::Entry::filename],
+    /// see [`sort_and_deduplicate()`][Tree::sort_and_deduplicate()].
     pub entries: Vec<tree::Entry>,
 }
 
@@ -236,6 +237,55 @@ impl Tree {
     pub fn empty() -> Self {
         Tree { entries: Vec::new() }
     }
+
+    /// Create a tree from `entries` which must already be sorted in the order git requires for serialization, see
+    /// [`tree::Entry::cmp_by_name()`], and must not contain duplicate filenames.
+    ///
+    /// Use [`from_entries_sorted()`][Tree::from_entries_sorted()] if `entries` aren't known to be sorted.
+    pub fn from_sorted_entries(entries: Vec<tree::Entry>) -> Result<Self, tree::Error> {
+        // duplicates aren't necessarily adjacent as trees sort as if they had a trailing slash,
+        // like in `a` (blob), `a.b`, `a` (tree).
+        let mut names = std::collections::HashSet::with_capacity(entries.len());
+        for (index, next) in entries.iter().enumerate() {
+            if !names.insert(&next.filename) {
+                return Err(tree::Error::DuplicateFilename {
+                    name: next.filename.clone(),
+                });
+            }
+            let previous = match index.checked_sub(1) {
+                Some(previous) => &entries[previous],
+                None => continue,
+            };
+            if tree::cmp_by_name(&previous.filename, previous.mode, &next.filename, next.mode)
+                != std::cmp::Ordering::Less
+            {
+                return Err(tree::Error::Unsorted {
+                    previous: previous.filename.clone(),
+                    next: next.filename.clone(),
+                });
+            }
+        }
+        Ok(Tree { entries })
+    }
+
+    /// Create a tree from `entries` in any order, sorting them and removing duplicates as done by
+    /// [`sort_and_deduplicate()`][Tree::sort_and_deduplicate()].
+    pub fn from_entries_sorted(entries: Vec<tree::Entry>) -> Self {
+        let mut tree = Tree { entries };
+        tree.sort_and_deduplicate();
+        tree
+    }
+
+    /// Sort all entries in the order git requires for serialization, see [`tree::Entry::cmp_by_name()`], after removing
+    /// entries with duplicate filenames while keeping the last one of them, similar to what `git` does.
+    pub fn sort_and_deduplicate(&mut self) {
+        self.entries.reverse();
+        // the sort is stable, so the last of the duplicates comes first.
+        self.entries.sort_by(|a, b| a.filename.cmp(&b.filename));
+        self.entries.dedup_by(|a, b| a.filename == b.filename);
+        self.entries
+            .sort_by(|a, b| tree::cmp_by_name(&a.filename, a.mode, &b.filename, b.mode));
+    }
 }
 
 /// A borrowed object using a slice as backing buffer, or in other words a bytes buffer that knows the kind of object it represents.
@@ -0,0 +1,76 @@
+use bstr::{BString, ByteSlice};
+use git_hash::ObjectId;
+use quick_error::quick_error;
+
+use crate::{encode, tag, tag::TagBuilder, Kind, Tag, WriteTo};
+
+quick_error! {
+    /// The error returned by [`TagBuilder::build()`].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        MissingObject {
+            display("A tag needs an object to point to, but the null id was given")
+        }
+        HashKindMismatch { id: ObjectId, expected: git_hash::Kind } {
+            display("The id {} is not of the kind {} used to compute the tag id", id, expected)
+        }
+        InvalidName(err: tag::write::Error) {
+            display("The tag name is invalid")
+            source(err)
+            from()
+        }
+        Encode(err: std::io::Error) {
+            display("The tag could not be serialized")
+            source(err)
+            from()
+        }
+    }
+}
+
+impl From<TagBuilder> for Tag {
+    fn from(builder: TagBuilder) -> Self {
+        let TagBuilder {
+            object,
+            object_kind,
+            name,
+            tagger,
+            message,
+        } = builder;
+        Tag {
+            target: object,
+            target_kind: object_kind,
+            name,
+            tagger,
+            message,
+            pgp_signature: None,
+        }
+    }
+}
+
+impl TagBuilder {
+    /// Serialize the tag and compute its id with the `hash` kind, returning both the serialized tag without the loose
+    /// object header and its id.
+    ///
+    /// Fields are written in the order `object`, `type`, `tag` and `tagger`, followed by an empty line and the message unless
+    /// it is empty.
+    /// Fail if the object id is null or doesn't match the `hash` kind, or if the name isn't a valid tag name.
+    pub fn build(self, hash: git_hash::Kind) -> Result<(BString, ObjectId), Error> {
+        if self.object.is_null() {
+            return Err(Error::MissingObject);
+        }
+        if self.object.kind() != hash {
+            return Err(Error::HashKindMismatch {
+                id: self.object,
+                expected: hash,
+            });
+        }
+        tag::write::validated_name(self.name.as_bstr())?;
+
+        let tag = Tag::from(self);
+        let mut data = Vec::with_capacity(tag.size());
+        tag.write_to(&mut data)?;
+        let id = encode::object_id(Kind::Tag, &data, hash);
+        Ok((data.into(), id))
+    }
+}
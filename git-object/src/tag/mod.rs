@@ -5,6 +5,24 @@ mod decode;
 ///
 pub mod write;
 
+///
+pub mod build;
+
+/// A way to assemble a new annotated tag which can be [built][TagBuilder::build()] into its serialized form along with its id.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct TagBuilder {
+    /// The id of the object the tag points to.
+    pub object: git_hash::ObjectId,
+    /// The kind of object the tag points to.
+    pub object_kind: crate::Kind,
+    /// The name of the tag, e.g. "v1.0", which must be a valid tag name.
+    pub name: bstr::BString,
+    /// Who created the tag, if known.
+    pub tagger: Option<git_actor::Signature>,
+    /// The message describing the tag, which may be empty.
+    pub message: bstr::BString,
+}
+
 ///
 pub mod ref_iter;
 
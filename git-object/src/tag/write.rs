@@ -111,7 +111,7 @@ impl<'a> crate::WriteTo for TagRef<'a> {
     }
 }
 
-fn validated_name(name: &BStr) -> Result<&BStr, Error> {
+pub(crate) fn validated_name(name: &BStr) -> Result<&BStr, Error> {
     git_validate::tag::name(name)?;
     if name[0] == b'-' {
         return Err(Error::StartsWithDash);
@@ -9,6 +9,20 @@ mod ref_iter;
 ///
 pub mod write;
 
+quick_error::quick_error! {
+    /// The error returned by [`Tree::from_sorted_entries()`][crate::Tree::from_sorted_entries()].
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Unsorted { previous: BString, next: BString } {
+            display("Tree entry {:?} must not come after {:?} as entries must be sorted by name", next, previous)
+        }
+        DuplicateFilename { name: BString } {
+            display("Tree entry {:?} occurs more than once", name)
+        }
+    }
+}
+
 /// The mode of items storable in a tree, similar to the file mode on a unix file system.
 ///
 /// Used in [mutable::Entry][crate::tree::Entry] and [EntryRef].
@@ -97,6 +111,23 @@ impl Ord for Entry {
     }
 }
 
+impl Entry {
+    /// Compare `a` and `b` in the order git requires for entries in a tree, which is by their filename as if trees had
+    /// a trailing `/`. This places the tree `a` after the file `a.b`, as `.` sorts before `/`.
+    pub fn cmp_by_name(a: &EntryRef<'_>, b: &EntryRef<'_>) -> Ordering {
+        cmp_by_name(a.filename, a.mode, b.filename, b.mode)
+    }
+}
+
+/// Compare filenames as if names of trees had a trailing `/`.
+pub(crate) fn cmp_by_name(a: &[u8], a_mode: EntryMode, b: &[u8], b_mode: EntryMode) -> Ordering {
+    let trailing_slash = |mode: EntryMode| mode.is_tree().then(|| b'/');
+    a.iter()
+        .copied()
+        .chain(trailing_slash(a_mode))
+        .cmp(b.iter().copied().chain(trailing_slash(b_mode)))
+}
+
 /// Serialization
 impl EntryMode {
     /// Return the representation as used in the git internal format.
@@ -5,7 +5,7 @@ use quick_error::quick_error;
 
 use crate::{
     encode::SPACE,
-    tree::{Entry, EntryRef},
+    tree::{self, Entry, EntryRef},
     Kind, Tree, TreeRef,
 };
 
@@ -33,11 +33,11 @@ impl crate::WriteTo for Tree {
         debug_assert_eq!(
             &{
                 let mut entries_sorted = self.entries.clone();
-                entries_sorted.sort();
+                entries_sorted.sort_by(|a, b| tree::cmp_by_name(&a.filename, a.mode, &b.filename, b.mode));
                 entries_sorted
             },
             &self.entries,
-            "entries for serialization must be sorted by filename"
+            "entries for serialization must be sorted by filename, with trees sorting as if they had a trailing slash"
         );
         for Entry { mode, filename, oid } in &self.entries {
             out.write_all(mode.as_bytes())?;
@@ -73,11 +73,11 @@ impl<'a> crate::WriteTo for TreeRef<'a> {
         debug_assert_eq!(
             &{
                 let mut entries_sorted = self.entries.clone();
-                entries_sorted.sort();
+                entries_sorted.sort_by(Entry::cmp_by_name);
                 entries_sorted
             },
             &self.entries,
-            "entries for serialization must be sorted by filename"
+            "entries for serialization must be sorted by filename, with trees sorting as if they had a trailing slash"
         );
         for EntryRef { mode, filename, oid } in &self.entries {
             out.write_all(mode.as_bytes())?;
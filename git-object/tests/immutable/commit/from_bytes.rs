@@ -264,3 +264,43 @@ fn newline_right_after_signature_multiline_header() -> crate::Result {
     assert!(commit.message.starts_with(b"Rollup"));
     Ok(())
 }
+
+#[test]
+fn extra_headers_with_signature_and_mergetag() -> crate::Result {
+    let fixture = fixture_bytes("commit", "two-multiline-headers.txt");
+    let commit = CommitRef::from_bytes(&fixture)?;
+    assert_eq!(
+        commit.extra_headers().map(|(name, _)| name).collect::<Vec<_>>(),
+        ["mergetag", "gpgsig"]
+    );
+    assert!(
+        commit
+            .gpg_signature()
+            .expect("signed")
+            .starts_with(b"-----BEGIN PGP SIGNATURE-----\n\niHUEABYIAB0W"),
+        "the leading space of continuation lines is removed"
+    );
+    let tag = commit.mergetag().expect("present")?;
+    assert_eq!(tag.name, "v5.7-rc1");
+    Ok(())
+}
+
+#[test]
+fn gpg_signature_of_sha256_commits() -> crate::Result {
+    let commit = b"tree 1b2dfb4ac5e42080b682fc676e9738c94ce6d54d
+author Sebastian Thiel <sebastian.thiel@icloud.com> 1592437401 +0800
+committer Sebastian Thiel <sebastian.thiel@icloud.com> 1592437401 +0800
+gpgsig-sha256 -----BEGIN PGP SIGNATURE-----
+ 
+ sig
+ -----END PGP SIGNATURE-----
+
+message";
+    let commit = CommitRef::from_bytes(commit)?;
+    assert_eq!(
+        commit.gpg_signature().expect("signed"),
+        "-----BEGIN PGP SIGNATURE-----\n\nsig\n-----END PGP SIGNATURE-----"
+    );
+    assert!(commit.mergetag().is_none());
+    Ok(())
+}
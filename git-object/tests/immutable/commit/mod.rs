@@ -146,6 +146,55 @@ mod method {
     }
 }
 
+mod build {
+    use git_object::{
+        bstr::ByteSlice,
+        commit::{build::Error, CommitBuilder},
+        Commit, CommitRef, Data, Kind,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::immutable::fixture_bytes;
+
+    fn builder(fixture: &[u8]) -> crate::Result<CommitBuilder> {
+        let commit: Commit = CommitRef::from_bytes(fixture)?.into();
+        Ok(CommitBuilder {
+            tree: commit.tree,
+            parents: commit.parents.to_vec(),
+            author: commit.author,
+            committer: commit.committer,
+            encoding: commit.encoding,
+            message: commit.message,
+            extra_headers: commit.extra_headers,
+        })
+    }
+
+    #[test]
+    fn serializes_like_git_and_computes_the_id() -> crate::Result {
+        for name in ["unsigned.txt", "signed-with-encoding.txt", "merge.txt"] {
+            let fixture = fixture_bytes("commit", name);
+            let (data, id) = builder(&fixture)?.build(git_hash::Kind::Sha1)?;
+            assert_eq!(data.as_bstr(), fixture.as_bstr(), "{}", name);
+            Data::new(Kind::Commit, &data).verify_checksum(id)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tree_and_message_are_required() -> crate::Result {
+        let fixture = fixture_bytes("commit", "unsigned.txt");
+
+        let mut commit = builder(&fixture)?;
+        commit.tree = git_hash::ObjectId::null(git_hash::Kind::Sha1);
+        assert!(matches!(commit.build(git_hash::Kind::Sha1), Err(Error::MissingTree)));
+
+        let mut commit = builder(&fixture)?;
+        commit.message.clear();
+        assert!(matches!(commit.build(git_hash::Kind::Sha1), Err(Error::EmptyMessage)));
+        Ok(())
+    }
+}
+
 mod from_bytes;
 mod iter;
 mod message;
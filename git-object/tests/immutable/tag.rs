@@ -113,6 +113,57 @@ KLMHist5yj0sw1E4hDTyQa0=
     }
 }
 
+mod build {
+    use git_object::{
+        bstr::ByteSlice,
+        tag::{build::Error, TagBuilder},
+        Data, Kind, TagRef,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{hex_to_id, immutable::signature};
+
+    fn builder() -> TagBuilder {
+        TagBuilder {
+            object: hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec"),
+            object_kind: Kind::Commit,
+            name: "v1.0.0".into(),
+            tagger: Some(signature(1592381636).into()),
+            message: "release\n".into(),
+        }
+    }
+
+    #[test]
+    fn fields_are_written_in_order_and_parse_back() -> crate::Result {
+        let (data, id) = builder().build(git_hash::Kind::Sha1)?;
+        assert_eq!(
+            data.as_bstr(),
+            b"object ffa700b4aca13b80cb6b98a078e7c96804f8e0ec\ntype commit\ntag v1.0.0\ntagger Sebastian Thiel <sebastian.thiel@icloud.com> 1592381636 +0800\n\nrelease\n"
+                .as_bstr()
+        );
+        Data::new(Kind::Tag, &data).verify_checksum(id)?;
+
+        let tag = TagRef::from_bytes(&data)?;
+        assert_eq!(tag.target(), builder().object);
+        assert_eq!(tag.target_kind, Kind::Commit);
+        assert_eq!(tag.name, "v1.0.0");
+        assert_eq!(tag.tagger, Some(signature(1592381636)));
+        assert_eq!(tag.message, "release\n");
+        Ok(())
+    }
+
+    #[test]
+    fn object_and_name_are_validated() {
+        let mut tag = builder();
+        tag.object = git_hash::ObjectId::null(git_hash::Kind::Sha1);
+        assert!(matches!(tag.build(git_hash::Kind::Sha1), Err(Error::MissingObject)));
+
+        let mut tag = builder();
+        tag.name = "-v1".into();
+        assert!(matches!(tag.build(git_hash::Kind::Sha1), Err(Error::InvalidName(_))));
+    }
+}
+
 mod from_bytes {
     use git_object::{bstr::ByteSlice, Kind, TagRef};
 
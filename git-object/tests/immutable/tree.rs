@@ -142,3 +142,145 @@ mod entry_mode {
         );
     }
 }
+
+mod sort {
+    use std::cmp::Ordering;
+
+    use git_object::{bstr::ByteSlice, tree, tree::EntryMode, Tree};
+
+    fn entry(mode: EntryMode, filename: &str, hex: &str) -> tree::Entry {
+        tree::Entry {
+            mode,
+            filename: filename.into(),
+            oid: crate::hex_to_id(hex),
+        }
+    }
+
+    #[test]
+    fn trees_sort_as_if_they_had_a_trailing_slash() {
+        let oid = crate::hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+        let entry_ref = |mode, filename: &'static str| tree::EntryRef {
+            mode,
+            filename: filename.as_bytes().as_bstr(),
+            oid: &oid,
+        };
+        for (a, b, expected) in [
+            (
+                entry_ref(EntryMode::Tree, "a"),
+                entry_ref(EntryMode::Blob, "a.b"),
+                Ordering::Greater,
+            ),
+            (
+                entry_ref(EntryMode::Blob, "a"),
+                entry_ref(EntryMode::Blob, "a.b"),
+                Ordering::Less,
+            ),
+            (
+                entry_ref(EntryMode::Tree, "a"),
+                entry_ref(EntryMode::Blob, "a0"),
+                Ordering::Less,
+            ),
+            (
+                entry_ref(EntryMode::Blob, "a"),
+                entry_ref(EntryMode::Tree, "a"),
+                Ordering::Less,
+            ),
+            (
+                entry_ref(EntryMode::Tree, "a"),
+                entry_ref(EntryMode::Tree, "a"),
+                Ordering::Equal,
+            ),
+        ] {
+            assert_eq!(tree::Entry::cmp_by_name(&a, &b), expected, "{:?} vs {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn sort_and_deduplicate_keeps_the_last_duplicate() {
+        let mut tree = Tree {
+            entries: vec![
+                entry(EntryMode::Blob, "b", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+                entry(EntryMode::Tree, "a", "4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+                entry(EntryMode::Blob, "b", "d95f3ad14dee633a758d2e331151e950dd13e4ed"),
+                entry(EntryMode::Blob, "a.b", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+            ],
+        };
+        tree.sort_and_deduplicate();
+        assert_eq!(
+            tree.entries,
+            vec![
+                entry(EntryMode::Blob, "a.b", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+                entry(EntryMode::Tree, "a", "4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+                entry(EntryMode::Blob, "b", "d95f3ad14dee633a758d2e331151e950dd13e4ed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_sorted_entries_accepts_entries_in_git_order() {
+        let entries = vec![
+            entry(EntryMode::Blob, "a.b", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+            entry(EntryMode::Tree, "a", "4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+            entry(EntryMode::Blob, "b", "d95f3ad14dee633a758d2e331151e950dd13e4ed"),
+        ];
+        let tree = Tree::from_sorted_entries(entries.clone()).expect("sorted");
+        assert_eq!(tree.entries, entries);
+        assert_eq!(
+            Tree::from_sorted_entries(Vec::new()).expect("empty is sorted"),
+            Tree::empty()
+        );
+    }
+
+    #[test]
+    fn from_sorted_entries_rejects_unsorted_and_duplicate_entries() {
+        let err = Tree::from_sorted_entries(vec![
+            entry(EntryMode::Tree, "a", "4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+            entry(EntryMode::Blob, "a.b", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        ])
+        .unwrap_err();
+        assert!(
+            matches!(err, tree::Error::Unsorted { ref previous, ref next } if previous == "a" && next == "a.b"),
+            "{:?}",
+            err
+        );
+
+        let err = Tree::from_sorted_entries(vec![
+            entry(EntryMode::Blob, "b", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+            entry(EntryMode::Blob, "b", "d95f3ad14dee633a758d2e331151e950dd13e4ed"),
+        ])
+        .unwrap_err();
+        assert!(
+            matches!(err, tree::Error::DuplicateFilename { ref name } if name == "b"),
+            "{:?}",
+            err
+        );
+
+        let err = Tree::from_sorted_entries(vec![
+            entry(EntryMode::Blob, "a", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+            entry(EntryMode::Blob, "a.b", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+            entry(EntryMode::Tree, "a", "4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+        ])
+        .unwrap_err();
+        assert!(
+            matches!(err, tree::Error::DuplicateFilename { ref name } if name == "a"),
+            "duplicates are detected even if they are sorted apart: {:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn from_entries_sorted_sorts_and_deduplicates() {
+        let tree = Tree::from_entries_sorted(vec![
+            entry(EntryMode::Blob, "b", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+            entry(EntryMode::Tree, "a", "4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+            entry(EntryMode::Blob, "b", "d95f3ad14dee633a758d2e331151e950dd13e4ed"),
+            entry(EntryMode::Blob, "a.b", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        ]);
+        assert_eq!(
+            Tree::from_sorted_entries(tree.entries.clone()).expect("sorted"),
+            tree,
+            "the result is valid"
+        );
+        assert_eq!(tree.entries.len(), 3);
+    }
+}
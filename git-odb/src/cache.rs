@@ -38,6 +38,46 @@ impl Cache<crate::store::Handle<Arc<crate::Store>>> {
     }
 }
 
+impl<S> Cache<crate::store::Handle<S>>
+where
+    S: Deref<Target = crate::Store> + Clone,
+{
+    /// Like [`try_find()`][crate::Find::try_find()], but report the amount of deltas applied while decoding a packed object
+    /// to `progress`, see [`Handle::try_find_cached_with_progress()`][crate::store::Handle::try_find_cached_with_progress()].
+    ///
+    /// Objects obtained from the object cache don't report any progress.
+    pub fn try_find_with_progress<'a>(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+        buffer: &'a mut Vec<u8>,
+        progress: &mut impl git_features::progress::Progress,
+    ) -> Result<Option<git_object::Data<'a>>, crate::store::find::Error> {
+        use git_pack::cache::Object;
+
+        let id = id.as_ref();
+        if let Some(mut obj_cache) = self.object_cache.as_ref().map(|rc| rc.borrow_mut()) {
+            if let Some(kind) = obj_cache.get(&id.to_owned(), buffer) {
+                return Ok(Some(git_object::Data::new(kind, buffer)));
+            }
+        }
+        let possibly_obj = match self.pack_cache.as_ref().map(|rc| rc.borrow_mut()) {
+            Some(mut pack_cache) => {
+                self.inner
+                    .try_find_cached_with_progress(id, buffer, pack_cache.deref_mut(), progress)?
+            }
+            None => self
+                .inner
+                .try_find_cached_with_progress(id, buffer, &mut git_pack::cache::Never, progress)?,
+        };
+        if let (Some(mut obj_cache), Some((obj, _location))) =
+            (self.object_cache.as_ref().map(|rc| rc.borrow_mut()), &possibly_obj)
+        {
+            obj_cache.put(id.to_owned(), obj.kind, obj.data);
+        }
+        Ok(possibly_obj.map(|(obj, _location)| obj))
+    }
+}
+
 impl<S> Cache<S> {
     /// Dissolve this instance, discard all caches, and return the inner implementation.
     pub fn into_inner(self) -> S {
@@ -81,6 +121,19 @@ impl<S> Cache<S> {
         self.object_cache = None;
         self.new_object_cache = None;
     }
+    /// Return true if an object cache is set on this instance.
+    pub fn has_object_cache(&self) -> bool {
+        self.object_cache.is_some()
+    }
+    /// Return true if the object with `id` is currently held in the object cache, which is never the case without one.
+    pub fn object_cache_contains(&self, id: impl AsRef<git_hash::oid>) -> bool {
+        self.object_cache.as_ref().map_or(false, |cache| {
+            cache
+                .borrow_mut()
+                .get(&id.as_ref().to_owned(), &mut Vec::new())
+                .is_some()
+        })
+    }
 }
 
 impl<S> From<S> for Cache<S>
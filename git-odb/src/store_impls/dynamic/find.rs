@@ -1,5 +1,6 @@
 use std::{convert::TryInto, ops::Deref};
 
+use git_features::progress::{self, Progress};
 use git_hash::{oid, ObjectId};
 use git_object::Data;
 use git_pack::{cache::DecodeEntry, data::entry::Location};
@@ -44,6 +45,8 @@ mod error {
             /// The original object to lookup
             id: git_hash::ObjectId,
         },
+        #[error(transparent)]
+        Promise(#[from] PromiseError),
         #[error("An error occurred when looking up a ref delta base object {} to decode {}", .base_id, .id)]
         DeltaBaseLookup {
             #[source]
@@ -55,6 +58,19 @@ mod error {
         },
     }
 
+    /// Returned if an object promised by a remote couldn't be obtained, see
+    /// [`Handle::fetch_promised_object`][crate::store::Handle::fetch_promised_object].
+    #[derive(thiserror::Error, Debug)]
+    #[allow(missing_docs)]
+    pub enum PromiseError {
+        #[error("Could not fetch the promised object {id}")]
+        FetchFailed {
+            id: git_hash::ObjectId,
+            #[source]
+            source: Box<dyn std::error::Error + Send + Sync>,
+        },
+    }
+
     #[derive(Copy, Clone)]
     pub(crate) struct DeltaBaseRecursion<'a> {
         pub depth: usize,
@@ -84,7 +100,7 @@ mod error {
         }
     }
 }
-pub use error::Error;
+pub use error::{Error, PromiseError};
 
 use crate::{
     find::{PotentialPrefix, PrefixLookupResult},
@@ -96,6 +112,35 @@ impl<S> super::Handle<S>
 where
     S: Deref<Target = super::Store> + Clone,
 {
+    /// Like [`try_find_cached()`][git_pack::Find::try_find_cached()], but report the amount of deltas applied while
+    /// decoding a packed object to `progress` as done by
+    /// [`decode_entry_with_progress()`][git_pack::data::File::decode_entry_with_progress()].
+    pub fn try_find_cached_with_progress<'a>(
+        &self,
+        id: impl AsRef<oid>,
+        buffer: &'a mut Vec<u8>,
+        pack_cache: &mut impl DecodeEntry,
+        progress: &mut impl Progress,
+    ) -> Result<Option<(Data<'a>, Option<Location>)>, Error> {
+        let id = id.as_ref();
+        let mut snapshot = self.snapshot.borrow_mut();
+        // The object data always spans the entire buffer, which allows to retry the lookup without keeping the first result.
+        let found = self
+            .try_find_cached_inner(id, buffer, pack_cache, &mut snapshot, None, progress)?
+            .map(|(data, location)| (data.kind, location));
+        match (found, self.fetch_promised_object.as_ref()) {
+            (Some((kind, location)), _) => Ok(Some((Data::new(kind, buffer.as_slice()), location))),
+            (None, Some(fetch)) => {
+                fetch(id).map_err(|source| PromiseError::FetchFailed {
+                    id: id.to_owned(),
+                    source,
+                })?;
+                self.try_find_cached_inner(id, buffer, pack_cache, &mut snapshot, None, progress)
+            }
+            (None, None) => Ok(None),
+        }
+    }
+
     /// Return the exact number of packed objects after loading all currently available indices
     /// as last seen on disk.
     pub fn packed_object_count(&self) -> Result<u64, Error> {
@@ -187,6 +232,79 @@ where
         }
     }
 
+    /// Return the kind of the packed object `id` by reading only the headers of its pack entry and those of its delta bases,
+    /// or `None` if it isn't packed or a delta base couldn't be found.
+    ///
+    /// Unlike [`try_find()`][crate::Find::try_find()], this doesn't decompress any object data.
+    pub fn packed_object_kind(&self, id: impl AsRef<oid>) -> Option<git_object::Kind> {
+        let mut id = id.as_ref().to_owned();
+        for _ in 0..=self.max_recursion_depth {
+            match self.packed_kind_or_ref_delta_base(&id)? {
+                Ok(kind) => return Some(kind),
+                Err(base_id) => id = base_id,
+            }
+        }
+        None
+    }
+
+    /// Return the kind of the packed object `id`, following offset deltas, or the id of the base object of a ref delta.
+    fn packed_kind_or_ref_delta_base(&self, id: &oid) -> Option<Result<git_object::Kind, ObjectId>> {
+        let mut snapshot = self.snapshot.borrow_mut();
+        'outer: loop {
+            {
+                let marker = snapshot.marker;
+                for index in snapshot.indices.iter_mut() {
+                    if let Some(handle::index_lookup::Outcome {
+                        object_index: handle::IndexForObjectInPack { pack_id, pack_offset },
+                        index_file: _,
+                        pack: possibly_pack,
+                    }) = index.lookup(id)
+                    {
+                        let pack = match possibly_pack {
+                            Some(pack) => pack,
+                            None => match self.store.load_pack(pack_id, marker).ok()? {
+                                Some(pack) => {
+                                    *possibly_pack = Some(pack);
+                                    possibly_pack.as_deref().expect("just put it in")
+                                }
+                                None => match self.store.load_one_index(self.refresh, snapshot.marker).ok()? {
+                                    Some(new_snapshot) => {
+                                        *snapshot = new_snapshot;
+                                        self.clear_cache();
+                                        continue 'outer;
+                                    }
+                                    None => return None,
+                                },
+                            },
+                        };
+                        let mut entry = pack.entry(pack_offset);
+                        loop {
+                            match entry.header {
+                                git_pack::data::entry::Header::OfsDelta { base_distance } => {
+                                    let base_offset = git_pack::data::entry::Header::verified_base_pack_offset(
+                                        entry.pack_offset(),
+                                        base_distance,
+                                    )?;
+                                    entry = pack.entry(base_offset);
+                                }
+                                git_pack::data::entry::Header::RefDelta { base_id } => return Some(Err(base_id)),
+                                header => return header.as_kind().map(Ok),
+                            }
+                        }
+                    }
+                }
+            }
+
+            match self.store.load_one_index(self.refresh, snapshot.marker).ok()? {
+                Some(new_snapshot) => {
+                    *snapshot = new_snapshot;
+                    self.clear_cache();
+                }
+                None => return None,
+            }
+        }
+    }
+
     fn try_find_cached_inner<'a, 'b>(
         &'b self,
         mut id: &'b git_hash::oid,
@@ -194,6 +312,7 @@ where
         pack_cache: &mut impl DecodeEntry,
         snapshot: &mut load_index::Snapshot,
         recursion: Option<error::DeltaBaseRecursion<'_>>,
+        progress: &mut impl Progress,
     ) -> Result<Option<(Data<'a>, Option<Location>)>, Error> {
         if let Some(r) = recursion {
             if r.depth >= self.max_recursion_depth {
@@ -249,7 +368,7 @@ where
                         };
                         let entry = pack.entry(pack_offset);
                         let header_size = entry.header_size();
-                        let res = match pack.decode_entry(
+                        let res = match pack.decode_entry_with_progress(
                             entry,
                             buffer,
                             |id, _out| {
@@ -258,6 +377,7 @@ where
                                     .map(|pack_offset| git_pack::data::ResolvedBase::InPack(pack.entry(pack_offset)))
                             },
                             pack_cache,
+                            progress,
                         ) {
                             Ok(r) => Ok((
                                 git_object::Data {
@@ -292,6 +412,7 @@ where
                                         recursion
                                             .map(|r| r.inc_depth())
                                             .or_else(|| error::DeltaBaseRecursion::new(id).into()),
+                                        progress,
                                     )
                                     .map_err(|err| Error::DeltaBaseLookup {
                                         err: Box::new(err),
@@ -319,7 +440,7 @@ where
                                     .expect("pack to still be available like just now");
                                 let entry = pack.entry(pack_offset);
                                 let header_size = entry.header_size();
-                                pack.decode_entry(
+                                pack.decode_entry_with_progress(
                                     entry,
                                     buffer,
                                     |id, out| {
@@ -340,6 +461,7 @@ where
                                             })
                                     },
                                     pack_cache,
+                                    progress,
                                 )
                                 .map(move |r| {
                                     (
@@ -434,9 +556,7 @@ where
         buffer: &'a mut Vec<u8>,
         pack_cache: &mut impl DecodeEntry,
     ) -> Result<Option<(Data<'a>, Option<Location>)>, Self::Error> {
-        let id = id.as_ref();
-        let mut snapshot = self.snapshot.borrow_mut();
-        self.try_find_cached_inner(id, buffer, pack_cache, &mut snapshot, None)
+        self.try_find_cached_with_progress(id, buffer, pack_cache, &mut progress::Discard)
     }
 
     fn location_by_oid(&self, id: impl AsRef<oid>, buf: &mut Vec<u8>) -> Option<Location> {
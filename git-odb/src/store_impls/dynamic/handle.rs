@@ -247,6 +247,7 @@ impl super::Store {
             store: self.clone(),
             refresh: RefreshMode::default(),
             ignore_replacements: false,
+            fetch_promised_object: None,
             token: Some(token),
             snapshot: RefCell::new(self.collect_snapshot()),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
@@ -263,6 +264,7 @@ impl super::Store {
             store: self.clone(),
             refresh: Default::default(),
             ignore_replacements: false,
+            fetch_promised_object: None,
             token: Some(token),
             snapshot: RefCell::new(self.collect_snapshot()),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
@@ -353,6 +355,7 @@ impl super::Handle<Rc<super::Store>> {
         let mut cache = store.to_handle_arc();
         cache.refresh = self.refresh;
         cache.max_recursion_depth = self.max_recursion_depth;
+        cache.fetch_promised_object = self.fetch_promised_object.clone();
         Ok(cache)
     }
 }
@@ -373,6 +376,7 @@ where
             store: self.store.clone(),
             refresh: self.refresh,
             ignore_replacements: self.ignore_replacements,
+            fetch_promised_object: self.fetch_promised_object.clone(),
             token: {
                 let token = self.store.register_handle();
                 match self.token.as_ref().expect("token is always set here ") {
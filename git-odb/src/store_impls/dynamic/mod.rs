@@ -21,12 +21,22 @@ where
 
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
+    /// If set, it is called with the id of each object that couldn't be found to fetch it from the remote that promised
+    /// to provide it, as is the case in partial clones. The lookup is retried once the function returns successfully.
+    pub fetch_promised_object: Option<FetchPromisedObject>,
 
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
     packed_object_count: RefCell<Option<u64>>,
 }
 
+/// A function to fetch the object with the given id into the object database from the remote that promised to provide it,
+/// usually by writing a new pack.
+///
+/// It must not use the handle it is set on to avoid infinite recursion.
+pub type FetchPromisedObject =
+    std::sync::Arc<dyn Fn(&git_hash::oid) -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync>;
+
 /// Decide what happens when all indices are loaded.
 #[derive(Clone, Copy)]
 pub enum RefreshMode {
@@ -105,6 +105,44 @@ impl Store {
         }
     }
 
+    /// Return the kind and size of the object identified by the given [`ObjectId`][git_hash::ObjectId] if present in this
+    /// database, writing the compressed content of its file, which includes the loose object header, into the given `out` buffer.
+    ///
+    /// Returns `Err` if there was an error locating or reading the object. Returns `Ok<None>` if
+    /// there was no such object.
+    pub fn try_find_compressed(
+        &self,
+        id: impl AsRef<git_hash::oid>,
+        out: &mut Vec<u8>,
+    ) -> Result<Option<(git_object::Kind, usize)>, Error> {
+        debug_assert_eq!(self.object_hash, id.as_ref().kind());
+        let path = hash_path(id.as_ref(), self.path.clone());
+        let mut istream = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => {
+                return Err(Error::Io {
+                    source,
+                    action: Self::OPEN_ACTION,
+                    path,
+                })
+            }
+        };
+        out.clear();
+        istream.read_to_end(out).map_err(|e| Error::Io {
+            source: e,
+            action: "read",
+            path: path.to_owned(),
+        })?;
+
+        let mut header = [0; HEADER_READ_UNCOMPRESSED_BYTES];
+        let (_status, _consumed_in, consumed_out) = zlib::Inflate::default()
+            .once(out, &mut header)
+            .map_err(|e| Error::DecompressFile { source: e, path })?;
+        let (kind, size, _header_size) = git_object::decode::loose_header(&header[..consumed_out])?;
+        Ok(Some((kind, size)))
+    }
+
     fn find_inner<'a>(&self, id: &git_hash::oid, buf: &'a mut Vec<u8>) -> Result<git_object::Data<'a>, Error> {
         let path = hash_path(id, self.path.clone());
 
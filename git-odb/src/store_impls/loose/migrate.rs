@@ -0,0 +1,203 @@
+use std::{
+    cmp::Reverse,
+    collections::VecDeque,
+    io::Write,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::progress::{self, Progress};
+use git_hash::ObjectId;
+use git_pack::data::output;
+
+use crate::store_impls::loose::{hash_path, Store};
+
+/// Options for use in [`Store::migrate_to_pack()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The amount of preceding objects of the same kind, ordered by size, to try as base when storing each object as delta
+    /// against another object of the pack, similar to `git repack --window`.
+    ///
+    /// If `0`, all objects are stored as base objects.
+    pub delta_window: usize,
+    /// The maximum length of delta chains, similar to `git repack --depth`.
+    pub max_delta_depth: usize,
+}
+
+impl Default for Options {
+    /// Store all objects as base objects, and limit delta chains to the same length as `git repack` does if deltas are enabled.
+    fn default() -> Self {
+        Options {
+            delta_window: 0,
+            max_delta_depth: 50,
+        }
+    }
+}
+
+/// Returned by [`Store::migrate_to_pack()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of loose objects written into the pack.
+    pub num_objects: usize,
+    /// The amount of objects stored as deltas against other objects of the pack.
+    pub computed_deltas: usize,
+    /// The amount of bytes written, including the header and the trailing checksum.
+    pub pack_size: u64,
+    /// The checksum of the pack, which also serves as its name.
+    pub checksum: ObjectId,
+}
+
+/// Returned by [`Store::migrate_to_pack()`].
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Iter(#[from] super::iter::Error),
+    #[error(transparent)]
+    Find(#[from] super::find::Error),
+    #[error("The loose object {id} vanished while writing the pack")]
+    Missing { id: ObjectId },
+    #[error(transparent)]
+    NewEntry(#[from] output::entry::Error),
+    #[error("An IO error occurred while writing the pack or deleting loose objects")]
+    Io(#[from] std::io::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// An object which may serve as delta base for the objects following it.
+struct Candidate {
+    kind: git_object::Kind,
+    entry_index: usize,
+    data: Vec<u8>,
+    depth: usize,
+}
+
+impl Store {
+    /// Write all loose objects of this store into a new pack written to `pack_out`, similar to `git repack` without `-a`.
+    ///
+    /// If [`Options::delta_window`] isn't `0`, objects are stored as deltas against other objects of the pack if that's at
+    /// most half their size.
+    ///
+    /// Loose objects are left untouched. Once the pack was moved into place and indexed, they can be deleted with
+    /// [`remove_packed()`][Store::remove_packed()], so they are kept if writing the pack fails at any point.
+    pub fn migrate_to_pack(
+        &self,
+        pack_out: impl Write,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        opts: Options,
+    ) -> Result<Outcome, Error> {
+        let ids = self.iter().collect::<Result<Vec<_>, _>>()?;
+
+        let mut buf = Vec::new();
+        let mut order = Vec::with_capacity(ids.len());
+        for id in &ids {
+            let data = self.try_find(id, &mut buf)?.ok_or(Error::Missing { id: *id })?;
+            order.push((data.kind, Reverse(data.data.len()), *id));
+        }
+        // like `git`, objects are sorted by kind and decreasing size so that smaller objects tend to become deltas of
+        // larger ones.
+        order.sort();
+
+        let mut entries_progress = progress.add_child("creating entries");
+        entries_progress.init(Some(order.len()), progress::count("objects"));
+        let mut entries = Vec::with_capacity(order.len());
+        let mut window = VecDeque::<Candidate>::with_capacity(opts.delta_window);
+        let mut computed_deltas = 0;
+        for (kind, _size, id) in order {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let data = self
+                .try_find(id, &mut buf)?
+                .ok_or(Error::Missing { id })?
+                .data
+                .to_owned();
+            let count = output::Count::from_data(id, None);
+            if window.back().map_or(false, |candidate| candidate.kind != kind) {
+                window.clear();
+            }
+
+            let mut best = None;
+            let mut max_delta_size = data.len() / 2;
+            for candidate in window.iter().filter(|candidate| candidate.depth < opts.max_delta_depth) {
+                if let Some(entry) =
+                    output::Entry::from_delta(&count, &data, candidate.entry_index, &candidate.data, max_delta_size)
+                {
+                    let entry = entry?;
+                    max_delta_size = entry.decompressed_size.saturating_sub(1);
+                    best = Some((entry, candidate.depth + 1));
+                }
+            }
+            let (entry, depth) = match best {
+                Some(delta) => {
+                    computed_deltas += 1;
+                    delta
+                }
+                None => (
+                    output::Entry::from_data(&count, &git_object::Data::new(kind, &data))?,
+                    0,
+                ),
+            };
+            entries.push(entry);
+            entries_progress.inc();
+
+            if opts.delta_window == 0 {
+                continue;
+            }
+            if window.len() == opts.delta_window {
+                window.pop_front();
+            }
+            window.push_back(Candidate {
+                kind,
+                entry_index: entries.len() - 1,
+                data,
+                depth,
+            });
+        }
+
+        let mut write_progress = progress.add_child("writing pack");
+        write_progress.init(None, progress::bytes());
+        let num_objects = entries.len();
+        let mut pack = output::bytes::FromEntriesIter::new(
+            std::iter::once(Ok::<_, std::convert::Infallible>(entries)),
+            pack_out,
+            num_objects as u32,
+            git_pack::data::Version::V2,
+            self.object_hash,
+        );
+        let mut pack_size = 0;
+        for written in pack.by_ref() {
+            let written = written.map_err(|err| match err {
+                output::bytes::Error::Io(err) => err,
+                output::bytes::Error::Input(never) => match never {},
+            })?;
+            pack_size += written;
+            write_progress.inc_by(written as usize);
+        }
+
+        Ok(Outcome {
+            num_objects,
+            computed_deltas,
+            pack_size,
+            checksum: pack.digest().expect("iteration is done"),
+        })
+    }
+
+    /// Delete all loose objects which are contained in the pack `index`, typically the index of a pack written by
+    /// [`migrate_to_pack()`][Store::migrate_to_pack()], and return the amount of deleted objects.
+    pub fn remove_packed(&self, index: &git_pack::index::File) -> Result<usize, Error> {
+        let ids = self.iter().collect::<Result<Vec<_>, _>>()?;
+        let mut deleted_objects = 0;
+        for id in ids.iter().filter(|id| index.lookup(id).is_some()) {
+            let path = hash_path(id, self.path.clone());
+            std::fs::remove_file(&path)?;
+            deleted_objects += 1;
+            if let Some(fan_out_dir) = path.parent() {
+                // fails if the directory isn't empty yet, which is expected.
+                std::fs::remove_dir(fan_out_dir).ok();
+            }
+        }
+        Ok(deleted_objects)
+    }
+}
@@ -72,6 +72,76 @@ mod write {
     }
 }
 
+mod migrate_to_pack {
+    use std::sync::atomic::AtomicBool;
+
+    use git_features::progress;
+    use git_odb::{loose, Write};
+
+    use crate::store::loose::{locate_oid, object_ids};
+
+    #[test]
+    fn all_objects_end_up_in_the_pack_and_loose_ones_are_deleted_once_it_is_indexed(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path(), git_hash::Kind::Sha1);
+        let mut buf = Vec::new();
+        for oid in object_ids() {
+            let obj = locate_oid(oid, &mut buf);
+            db.write_buf(obj.kind, obj.data)?;
+        }
+
+        for delta_window in [0, 10] {
+            let mut pack = Vec::new();
+            let outcome = db.migrate_to_pack(
+                &mut pack,
+                progress::Discard,
+                &AtomicBool::new(false),
+                loose::migrate::Options {
+                    delta_window,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(outcome.num_objects, 7);
+            assert_eq!(outcome.pack_size, pack.len() as u64);
+            if delta_window == 0 {
+                assert_eq!(
+                    outcome.computed_deltas, 0,
+                    "deltas are only computed if there is a window"
+                );
+            }
+            assert_eq!(db.iter().count(), 7, "loose objects are left untouched");
+
+            let pack_dir = tempfile::tempdir()?;
+            let bundle = git_odb::pack::Bundle::write_to_directory(
+                pack.as_slice(),
+                Some(pack_dir.path()),
+                progress::Discard,
+                &AtomicBool::new(false),
+                None,
+                Default::default(),
+            )?
+            .to_bundle()
+            .expect("written to directory")?;
+            let mut pack_buf = Vec::new();
+            for oid in object_ids() {
+                let expected = locate_oid(oid, &mut buf);
+                let (actual, _location) = bundle
+                    .find(oid, &mut pack_buf, &mut git_odb::pack::cache::Never)?
+                    .expect("object is in pack");
+                assert_eq!(actual.kind, expected.kind);
+                assert_eq!(actual.data, expected.data);
+            }
+
+            if delta_window != 0 {
+                assert_eq!(db.remove_packed(&bundle.index)?, 7);
+                assert_eq!(db.iter().count(), 0, "all loose objects were removed");
+            }
+        }
+        Ok(())
+    }
+}
+
 mod contains {
     use crate::store::loose::ldb;
 
@@ -84,6 +154,40 @@ mod contains {
     }
 }
 
+mod try_find_compressed {
+    use git_features::zlib;
+
+    use crate::{hex_to_id, store::loose::ldb};
+
+    #[test]
+    fn objects_are_returned_as_stored_including_their_header() -> Result<(), Box<dyn std::error::Error>> {
+        let store = ldb();
+        let id = hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
+        let mut compressed = Vec::new();
+        let (kind, size) = store.try_find_compressed(id, &mut compressed)?.expect("present");
+        assert_eq!(kind, git_object::Kind::Blob);
+
+        let mut buf = Vec::new();
+        let data = store.try_find(id, &mut buf)?.expect("present");
+        assert_eq!(size, data.data.len());
+        let mut decompressed = vec![0; 1024];
+        let (_status, consumed_in, consumed_out) = zlib::Inflate::default().once(&compressed, &mut decompressed)?;
+        assert_eq!(consumed_in, compressed.len(), "the whole file is returned");
+        let header = git_object::encode::loose_header(kind, size);
+        assert_eq!(&decompressed[..header.len()], &header[..]);
+        assert_eq!(&decompressed[header.len()..consumed_out], data.data);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_objects_yield_none() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(ldb()
+            .try_find_compressed(git_hash::ObjectId::null(git_hash::Kind::Sha1), &mut Vec::new())?
+            .is_none());
+        Ok(())
+    }
+}
+
 mod lookup_prefix {
     use git_testtools::{fixture_path, hex_to_id};
 
@@ -10,7 +10,7 @@ use git_tempfile::{handle::Writable, AutoRemove, ContainingDirectory};
 use crate::data;
 
 mod error;
-use error::Error;
+pub use error::Error;
 
 mod types;
 use types::{LockWriter, PassThrough};
@@ -15,6 +15,93 @@ pub fn decode_header_size(d: &[u8]) -> (u64, usize) {
     (size, consumed)
 }
 
+/// The amount of bytes in `base` and `target` which must match for them to be found by [`encode()`].
+const BLOCK_SIZE: usize = 16;
+
+/// Write a delta to `out` which turns `base` into `target` when [applied][apply()], or return `false` if the delta would
+/// be larger than `max_size` bytes.
+///
+/// Matches are found by indexing non-overlapping blocks of `base` and looking up each block-sized window of `target`, which
+/// is simpler but not as thorough as the rolling hash used by [git](https://github.com/git/git/blob/master/diff-delta.c).
+pub fn encode(base: &[u8], target: &[u8], max_size: usize, out: &mut Vec<u8>) -> bool {
+    out.clear();
+    encode_header_size(base.len() as u64, out);
+    encode_header_size(target.len() as u64, out);
+
+    let mut blocks = std::collections::HashMap::<&[u8], usize>::new();
+    for (block_index, block) in base.chunks_exact(BLOCK_SIZE).enumerate() {
+        blocks.entry(block).or_insert(block_index * BLOCK_SIZE);
+    }
+
+    let mut insert_start = 0;
+    let mut pos = 0;
+    while pos < target.len() {
+        if out.len() + (pos - insert_start) > max_size {
+            return false;
+        }
+        match target.get(pos..pos + BLOCK_SIZE).and_then(|block| blocks.get(block)) {
+            Some(&base_ofs) => {
+                let len = base[base_ofs..]
+                    .iter()
+                    .zip(&target[pos..])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                encode_insert(&target[insert_start..pos], out);
+                encode_copy(base_ofs, len, out);
+                pos += len;
+                insert_start = pos;
+            }
+            None => pos += 1,
+        }
+    }
+    encode_insert(&target[insert_start..], out);
+    out.len() <= max_size
+}
+
+/// The inverse of [`decode_header_size()`].
+fn encode_header_size(mut size: u64, out: &mut Vec<u8>) {
+    loop {
+        let byte = (size & 0x7f) as u8;
+        size >>= 7;
+        if size == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn encode_insert(data: &[u8], out: &mut Vec<u8>) {
+    for chunk in data.chunks(0x7f) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+fn encode_copy(mut ofs: usize, mut len: usize, out: &mut Vec<u8>) {
+    while len != 0 {
+        let size = len.min(0x10000);
+        let cmd_pos = out.len();
+        let mut cmd = 0b1000_0000;
+        out.push(cmd);
+        for (bit, byte) in (ofs as u32).to_le_bytes().iter().enumerate() {
+            if *byte != 0 {
+                cmd |= 1 << bit;
+                out.push(*byte);
+            }
+        }
+        for (bit, byte) in (size as u32).to_le_bytes()[..3].iter().enumerate() {
+            if *byte != 0 {
+                cmd |= 1 << (bit + 4);
+                out.push(*byte);
+            }
+        }
+        out[cmd_pos] = cmd;
+        ofs += size;
+        len -= size;
+    }
+}
+
 pub fn apply(base: &[u8], mut target: &mut [u8], data: &[u8]) {
     let mut i = 0;
     while let Some(cmd) = data.get(i) {
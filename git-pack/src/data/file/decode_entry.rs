@@ -1,6 +1,9 @@
 use std::{convert::TryInto, ops::Range};
 
-use git_features::zlib;
+use git_features::{
+    progress::{self, Progress},
+    zlib,
+};
 use smallvec::SmallVec;
 
 use super::ResolvedBase;
@@ -142,6 +145,22 @@ impl File {
         out: &mut Vec<u8>,
         resolve: impl Fn(&git_hash::oid, &mut Vec<u8>) -> Option<ResolvedBase>,
         delta_cache: &mut impl cache::DecodeEntry,
+    ) -> Result<Outcome, Error> {
+        self.decode_entry_with_progress(entry, out, resolve, delta_cache, &mut progress::Discard)
+    }
+
+    /// Like [`decode_entry()`][File::decode_entry()], but reports the amount of deltas applied so far to `progress`,
+    /// which is useful to observe the resolution of long delta chains.
+    ///
+    /// `progress` is initialized with the length of the delta chain once it is known and incremented once per applied delta.
+    /// It isn't touched if the entry isn't a delta or if the entire object was retrieved from the `delta_cache`.
+    pub fn decode_entry_with_progress(
+        &self,
+        entry: crate::data::Entry,
+        out: &mut Vec<u8>,
+        resolve: impl Fn(&git_hash::oid, &mut Vec<u8>) -> Option<ResolvedBase>,
+        delta_cache: &mut impl cache::DecodeEntry,
+        progress: &mut impl Progress,
     ) -> Result<Outcome, Error> {
         use crate::data::entry::Header::*;
         match entry.header {
@@ -161,7 +180,7 @@ impl File {
                     )
                 })
             }
-            OfsDelta { .. } | RefDelta { .. } => self.resolve_deltas(entry, resolve, out, delta_cache),
+            OfsDelta { .. } | RefDelta { .. } => self.resolve_deltas(entry, resolve, out, delta_cache, progress),
         }
     }
 
@@ -174,6 +193,7 @@ impl File {
         resolve: impl Fn(&git_hash::oid, &mut Vec<u8>) -> Option<ResolvedBase>,
         out: &mut Vec<u8>,
         cache: &mut impl cache::DecodeEntry,
+        progress: &mut impl Progress,
     ) -> Result<Outcome, Error> {
         // all deltas, from the one that produces the desired object (first) to the oldest at the end of the chain
         let mut chain = SmallVec::<[Delta; 10]>::default();
@@ -331,6 +351,7 @@ impl File {
         let (buffers, instructions) = out.split_at_mut(second_buffer_end);
         let (mut source_buf, mut target_buf) = buffers.split_at_mut(first_buffer_end);
 
+        progress.init(Some(chain_len), progress::count("deltas"));
         let mut last_result_size = None;
         for (
             delta_idx,
@@ -347,6 +368,7 @@ impl File {
                 last_result_size = Some(result_size);
             }
             delta::apply(&source_buf[..base_size], &mut target_buf[..result_size], data);
+            progress.inc();
             // use the target as source for the next delta
             std::mem::swap(&mut source_buf, &mut target_buf);
         }
@@ -230,6 +230,44 @@ where
     )
 }
 
+/// Calculate entries for all objects in `ids` like [`iter_from_counts()`] does, but return them all at once.
+///
+/// `ids` are split into chunks which are processed by up to `thread_count` threads, each with its own clone of `db`, which
+/// is typically a handle to the object database. `thread_count` takes precedence over [`Options::thread_limit`].
+/// Objects whose id appears more than once in `ids` are only added once. Note that unpacked objects are added as base
+/// objects, as delta compression isn't performed yet.
+pub fn objects_to_entries_threaded<Find>(
+    ids: &[git_hash::ObjectId],
+    db: Find,
+    options: Options,
+    thread_count: usize,
+) -> Result<Vec<output::Entry>, Error<Find::Error>>
+where
+    Find: crate::Find + Send + Clone + 'static,
+    <Find as crate::Find>::Error: Send,
+{
+    let mut seen = std::collections::HashSet::new();
+    let counts = ids
+        .iter()
+        .filter(|id| seen.insert(**id))
+        .map(|id| output::Count {
+            id: *id,
+            entry_pack_location: output::count::PackLocation::NotLookedUp,
+        })
+        .collect();
+    let entries = iter_from_counts(
+        counts,
+        db,
+        git_features::progress::Discard,
+        Options {
+            thread_limit: Some(thread_count),
+            ..options
+        },
+    );
+    let chunks = parallel::InOrderIter::from(entries).collect::<Result<Vec<_>, _>>()?;
+    Ok(chunks.into_iter().flatten().collect())
+}
+
 mod util {
     #[derive(Clone)]
     pub struct ChunkRanges {
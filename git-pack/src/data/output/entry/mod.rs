@@ -6,7 +6,7 @@ use crate::{data, data::output, find};
 
 ///
 pub mod iter_from_counts;
-pub use iter_from_counts::iter_from_counts;
+pub use iter_from_counts::{iter_from_counts, objects_to_entries_threaded};
 
 /// The kind of pack entry to be written
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -132,20 +132,35 @@ impl output::Entry {
             id: count.id.to_owned(),
             kind: Kind::Base(obj.kind),
             decompressed_size: obj.data.len(),
-            compressed_data: {
-                let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
-                if let Err(err) = std::io::copy(&mut &*obj.data, &mut out) {
-                    match err.kind() {
-                        std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
-                        err => unreachable!("Should never see other errors than zlib, but got {:?}", err,),
-                    }
-                };
-                out.flush()?;
-                out.into_inner()
-            },
+            compressed_data: compress(obj.data)?,
         })
     }
 
+    /// Create a new instance storing the object of `count` with `data` as delta against `base`, the data of the object written
+    /// as entry `base_index` of the same pack.
+    ///
+    /// Return `None` if the delta would be larger than `max_delta_size` bytes, in which case it's not worth storing.
+    pub fn from_delta(
+        count: &output::Count,
+        data: &[u8],
+        base_index: usize,
+        base: &[u8],
+        max_delta_size: usize,
+    ) -> Option<Result<Self, Error>> {
+        let mut delta = Vec::new();
+        if !crate::data::delta::encode(base, data, max_delta_size, &mut delta) {
+            return None;
+        }
+        Some(compress(&delta).map(|compressed_data| output::Entry {
+            id: count.id.to_owned(),
+            kind: Kind::DeltaRef {
+                object_index: base_index,
+            },
+            decompressed_size: delta.len(),
+            compressed_data,
+        }))
+    }
+
     /// Transform ourselves into pack entry header of `version` which can be written into a pack.
     ///
     /// `index_to_pack(object_index) -> pack_offset` is a function to convert the base object's index into
@@ -179,3 +194,15 @@ impl output::Entry {
         }
     }
 }
+
+fn compress(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+    if let Err(err) = std::io::copy(&mut &*data, &mut out) {
+        match err.kind() {
+            std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
+            err => unreachable!("Should never see other errors than zlib, but got {:?}", err,),
+        }
+    };
+    out.flush()?;
+    Ok(out.into_inner())
+}
@@ -321,6 +321,45 @@ fn traversals() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn objects_to_entries_threaded_matches_the_single_threaded_iterator() -> crate::Result {
+    let db = db(DbKind::DeterministicGeneratedContent)?;
+    let (counts, _stats) = output::count::objects(
+        db.clone(),
+        std::iter::once(Ok::<_, Infallible>(hex_to_id(
+            "dfcb5e39ac6eb30179808bbab721e8a28ce1b52e",
+        ))),
+        progress::Discard,
+        &AtomicBool::new(false),
+        count::objects::Options {
+            input_object_expansion: count::objects::ObjectExpansion::TreeContents,
+            thread_limit: Some(1),
+            ..Default::default()
+        },
+    )?;
+    let ids: Vec<_> = counts.iter().map(|count| count.id).collect();
+
+    let expected: Vec<_> = InOrderIter::from(output::entry::iter_from_counts(
+        counts,
+        db.clone(),
+        progress::Discard,
+        output::entry::iter_from_counts::Options {
+            thread_limit: Some(1),
+            ..Default::default()
+        },
+    ))
+    .collect::<Result<Vec<_>, _>>()?
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let duplicated_ids: Vec<_> = ids.iter().chain(ids.iter()).copied().collect();
+    let entries = output::entry::objects_to_entries_threaded(&duplicated_ids, db, Default::default(), 4)?;
+    assert_eq!(entries.len(), ids.len(), "duplicates are only added once");
+    assert_eq!(entries, expected);
+    Ok(())
+}
+
 fn write_and_verify(
     db: git_odb::HandleArc,
     entries: Vec<output::Entry>,
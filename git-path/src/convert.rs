@@ -64,6 +64,12 @@ pub fn into_bstr<'a>(path: impl Into<Cow<'a, Path>>) -> Cow<'a, BStr> {
     try_into_bstr(path).expect("prefix path doesn't contain ill-formed UTF-8")
 }
 
+/// Like [`into_bstr()`], but also converts native path separators into the slashes used by git, which makes it the inverse
+/// of [`from_bstr()`] for relative paths like the ones produced by [`relative_path()`].
+pub fn to_bstr<'a>(path: impl Into<Cow<'a, Path>>) -> Cow<'a, BStr> {
+    to_unix_separators_on_windows(into_bstr(path))
+}
+
 /// Given `input` bytes, produce a `Path` from them ignoring encoding entirely if on unix.
 ///
 /// On windows, the input is required to be valid UTF-8, which is guaranteed if we wrote it before. There are some potential
@@ -229,3 +235,36 @@ pub fn absolutize<'a>(path: impl Into<Cow<'a, Path>>, mut current_dir: Option<im
         path.into()
     }
 }
+
+/// Compute the path leading from the directory `from` to `to`, both of which are expected to be absolute, for example
+/// to turn `/a/b/c` and `/a/d/file.txt` into `../../d/file.txt`, without accessing the file system.
+///
+/// Relative components like `..` are resolved in both paths first. If they don't share the same root, as is the case
+/// for paths on different drives or UNC shares on windows, there is no relative path between them and `to` is returned
+/// as is. An empty result is returned as `.`.
+///
+/// The result uses native path separators, use [`to_bstr()`] to obtain it in the form used by git, and [`from_bstr()`] to
+/// turn it back into a path.
+pub fn relative_path(from: impl AsRef<Path>, to: impl AsRef<Path>) -> PathBuf {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let from = absolutize(from, None::<&Path>);
+    let absolute_to = absolutize(to, None::<&Path>);
+    let mut from_components = from.components().peekable();
+    let mut to_components = absolute_to.components().peekable();
+    if from_components.peek() != to_components.peek() {
+        return to.to_owned();
+    }
+    while from_components.peek().is_some() && from_components.peek() == to_components.peek() {
+        from_components.next();
+        to_components.next();
+    }
+    let path: PathBuf = from_components
+        .map(|_| std::path::Component::ParentDir)
+        .chain(to_components)
+        .collect();
+    if path.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        path
+    }
+}
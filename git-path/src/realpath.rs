@@ -1,7 +1,11 @@
 use std::path::PathBuf;
 
+/// The maximum amount of symlinks to follow, the same value git uses.
+pub const MAX_SYMLINKS: u8 = 32;
+
 /// the error returned by [`realpath()`][super::realpath()].
 #[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
 pub enum Error {
     #[error("The maximum allowed number {} of symlinks in path is exceeded", .max_symlinks)]
     MaxSymlinksExceeded { max_symlinks: u8 },
@@ -11,22 +15,36 @@ pub enum Error {
     EmptyPath,
     #[error("Parent component of {} does not exist, {}", .path.display(), .msg)]
     MissingParent { path: PathBuf, msg: &'static str },
+    #[error("The current working directory could not be obtained to make a relative path absolute")]
+    CurrentWorkingDir(#[source] std::io::Error),
 }
 
 pub(crate) mod function {
-    use super::Error;
+    use super::{Error, MAX_SYMLINKS};
     use std::path::Component::{CurDir, Normal, ParentDir, Prefix, RootDir};
     use std::path::{Path, PathBuf};
 
-    // TODO
-    #[allow(missing_docs)]
-    pub fn realpath(path: impl AsRef<Path>, cwd: impl AsRef<Path>) -> Result<PathBuf, Error> {
-        let git_default = 32;
-        realpath_opts(path, cwd, git_default)
+    /// Resolve `path` into an absolute path without symlinks, similar to `realpath(3)`, but without requiring all of its
+    /// components to exist, which makes it suitable for paths that are yet to be created unlike [`std::fs::canonicalize()`].
+    ///
+    /// Relative paths are made absolute using the current working directory, and symlinks are followed up to
+    /// [`MAX_SYMLINKS`][super::MAX_SYMLINKS] times. See [`realpath_opts()`] for details.
+    pub fn realpath(path: impl AsRef<Path>) -> Result<PathBuf, Error> {
+        let path = path.as_ref();
+        let cwd = if path.is_relative() {
+            std::env::current_dir().map_err(Error::CurrentWorkingDir)?
+        } else {
+            PathBuf::new()
+        };
+        realpath_opts(path, cwd, MAX_SYMLINKS)
     }
 
-    // TODO
-    #[allow(missing_docs)]
+    /// Resolve `path` into an absolute path without symlinks, component by component, making it absolute using `cwd` if
+    /// it is relative and following at most `max_symlinks` symlinks.
+    ///
+    /// `.` and `..` components are resolved as they are encountered, after symlinks leading up to them were followed.
+    /// Components that don't exist are kept as they are instead of causing an error, so only the existing portion
+    /// of `path` is resolved.
     pub fn realpath_opts(path: impl AsRef<Path>, cwd: impl AsRef<Path>, max_symlinks: u8) -> Result<PathBuf, Error> {
         let path = path.as_ref();
         if path.as_os_str().is_empty() {
@@ -79,4 +97,19 @@ pub(crate) mod function {
         }
         Ok(real_path)
     }
+
+    /// Return true if `candidate`, taken relative to `git_dir` if it is relative, is located within `git_dir` after
+    /// resolving `..` components and symlinks in both using [`realpath()`].
+    ///
+    /// Use this to assure paths derived from user input don't escape the git directory before opening them.
+    /// If either path can't be resolved, `false` is returned.
+    pub fn is_inside_git_dir(git_dir: impl AsRef<Path>, candidate: impl AsRef<Path>) -> bool {
+        let git_dir = match realpath(git_dir) {
+            Ok(git_dir) => git_dir,
+            Err(_) => return false,
+        };
+        realpath_opts(candidate, &git_dir, MAX_SYMLINKS)
+            .map(|candidate| candidate.starts_with(&git_dir))
+            .unwrap_or(false)
+    }
 }
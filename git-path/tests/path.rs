@@ -19,6 +19,71 @@ mod convert {
         assert_eq!(to_windows_separators(b"/a/b//".as_bstr()).as_bstr(), "\\a\\b\\\\");
     }
 
+    mod relative_path {
+        use std::path::Path;
+
+        use git_path::relative_path;
+
+        #[test]
+        #[cfg(not(windows))]
+        fn paths_with_a_common_root() {
+            for (from, to, expected) in [
+                ("/a/b/c", "/a/d/file.txt", "../../d/file.txt"),
+                ("/a/b", "/a/b/c/file.txt", "c/file.txt"),
+                ("/a/b/c", "/a", "../.."),
+                ("/a/b", "/a/b", "."),
+                ("/", "/a/b", "a/b"),
+                ("/a/b/../c", "/a/./c/d", "d"),
+            ] {
+                assert_eq!(relative_path(from, to), Path::new(expected), "{} -> {}", from, to);
+            }
+        }
+
+        #[test]
+        #[cfg(windows)]
+        fn paths_with_a_common_root() {
+            for (from, to, expected) in [
+                ("C:\\a\\b\\c", "C:\\a\\d\\file.txt", "..\\..\\d\\file.txt"),
+                ("C:\\a\\b", "C:\\a\\b", "."),
+                ("\\\\server\\share\\a", "\\\\server\\share\\b", "..\\b"),
+            ] {
+                assert_eq!(relative_path(from, to), Path::new(expected), "{} -> {}", from, to);
+            }
+        }
+
+        #[test]
+        #[cfg(not(windows))]
+        fn round_trips_through_bytes_with_slashes() {
+            let path = relative_path("/a/b/c", "/a/d/file.txt");
+            let bytes = git_path::to_bstr(path.as_path());
+            assert_eq!(bytes.as_ref(), "../../d/file.txt");
+            assert_eq!(git_path::from_bstr(bytes), path);
+            assert_eq!(git_path::to_bstr(relative_path("/a", "/a")).as_ref(), ".");
+        }
+
+        #[test]
+        #[cfg(windows)]
+        fn round_trips_through_bytes_with_slashes() {
+            let path = relative_path("C:\\a\\b\\c", "C:\\a\\d\\file.txt");
+            let bytes = git_path::to_bstr(path.as_path());
+            assert_eq!(bytes.as_ref(), "../../d/file.txt");
+            assert_eq!(git_path::from_bstr(bytes), path);
+            assert_eq!(git_path::to_bstr(relative_path("C:\\a", "C:\\a")).as_ref(), ".");
+        }
+
+        #[test]
+        #[cfg(windows)]
+        fn paths_without_a_common_root_are_returned_unchanged() {
+            for (from, to) in [
+                ("C:\\a", "D:\\a\\file.txt"),
+                ("\\\\server\\share\\a", "\\\\server\\other\\a"),
+                ("C:\\a", "\\\\server\\share\\a"),
+            ] {
+                assert_eq!(relative_path(from, to), Path::new(to));
+            }
+        }
+    }
+
     mod absolutize {
         use std::{borrow::Cow, path::Path};
 
@@ -78,3 +143,5 @@ mod convert {
         }
     }
 }
+
+mod realpath;
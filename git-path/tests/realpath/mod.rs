@@ -1,4 +1,4 @@
-use git_path::{create_symlink, realpath, realpath::Error, CanonicalizedTempDir};
+use git_path::{create_symlink, is_inside_git_dir, realpath, realpath::Error, realpath_opts, CanonicalizedTempDir};
 use std::path::Path;
 use tempfile::tempdir;
 
@@ -9,36 +9,36 @@ fn assorted() {
     let symlinks_disabled = 0;
 
     assert!(
-        matches!(realpath("", cwd, symlinks_disabled), Err(Error::EmptyPath)),
+        matches!(realpath_opts("", cwd, symlinks_disabled), Err(Error::EmptyPath)),
         "Empty path is not allowed"
     );
 
     assert_eq!(
-        realpath("b/.git", cwd, symlinks_disabled).unwrap(),
+        realpath_opts("b/.git", cwd, symlinks_disabled).unwrap(),
         cwd.join("b").join(".git"),
         "relative paths are prefixed with current dir"
     );
 
     assert_eq!(
-        realpath("b//.git", cwd, symlinks_disabled).unwrap(),
+        realpath_opts("b//.git", cwd, symlinks_disabled).unwrap(),
         cwd.join("b").join(".git"),
         "empty path components are ignored"
     );
 
     assert_eq!(
-        realpath("./tmp/.git", cwd, symlinks_disabled).unwrap(),
+        realpath_opts("./tmp/.git", cwd, symlinks_disabled).unwrap(),
         cwd.join("tmp").join(".git"),
         "path starting with dot is relative and is prefixed with current dir"
     );
 
     assert_eq!(
-        realpath("./tmp/a/./.git", cwd, symlinks_disabled).unwrap(),
+        realpath_opts("./tmp/a/./.git", cwd, symlinks_disabled).unwrap(),
         cwd.join("tmp").join("a").join(".git"),
         "all ./ path components are ignored unless they the one at the beginning of the path"
     );
 
     assert_eq!(
-        realpath("./b/../tmp/.git", cwd, symlinks_disabled).unwrap(),
+        realpath_opts("./b/../tmp/.git", cwd, symlinks_disabled).unwrap(),
         cwd.join("tmp").join(".git"),
         "dot dot goes to parent path component"
     );
@@ -49,7 +49,7 @@ fn assorted() {
         #[cfg(target_os = "windows")]
         let absolute_path = Path::new("C:\\c\\d\\.git");
         assert_eq!(
-            realpath(absolute_path, cwd, symlinks_disabled).unwrap(),
+            realpath_opts(absolute_path, cwd, symlinks_disabled).unwrap(),
             absolute_path,
             "absolute path without symlinks has nothing to resolve and remains unchanged"
         );
@@ -67,7 +67,7 @@ fn link_cycle_is_detected() {
 
     assert!(
         matches!(
-            realpath(link_path.join(".git"), "", max_symlinks),
+            realpath_opts(link_path.join(".git"), "", max_symlinks),
             Err(Error::MaxSymlinksExceeded { max_symlinks: 8 })
         ),
         "link cycle is detected"
@@ -82,7 +82,7 @@ fn symlink_with_absolute_path_gets_expanded() {
     create_symlink(&link_from, &link_to);
     let max_symlinks = 8;
     assert_eq!(
-        realpath(link_from.join(".git"), tmp_dir, max_symlinks).unwrap(),
+        realpath_opts(link_from.join(".git"), tmp_dir, max_symlinks).unwrap(),
         link_to.join(".git"),
         "symlink with absolute path gets expanded"
     );
@@ -94,7 +94,7 @@ fn symlink_to_relative_path_gets_expanded_into_absolute_path() {
     let link_name = "pq_link";
     create_symlink(&cwd.join("r").join(link_name), &Path::new("p").join("q"));
     assert_eq!(
-        realpath(Path::new(link_name).join(".git"), cwd.join("r"), 8).unwrap(),
+        realpath_opts(Path::new(link_name).join(".git"), cwd.join("r"), 8).unwrap(),
         cwd.join("r").join("p").join("q").join(".git"),
         "symlink to relative path gets expanded into absolute path"
     );
@@ -107,9 +107,54 @@ fn symlink_processing_is_disabled_if_the_value_is_zero() {
     create_symlink(&cwd.join(link_name), Path::new("link destination does not exist"));
     assert!(
         matches!(
-            realpath(&Path::new(link_name).join(".git"), &cwd, 0),
+            realpath_opts(&Path::new(link_name).join(".git"), &cwd, 0),
             Err(Error::MaxSymlinksExceeded { max_symlinks: 0 })
         ),
         "symlink processing is disabled if the value is zero"
     );
 }
+
+#[test]
+fn non_existing_components_are_kept_after_resolving_existing_ones() {
+    let tmp_dir = CanonicalizedTempDir::new();
+    let link_from = tmp_dir.join("link");
+    let link_to = tmp_dir.join("p").join("q");
+    create_symlink(&link_from, &link_to);
+    assert_eq!(
+        realpath(link_from.join("does-not-exist").join("..").join("missing").join(".git")).unwrap(),
+        link_to.join("missing").join(".git"),
+        "intermediate components that don't exist don't cause an error"
+    );
+}
+
+#[test]
+fn relative_paths_are_made_absolute_with_the_current_working_dir() {
+    assert_eq!(
+        realpath(Path::new("does-not-exist").join(".git")).unwrap(),
+        std::env::current_dir().unwrap().join("does-not-exist").join(".git")
+    );
+}
+
+#[test]
+fn is_inside_git_dir_resolves_parent_components_and_symlinks() {
+    let tmp_dir = CanonicalizedTempDir::new();
+    let git_dir = tmp_dir.join(".git");
+    std::fs::create_dir_all(git_dir.join("refs")).unwrap();
+    create_symlink(&git_dir.join("escape"), &tmp_dir.join("outside"));
+
+    assert!(is_inside_git_dir(&git_dir, "refs/heads/main"));
+    assert!(is_inside_git_dir(&git_dir, git_dir.join("config")));
+    assert!(is_inside_git_dir(&git_dir, "refs/../HEAD"));
+    assert!(
+        !is_inside_git_dir(&git_dir, "refs/../../config"),
+        "parent components can't be used to leave the git dir"
+    );
+    assert!(
+        !is_inside_git_dir(&git_dir, tmp_dir.join("config")),
+        "absolute paths outside of the git dir are rejected"
+    );
+    assert!(
+        !is_inside_git_dir(&git_dir, "escape/file"),
+        "symlinks pointing outside of the git dir are followed"
+    );
+}
@@ -17,6 +17,7 @@ pub struct Arguments {
     deepen_not: bool,
     deepen_relative: bool,
     ref_in_want: bool,
+    packfile_uris: bool,
 
     features_for_first_want: Option<Vec<String>>,
     #[cfg(any(feature = "async-client", feature = "blocking-client"))]
@@ -65,6 +66,13 @@ impl Arguments {
     pub fn can_use_ref_in_want(&self) -> bool {
         self.ref_in_want
     }
+    /// Return true if the 'packfile-uris' capability is supported.
+    ///
+    /// This allows the server to offload parts of the pack to be downloaded separately, see
+    /// [`packfile_uris()`][Arguments::packfile_uris()].
+    pub fn can_use_packfile_uris(&self) -> bool {
+        self.packfile_uris
+    }
 
     /// Add the given `id` pointing to a commit to the 'want' list.
     ///
@@ -117,6 +125,14 @@ impl Arguments {
         line.extend_from_slice(ref_path);
         self.args.push(line);
     }
+    /// Indicate that packs may be downloaded separately using any of the given `protocols`, like `https`.
+    ///
+    /// The server will then list these packs in the [packfile-uris][crate::fetch::Response::packfile_uris()] section
+    /// of its response, and omit their objects from the packfile it sends.
+    pub fn packfile_uris(&mut self, protocols: &[&str]) {
+        assert!(self.packfile_uris, "'packfile-uris' feature required");
+        self.prefixed("packfile-uris ", protocols.join(","));
+    }
     /// Set the given filter `spec` when listing references.
     pub fn filter(&mut self, spec: &str) {
         assert!(self.filter, "'filter' feature required");
@@ -132,6 +148,7 @@ impl Arguments {
         let filter = has("filter");
         let shallow = has("shallow");
         let ref_in_want = has("ref-in-want");
+        let packfile_uris = has("packfile-uris");
         let mut deepen_since = shallow;
         let mut deepen_not = shallow;
         let mut deepen_relative = shallow;
@@ -162,6 +179,7 @@ impl Arguments {
             deepen_not,
             deepen_relative,
             ref_in_want,
+            packfile_uris,
             deepen_since,
             features_for_first_want,
         }
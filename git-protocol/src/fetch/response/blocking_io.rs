@@ -4,7 +4,7 @@ use git_transport::{client, Protocol};
 
 use crate::fetch::{
     response,
-    response::{Acknowledgement, ShallowUpdate, WantedRef},
+    response::{Acknowledgement, PackfileUri, ShallowUpdate, WantedRef},
     Response,
 };
 
@@ -81,6 +81,7 @@ impl Response {
                     acks,
                     shallows,
                     wanted_refs: vec![],
+                    packfile_uris: vec![],
                     has_pack,
                 })
             }
@@ -91,6 +92,7 @@ impl Response {
                 let mut acks = Vec::<Acknowledgement>::new();
                 let mut shallows = Vec::<ShallowUpdate>::new();
                 let mut wanted_refs = Vec::<WantedRef>::new();
+                let mut packfile_uris = Vec::<PackfileUri>::new();
                 let has_pack = 'section: loop {
                     line.clear();
                     if reader.read_line(&mut line)? == 0 {
@@ -116,6 +118,11 @@ impl Response {
                                 break 'section false;
                             }
                         }
+                        "packfile-uris" => {
+                            if parse_v2_section(&mut line, reader, &mut packfile_uris, PackfileUri::from_line)? {
+                                break 'section false;
+                            }
+                        }
                         "packfile" => {
                             // what follows is the packfile itself, which can be read with a sideband enabled reader
                             break 'section true;
@@ -127,6 +134,7 @@ impl Response {
                     acks,
                     shallows,
                     wanted_refs,
+                    packfile_uris,
                     has_pack,
                 })
             }
@@ -84,6 +84,16 @@ pub struct WantedRef {
     pub path: BString,
 }
 
+/// A packfile-uris line received from the server, naming a pack to download separately from the packfile it sends inline.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackfileUri {
+    /// The checksum of the pack to download, which also serves as its name.
+    pub hash: git_hash::ObjectId,
+    /// The URI to download the pack from, using one of the protocols requested with the `packfile-uris` argument.
+    pub uri: BString,
+}
+
 impl ShallowUpdate {
     /// Parse a `ShallowUpdate` from a `line` as received to the server.
     pub fn from_line(line: &str) -> Result<ShallowUpdate, Error> {
@@ -153,11 +163,26 @@ impl WantedRef {
     }
 }
 
+impl PackfileUri {
+    /// Parse a `PackfileUri` from a `line` as received from the server.
+    pub fn from_line(line: &str) -> Result<PackfileUri, Error> {
+        match line.trim_end().split_once(' ') {
+            Some((hash, uri)) if !uri.is_empty() => {
+                let hash = git_hash::ObjectId::from_hex(hash.as_bytes())
+                    .map_err(|_| Error::UnknownLineType(line.to_owned()))?;
+                Ok(PackfileUri { hash, uri: uri.into() })
+            }
+            _ => Err(Error::UnknownLineType(line.to_owned())),
+        }
+    }
+}
+
 /// A representation of a complete fetch response
 pub struct Response {
     acks: Vec<Acknowledgement>,
     shallows: Vec<ShallowUpdate>,
     wanted_refs: Vec<WantedRef>,
+    packfile_uris: Vec<PackfileUri>,
     has_pack: bool,
 }
 
@@ -206,6 +231,14 @@ impl Response {
     pub fn wanted_refs(&self) -> &[WantedRef] {
         &self.wanted_refs
     }
+
+    /// Return all packfile-uris [parsed previously][Response::from_line_reader()].
+    ///
+    /// These packs are not part of the packfile sent along with the response and have to be downloaded separately
+    /// to obtain all requested objects.
+    pub fn packfile_uris(&self) -> &[PackfileUri] {
+        &self.packfile_uris
+    }
 }
 
 #[cfg(any(feature = "async-client", feature = "blocking-client"))]
@@ -116,7 +116,7 @@ mod v2 {
         use futures_lite::io::AsyncReadExt;
         use git_protocol::fetch::{
             self,
-            response::{Acknowledgement, ShallowUpdate},
+            response::{Acknowledgement, PackfileUri, ShallowUpdate},
         };
         use git_transport::Protocol;
 
@@ -166,6 +166,22 @@ mod v2 {
             Ok(())
         }
 
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn clone_with_packfile_uris() -> crate::Result {
+            let mut provider = mock_reader("v2/clone-packfile-uris.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader).await?;
+            assert_eq!(
+                r.packfile_uris(),
+                &[PackfileUri {
+                    hash: id("a9d28a2d61d5b7d6e0a54d6e3b6e6ab5b8c6f4a1"),
+                    uri: "https://cdn.example.com/pack-a9d28a2d61d5b7d6e0a54d6e3b6e6ab5b8c6f4a1.pack".into()
+                }]
+            );
+            assert!(r.has_pack(), "the remaining objects are sent inline");
+            Ok(())
+        }
+
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn clone_with_sidebands() -> crate::Result {
             let mut provider = mock_reader("v2/clone-only-2.response");
@@ -180,6 +180,25 @@ impl FullName {
         self
     }
 
+    /// Return a new name which is this name within `namespace`, like `refs/namespaces/foo/refs/heads/main` for
+    /// `refs/heads/main` in namespace `foo`.
+    ///
+    /// Unlike [`prefix_namespace()`][FullName::prefix_namespace()], the namespace is prepended even if this name is already
+    /// in it, making it the inverse of [`without_namespace()`][FullName::without_namespace()].
+    pub fn with_namespace(&self, namespace: &Namespace) -> FullName {
+        namespace.to_owned().into_namespaced_name(self.as_ref())
+    }
+
+    /// Return a new name without the `namespace` prefix, like `refs/heads/main` for `refs/namespaces/foo/refs/heads/main`
+    /// in namespace `foo`, or `None` if this name isn't in `namespace`.
+    ///
+    /// Use [`strip_namespace()`][FullName::strip_namespace()] to strip the namespace in place if present.
+    pub fn without_namespace(&self, namespace: &Namespace) -> Option<FullName> {
+        self.0
+            .strip_prefix(namespace.0.as_slice())
+            .map(|name| FullName(name.into()))
+    }
+
     /// Strip well-known prefixes from the name and return it.
     ///
     /// If there is no such prefix, the original name is returned.
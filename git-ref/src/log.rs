@@ -1,6 +1,9 @@
 use git_hash::ObjectId;
 use git_object::bstr::BString;
 
+/// Parse individual reflog lines or entire reflog files without going through a ref store.
+pub use crate::store_impl::file::log::{iter, LineRef};
+
 /// A parsed ref log line that can be changed
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
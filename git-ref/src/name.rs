@@ -101,6 +101,20 @@ impl PartialNameRef {
     }
 }
 
+impl PartialNameRef {
+    /// Create a partial name from a single path `component`, like a branch name provided by a user, which is validated like
+    /// any other partial name but additionally must not contain slashes as these would introduce additional components,
+    /// which is reported as [`SlashInComponent`][git_validate::reference::name::Error::SlashInComponent].
+    pub fn from_path_component(component: &BStr) -> Result<&Self, Error> {
+        if component.find_byte(b'/').is_some() {
+            return Err(Error::SlashInComponent);
+        }
+        Ok(PartialNameRef::new_unchecked(git_validate::reference::name_partial(
+            component,
+        )?))
+    }
+}
+
 impl PartialName {
     /// Append the `component` to ourselves and validate the newly created partial path.
     pub fn join(self, component: impl AsRef<[u8]>) -> Result<Self, Error> {
@@ -1,6 +1,7 @@
 use std::{
     convert::TryInto,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 use git_object::bstr::{BStr, BString, ByteSlice, ByteVec};
@@ -16,6 +17,11 @@ impl Namespace {
     pub fn as_bstr(&self) -> &BStr {
         self.0.as_ref()
     }
+    /// Return ourselves as partial name without the trailing slash, like `refs/namespaces/foo`, for use in reference lookups
+    /// and iteration.
+    pub fn as_partial_name(&self) -> &PartialNameRef {
+        PartialNameRef::new_unchecked(self.0[..self.0.len() - 1].as_bstr())
+    }
     /// Return ourselves as a path for use within the filesystem.
     pub fn to_path(&self) -> &Path {
         git_path::from_byte_slice(&self.0)
@@ -33,6 +39,55 @@ impl Namespace {
     }
 }
 
+///
+pub mod from_str {
+    use git_object::bstr::BString;
+    use quick_error::quick_error;
+
+    quick_error! {
+        /// The error returned by [`Namespace::from_str()`][crate::Namespace::from_str()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            InvalidName(err: git_validate::refname::Error) {
+                display("The namespace is not a valid partial reference name")
+                from()
+                source(err)
+            }
+            PartiallyPrefixed(name: BString) {
+                display("The namespace '{}' starts with 'refs/namespaces/' but not all of its components are prefixed", name)
+            }
+        }
+    }
+}
+
+impl FromStr for Namespace {
+    type Err = from_str::Error;
+
+    /// Parse `s` as namespace, which is [expanded][expand()] unless it starts with `refs/namespaces/` already, in which case
+    /// all of its components must be prefixed like in `refs/namespaces/foo/refs/namespaces/bar`.
+    /// A trailing slash is permitted for already expanded namespaces only.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const PREFIX: &str = "refs/namespaces/";
+        if !s.starts_with(PREFIX) {
+            return Ok(expand(s)?);
+        }
+        let name = s.strip_suffix('/').unwrap_or(s);
+        git_validate::reference::name_partial(name.into())?;
+        let components: Vec<_> = name.split('/').collect();
+        if components.len() % 3 != 0
+            || !components
+                .chunks(3)
+                .all(|component| component[0] == "refs" && component[1] == "namespaces")
+        {
+            return Err(from_str::Error::PartiallyPrefixed(s.into()));
+        }
+        let mut out = BString::from(name);
+        out.push_byte(b'/');
+        Ok(Namespace(out))
+    }
+}
+
 /// Given a `namespace` 'foo we output 'refs/namespaces/foo', and given 'foo/bar' we output 'refs/namespaces/foo/refs/namespaces/bar'.
 ///
 /// For more information, consult the [git namespace documentation](https://git-scm.com/docs/gitnamespaces).
@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use git_features::threading::{get_mut, get_ref, MutableOnDemand, OwnShared};
+use git_hash::{oid, ObjectId};
+
+use crate::{file, packed, FullName, Reference, Target};
+
+mod error {
+    use quick_error::quick_error;
+
+    use crate::store_impl::{file, packed};
+
+    quick_error! {
+        /// The error returned by [`file::Store::iter_references_by_target()`].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            PackedOpen(err: packed::buffer::open::Error) {
+                display("The packed-refs file could not be opened")
+                from()
+                source(err)
+            }
+            Traversal(err: std::io::Error) {
+                display("The references could not be traversed to build the index")
+                from()
+                source(err)
+            }
+            Iter(err: file::iter::loose_then_packed::Error) {
+                display("A reference could not be read while building the index")
+                from()
+                source(err)
+            }
+            Find(err: file::find::Error) {
+                display("An indexed reference could not be looked up")
+                from()
+                source(err)
+            }
+        }
+    }
+}
+pub use error::Error;
+
+/// A lazily built index of reference names by the object they point to.
+#[derive(Debug, Default)]
+pub(crate) struct State {
+    /// Incremented each time references are changed through this store, invalidating the index.
+    generation: AtomicUsize,
+    index: MutableOnDemand<Option<Index>>,
+}
+
+#[derive(Debug)]
+struct Index {
+    /// The generation of the state at the time the index was built.
+    generation: usize,
+    /// The packed buffer the index was built from, to detect changes made by others.
+    packed: Option<OwnShared<packed::Buffer>>,
+    names_by_target: HashMap<ObjectId, Vec<FullName>>,
+}
+
+impl State {
+    /// Force the index to be rebuilt the next time it is used.
+    pub(crate) fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl file::Store {
+    /// Return an iterator over all references which directly point to `target`, similar to what `git log --decorate` displays
+    /// next to commits.
+    ///
+    /// Symbolic references are not followed, and annotated tags are returned if `target` is their tag object, not the object
+    /// they point to.
+    ///
+    /// ### Performance Note
+    ///
+    /// The first call builds an index of all references by their target which is reused by subsequent calls, until
+    /// references are changed by transactions of this store or one of its clones, or the packed-refs file changes.
+    /// Loose references created by other processes are not picked up until then, whereas indexed references that were
+    /// changed or deleted by others aren't returned.
+    pub fn iter_references_by_target(
+        &self,
+        target: &oid,
+    ) -> Result<impl Iterator<Item = Result<Reference, Error>> + '_, Error> {
+        let packed = self.assure_packed_refs_uptodate()?;
+        let generation = self.by_target.generation.load(Ordering::SeqCst);
+        let is_current = |index: &Index| {
+            index.generation == generation
+                && match (&index.packed, &packed) {
+                    (Some(indexed), Some(packed)) => OwnShared::ptr_eq(indexed, packed),
+                    (None, None) => true,
+                    _ => false,
+                }
+        };
+        let cached_names = {
+            let index = get_ref(&self.by_target.index);
+            index
+                .as_ref()
+                .filter(|index| is_current(index))
+                .map(|index| index.names_by_target.get(target).cloned().unwrap_or_default())
+        };
+        let names = match cached_names {
+            Some(names) => names,
+            None => {
+                let index = self.build_index_by_target(generation, packed.clone())?;
+                let names = index.names_by_target.get(target).cloned().unwrap_or_default();
+                *get_mut(&self.by_target.index) = Some(index);
+                names
+            }
+        };
+
+        let target = target.to_owned();
+        Ok(names.into_iter().filter_map(
+            move |name| match self.try_find_packed(name.as_ref(), packed.as_deref()) {
+                Ok(Some(reference)) => (reference.target == Target::Peeled(target)).then(|| Ok(reference)),
+                Ok(None) => None,
+                Err(err) => Some(Err(err.into())),
+            },
+        ))
+    }
+
+    fn build_index_by_target(
+        &self,
+        generation: usize,
+        packed: Option<OwnShared<packed::Buffer>>,
+    ) -> Result<Index, Error> {
+        let mut names_by_target = HashMap::<_, Vec<_>>::new();
+        for reference in self.iter_packed(packed.as_deref())? {
+            let reference = reference?;
+            if let Target::Peeled(id) = reference.target {
+                names_by_target.entry(id).or_default().push(reference.name);
+            }
+        }
+        Ok(Index {
+            generation,
+            packed,
+            names_by_target,
+        })
+    }
+}
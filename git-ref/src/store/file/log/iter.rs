@@ -77,6 +77,48 @@ impl<'a> Iterator for Forward<'a> {
     }
 }
 
+/// Returns a backward iterator over the given `lines`, starting from the last line in the file and ending at the first.
+///
+/// Note that `lines` are an entire reflog file. Use [`reverse()`] to iterate a log in reverse without reading it entirely.
+///
+/// It will continue parsing even if individual log entries failed to parse, leaving it to the driver to decide whether to
+/// abort or continue.
+pub fn backward(lines: &[u8]) -> Backward<'_> {
+    Backward {
+        remaining: lines,
+        count: 0,
+    }
+}
+
+/// An iterator yielding parsed lines in a file from end to start, newest to oldest.
+pub struct Backward<'a> {
+    remaining: &'a [u8],
+    count: usize,
+}
+
+impl<'a> Iterator for Backward<'a> {
+    type Item = Result<log::LineRef<'a>, decode::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = self.remaining.strip_suffix(b"\n").unwrap_or(self.remaining);
+        if remaining.is_empty() {
+            return None;
+        }
+        let (rest, line) = match remaining.rfind_byte(b'\n') {
+            Some(pos) => (&remaining[..=pos], &remaining[pos + 1..]),
+            None => (&[][..], remaining),
+        };
+        self.remaining = rest;
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let line_number = self.count;
+        self.count += 1;
+        Some(
+            log::LineRef::from_bytes(line)
+                .map_err(|err| decode::Error::new(err, decode::LineNumber::FromEnd(line_number))),
+        )
+    }
+}
+
 /// A platform to store a buffer to hold ref log lines for iteration.
 #[must_use = "Iterators should be obtained from this platform"]
 pub struct Platform<'a, 's> {
@@ -40,6 +40,7 @@ mod init {
                 write_reflog,
                 namespace: None,
                 packed: Default::default(),
+                by_target: Default::default(),
                 object_hash,
             }
         }
@@ -58,6 +59,7 @@ mod init {
                 write_reflog,
                 namespace: None,
                 packed: Default::default(),
+                by_target: Default::default(),
                 object_hash,
             }
         }
@@ -1,8 +1,12 @@
-use std::{convert::TryInto, io::Read, path::PathBuf};
+use std::{
+    convert::TryInto,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
 use crate::{
-    store_impl::{file, file::log},
-    FullNameRef,
+    store_impl::{file, file::log, file::loose::iter::SortedLoosePaths},
+    FullName, FullNameRef,
 };
 
 impl file::Store {
@@ -73,6 +77,27 @@ impl file::Store {
             Err(err) => Err(err.into()),
         }
     }
+
+    /// Return an iterator over the names of all references which have a reflog, along with the path to their log file,
+    /// sorted by name within the private and common reflog directories respectively.
+    ///
+    /// Each name can be passed to [`reflog_iter()`][file::Store::reflog_iter()] or [`reflog_iter_rev()`][file::Store::reflog_iter_rev()].
+    /// Log files that do not constitute valid reference names will be silently ignored.
+    pub fn iter_logs(&self) -> impl Iterator<Item = std::io::Result<(FullName, PathBuf)>> {
+        let logs_in = |base: &Path, root: PathBuf| {
+            let base = base.join("logs");
+            root.is_dir().then(|| SortedLoosePaths::at(root, base, None))
+        };
+        let git_dir_logs = logs_in(self.git_dir(), self.git_dir().join("logs"));
+        let common_dir_logs = self
+            .common_dir()
+            .and_then(|common_dir| logs_in(common_dir, common_dir.join("logs").join("refs")));
+        git_dir_logs
+            .into_iter()
+            .chain(common_dir_logs)
+            .flatten()
+            .map(|res| res.map(|(path, name)| (name, path)))
+    }
 }
 
 impl file::Store {
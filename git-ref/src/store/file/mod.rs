@@ -31,6 +31,8 @@ pub struct Store {
     /// It's updated only in one spot, which is prior to reading it based on file stamps.
     /// Doing it like this has the benefit of being able to hand snapshots out to people without blocking others from updating it.
     packed: OwnShared<MutableOnDemand<packed::modifiable::State>>,
+    /// An index of references by the object they point to, built on demand and shared among clones of this store.
+    by_target: OwnShared<by_target::State>,
 }
 
 mod access {
@@ -102,5 +104,11 @@ pub mod transaction;
 ///
 pub mod packed;
 
+///
+pub mod rename;
+
+///
+pub mod by_target;
+
 mod raw_ext;
 pub use raw_ext::ReferenceExt;
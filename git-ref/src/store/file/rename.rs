@@ -0,0 +1,171 @@
+use std::io::Write;
+
+use git_object::bstr::BString;
+
+use crate::{
+    store_impl::{file, file::WriteReflog},
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, FullNameRef, Target,
+};
+
+/// Options for use in [`file::Store::rename_reference()`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If true, symbolic references pointing to the renamed reference, like `HEAD`, will be changed to point to its new name.
+    pub update_symrefs: bool,
+    /// How to handle the case of a lock that can't be acquired right away.
+    pub lock_fail_mode: git_lock::acquire::Fail,
+}
+
+impl file::Store {
+    /// Rename the reference `old_name` to `new_name`, similar to `git branch -m`, and return the edit which created `new_name`.
+    ///
+    /// The new reference is created with the target of the old one while respecting `constraint`, and the old one is deleted,
+    /// both within a single transaction recording `log_message` on behalf of `committer`.
+    /// If [`Options::update_symrefs`] is set, symbolic references pointing to `old_name` are updated within the same transaction.
+    ///
+    /// The reflog of `old_name` is moved to `new_name` only once the transaction was committed successfully, so a failed
+    /// rename leaves all reflogs untouched.
+    pub fn rename_reference(
+        &self,
+        old_name: &FullNameRef,
+        new_name: FullName,
+        constraint: PreviousValue,
+        log_message: BString,
+        committer: git_actor::SignatureRef<'_>,
+        opts: Options,
+    ) -> Result<RefEdit, Error> {
+        let old = self.find(old_name)?;
+        let mut edits = vec![
+            RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: log_message.clone(),
+                    },
+                    expected: constraint,
+                    new: old.target.clone(),
+                },
+                name: new_name.clone(),
+                deref: false,
+            },
+            RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(old.target),
+                    log: RefLog::AndReference,
+                },
+                name: old.name,
+                deref: false,
+            },
+        ];
+
+        if opts.update_symrefs {
+            let head = self.try_find_loose("HEAD")?.map(crate::Reference::from);
+            for r in head.into_iter().map(Ok).chain(self.loose_iter()?) {
+                let r = r?;
+                if r.target.try_name() != Some(old_name.as_bstr()) {
+                    continue;
+                }
+                edits.push(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: log_message.clone(),
+                        },
+                        expected: PreviousValue::MustExistAndMatch(r.target),
+                        new: Target::Symbolic(new_name.clone()),
+                    },
+                    name: r.name,
+                    deref: false,
+                });
+            }
+        }
+
+        let old_log = match self.write_reflog {
+            WriteReflog::Normal => read_if_present(&self.reflog_path(old_name))?,
+            WriteReflog::Disable => None,
+        };
+        let edit = self
+            .transaction()
+            .prepare(edits, opts.lock_fail_mode)?
+            .commit(committer)?
+            .into_iter()
+            .find(|edit| edit.name == new_name)
+            .expect("the edit to create the new reference is always present");
+
+        // The commit deleted the old log, which now precedes the new one and its line of the rename.
+        if let Some(mut log) = old_log {
+            let new_log_path = self.reflog_path(new_name.as_ref());
+            if let Some(new_log) = read_if_present(&new_log_path)? {
+                log.extend_from_slice(&new_log);
+            }
+            let parent_dir = new_log_path.parent().expect("always with parent directory");
+            git_tempfile::create_dir::all(parent_dir, Default::default())?;
+            let mut lock = git_lock::File::acquire_to_update_resource(&new_log_path, opts.lock_fail_mode, None)?;
+            lock.write_all(&log)?;
+            lock.commit().map_err(|err| err.error)?;
+        }
+        Ok(edit)
+    }
+}
+
+fn read_if_present(path: &std::path::Path) -> std::io::Result<Option<Vec<u8>>> {
+    match std::fs::read(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(_) if path.is_dir() => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+mod error {
+    use quick_error::quick_error;
+
+    use crate::store_impl::file;
+
+    quick_error! {
+        /// The error returned by [`file::Store::rename_reference()`][crate::file::Store::rename_reference()].
+        #[derive(Debug)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            Find(err: file::find::existing::Error) {
+                display("The reference to rename could not be found")
+                from()
+                source(err)
+            }
+            FindSymbolicRef(err: file::find::Error) {
+                display("A symbolic reference to update could not be read")
+                from()
+                source(err)
+            }
+            IterSymbolicRefs(err: file::iter::loose_then_packed::Error) {
+                display("Symbolic references to update could not be traversed")
+                from()
+                source(err)
+            }
+            Prepare(err: file::transaction::prepare::Error) {
+                display("The rename transaction could not be prepared")
+                from()
+                source(err)
+            }
+            LockReflog(err: git_lock::acquire::Error) {
+                display("The lock for the reflog of the new reference could not be obtained")
+                from()
+                source(err)
+            }
+            Commit(err: file::transaction::commit::Error) {
+                display("The rename transaction could not be committed")
+                from()
+                source(err)
+            }
+            Io(err: std::io::Error) {
+                display("An IO error occurred while moving the reflog or listing references")
+                from()
+                source(err)
+            }
+        }
+    }
+}
+pub use error::Error;
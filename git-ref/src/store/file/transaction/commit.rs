@@ -24,6 +24,14 @@ impl<'s> Transaction<'s> {
     ///
     /// Note that transactions will be prepared automatically as needed.
     pub fn commit(self, committer: git_actor::SignatureRef<'_>) -> Result<Vec<RefEdit>, Error> {
+        let store = self.store;
+        let res = self.commit_inner(committer);
+        // even failed transactions may have changed references.
+        store.by_target.invalidate();
+        res
+    }
+
+    fn commit_inner(self, committer: git_actor::SignatureRef<'_>) -> Result<Vec<RefEdit>, Error> {
         let mut updates = self.updates.expect("BUG: must call prepare before commit");
         let delete_loose_refs = matches!(
             self.packed_refs,
@@ -43,7 +51,37 @@ impl<'s> Transaction<'s> {
                     };
                     if update_reflog {
                         match new {
-                            Target::Symbolic(_) => {} // no reflog for symref changes
+                            Target::Symbolic(new_referent) => {
+                                // like git, log a symbolic reference pointing to another existing referent, as
+                                // happens when `HEAD` is changed to another branch.
+                                let peeled_referent = |name: &crate::FullNameRef| {
+                                    self.store
+                                        .try_find(name)
+                                        .map(|r| r.and_then(|r| r.target.try_id().map(ToOwned::to_owned)))
+                                        .map_err(|err| Error::FindReferent {
+                                            full_name: name.as_bstr().to_owned(),
+                                            err,
+                                        })
+                                };
+                                if let Some(new_oid) = peeled_referent(new_referent.as_ref())? {
+                                    let previous = match expected {
+                                        PreviousValue::MustExistAndMatch(Target::Peeled(oid)) => Some(oid.to_owned()),
+                                        PreviousValue::MustExistAndMatch(Target::Symbolic(previous_referent)) => {
+                                            peeled_referent(previous_referent.as_ref())?
+                                        }
+                                        _ => None,
+                                    };
+                                    self.store.reflog_create_or_append(
+                                        change.update.name.as_ref(),
+                                        &lock,
+                                        previous,
+                                        &new_oid,
+                                        committer,
+                                        log.message.as_ref(),
+                                        log.force_create_reflog,
+                                    )?;
+                                }
+                            }
                             Target::Peeled(new_oid) => {
                                 let previous = match expected {
                                     PreviousValue::MustExistAndMatch(Target::Peeled(oid)) => Some(oid.to_owned()),
@@ -186,6 +224,10 @@ mod error {
                 display("The reflog of reference '{}' could not be deleted", full_name)
                 source(err)
             }
+            FindReferent{ full_name: BString, err: file::find::Error } {
+                display("The referent '{}' of a symbolic reference could not be read", full_name)
+                source(err)
+            }
             CreateOrUpdateRefLog(err: file::log::create_or_update::Error) {
                 display("The reflog could not be created or updated")
                 from()
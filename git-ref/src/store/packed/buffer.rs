@@ -15,6 +15,15 @@ impl AsRef<[u8]> for packed::Backing {
     }
 }
 
+impl packed::Buffer {
+    /// Return the modification time of the `packed-refs` file at the time it was opened, if the filesystem provides it.
+    ///
+    /// Compare it with the current modification time of the file to learn if this buffer is stale without reading it again.
+    pub fn mtime(&self) -> Option<std::time::SystemTime> {
+        self.mtime
+    }
+}
+
 ///
 pub mod open {
     use std::path::PathBuf;
@@ -31,8 +40,9 @@ pub mod open {
         /// If that's not the case, they will be sorted on the fly with the data being written into a memory buffer.
         pub fn open(path: impl Into<PathBuf>, use_memory_map_if_larger_than_bytes: u64) -> Result<Self, Error> {
             let path = path.into();
+            let metadata = std::fs::metadata(&path)?;
             let (backing, offset) = {
-                let backing = if std::fs::metadata(&path)?.len() <= use_memory_map_if_larger_than_bytes {
+                let backing = if metadata.len() <= use_memory_map_if_larger_than_bytes {
                     packed::Backing::InMemory(std::fs::read(&path)?)
                 } else {
                     packed::Backing::Mapped(
@@ -80,6 +90,7 @@ pub mod open {
                 offset,
                 data: backing,
                 path,
+                mtime: metadata.modified().ok(),
             })
         }
     }
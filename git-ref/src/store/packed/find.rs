@@ -70,6 +70,14 @@ impl packed::Buffer {
         }
     }
 
+    /// Return true if a reference with the full `name` exists, without decoding it.
+    ///
+    /// Like [`try_find()`][packed::Buffer::try_find()], it doesn't deal with namespaces, and parse failures are treated like
+    /// the reference doesn't exist.
+    pub fn contains(&self, name: &FullNameRef) -> bool {
+        transform_full_name_for_lookup(name).map_or(false, |name| self.binary_search_by(name.as_bstr()).is_ok())
+    }
+
     /// Perform a binary search where `Ok(pos)` is the beginning of the line that matches `name` perfectly and `Err(pos)`
     /// is the beginning of the line at which `name` could be inserted to still be in sort order.
     pub(in crate::store_impl::packed) fn binary_search_by(&self, full_name: &BStr) -> Result<usize, (bool, usize)> {
@@ -14,6 +14,9 @@ impl packed::Buffer {
     }
 
     /// Return an iterator yielding only references matching the given prefix, ordered by reference name.
+    ///
+    /// As the buffer is sorted, the first matching reference is found using a binary search, making the cost of
+    /// starting the iteration logarithmic in the amount of references.
     pub fn iter_prefixed(&self, prefix: impl Into<BString>) -> Result<packed::Iter<'_>, packed::iter::Error> {
         let prefix = prefix.into();
         let first_record_with_prefix = self.binary_search_by(prefix.as_bstr()).unwrap_or_else(|(_, pos)| pos);
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::SystemTime};
 
 use git_features::threading::OwnShared;
 use git_hash::ObjectId;
@@ -25,6 +25,8 @@ pub struct Buffer {
     offset: usize,
     /// The path from which we were loaded
     path: PathBuf,
+    /// The modification time of the file at `path` when it was loaded, if the filesystem provides it.
+    mtime: Option<SystemTime>,
 }
 
 struct Edit {
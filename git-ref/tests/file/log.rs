@@ -46,6 +46,32 @@ mod iter {
     }
 
     mod backward {
+        use crate::file::log::iter::reflog;
+
+        #[test]
+        fn in_memory_yields_lines_in_reverse() -> crate::Result {
+            let log = reflog("HEAD")?;
+            let mut forward = git_ref::file::log::iter::forward(&log).collect::<Result<Vec<_>, _>>()?;
+            forward.reverse();
+            assert_eq!(
+                git_ref::file::log::iter::backward(&log).collect::<Result<Vec<_>, _>>()?,
+                forward
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn in_memory_a_single_failure_does_not_abort_iteration() {
+            let log_last_broken = "0000000000000000000000000000000000000000 134385f6d781b7e97062102c6a483440bfda2a03 committer <committer@example.com> 946771200 +0000	commit (initial): c1
+0000000000000000000000000000000000000000 134385fbroken7062102c6a483440bfda2a03 committer <committer@example.com> 946771200 +0000	commit";
+
+            let mut iter = git_ref::file::log::iter::backward(log_last_broken.as_bytes());
+            let err = iter.next().expect("error is not none").expect_err("the line is broken");
+            assert!(err.to_string().starts_with("In line 1 from the end: "));
+            assert!(iter.next().expect("a second line").is_ok(), "line parses ok");
+            assert!(iter.next().is_none(), "iterator exhausted");
+        }
+
         mod with_zero_sized_buffer {
 
             #[test]
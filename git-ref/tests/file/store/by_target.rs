@@ -0,0 +1,50 @@
+use std::convert::TryInto;
+
+use git_ref::{file::rename, transaction::PreviousValue, FullName, Target};
+
+use crate::file::{store_writable, transaction::prepare_and_commit::committer};
+
+fn names_pointing_to(store: &crate::file::Store, id: &git_hash::oid) -> crate::Result<Vec<FullName>> {
+    let mut names = store
+        .iter_references_by_target(id)?
+        .map(|r| r.map(|r| r.name))
+        .collect::<Result<Vec<_>, _>>()?;
+    names.sort();
+    Ok(names)
+}
+
+#[test]
+fn finds_all_references_directly_pointing_to_an_object_and_picks_up_changes() -> crate::Result {
+    let (_keep, store) = store_writable("make_ref_repository.sh")?;
+    let id = store.find("main")?.target.into_id();
+    let mut expected = store
+        .iter()?
+        .all()?
+        .map(Result::unwrap)
+        .filter(|r| r.target == Target::Peeled(id))
+        .map(|r| r.name)
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert!(expected.iter().any(|name| name.as_bstr() == "refs/heads/main"));
+    assert_eq!(names_pointing_to(&store, &id)?, expected);
+    assert_eq!(names_pointing_to(&store, &id)?, expected, "the cached index is used");
+
+    let old_name: FullName = "refs/heads/main".try_into()?;
+    store.rename_reference(
+        old_name.as_ref(),
+        "refs/heads/renamed".try_into()?,
+        PreviousValue::MustNotExist,
+        "rename".into(),
+        committer().to_ref(),
+        rename::Options::default(),
+    )?;
+    let names = names_pointing_to(&store, &id)?;
+    assert!(
+        names.iter().any(|name| name.as_bstr() == "refs/heads/renamed"),
+        "changes made through the store are picked up"
+    );
+    assert!(names.iter().all(|name| name.as_bstr() != "refs/heads/main"));
+
+    assert!(names_pointing_to(&store, &git_hash::ObjectId::null(git_hash::Kind::Sha1))?.is_empty());
+    Ok(())
+}
@@ -1,3 +1,5 @@
+mod by_target;
 mod find;
 mod iter;
 mod reflog;
+mod rename;
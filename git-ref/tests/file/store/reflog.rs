@@ -65,3 +65,23 @@ mod iter_rev {
         Ok(())
     }
 }
+
+#[test]
+fn iter_logs() -> crate::Result {
+    let store = store()?;
+    let logs = store.iter_logs().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        logs.iter().map(|(name, _)| name.as_bstr()).collect::<Vec<_>>(),
+        vec!["HEAD", "refs/heads/main"]
+    );
+
+    let mut buf = Vec::new();
+    for (name, path) in logs {
+        assert!(path.is_file());
+        assert!(
+            store.reflog_iter(name.as_ref(), &mut buf)?.is_some(),
+            "each name can be used to read the log"
+        );
+    }
+    Ok(())
+}
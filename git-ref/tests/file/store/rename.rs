@@ -0,0 +1,100 @@
+use std::convert::TryInto;
+
+use git_ref::{file::rename, transaction::PreviousValue, FullName, Target};
+
+use crate::file::{store_writable, transaction::prepare_and_commit::committer};
+
+#[test]
+fn branch_with_symrefs_and_reflog() -> crate::Result {
+    let (_keep, store) = store_writable("make_ref_repository.sh")?;
+    let old_name: FullName = "refs/heads/main".try_into()?;
+    let old_target = store.find("main")?.target;
+    let mut buf = Vec::new();
+    let old_log_len = store.reflog_iter("refs/heads/main", &mut buf)?.expect("exists").count();
+
+    let edit = store.rename_reference(
+        old_name.as_ref(),
+        "refs/heads/renamed".try_into()?,
+        PreviousValue::MustNotExist,
+        "Branch: renamed refs/heads/main to refs/heads/renamed".into(),
+        committer().to_ref(),
+        rename::Options {
+            update_symrefs: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(edit.name.as_bstr(), "refs/heads/renamed");
+
+    assert!(
+        store.try_find("refs/heads/main")?.is_none(),
+        "the old reference is gone"
+    );
+    assert_eq!(store.find("refs/heads/renamed")?.target, old_target);
+    assert_eq!(
+        store.find_loose("HEAD")?.target,
+        Target::Symbolic("refs/heads/renamed".try_into()?),
+        "HEAD now points to the new name"
+    );
+
+    assert!(store.reflog_iter("refs/heads/main", &mut buf)?.is_none());
+    let lines = store
+        .reflog_iter("refs/heads/renamed", &mut buf)?
+        .expect("moved")
+        .map(|l| l.map(git_ref::log::Line::from))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        lines.len(),
+        old_log_len + 1,
+        "the old log is kept and the rename is appended"
+    );
+    assert_eq!(
+        lines.last().expect("present").message,
+        "Branch: renamed refs/heads/main to refs/heads/renamed"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_source_fails() -> crate::Result {
+    let (_keep, store) = store_writable("make_ref_repository.sh")?;
+    let old_name: FullName = "refs/heads/does-not-exist".try_into()?;
+    assert!(matches!(
+        store.rename_reference(
+            old_name.as_ref(),
+            "refs/heads/renamed".try_into()?,
+            PreviousValue::MustNotExist,
+            "rename".into(),
+            committer().to_ref(),
+            Default::default(),
+        ),
+        Err(rename::Error::Find(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn failing_renames_leave_reflogs_untouched() -> crate::Result {
+    let (_keep, store) = store_writable("make_ref_repository.sh")?;
+    let old_name: FullName = "refs/heads/main".try_into()?;
+    let log_of = |name: &str| -> std::io::Result<Vec<u8>> { std::fs::read(store.git_dir().join("logs").join(name)) };
+    let (main_log, d1_log) = (log_of("refs/heads/main")?, log_of("refs/heads/d1")?);
+
+    assert!(matches!(
+        store.rename_reference(
+            old_name.as_ref(),
+            "refs/heads/d1".try_into()?,
+            PreviousValue::MustNotExist,
+            "rename".into(),
+            committer().to_ref(),
+            Default::default(),
+        ),
+        Err(rename::Error::Prepare(_))
+    ));
+    assert_eq!(log_of("refs/heads/main")?, main_log);
+    assert_eq!(
+        log_of("refs/heads/d1")?,
+        d1_log,
+        "the log of the existing reference doesn't receive the log to move"
+    );
+    Ok(())
+}
@@ -604,6 +604,46 @@ fn write_reference_to_which_head_points_to_does_not_update_heads_reflog_even_tho
     Ok(())
 }
 
+#[test]
+fn pointing_head_to_another_existing_referent_is_logged() -> crate::Result {
+    let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+    let previous_id = hex_to_id("02a7a22d90d7c02fb494ed25551850b868e634f0");
+    let new_id = hex_to_id("01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc");
+    std::fs::write(store.git_dir().join("refs/heads/other"), format!("{}\n", new_id))?;
+    store
+        .transaction()
+        .prepare(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "checkout: moving from main to other".into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Symbolic("refs/heads/other".try_into()?),
+                },
+                name: "HEAD".try_into()?,
+                deref: false,
+            }),
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+
+    let head = store.find_loose("HEAD")?;
+    assert_eq!(head.kind(), git_ref::Kind::Symbolic, "HEAD is still symbolic");
+    assert_eq!(
+        reflog_lines(&store, "HEAD")?.last().expect("at least one line"),
+        &log_line(previous_id, new_id, "checkout: moving from main to other"),
+        "the ids of the previous and the new referent are logged"
+    );
+    assert!(
+        !store.reflog_exists("refs/heads/other")?,
+        "the new referent itself is not logged"
+    );
+    Ok(())
+}
+
 #[test]
 fn packed_refs_are_looked_up_when_checking_existing_values() -> crate::Result {
     let (_keep, store) = store_writable("make_packed_ref_repository.sh")?;
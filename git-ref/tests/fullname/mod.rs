@@ -8,6 +8,35 @@ fn cow() {
     fn _pn(_x: Cow<'_, PartialNameRef>) {}
 }
 
+#[test]
+fn partial_name_from_path_component() {
+    use git_ref::bstr::ByteSlice;
+
+    assert_eq!(
+        PartialNameRef::from_path_component(b"feature".as_bstr())
+            .expect("valid")
+            .as_bstr(),
+        "feature"
+    );
+    for with_slash in ["feature/sub", "/feature", "feature/"] {
+        assert!(
+            matches!(
+                PartialNameRef::from_path_component(with_slash.as_bytes().as_bstr()),
+                Err(git_validate::refname::Error::SlashInComponent)
+            ),
+            "{:?} contains a slash",
+            with_slash
+        );
+    }
+    for invalid in ["a..b", "a\0b", ".", "..", "", "a b", "a.lock"] {
+        assert!(
+            PartialNameRef::from_path_component(invalid.as_bytes().as_bstr()).is_err(),
+            "{:?} is not a valid path component",
+            invalid
+        );
+    }
+}
+
 #[test]
 fn file_name() {
     let name: git_ref::FullName = "refs/heads/main".try_into().unwrap();
@@ -119,3 +148,25 @@ fn prefix_with_namespace_and_stripping() {
         "idempotent stripping"
     );
 }
+
+#[test]
+fn with_and_without_namespace() {
+    let name: git_ref::FullName = "refs/heads/main".try_into().unwrap();
+    let ns = git_ref::namespace::expand("foo").unwrap();
+    let namespaced = name.with_namespace(&ns);
+    assert_eq!(namespaced.as_bstr(), "refs/namespaces/foo/refs/heads/main");
+    assert_eq!(namespaced.without_namespace(&ns), Some(name.clone()));
+    assert_eq!(
+        namespaced.with_namespace(&ns).as_bstr(),
+        "refs/namespaces/foo/refs/namespaces/foo/refs/heads/main",
+        "the namespace is always prepended"
+    );
+
+    assert_eq!(name.without_namespace(&ns), None, "the name isn't in the namespace");
+    let other_ns = git_ref::namespace::expand("foo/bar").unwrap();
+    assert_eq!(
+        namespaced.without_namespace(&other_ns),
+        None,
+        "the namespace must match entirely"
+    );
+}
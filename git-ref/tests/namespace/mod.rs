@@ -67,3 +67,54 @@ mod expand {
         ));
     }
 }
+
+mod from_str {
+    use std::str::FromStr;
+
+    use git_ref::{namespace::from_str::Error, Namespace};
+
+    #[test]
+    fn names_are_expanded() {
+        let ns = Namespace::from_str("foo/bar").unwrap();
+        assert_eq!(ns.as_bstr(), "refs/namespaces/foo/refs/namespaces/bar/");
+        assert_eq!(
+            ns.as_partial_name().as_bstr(),
+            "refs/namespaces/foo/refs/namespaces/bar",
+            "the trailing slash isn't part of the partial name"
+        );
+    }
+
+    #[test]
+    fn expanded_namespaces_are_kept_with_or_without_trailing_slash() {
+        for input in [
+            "refs/namespaces/foo/refs/namespaces/bar",
+            "refs/namespaces/foo/refs/namespaces/bar/",
+        ] {
+            assert_eq!(
+                Namespace::from_str(input).unwrap().as_bstr(),
+                "refs/namespaces/foo/refs/namespaces/bar/"
+            );
+        }
+    }
+
+    #[test]
+    fn partially_prefixed_namespaces_are_ambiguous() {
+        for input in ["refs/namespaces/foo/bar", "refs/namespaces/foo/refs/bar"] {
+            assert!(matches!(
+                Namespace::from_str(input),
+                Err(Error::PartiallyPrefixed(name)) if name == input
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        for input in ["/foo", "foo\\bar", "foo..bar", "", "foo/", "refs/namespaces//foo"] {
+            assert!(
+                matches!(Namespace::from_str(input), Err(Error::InvalidName(_))),
+                "{:?} should be invalid",
+                input
+            );
+        }
+    }
+}
@@ -27,6 +27,7 @@ fn all_iterable_refs_can_be_found() -> crate::Result {
         assert_eq!(reference, found, "both refs are exactly the same");
         let found = packed_refs.find(reference.name)?;
         assert_eq!(reference, found);
+        assert!(packed_refs.contains(reference.name));
     }
     Ok(())
 }
@@ -35,6 +36,7 @@ fn all_iterable_refs_can_be_found() -> crate::Result {
 fn binary_search_a_name_past_the_end_of_the_packed_refs_file() -> crate::Result {
     let packed_refs = packed::Buffer::open(fixture_path("packed-refs").join("triggers-out-of-bounds"), 32)?;
     assert!(packed_refs.try_find("v0.0.1")?.is_none());
+    assert!(!packed_refs.contains("refs/tags/v0.0.1".try_into()?));
     Ok(())
 }
 
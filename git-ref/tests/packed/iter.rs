@@ -77,6 +77,14 @@ fn iter_prefix() -> crate::Result {
         vec![first_ref_in_file.as_bytes().as_bstr()],
         "prefixes which are a ref also work, and this one at the beginning of the file"
     );
+
+    for prefix_without_match in ["refs/heads/e", "refs/a", "refs/zzz"] {
+        assert_eq!(
+            packed.iter_prefixed(prefix_without_match)?.count(),
+            0,
+            "prefixes sorting between, before or after all records yield nothing"
+        );
+    }
     Ok(())
 }
 
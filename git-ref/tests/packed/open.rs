@@ -10,6 +10,14 @@ fn sorted_buffer_works() {
     store.open_packed_buffer().unwrap();
 }
 
+#[test]
+fn the_modification_time_is_recorded() -> crate::Result {
+    let (_keep, path) = write_packed_refs_with(&[])?;
+    let expected = std::fs::metadata(&path)?.modified()?;
+    assert_eq!(git_ref::packed::Buffer::open(path, 512)?.mtime(), Some(expected));
+    Ok(())
+}
+
 #[test]
 fn empty_buffers_should_not_exist_but_are_fine_to_open() -> crate::Result {
     let (_keep, path) = write_packed_refs_with(&[])?;
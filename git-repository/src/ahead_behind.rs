@@ -0,0 +1,53 @@
+//! Count how many commits two commits are ahead of and behind each other.
+use std::collections::HashMap;
+
+use git_hash::ObjectId;
+
+/// The error returned by [`Repository::compute_ahead_behind()`][crate::Repository::compute_ahead_behind()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindCommit(#[from] git_odb::find::existing_iter::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[cfg(feature = "git-commitgraph")]
+    #[error("The commit-graph could not be read")]
+    CommitGraph(#[from] git_commitgraph::file::commit::Error),
+}
+
+/// The results of previous computations, keyed by the `local` and `upstream` commits.
+pub(crate) type Cache = HashMap<(ObjectId, ObjectId), (usize, usize)>;
+
+/// The amount of results to keep in the cache before it is cleared.
+const MAX_CACHED_RESULTS: usize = 64;
+
+impl crate::Repository {
+    /// Return `(ahead, behind)`, the amount of commits reachable from `local` but not from `upstream`, and the amount of commits
+    /// reachable from `upstream` but not from `local`, similar to what `git rev-list --left-right --count local...upstream`
+    /// prints and what `git status` uses to report that a branch is ahead of its upstream.
+    ///
+    /// Commits are traversed newest first until only commits reachable from both sides, i.e. their merge bases and their
+    /// ancestors, remain. Commits in the commit-graph are ordered by their generation number, which makes the result exact
+    /// and stops the traversal as early as possible. All other commits are ordered by their committer time, making the result
+    /// subject to clock skew like it is in `git`.
+    ///
+    /// Results are cached for the lifetime of this instance, as they never change for the same inputs.
+    pub fn compute_ahead_behind(&self, local: ObjectId, upstream: ObjectId) -> Result<(usize, usize), Error> {
+        if let Some(counts) = self.ahead_behind.borrow().get(&(local, upstream)) {
+            return Ok(*counts);
+        }
+        let counts = self.compute_ahead_behind_uncached(local, upstream)?;
+        let mut cache = self.ahead_behind.borrow_mut();
+        if cache.len() >= MAX_CACHED_RESULTS {
+            cache.clear();
+        }
+        cache.insert((local, upstream), counts);
+        Ok(counts)
+    }
+
+    fn compute_ahead_behind_uncached(&self, local: ObjectId, upstream: ObjectId) -> Result<(usize, usize), Error> {
+        let (ahead, behind) = self.commits().left_right::<Error>(&[local], &[upstream])?;
+        Ok((ahead.len(), behind.len()))
+    }
+}
@@ -0,0 +1,648 @@
+//! Apply patches to the working tree, similar to `git apply`.
+use std::path::{Path, PathBuf};
+
+use git_index::entry::Mode;
+use git_object::bstr::{BStr, BString, ByteSlice, ByteVec};
+use git_odb::FindExt;
+
+use crate::{blob_diff::lines, conflict_file, format_patch::BASE85_ALPHABET, index_diff::mode_of, restore::write_file};
+
+/// Options for use in [`Repository::apply_patch()`][crate::Repository::apply_patch()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If true, hunks that can't be applied are written to a `<path>.rej` file while all other hunks are applied, similar to
+    /// `git apply --reject`. Otherwise no file is changed if any hunk can't be applied.
+    pub reject: bool,
+}
+
+/// The result of [`Repository::apply_patch()`][crate::Repository::apply_patch()].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of hunks that were applied, with each binary patch counting as one hunk.
+    pub applied_hunks: usize,
+    /// The amount of hunks that couldn't be applied and were written to `.rej` files instead.
+    pub rejected_hunks: usize,
+    /// The paths of all files that were changed, created or deleted, in the order in which they appear in the patch.
+    pub modified_files: Vec<BString>,
+    /// The paths of files whose changes conflicted when merging them with the three-way fallback, and which now contain
+    /// conflict markers.
+    pub conflicting_files: Vec<BString>,
+}
+
+/// The error returned by [`Repository::apply_patch()`][crate::Repository::apply_patch()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to apply patches to")]
+    MissingWorkTree,
+    #[error("The patch is malformed at line {line}: {message}")]
+    Parse { line: usize, message: &'static str },
+    #[error("The input doesn't contain any patches")]
+    NoPatch,
+    #[error("Hunk #{hunk} can't be applied to '{path}'")]
+    HunkFailed { path: BString, hunk: usize },
+    #[error("'{path}' already exists in the working tree")]
+    AlreadyExists { path: BString },
+    #[error("'{path}' doesn't exist in the working tree")]
+    DoesNotExist { path: BString },
+    #[error("The binary patch for '{path}' can't be applied as it contains no data, or a delta")]
+    UnsupportedBinaryPatch { path: BString },
+    #[error(transparent)]
+    PathTraversal(#[from] git_sec::permission::TraversalError),
+    #[error(transparent)]
+    FindBlob(#[from] git_odb::find::existing_object::Error<git_odb::store::find::Error>),
+    #[error("Could not access '{}' in the working tree", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+/// The changes to a single file, as parsed from a patch.
+#[derive(Default)]
+struct FilePatch<'a> {
+    /// The path of the file before the change, or `None` if it's created.
+    old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if it's deleted.
+    new_path: Option<BString>,
+    new_mode: Option<Mode>,
+    /// The possibly abbreviated hexadecimal id of the blob the patch was created from.
+    old_id: Option<&'a BStr>,
+    /// If true, the file at `old_path` is kept instead of renamed.
+    is_copy: bool,
+    hunks: Vec<Hunk<'a>>,
+    binary: Option<Binary>,
+}
+
+/// A binary patch.
+enum Binary {
+    /// The complete content of the file after the change.
+    Literal(Vec<u8>),
+    /// A delta, or a patch that only states that binary files differ.
+    Unsupported,
+}
+
+struct Hunk<'a> {
+    /// The 1-based number of the first line in `old`, or the number of the line after which `new` is inserted if `old`
+    /// is empty.
+    old_start: usize,
+    /// The context and removed lines.
+    old: Vec<&'a [u8]>,
+    /// The context and added lines.
+    new: Vec<&'a [u8]>,
+    /// The hunk as it appears in the patch, including its header.
+    text: &'a [u8],
+}
+
+/// A change to the working tree, which is only made once all patches are known to apply.
+enum Change {
+    Write { path: BString, mode: Mode, data: Vec<u8> },
+    Delete { path: BString },
+}
+
+impl crate::Repository {
+    /// Apply `patch`, which contains changes in the unified diff format like produced by `git diff` or `git format-patch`, to
+    /// the files in the working tree, similar to `git apply --3way`.
+    ///
+    /// Each hunk is applied where its context and removed lines match exactly, closest to the line it was created for.
+    /// If that's impossible as the file changed since, but the blob the patch was created from is known from its `index` line
+    /// and present in the object database, the change is merged into the file by three-way merge between the versions of the
+    /// file before and after the patch, leaving conflict markers where changes conflict.
+    /// If hunks still can't be applied, no file is changed unless [`Options::reject`] is set.
+    ///
+    /// Binary patches are supported if they contain the new content literally, like those produced by
+    /// [`generate_patch()`][crate::Repository::generate_patch()].
+    ///
+    /// Note that paths in the patch have their first component removed like `git apply -p1`, and that the index isn't
+    /// changed.
+    pub fn apply_patch(&self, patch: &[u8], opts: Options) -> Result<Outcome, Error> {
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        let files = parse(patch)?;
+        if files.is_empty() {
+            return Err(Error::NoPatch);
+        }
+
+        let mut outcome = Outcome::default();
+        let mut changes = Vec::new();
+        for file in &files {
+            let path = file
+                .new_path
+                .as_ref()
+                .or_else(|| file.old_path.as_ref())
+                .expect("parsing assures there is at least one path");
+            for path in file.old_path.iter().chain(file.new_path.iter()) {
+                git_sec::permission::check_path_traversal(work_dir, &git_path::from_bstr(path.as_bstr()))?;
+            }
+            let (current, current_mode) = match &file.old_path {
+                Some(old_path) => {
+                    let (data, mode) =
+                        read_file(work_dir, old_path)?.ok_or_else(|| Error::DoesNotExist { path: old_path.clone() })?;
+                    (data, Some(mode))
+                }
+                None => (Vec::new(), None),
+            };
+            if let Some(new_path) = &file.new_path {
+                if file.old_path.as_ref() != Some(new_path) && read_file(work_dir, new_path)?.is_some() {
+                    return Err(Error::AlreadyExists { path: new_path.clone() });
+                }
+            }
+
+            let mut rejected = Vec::new();
+            let data = match &file.binary {
+                Some(Binary::Literal(data)) => {
+                    if let (Some(_), Some(old_id)) = (&file.old_path, file.old_id) {
+                        use git_odb::Write;
+                        let id = git_odb::sink(self.object_hash())
+                            .write_buf(git_object::Kind::Blob, &current)
+                            .expect("hashing into a sink can't fail");
+                        if !id.to_hex().to_string().starts_with(old_id.to_str_lossy().as_ref()) {
+                            return Err(Error::HunkFailed {
+                                path: path.clone(),
+                                hunk: 1,
+                            });
+                        }
+                    }
+                    data.clone()
+                }
+                Some(Binary::Unsupported) => return Err(Error::UnsupportedBinaryPatch { path: path.clone() }),
+                None => {
+                    let (mut data, failed) = apply_hunks(&current, &file.hunks);
+                    rejected = failed;
+                    if !rejected.is_empty() {
+                        if let Some(preimage) = self.preimage(file.old_id)? {
+                            let (postimage, failed) = apply_hunks(&preimage, &file.hunks);
+                            if failed.is_empty() {
+                                let (merged, has_conflicts) = conflict_file::merge(
+                                    &preimage,
+                                    &current,
+                                    &postimage,
+                                    conflict_file::Style::Merge,
+                                    &Default::default(),
+                                );
+                                data = merged;
+                                rejected.clear();
+                                if has_conflicts {
+                                    outcome.conflicting_files.push(path.clone());
+                                }
+                            }
+                        }
+                    }
+                    if !rejected.is_empty() && !opts.reject {
+                        return Err(Error::HunkFailed {
+                            path: path.clone(),
+                            hunk: rejected[0] + 1,
+                        });
+                    }
+                    data
+                }
+            };
+            let hunks = if file.binary.is_some() { 1 } else { file.hunks.len() };
+            outcome.applied_hunks += hunks - rejected.len();
+            outcome.rejected_hunks += rejected.len();
+
+            if !rejected.is_empty() {
+                let mut rej = BString::from(format!(
+                    "diff a/{} b/{}\t(rejected hunks)\n",
+                    file.old_path.as_ref().unwrap_or(path),
+                    path
+                ));
+                for index in &rejected {
+                    rej.push_str(file.hunks[*index].text);
+                }
+                let mut rej_path = path.clone();
+                rej_path.push_str(".rej");
+                changes.push(Change::Write {
+                    path: rej_path,
+                    mode: Mode::FILE,
+                    data: rej.into(),
+                });
+            }
+
+            let mode = file.new_mode.or(current_mode).unwrap_or(Mode::FILE);
+            match (&file.old_path, &file.new_path) {
+                (Some(old_path), Some(new_path)) if old_path == new_path => {
+                    if data == current && Some(mode) == current_mode {
+                        continue;
+                    }
+                }
+                (Some(old_path), _) if file.is_copy || (file.new_path.is_none() && !rejected.is_empty()) => {
+                    // keep the source of copies, and files whose deletion was partially rejected.
+                    if file.new_path.is_none() {
+                        changes.push(Change::Write {
+                            path: old_path.clone(),
+                            mode,
+                            data,
+                        });
+                        outcome.modified_files.push(path.clone());
+                        continue;
+                    }
+                }
+                (Some(old_path), _) => changes.push(Change::Delete { path: old_path.clone() }),
+                (None, _) => {}
+            }
+            if let Some(new_path) = &file.new_path {
+                changes.push(Change::Write {
+                    path: new_path.clone(),
+                    mode,
+                    data,
+                });
+            }
+            outcome.modified_files.push(path.clone());
+        }
+
+        for change in changes {
+            match change {
+                Change::Write { path, mode, data } => {
+                    let file_path = work_dir.join(git_path::from_bstr(path.as_bstr()));
+                    write_file(&file_path, mode, &data).map_err(|source| Error::Io {
+                        path: file_path,
+                        source,
+                    })?;
+                }
+                Change::Delete { path } => {
+                    let file_path = work_dir.join(git_path::from_bstr(path.as_bstr()));
+                    std::fs::remove_file(&file_path).map_err(|source| Error::Io {
+                        path: file_path.clone(),
+                        source,
+                    })?;
+                    // like `git`, remove directories that became empty.
+                    let mut dir = file_path.parent();
+                    while let Some(parent) = dir.filter(|dir| *dir != work_dir) {
+                        if std::fs::remove_dir(parent).is_err() {
+                            break;
+                        }
+                        dir = parent.parent();
+                    }
+                }
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Return the content of the blob whose hexadecimal id starts with `id`, if it's known and present.
+    fn preimage(&self, id: Option<&BStr>) -> Result<Option<Vec<u8>>, Error> {
+        let id = match id.and_then(|id| id.to_str().ok()) {
+            Some(id) if id.bytes().any(|b| b != b'0') => id,
+            _ => return Ok(None),
+        };
+        let id = match self.find_unique_prefix(id) {
+            Ok(id) => id,
+            Err(_) => return Ok(None),
+        };
+        let mut buf = Vec::new();
+        Ok(Some(self.objects.find_blob(id, &mut buf)?.data.to_owned()))
+    }
+}
+
+/// Read the file or symbolic link at `path` in `work_dir` along with its mode, or return `None` if it doesn't exist.
+fn read_file(work_dir: &Path, path: &BStr) -> Result<Option<(Vec<u8>, Mode)>, Error> {
+    let file_path = work_dir.join(git_path::from_bstr(path));
+    let io_err = |source| Error::Io {
+        path: file_path.clone(),
+        source,
+    };
+    let metadata = match std::fs::symlink_metadata(&file_path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(io_err(err)),
+    };
+    let data = if metadata.file_type().is_symlink() {
+        git_path::into_bstr(std::fs::read_link(&file_path).map_err(io_err)?)
+            .into_owned()
+            .into()
+    } else {
+        std::fs::read(&file_path).map_err(io_err)?
+    };
+    Ok(Some((data, mode_of(&metadata, Mode::FILE))))
+}
+
+/// Apply `hunks` to `content`, with each hunk applied where its old lines match exactly, closest to where it expects them.
+///
+/// Return the new content and the indices of all hunks that couldn't be applied.
+fn apply_hunks(content: &[u8], hunks: &[Hunk<'_>]) -> (Vec<u8>, Vec<usize>) {
+    let lines = lines(content);
+    let mut out = Vec::with_capacity(content.len());
+    let mut rejected = Vec::new();
+    // the offset of the lines in `content` compared to the line numbers in the patch, as found by previous hunks.
+    let (mut pos, mut offset) = (0, 0isize);
+    for (index, hunk) in hunks.iter().enumerate() {
+        let expected = if hunk.old.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        match find_lines(&lines, &hunk.old, pos, expected as isize + offset) {
+            Some(at) => {
+                lines[pos..at].iter().for_each(|line| out.extend_from_slice(line));
+                hunk.new.iter().for_each(|line| out.extend_from_slice(line));
+                pos = at + hunk.old.len();
+                offset = at as isize - expected as isize;
+            }
+            None => rejected.push(index),
+        }
+    }
+    lines[pos..].iter().for_each(|line| out.extend_from_slice(line));
+    (out, rejected)
+}
+
+/// Return the position of `needle` in `lines` at or after `min_pos` that is closest to `expected`.
+fn find_lines(lines: &[&[u8]], needle: &[&[u8]], min_pos: usize, expected: isize) -> Option<usize> {
+    let max_pos = lines.len().checked_sub(needle.len())?;
+    if min_pos > max_pos {
+        return None;
+    }
+    let expected = expected.clamp(min_pos as isize, max_pos as isize) as usize;
+    let matches = |at: usize| lines[at..at + needle.len()] == *needle;
+    for distance in 0..=(max_pos - min_pos) {
+        if expected + distance <= max_pos && matches(expected + distance) {
+            return Some(expected + distance);
+        }
+        if expected >= min_pos + distance && matches(expected - distance) {
+            return Some(expected - distance);
+        }
+    }
+    None
+}
+
+/// The lines of a patch, along with the number of the line that was read last.
+struct Lines<'a> {
+    data: &'a [u8],
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn peek(&self) -> Option<&'a [u8]> {
+        let rest = &self.data[self.pos..];
+        (!rest.is_empty()).then(|| &rest[..rest.find_byte(b'\n').map_or(rest.len(), |pos| pos + 1)])
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let line = self.peek()?;
+        self.pos += line.len();
+        self.line += 1;
+        Some(line)
+    }
+
+    fn peek_starts_with(&self, prefix: &[u8]) -> bool {
+        self.peek().map_or(false, |line| line.starts_with(prefix))
+    }
+
+    fn error(&self, message: &'static str) -> Error {
+        Error::Parse {
+            line: self.line,
+            message,
+        }
+    }
+}
+
+/// Parse all file patches in `patch`, ignoring everything that isn't part of them like the headers of patch emails.
+fn parse(patch: &[u8]) -> Result<Vec<FilePatch<'_>>, Error> {
+    let mut lines = Lines {
+        data: patch,
+        pos: 0,
+        line: 0,
+    };
+    let mut files = Vec::new();
+    while let Some(line) = lines.next() {
+        let mut file = FilePatch::default();
+        let minus_line = if let Some(paths) = line.strip_prefix(b"diff --git ") {
+            let path = path_from_git_header(trim_newline(paths));
+            file.old_path = path.clone();
+            file.new_path = path;
+            parse_extended_headers(&mut lines, &mut file)?;
+            if lines.peek_starts_with(b"--- ") {
+                lines.next()
+            } else {
+                None
+            }
+        } else if line.starts_with(b"--- ") && lines.peek_starts_with(b"+++ ") {
+            Some(line)
+        } else {
+            continue;
+        };
+        if let Some(minus_line) = minus_line {
+            let plus_line = lines
+                .next()
+                .filter(|line| line.starts_with(b"+++ "))
+                .ok_or_else(|| lines.error("expected a line starting with '+++'"))?;
+            file.old_path = path_from_file_line(&minus_line[4..]);
+            file.new_path = path_from_file_line(&plus_line[4..]);
+        }
+        if file.old_path.is_none() && file.new_path.is_none() {
+            return Err(lines.error("the names of the changed file are unknown"));
+        }
+
+        if lines.peek() == Some(b"GIT binary patch\n") {
+            lines.next();
+            file.binary = Some(parse_binary_hunk(&mut lines)?);
+            if lines.peek_starts_with(b"literal ") || lines.peek_starts_with(b"delta ") {
+                // the reverse change isn't needed.
+                parse_binary_hunk(&mut lines)?;
+            }
+        }
+        while lines.peek_starts_with(b"@@ -") {
+            file.hunks.push(parse_hunk(&mut lines)?);
+        }
+        files.push(file);
+    }
+    Ok(files)
+}
+
+fn parse_extended_headers<'a>(lines: &mut Lines<'a>, file: &mut FilePatch<'a>) -> Result<(), Error> {
+    while let Some(line) = lines.peek() {
+        let line = trim_newline(line);
+        let value = |prefix: &str| line.strip_prefix(prefix.as_bytes());
+        if let Some(mode) = value("new mode ") {
+            file.new_mode = Some(parse_mode(mode).ok_or_else(|| lines.error("invalid file mode"))?);
+        } else if value("deleted file mode ").is_some() {
+            file.new_path = None;
+        } else if let Some(mode) = value("new file mode ") {
+            file.new_mode = Some(parse_mode(mode).ok_or_else(|| lines.error("invalid file mode"))?);
+            file.old_path = None;
+        } else if let Some(ids) = value("index ") {
+            let ids = ids.find_byte(b' ').map_or(ids, |pos| &ids[..pos]);
+            file.old_id = ids.find("..").map(|pos| ids[..pos].as_bstr());
+        } else if let Some(path) = value("rename from ") {
+            file.old_path = Some(path.into());
+        } else if let Some(path) = value("copy from ") {
+            file.old_path = Some(path.into());
+            file.is_copy = true;
+        } else if let Some(path) = value("rename to ").or_else(|| value("copy to ")) {
+            file.new_path = Some(path.into());
+        } else if line.starts_with(b"Binary files ") {
+            file.binary = Some(Binary::Unsupported);
+        } else if value("old mode ").is_none()
+            && value("similarity index ").is_none()
+            && value("dissimilarity index ").is_none()
+        {
+            break;
+        }
+        lines.next();
+    }
+    Ok(())
+}
+
+fn parse_hunk<'a>(lines: &mut Lines<'a>) -> Result<Hunk<'a>, Error> {
+    let start = lines.pos;
+    let header = lines.next().expect("peeked");
+    let (old_start, mut old_left, mut new_left) =
+        parse_hunk_header(header).ok_or_else(|| lines.error("invalid hunk header"))?;
+    let (mut old, mut new) = (Vec::new(), Vec::new());
+    while old_left > 0 || new_left > 0 {
+        let line = lines.next().ok_or_else(|| lines.error("the hunk ends prematurely"))?;
+        // some editors strip the space of empty context lines.
+        let (kind, content) = match line {
+            b"\n" => (b' ', line),
+            _ => (line[0], &line[1..]),
+        };
+        match kind {
+            b' ' if old_left > 0 && new_left > 0 => {
+                old.push(content);
+                new.push(content);
+                old_left -= 1;
+                new_left -= 1;
+            }
+            b'-' if old_left > 0 => {
+                old.push(content);
+                old_left -= 1;
+            }
+            b'+' if new_left > 0 => {
+                new.push(content);
+                new_left -= 1;
+            }
+            _ => return Err(lines.error("unexpected line in hunk")),
+        }
+        if lines.peek_starts_with(b"\\") {
+            // `\ No newline at end of file` applies to the previous line.
+            lines.next();
+            if kind != b'+' {
+                strip_newline(old.last_mut().expect("just pushed"));
+            }
+            if kind != b'-' {
+                strip_newline(new.last_mut().expect("just pushed"));
+            }
+        }
+    }
+    Ok(Hunk {
+        old_start,
+        old,
+        new,
+        text: &lines.data[start..lines.pos],
+    })
+}
+
+/// Parse `@@ -<old-start>,<old-len> +<new-start>,<new-len> @@` into the old start and the amount of old and new lines.
+fn parse_hunk_header(line: &[u8]) -> Option<(usize, usize, usize)> {
+    let ranges = line.strip_prefix(b"@@ -")?;
+    let ranges = ranges[..ranges.find(" @@")?].to_str().ok()?;
+    let separator = ranges.find(" +")?;
+    let range = |range: &str| -> Option<(usize, usize)> {
+        Some(match range.find(',') {
+            Some(pos) => (range[..pos].parse().ok()?, range[pos + 1..].parse().ok()?),
+            None => (range.parse().ok()?, 1),
+        })
+    };
+    let ((old_start, old_len), (_, new_len)) = (range(&ranges[..separator])?, range(&ranges[separator + 2..])?);
+    Some((old_start, old_len, new_len))
+}
+
+/// Parse a `literal` or `delta` section of a binary patch, up to and including the empty line that ends it.
+fn parse_binary_hunk(lines: &mut Lines<'_>) -> Result<Binary, Error> {
+    let header = lines
+        .next()
+        .map(trim_newline)
+        .ok_or_else(|| lines.error("the binary patch ends prematurely"))?;
+    let (is_literal, size) = match (header.strip_prefix(b"literal "), header.strip_prefix(b"delta ")) {
+        (Some(size), _) => (true, size),
+        (None, Some(size)) => (false, size),
+        (None, None) => return Err(lines.error("expected 'literal' or 'delta' in binary patch")),
+    };
+    let size: usize = size
+        .to_str()
+        .ok()
+        .and_then(|size| size.parse().ok())
+        .ok_or_else(|| lines.error("invalid size in binary patch"))?;
+
+    let mut compressed = Vec::new();
+    loop {
+        let line = lines
+            .next()
+            .map(trim_newline)
+            .ok_or_else(|| lines.error("the binary patch ends prematurely"))?;
+        if line.is_empty() {
+            break;
+        }
+        // the amount of bytes on each line is encoded as `A-Z` for 1 to 26 and `a-z` for 27 to 52.
+        let len = match line[0] {
+            b'A'..=b'Z' => line[0] - b'A' + 1,
+            b'a'..=b'z' => line[0] - b'a' + 27,
+            _ => return Err(lines.error("invalid line length in binary patch")),
+        } as usize;
+        let start = compressed.len();
+        decode_base85(&line[1..], &mut compressed).ok_or_else(|| lines.error("invalid base85 data in binary patch"))?;
+        if compressed.len() < start + len {
+            return Err(lines.error("invalid line length in binary patch"));
+        }
+        compressed.truncate(start + len);
+    }
+    if !is_literal {
+        return Ok(Binary::Unsupported);
+    }
+
+    let mut data = vec![0; size];
+    let (_status, _consumed_in, consumed_out) = git_features::zlib::Inflate::default()
+        .once(&compressed, &mut data)
+        .map_err(|_| lines.error("invalid compressed data in binary patch"))?;
+    if consumed_out != size {
+        return Err(lines.error("the size of the binary data doesn't match"));
+    }
+    Ok(Binary::Literal(data))
+}
+
+fn decode_base85(data: &[u8], out: &mut Vec<u8>) -> Option<()> {
+    if data.len() % 5 != 0 {
+        return None;
+    }
+    for group in data.chunks(5) {
+        let mut value = 0u32;
+        for digit in group {
+            let digit = BASE85_ALPHABET.iter().position(|b| b == digit)? as u32;
+            value = value.checked_mul(85)?.checked_add(digit)?;
+        }
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    Some(())
+}
+
+/// Extract the path from `a/<path> b/<path>`, which is only possible without ambiguity if both paths are the same.
+fn path_from_git_header(paths: &[u8]) -> Option<BString> {
+    if paths.len() < 5 || paths.len() % 2 == 0 {
+        return None;
+    }
+    let len = (paths.len() - 5) / 2;
+    let (old, separator, new) = (&paths[..len + 2], paths[len + 2], &paths[len + 3..]);
+    let old = old.strip_prefix(b"a/")?;
+    (separator == b' ' && Some(old) == new.strip_prefix(b"b/")).then(|| old.into())
+}
+
+/// Extract the path from what follows `--- ` or `+++ `, without its first component like `a/`, or `None` for `/dev/null`.
+fn path_from_file_line(value: &[u8]) -> Option<BString> {
+    let value = trim_newline(value);
+    // some tools add a timestamp after a tab.
+    let value = value.find_byte(b'\t').map_or(value, |pos| &value[..pos]);
+    if value == b"/dev/null" {
+        return None;
+    }
+    Some(value.find_byte(b'/').map_or(value, |pos| &value[pos + 1..]).into())
+}
+
+fn parse_mode(mode: &[u8]) -> Option<Mode> {
+    Mode::from_bits(u32::from_str_radix(mode.to_str().ok()?, 8).ok()?)
+}
+
+fn trim_newline(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
+
+fn strip_newline(line: &mut &[u8]) {
+    *line = trim_newline(*line);
+}
@@ -0,0 +1,355 @@
+//!
+use std::io::Write;
+
+use git_object::{
+    bstr::{BString, ByteSlice, ByteVec},
+    tree::EntryMode,
+};
+
+use crate::{object, Id};
+
+/// The format of the archive to produce with [`Repository::archive()`][crate::Repository::archive()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// An uncompressed `tar` archive.
+    Tar,
+    /// A `tar` archive compressed with `gzip`.
+    TarGz,
+    /// A `zip` archive whose entries are compressed with `deflate`.
+    Zip,
+}
+
+/// Options for use in [`Repository::archive()`][crate::Repository::archive()].
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// If set, this value is prepended to the path of each entry in the archive verbatim, similar to `git archive --prefix`.
+    ///
+    /// Note that it should end with a slash to place all entries into a directory.
+    pub prefix: Option<BString>,
+    /// The modification time to set for each entry, or the current time if unset.
+    pub mtime: Option<git_actor::Time>,
+}
+
+/// Statistics about the archive produced by [`Repository::archive()`][crate::Repository::archive()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The amount of entries written into the archive, including directories.
+    pub entries: usize,
+    /// The amount of uncompressed bytes of all blobs written into the archive.
+    pub bytes: u64,
+}
+
+/// The error returned by [`Repository::archive()`][crate::Repository::archive()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::OdbError),
+    #[error(transparent)]
+    PeelToTree(#[from] object::peel::to_kind::Error),
+    #[error(transparent)]
+    Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+    #[error("The path {path:?} could not be converted into a path suitable for the archive")]
+    IllformedPath { path: BString },
+    #[error(
+        "The zip archive would exceed the size or entry limits of the format, which doesn't support zip64 extensions"
+    )]
+    ZipTooLarge,
+    #[error("Could not write the archive")]
+    Io(#[from] std::io::Error),
+}
+
+/// An entry as it is placed into the archive.
+enum Entry<'a> {
+    Directory,
+    File { data: &'a [u8], executable: bool },
+    Symlink { target: &'a [u8] },
+}
+
+impl crate::Repository {
+    /// Write all entries reachable from `tree` into an archive of the given `format` and write it to `out`, similar to `git archive`.
+    ///
+    /// `tree` may also be a commit or a tag, which will be peeled until a tree is found. Submodules are not included.
+    /// Use `opts` to further configure the archive's contents.
+    pub fn archive(&self, tree: Id<'_>, format: Format, out: impl Write, opts: Options) -> Result<Stats, Error> {
+        let tree = self
+            .find_object(tree.detach())?
+            .peel_to_kind(git_object::Kind::Tree)?
+            .into_tree();
+        let mut entries = tree.traverse().breadthfirst.files()?;
+        entries.sort_by(|a, b| a.filepath.cmp(&b.filepath));
+        drop(tree);
+
+        let mtime = opts
+            .mtime
+            .map(|t| t.seconds_since_unix_epoch as u64)
+            .unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or_default()
+            });
+        let mut archive = match format {
+            Format::Tar => Writer::Tar(tar::Builder::new(out)),
+            Format::TarGz => Writer::TarGz(tar::Builder::new(flate2::write::GzEncoder::new(
+                out,
+                flate2::Compression::default(),
+            ))),
+            Format::Zip => Writer::Zip(zip::Writer::new(out)),
+        };
+
+        let mut stats = Stats::default();
+        let mut buf = Vec::new();
+        for entry in entries {
+            let mut path = opts.prefix.clone().unwrap_or_default();
+            path.push_str(&entry.filepath);
+            match entry.mode {
+                EntryMode::Tree => {
+                    path.push_byte(b'/');
+                    archive.append(path, Entry::Directory, mtime)?;
+                }
+                EntryMode::Blob | EntryMode::BlobExecutable | EntryMode::Link => {
+                    let object = self.objects.find(&entry.oid, &mut buf)?;
+                    stats.bytes += object.data.len() as u64;
+                    let entry = match entry.mode {
+                        EntryMode::Link => Entry::Symlink { target: object.data },
+                        mode => Entry::File {
+                            data: object.data,
+                            executable: mode == EntryMode::BlobExecutable,
+                        },
+                    };
+                    archive.append(path, entry, mtime)?;
+                }
+                EntryMode::Commit => continue,
+            }
+            stats.entries += 1;
+        }
+        archive.finish()?;
+        Ok(stats)
+    }
+}
+
+enum Writer<W: Write> {
+    Tar(tar::Builder<W>),
+    TarGz(tar::Builder<flate2::write::GzEncoder<W>>),
+    Zip(zip::Writer<W>),
+}
+
+impl<W: Write> Writer<W> {
+    fn append(&mut self, path: BString, entry: Entry<'_>, mtime: u64) -> Result<(), Error> {
+        match self {
+            Writer::Tar(builder) => append_to_tar(builder, path, entry, mtime),
+            Writer::TarGz(builder) => append_to_tar(builder, path, entry, mtime),
+            Writer::Zip(zip) => zip.append(path, entry, mtime),
+        }
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        match self {
+            Writer::Tar(builder) => builder.into_inner()?.flush()?,
+            Writer::TarGz(builder) => builder.into_inner()?.finish()?.flush()?,
+            Writer::Zip(zip) => zip.finish()?,
+        }
+        Ok(())
+    }
+}
+
+fn append_to_tar<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: BString,
+    entry: Entry<'_>,
+    mtime: u64,
+) -> Result<(), Error> {
+    let path = git_path::try_from_bstring(path.clone()).map_err(|_| Error::IllformedPath { path })?;
+    let mut header = tar::Header::new_gnu();
+    header.set_mtime(mtime);
+    match entry {
+        Entry::Directory => {
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_mode(0o755);
+            header.set_size(0);
+            builder.append_data(&mut header, path, std::io::empty())?;
+        }
+        Entry::File { data, executable } => {
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_mode(if executable { 0o755 } else { 0o644 });
+            header.set_size(data.len() as u64);
+            builder.append_data(&mut header, path, data)?;
+        }
+        Entry::Symlink { target } => {
+            let target = git_path::try_from_byte_slice(target).map_err(|_| Error::IllformedPath {
+                path: target.as_bstr().to_owned(),
+            })?;
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_mode(0o777);
+            header.set_size(0);
+            builder.append_link(&mut header, path, target)?;
+        }
+    }
+    Ok(())
+}
+
+/// A minimal zip writer for entries whose data is known upfront, without support for zip64 extensions.
+mod zip {
+    use std::io::Write;
+
+    use git_object::bstr::BString;
+
+    use super::{Entry, Error};
+    use crate::date::civil_from_days;
+
+    const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+    const CENTRAL_DIRECTORY_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+    const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+    /// Version 2.0, which supports directories and deflate compression.
+    const VERSION_NEEDED: u16 = 20;
+    /// The upper byte indicates that external attributes are unix permissions.
+    const VERSION_MADE_BY: u16 = (3 << 8) | VERSION_NEEDED;
+    /// File names are encoded in UTF-8, which is what git assumes as well.
+    const FLAG_UTF8: u16 = 1 << 11;
+    const METHOD_STORED: u16 = 0;
+    const METHOD_DEFLATE: u16 = 8;
+
+    struct Record {
+        path: BString,
+        method: u16,
+        time: u16,
+        date: u16,
+        crc32: u32,
+        compressed_size: u32,
+        size: u32,
+        external_attributes: u32,
+        offset: u32,
+    }
+
+    pub(super) struct Writer<W> {
+        out: W,
+        offset: u64,
+        records: Vec<Record>,
+        buf: Vec<u8>,
+    }
+
+    impl<W: Write> Writer<W> {
+        pub fn new(out: W) -> Self {
+            Writer {
+                out,
+                offset: 0,
+                records: Vec::new(),
+                buf: Vec::new(),
+            }
+        }
+
+        pub fn append(&mut self, path: BString, entry: Entry<'_>, mtime: u64) -> Result<(), Error> {
+            let (mode, data): (u32, &[u8]) = match entry {
+                Entry::Directory => (0o040755, &[]),
+                Entry::File { data, executable } => (if executable { 0o100755 } else { 0o100644 }, data),
+                Entry::Symlink { target } => (0o120777, target),
+            };
+            let (method, compressed) = if data.is_empty() {
+                (METHOD_STORED, data)
+            } else {
+                self.buf.clear();
+                let mut deflate = flate2::write::DeflateEncoder::new(&mut self.buf, flate2::Compression::default());
+                deflate.write_all(data)?;
+                deflate.finish()?;
+                (METHOD_DEFLATE, self.buf.as_slice())
+            };
+            let (time, date) = dos_time_and_date(mtime);
+            let record = Record {
+                method,
+                time,
+                date,
+                crc32: git_features::hash::crc32(data),
+                compressed_size: to_u32(compressed.len() as u64)?,
+                size: to_u32(data.len() as u64)?,
+                external_attributes: (mode << 16) | if matches!(entry, Entry::Directory) { 0x10 } else { 0 },
+                offset: to_u32(self.offset)?,
+                path,
+            };
+            let path_len = to_u16(record.path.len())?;
+
+            let mut header = Vec::with_capacity(30 + record.path.len());
+            header.extend_from_slice(&LOCAL_FILE_HEADER_SIGNATURE.to_le_bytes());
+            header.extend_from_slice(&VERSION_NEEDED.to_le_bytes());
+            header.extend_from_slice(&FLAG_UTF8.to_le_bytes());
+            header.extend_from_slice(&record.method.to_le_bytes());
+            header.extend_from_slice(&time.to_le_bytes());
+            header.extend_from_slice(&date.to_le_bytes());
+            header.extend_from_slice(&record.crc32.to_le_bytes());
+            header.extend_from_slice(&record.compressed_size.to_le_bytes());
+            header.extend_from_slice(&record.size.to_le_bytes());
+            header.extend_from_slice(&path_len.to_le_bytes());
+            header.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+            header.extend_from_slice(&record.path);
+
+            self.out.write_all(&header)?;
+            self.out.write_all(compressed)?;
+            self.offset += (header.len() + compressed.len()) as u64;
+            self.records.push(record);
+            Ok(())
+        }
+
+        pub fn finish(mut self) -> Result<(), Error> {
+            let central_directory_offset = to_u32(self.offset)?;
+            let mut central_directory_size = 0u64;
+            for record in &self.records {
+                let mut header = Vec::with_capacity(46 + record.path.len());
+                header.extend_from_slice(&CENTRAL_DIRECTORY_HEADER_SIGNATURE.to_le_bytes());
+                header.extend_from_slice(&VERSION_MADE_BY.to_le_bytes());
+                header.extend_from_slice(&VERSION_NEEDED.to_le_bytes());
+                header.extend_from_slice(&FLAG_UTF8.to_le_bytes());
+                header.extend_from_slice(&record.method.to_le_bytes());
+                header.extend_from_slice(&record.time.to_le_bytes());
+                header.extend_from_slice(&record.date.to_le_bytes());
+                header.extend_from_slice(&record.crc32.to_le_bytes());
+                header.extend_from_slice(&record.compressed_size.to_le_bytes());
+                header.extend_from_slice(&record.size.to_le_bytes());
+                header.extend_from_slice(&to_u16(record.path.len())?.to_le_bytes());
+                header.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+                header.extend_from_slice(&0u16.to_le_bytes()); // file comment length
+                header.extend_from_slice(&0u16.to_le_bytes()); // disk number start
+                header.extend_from_slice(&0u16.to_le_bytes()); // internal file attributes
+                header.extend_from_slice(&record.external_attributes.to_le_bytes());
+                header.extend_from_slice(&record.offset.to_le_bytes());
+                header.extend_from_slice(&record.path);
+                self.out.write_all(&header)?;
+                central_directory_size += header.len() as u64;
+            }
+
+            let num_entries = to_u16(self.records.len())?;
+            let mut trailer = Vec::with_capacity(22);
+            trailer.extend_from_slice(&END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+            trailer.extend_from_slice(&0u16.to_le_bytes()); // number of this disk
+            trailer.extend_from_slice(&0u16.to_le_bytes()); // disk where the central directory starts
+            trailer.extend_from_slice(&num_entries.to_le_bytes());
+            trailer.extend_from_slice(&num_entries.to_le_bytes());
+            trailer.extend_from_slice(&to_u32(central_directory_size)?.to_le_bytes());
+            trailer.extend_from_slice(&central_directory_offset.to_le_bytes());
+            trailer.extend_from_slice(&0u16.to_le_bytes()); // comment length
+            self.out.write_all(&trailer)?;
+            self.out.flush()?;
+            Ok(())
+        }
+    }
+
+    fn to_u32(value: u64) -> Result<u32, Error> {
+        std::convert::TryFrom::try_from(value).map_err(|_| Error::ZipTooLarge)
+    }
+
+    fn to_u16(value: usize) -> Result<u16, Error> {
+        std::convert::TryFrom::try_from(value).map_err(|_| Error::ZipTooLarge)
+    }
+
+    /// Convert `seconds` since the unix epoch into the MS-DOS time and date format, clamped to the earliest representable date.
+    fn dos_time_and_date(seconds: u64) -> (u16, u16) {
+        let days = seconds / 86400;
+        let secs_of_day = seconds % 86400;
+        let (year, month, day) = civil_from_days(days as i64);
+        if year < 1980 {
+            return (0, (1 << 5) | 1);
+        }
+        let time = ((secs_of_day / 3600) << 11) | (((secs_of_day % 3600) / 60) << 5) | ((secs_of_day % 60) / 2);
+        let date = (((year - 1980).min(127) as u64) << 9) | ((month as u64) << 5) | day as u64;
+        (time as u16, date as u16)
+    }
+}
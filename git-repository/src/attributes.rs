@@ -0,0 +1,70 @@
+//! Obtain the attributes which apply to paths in the repository.
+use std::path::PathBuf;
+
+use git_object::bstr::{BStr, ByteSlice};
+
+/// The error returned by [`Repository::attributes()`][crate::Repository::attributes()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read an attributes file")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    PathInterpolation(#[from] git_config::values::path::interpolate::Error),
+    #[error(transparent)]
+    EnvironmentPermission(#[from] git_sec::permission::Error<PathBuf, git_sec::Permission>),
+}
+
+impl crate::Repository {
+    /// Load all attribute files which may affect `relative_path`, a path relative to the root of the working tree,
+    /// for use with [`MatchGroup::attribute_matching_relative_path()`][git_attributes::MatchGroup::attribute_matching_relative_path()].
+    ///
+    /// These are, in order of increasing precedence, the file configured in `core.attributesFile` or its default location,
+    /// the `.gitattributes` files in the root of the working tree and in each directory leading to `relative_path`, and
+    /// `info/attributes` in the git directory.
+    /// Note that `.gitattributes` files are read from the working tree only, and not from the index.
+    pub fn attributes(&self, relative_path: &BStr) -> Result<git_attributes::MatchGroup, Error> {
+        let mut group = git_attributes::MatchGroup::default();
+        let mut buf = Vec::new();
+        let follow_symlinks = true;
+
+        let global_file = match self.config.resolved.path("core", None, "attributesFile") {
+            Some(path) => Some(path.interpolate(None)?.into_owned()),
+            None => self.config.xdg_config_path("attributes")?,
+        };
+        if let Some(global_file) = global_file {
+            group.add_patterns_file(global_file, follow_symlinks, None, &mut buf)?;
+        }
+
+        if let Some(work_dir) = self.work_dir() {
+            let mut directory = work_dir.to_owned();
+            group.add_patterns_file(
+                directory.join(".gitattributes"),
+                follow_symlinks,
+                Some(work_dir),
+                &mut buf,
+            )?;
+            let mut components = relative_path.split_str("/").peekable();
+            while let Some(component) = components.next() {
+                if components.peek().is_none() || component.is_empty() {
+                    break;
+                }
+                directory.push(git_path::from_bstr(component.as_bstr()));
+                group.add_patterns_file(
+                    directory.join(".gitattributes"),
+                    follow_symlinks,
+                    Some(work_dir),
+                    &mut buf,
+                )?;
+            }
+        }
+
+        group.add_patterns_file(
+            self.git_dir().join("info").join("attributes"),
+            follow_symlinks,
+            None,
+            &mut buf,
+        )?;
+        Ok(group)
+    }
+}
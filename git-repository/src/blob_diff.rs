@@ -0,0 +1,279 @@
+//! Compute character-level differences between blobs.
+use std::ops::Range;
+
+use git_object::bstr::{BStr, ByteSlice};
+use git_odb::FindExt;
+
+use crate::Id;
+
+/// The error returned by [`Repository::blob_diff_chars()`][crate::Repository::blob_diff_chars()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindBlob(#[from] git_odb::find::existing_object::Error<git_odb::store::find::Error>),
+}
+
+/// A change from one blob to another, with context preceding it, as returned by [`CharDiff::iter()`].
+///
+/// Either `deletions` or `insertions` or both are non-empty, unless this is the last hunk which may consist of context only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CharHunk<'a> {
+    /// Text that is the same in both blobs.
+    pub context: &'a BStr,
+    /// Text of the old blob that was removed right after `context`.
+    pub deletions: &'a BStr,
+    /// Text of the new blob that was added in place of `deletions`.
+    pub insertions: &'a BStr,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Hunk {
+    /// A range into the new blob.
+    context: Range<usize>,
+    /// A range into the old blob.
+    deletions: Range<usize>,
+    /// A range into the new blob.
+    insertions: Range<usize>,
+}
+
+/// The character-level differences between two blobs, as returned by
+/// [`Repository::blob_diff_chars()`][crate::Repository::blob_diff_chars()].
+///
+/// Iterating it yields [`CharHunk`]s which, when concatenating their `context` and `deletions`, reproduce the old blob,
+/// and when concatenating their `context` and `insertions` reproduce the new one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharDiff {
+    old: Vec<u8>,
+    new: Vec<u8>,
+    hunks: Vec<Hunk>,
+}
+
+impl CharDiff {
+    /// Compute the differences between `old` and `new` by diffing their lines first, and then the characters of each block
+    /// of changed lines.
+    pub fn new(old: Vec<u8>, new: Vec<u8>) -> Self {
+        let mut hunks = Hunks::default();
+        let (old_lines, new_lines) = (lines(&old), lines(&new));
+        let (mut old_line, mut new_line) = (old_lines.iter(), new_lines.iter());
+        let (mut old_pos, mut new_pos) = (0, 0);
+        let mut changed: Option<(Range<usize>, Range<usize>)> = None;
+        for edit in myers(&old_lines, &new_lines) {
+            match edit {
+                Edit::Equal => {
+                    if let Some((old_range, new_range)) = changed.take() {
+                        diff_chars(&mut hunks, &old, old_range, &new, new_range);
+                    }
+                    let len = old_line.next().expect("in bounds").len();
+                    new_line.next();
+                    hunks.equal(new_pos..new_pos + len);
+                    old_pos += len;
+                    new_pos += len;
+                }
+                Edit::Delete => {
+                    let len = old_line.next().expect("in bounds").len();
+                    let (old_range, _) = changed.get_or_insert((old_pos..old_pos, new_pos..new_pos));
+                    old_range.end = old_pos + len;
+                    old_pos += len;
+                }
+                Edit::Insert => {
+                    let len = new_line.next().expect("in bounds").len();
+                    let (_, new_range) = changed.get_or_insert((old_pos..old_pos, new_pos..new_pos));
+                    new_range.end = new_pos + len;
+                    new_pos += len;
+                }
+            }
+        }
+        if let Some((old_range, new_range)) = changed {
+            diff_chars(&mut hunks, &old, old_range, &new, new_range);
+        }
+        let hunks = hunks.finish();
+        CharDiff { old, new, hunks }
+    }
+
+    /// Return an iterator over all hunks, in order.
+    pub fn iter(&self) -> impl Iterator<Item = CharHunk<'_>> + '_ {
+        self.hunks.iter().map(move |hunk| CharHunk {
+            context: self.new[hunk.context.clone()].as_bstr(),
+            deletions: self.old[hunk.deletions.clone()].as_bstr(),
+            insertions: self.new[hunk.insertions.clone()].as_bstr(),
+        })
+    }
+
+    /// Return true if both blobs are equal.
+    pub fn is_empty(&self) -> bool {
+        self.hunks
+            .iter()
+            .all(|hunk| hunk.deletions.is_empty() && hunk.insertions.is_empty())
+    }
+}
+
+impl<'a> IntoIterator for &'a CharDiff {
+    type Item = CharHunk<'a>;
+    type IntoIter = Box<dyn Iterator<Item = CharHunk<'a>> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl crate::Repository {
+    /// Compute the character-level differences between the `old` and the `new` blob, similar to what
+    /// `git diff --word-diff=color --word-diff-regex=.` displays.
+    ///
+    /// Lines are diffed first, and only the characters of changed lines are diffed once more to find the exact characters
+    /// that changed. Characters are decoded as UTF-8, with invalid sequences treated as characters of their own.
+    ///
+    /// Note that both diffs use the Myers algorithm, whose memory usage grows quadratically with the amount of changes,
+    /// making it unsuitable for blobs with many changes.
+    pub fn blob_diff_chars(&self, old: Id<'_>, new: Id<'_>) -> Result<CharDiff, Error> {
+        let mut buf = Vec::new();
+        let old = self.objects.find_blob(old.inner, &mut buf)?.data.to_owned();
+        let new = self.objects.find_blob(new.inner, &mut buf)?.data.to_owned();
+        Ok(CharDiff::new(old, new))
+    }
+}
+
+/// Collects hunks from a sequence of equal and changed ranges.
+#[derive(Default)]
+struct Hunks {
+    hunks: Vec<Hunk>,
+    current: Option<Hunk>,
+}
+
+impl Hunks {
+    fn equal(&mut self, new: Range<usize>) {
+        match &mut self.current {
+            Some(hunk) if hunk.deletions.is_empty() && hunk.insertions.is_empty() => hunk.context.end = new.end,
+            current => {
+                self.hunks.extend(current.take());
+                *current = Some(Hunk {
+                    context: new,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    fn change(&mut self, old: Range<usize>, new: Range<usize>) {
+        let hunk = self.current.get_or_insert_with(|| Hunk {
+            context: new.start..new.start,
+            ..Default::default()
+        });
+        // changes are contiguous in both blobs, so only the end moves once the start is known.
+        if hunk.deletions.is_empty() {
+            hunk.deletions = old.start..old.start;
+        }
+        if hunk.insertions.is_empty() {
+            hunk.insertions = new.start..new.start;
+        }
+        hunk.deletions.end = old.end;
+        hunk.insertions.end = new.end;
+    }
+
+    fn finish(mut self) -> Vec<Hunk> {
+        self.hunks.extend(self.current.take());
+        self.hunks
+    }
+}
+
+/// Diff the characters in the `old_range` of `old` with those in the `new_range` of `new`, and record the result in `hunks`.
+fn diff_chars(hunks: &mut Hunks, old: &[u8], old_range: Range<usize>, new: &[u8], new_range: Range<usize>) {
+    let (old_chars, new_chars) = (chars(&old[old_range.clone()]), chars(&new[new_range.clone()]));
+    let (mut old_pos, mut new_pos) = (old_range.start, new_range.start);
+    let (mut old_char, mut new_char) = (old_chars.iter(), new_chars.iter());
+    for edit in myers(&old_chars, &new_chars) {
+        match edit {
+            Edit::Equal => {
+                let len = old_char.next().expect("in bounds").len();
+                new_char.next();
+                hunks.equal(new_pos..new_pos + len);
+                old_pos += len;
+                new_pos += len;
+            }
+            Edit::Delete => {
+                let len = old_char.next().expect("in bounds").len();
+                hunks.change(old_pos..old_pos + len, new_pos..new_pos);
+                old_pos += len;
+            }
+            Edit::Insert => {
+                let len = new_char.next().expect("in bounds").len();
+                hunks.change(old_pos..old_pos, new_pos..new_pos + len);
+                new_pos += len;
+            }
+        }
+    }
+}
+
+pub(crate) fn lines(data: &[u8]) -> Vec<&[u8]> {
+    data.lines_with_terminator().collect()
+}
+
+fn chars(data: &[u8]) -> Vec<&[u8]> {
+    data.char_indices().map(|(start, end, _)| &data[start..end]).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Edit {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Return the shortest sequence of edits to turn `a` into `b`, as found by the Myers diff algorithm.
+pub(crate) fn myers<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    let offset = max + 1;
+    let at = |k: isize| (offset + k) as usize;
+    let mut v = vec![0isize; 2 * max as usize + 3];
+    // Only the diagonals `-d..=d` can be reached after `d` steps, so that's all we keep of each step for backtracking.
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'search: for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                v[at(k + 1)]
+            } else {
+                v[at(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[at(k)] = x;
+            if x >= n && y >= m {
+                trace.push(v[at(-d)..=at(d)].to_vec());
+                break 'search;
+            }
+        }
+        trace.push(v[at(-d)..=at(d)].to_vec());
+    }
+
+    let mut edits = Vec::with_capacity(max as usize);
+    let (mut x, mut y) = (n, m);
+    for d in (1..trace.len()).rev() {
+        let prev = &trace[d - 1];
+        let d = d as isize;
+        let prev_v = |k: isize| prev[(k + d - 1) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && prev_v(k - 1) < prev_v(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = prev_v(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            edits.push(Edit::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        edits.push(if x == prev_x { Edit::Insert } else { Edit::Delete });
+        x = prev_x;
+        y = prev_y;
+    }
+    edits.extend((0..x).map(|_| Edit::Equal));
+    edits.reverse();
+    edits
+}
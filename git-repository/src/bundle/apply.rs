@@ -0,0 +1,108 @@
+use std::{
+    convert::TryFrom,
+    path::{Path, PathBuf},
+};
+
+use git_object::bstr::{BString, ByteSlice, ByteVec};
+use git_odb::pack::Find;
+use git_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::{bundle, reference, Progress};
+
+/// Options for use in [`Repository::bundle_apply()`][crate::Repository::bundle_apply()].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If set, references of the bundle are created below this prefix instead of their own location, with their
+    /// leading `refs/` replaced by it. With `refs/bundles/` for instance, `refs/heads/main` is written to
+    /// `refs/bundles/heads/main`.
+    pub refname_prefix: Option<BString>,
+    /// The amount of threads to use at most when indexing the pack, or all logical cores if `None`.
+    pub thread_limit: Option<usize>,
+}
+
+/// The error returned by [`Repository::bundle_apply()`][crate::Repository::bundle_apply()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open the bundle at '{}'", path.display())]
+    Open { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    Verify(#[from] bundle::verify::Error),
+    #[error("Could not write the pack of the bundle into the object database")]
+    WritePack(#[from] git_odb::pack::bundle::write::Error),
+    #[error("The reference name {name:?} obtained with the refname prefix is invalid")]
+    InvalidRefName {
+        name: BString,
+        source: git_validate::refname::Error,
+    },
+    #[error(transparent)]
+    EditReferences(#[from] reference::edit::Error),
+}
+
+impl crate::Repository {
+    /// Apply the bundle at `path` to this repository by verifying it like [`bundle_verify()`][crate::Repository::bundle_verify()],
+    /// writing its pack along with an index into the object database and updating references as listed in the bundle,
+    /// possibly below [`Options::refname_prefix`].
+    ///
+    /// Return the reference edits that were performed. Note that references are updated even if their new value isn't a
+    /// descendant of their previous one, similar to `git fetch <bundle> '+refs/*:refs/*'`.
+    pub fn bundle_apply(&self, path: &Path, progress: impl Progress, opts: Options) -> Result<Vec<RefEdit>, Error> {
+        let file = std::fs::File::open(path).map_err(|source| Error::Open {
+            path: path.to_owned(),
+            source,
+        })?;
+        let mut read = std::io::BufReader::new(file);
+        let info = self.bundle_read_header(&mut read)?;
+
+        // Packs in bundles are thin and may refer to bases among the prerequisites, which we already have.
+        let objects = self.objects.clone();
+        git_odb::pack::Bundle::write_to_directory(
+            read,
+            Some(self.objects.store_ref().path().join("pack")),
+            progress,
+            &crate::interrupt::IS_INTERRUPTED,
+            Some(Box::new(move |id, buf| {
+                objects.try_find(id, buf).ok().flatten().map(|(data, _)| data)
+            })),
+            git_odb::pack::bundle::write::Options {
+                thread_limit: opts.thread_limit,
+                object_hash: info.object_hash,
+                ..Default::default()
+            },
+        )?;
+
+        let message: BString = format!("bundle: {}", path.display()).into();
+        let mut edits = Vec::with_capacity(info.references.len());
+        for (name, id) in info.references {
+            let name = match &opts.refname_prefix {
+                Some(prefix) => {
+                    let mut prefixed = prefix.clone();
+                    let name = name.as_bstr().as_bytes();
+                    prefixed.push_str(name.strip_prefix(b"refs/").unwrap_or(name));
+                    FullName::try_from(prefixed.as_bstr()).map_err(|source| Error::InvalidRefName {
+                        name: prefixed.clone(),
+                        source,
+                    })?
+                }
+                None => name,
+            };
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: message.clone(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Peeled(id),
+                },
+                name,
+                deref: false,
+            });
+        }
+        Ok(self.edit_references(edits, git_lock::acquire::Fail::Immediately, None)?)
+    }
+}
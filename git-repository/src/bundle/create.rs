@@ -0,0 +1,130 @@
+use std::{convert::TryFrom, io::Write};
+
+use git_hash::ObjectId;
+
+use crate::{bundle, object, pack_objects, Progress};
+
+/// Options for use in [`Repository::bundle_create()`][crate::Repository::bundle_create()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The amount of threads to use at most when creating the pack, or all logical cores if `None`.
+    pub thread_limit: Option<usize>,
+}
+
+/// Information about the bundle written by [`Repository::bundle_create()`][crate::Repository::bundle_create()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of commits reachable from the tips but not from the prerequisites.
+    pub num_commits: usize,
+    /// Information about the pack following the bundle header.
+    pub pack: pack_objects::Outcome,
+}
+
+/// The error returned by [`Repository::bundle_create()`][crate::Repository::bundle_create()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The tip name '{name}' is not a valid reference name")]
+    InvalidRefName {
+        name: String,
+        source: git_validate::refname::Error,
+    },
+    #[error("The tip '{name}' could not be peeled to a commit")]
+    PeelToCommit {
+        name: String,
+        source: object::peel::to_kind::Error,
+    },
+    #[error(transparent)]
+    FindCommit(#[from] git_odb::find::existing_iter::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[cfg(feature = "git-commitgraph")]
+    #[error("The commit-graph could not be read")]
+    CommitGraph(#[from] git_commitgraph::file::commit::Error),
+    #[error("Refusing to create an empty bundle as all tips are reachable from the prerequisites")]
+    Empty,
+    #[error("Could not write the bundle header")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    PackObjects(#[from] pack_objects::Error),
+}
+
+impl crate::Repository {
+    /// Write a bundle to `out` which contains the references named in `tips` along with all objects reachable from them
+    /// but not from the `prereqs` commits, similar to `git bundle create <file> <tips> --not <prereqs>`.
+    ///
+    /// The names of tips must be full reference names like `refs/heads/main` and may point to annotated tags. Each
+    /// prerequisite is expected to be present in the repository the bundle is applied to.
+    /// Bundles are written in version 2, or version 3 if the repository doesn't use SHA-1 object ids.
+    ///
+    /// Note that the pack isn't minimal as it also contains the parent commits of the oldest included commits along with their
+    /// trees, which is how [`ObjectExpansion::TreeAdditionsComparedToAncestor`][pack_objects::ObjectExpansion::TreeAdditionsComparedToAncestor]
+    /// determines the objects to add. This doesn't affect applying the bundle.
+    pub fn bundle_create(
+        &self,
+        mut out: impl Write,
+        tips: &[(&str, ObjectId)],
+        prereqs: &[ObjectId],
+        progress: impl Progress,
+        opts: Options,
+    ) -> Result<Outcome, Error> {
+        let mut tip_commits = Vec::with_capacity(tips.len());
+        let mut tip_tags = Vec::new();
+        for (name, id) in tips {
+            git_ref::FullName::try_from(*name).map_err(|source| Error::InvalidRefName {
+                name: name.to_string(),
+                source,
+            })?;
+            let commit = self
+                .find_object(*id)
+                .map_err(object::peel::to_kind::Error::from)
+                .and_then(|object| object.peel_to_kind(object::Kind::Commit))
+                .map_err(|source| Error::PeelToCommit {
+                    name: name.to_string(),
+                    source,
+                })?;
+            if commit.id != *id {
+                tip_tags.push(*id);
+            }
+            tip_commits.push(commit.id);
+        }
+
+        let (commits, _) = self.commits().left_right::<Error>(&tip_commits, prereqs)?;
+        if commits.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        let mut header = Vec::new();
+        match self.object_hash() {
+            git_hash::Kind::Sha1 => header.extend_from_slice(bundle::V2_SIGNATURE),
+            #[allow(unreachable_patterns)]
+            other => {
+                header.extend_from_slice(bundle::V3_SIGNATURE);
+                header.extend_from_slice(format!("@object-format={}\n", other.to_string().to_lowercase()).as_bytes());
+            }
+        }
+        for id in prereqs {
+            writeln!(header, "-{}", id)?;
+        }
+        for (name, id) in tips {
+            writeln!(header, "{} {}", id, name)?;
+        }
+        header.push(b'\n');
+        out.write_all(&header)?;
+
+        let num_commits = commits.len();
+        let pack = self.pack_objects(
+            tip_tags.into_iter().chain(commits),
+            out,
+            progress,
+            pack_objects::Options {
+                expansion: pack_objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+                thread_limit: opts.thread_limit,
+                allow_thin_pack: false,
+                delta_reuse: true,
+                ..Default::default()
+            },
+        )?;
+        Ok(Outcome { num_commits, pack })
+    }
+}
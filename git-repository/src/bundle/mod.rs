@@ -0,0 +1,29 @@
+//! Create and consume git bundles, self-contained files with references and the objects they need, as described in
+//! `gitformat-bundle(5)`.
+use git_hash::ObjectId;
+use git_ref::FullName;
+
+///
+pub mod apply;
+///
+pub mod create;
+///
+pub mod verify;
+
+/// The first line of a bundle in version 2, which is used for repositories with SHA-1 object ids.
+const V2_SIGNATURE: &[u8] = b"# v2 git bundle\n";
+/// The first line of a bundle in version 3, which supports capabilities like `@object-format=sha256`.
+const V3_SIGNATURE: &[u8] = b"# v3 git bundle\n";
+
+/// Information from the header of a bundle, as returned by [`Repository::bundle_verify()`][crate::Repository::bundle_verify()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+    /// The version of the bundle format, either 2 or 3.
+    pub version: u8,
+    /// The kind of object ids used in the bundle.
+    pub object_hash: git_hash::Kind,
+    /// The commits which must be present in a repository for the bundle to be applied to it.
+    pub prerequisites: Vec<ObjectId>,
+    /// The references contained in the bundle along with the objects they point to, in the order they are listed.
+    pub references: Vec<(FullName, ObjectId)>,
+}
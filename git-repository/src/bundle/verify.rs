@@ -0,0 +1,138 @@
+use std::{
+    convert::TryFrom,
+    io::BufRead,
+    path::{Path, PathBuf},
+};
+
+use git_hash::ObjectId;
+use git_object::bstr::{BString, ByteSlice};
+use git_odb::Find;
+use git_ref::FullName;
+
+use crate::bundle;
+
+/// The error returned by [`Repository::bundle_verify()`][crate::Repository::bundle_verify()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not open the bundle at '{}'", path.display())]
+    Open { path: PathBuf, source: std::io::Error },
+    #[error("Could not read the bundle header")]
+    Io(#[from] std::io::Error),
+    #[error("The file doesn't start with a known bundle signature")]
+    UnknownSignature,
+    #[error("{message}: {line:?}")]
+    Parse { line: BString, message: &'static str },
+    #[error("The capability {capability:?} is not supported")]
+    UnsupportedCapability { capability: BString },
+    #[error("The bundle uses {actual} object ids, but the repository uses {expected}")]
+    ObjectHashMismatch {
+        actual: git_hash::Kind,
+        expected: git_hash::Kind,
+    },
+    #[error("The bundle references '{name}', which is not a valid reference name")]
+    InvalidRefName {
+        name: BString,
+        source: git_validate::refname::Error,
+    },
+    #[error("The repository lacks {} prerequisite commit(s) of the bundle: {}", .ids.len(), .ids.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    MissingPrerequisites { ids: Vec<ObjectId> },
+}
+
+impl crate::Repository {
+    /// Read the header of the bundle at `path`, similar to `git bundle verify <path>`, and assure all of its prerequisites
+    /// are present in this repository.
+    ///
+    /// Note that the pack following the header isn't read, and unlike `git` it's not checked whether the prerequisites are
+    /// connected to the references of this repository.
+    pub fn bundle_verify(&self, path: &Path) -> Result<bundle::Info, Error> {
+        let file = std::fs::File::open(path).map_err(|source| Error::Open {
+            path: path.to_owned(),
+            source,
+        })?;
+        self.bundle_read_header(&mut std::io::BufReader::new(file))
+    }
+
+    /// Parse the bundle header from `read` and verify it, leaving `read` positioned at the start of the pack.
+    pub(crate) fn bundle_read_header(&self, read: &mut impl BufRead) -> Result<bundle::Info, Error> {
+        let mut line = Vec::new();
+        read.read_until(b'\n', &mut line)?;
+        let version = match line.as_slice() {
+            bundle::V2_SIGNATURE => 2,
+            bundle::V3_SIGNATURE => 3,
+            _ => return Err(Error::UnknownSignature),
+        };
+
+        let mut object_hash = git_hash::Kind::Sha1;
+        let mut prerequisites = Vec::new();
+        let mut references = Vec::new();
+        loop {
+            line.clear();
+            if read.read_until(b'\n', &mut line)? == 0 {
+                return Err(Error::Parse {
+                    line: BString::default(),
+                    message: "The bundle header isn't terminated by an empty line",
+                });
+            }
+            let text = line.strip_suffix(b"\n").unwrap_or(&line);
+            if text.is_empty() {
+                break;
+            }
+            if let Some(capability) = text.strip_prefix(b"@").filter(|_| version == 3) {
+                object_hash = match capability.strip_prefix(b"object-format=") {
+                    Some(b"sha1") => git_hash::Kind::Sha1,
+                    _ => {
+                        return Err(Error::UnsupportedCapability {
+                            capability: capability.into(),
+                        })
+                    }
+                };
+            } else if let Some(prerequisite) = text.strip_prefix(b"-") {
+                // the id may be followed by a comment, usually the subject of the commit.
+                let hex = prerequisite
+                    .find_byte(b' ')
+                    .map_or(prerequisite, |pos| &prerequisite[..pos]);
+                prerequisites.push(parse_id(hex, text)?);
+            } else {
+                let pos = text.find_byte(b' ').ok_or_else(|| Error::Parse {
+                    line: text.into(),
+                    message: "Expected a reference line like '<id> <name>'",
+                })?;
+                let (hex, name) = (&text[..pos], &text[pos + 1..]);
+                let name = FullName::try_from(name.as_bstr()).map_err(|source| Error::InvalidRefName {
+                    name: name.into(),
+                    source,
+                })?;
+                references.push((name, parse_id(hex, text)?));
+            }
+        }
+
+        if object_hash != self.object_hash() {
+            return Err(Error::ObjectHashMismatch {
+                actual: object_hash,
+                expected: self.object_hash(),
+            });
+        }
+        let missing: Vec<_> = prerequisites
+            .iter()
+            .filter(|id| !self.objects.contains(id))
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            return Err(Error::MissingPrerequisites { ids: missing });
+        }
+        Ok(bundle::Info {
+            version,
+            object_hash,
+            prerequisites,
+            references,
+        })
+    }
+}
+
+fn parse_id(hex: &[u8], line: &[u8]) -> Result<ObjectId, Error> {
+    ObjectId::from_hex(hex).map_err(|_| Error::Parse {
+        line: line.into(),
+        message: "Invalid object id",
+    })
+}
@@ -0,0 +1,342 @@
+//! Switch to another branch by updating the working tree, the index and `HEAD`.
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_hash::ObjectId;
+use git_index::{
+    entry::{Flags, Mode},
+    Entry,
+};
+use git_object::bstr::{BString, ByteSlice};
+use git_odb::FindExt;
+use git_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Category, FullName, FullNameRef, Target,
+};
+
+use crate::{
+    index_diff::{hash_file, mode_of, stat_of},
+    object, reference,
+    restore::write_file,
+};
+
+/// Options for use in [`Repository::checkout_branch()`][crate::Repository::checkout_branch()].
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If true, local changes to files that differ between both branches are discarded and untracked files in the way are
+    /// overwritten, similar to `git checkout --force`, instead of failing with [`Error::Conflict`].
+    pub force: bool,
+    /// If set, the checkout stops with [`Error::Interrupted`] once the token is cancelled, instead of when the
+    /// [global interrupt flag][crate::interrupt::IS_INTERRUPTED] is set.
+    ///
+    /// Files written or removed up to that point remain changed and are recorded in the index, whereas `HEAD` is left
+    /// untouched.
+    pub cancellation: Option<crate::interrupt::CancellationToken>,
+}
+
+/// The amount of files changed in the working tree by [`Repository::checkout_branch()`][crate::Repository::checkout_branch()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// The amount of files that didn't exist in the tree of the previous `HEAD` commit.
+    pub files_added: usize,
+    /// The amount of files whose content or mode changed.
+    pub files_modified: usize,
+    /// The amount of files that don't exist in the tree of the checked out branch.
+    pub files_removed: usize,
+}
+
+/// The error returned by [`Repository::checkout_branch()`][crate::Repository::checkout_branch()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to checkout a branch")]
+    MissingWorkTree,
+    #[error("'{name}' is not a local branch")]
+    NotABranch { name: FullName },
+    #[error("Local changes or untracked files would be overwritten by the checkout: {}", paths.iter().map(|path| path.to_string()).collect::<Vec<_>>().join(", "))]
+    Conflict { paths: Vec<BString> },
+    #[error(transparent)]
+    FindReference(#[from] reference::find::existing::Error),
+    #[error(transparent)]
+    PeelReference(#[from] reference::peel::Error),
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::OdbError),
+    #[error(transparent)]
+    FindBlob(#[from] git_odb::find::existing_object::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    PeelToTree(#[from] object::peel::to_kind::Error),
+    #[error(transparent)]
+    Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    EditReference(#[from] reference::edit::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    AddEntry(#[from] git_index::edit::add_entry::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] crate::worktree::write_index::Error),
+    #[error(transparent)]
+    PathTraversal(#[from] git_sec::permission::TraversalError),
+    #[error("Could not access '{}' in the working tree", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// The mode and id of all files in a tree, by their path.
+pub(crate) type Files = BTreeMap<BString, (Mode, ObjectId)>;
+
+/// The path of a file along with its mode and id in the tree of the current `HEAD` commit and in the tree of the branch.
+type FileChange = (BString, Option<(Mode, ObjectId)>, Option<(Mode, ObjectId)>);
+
+impl crate::Repository {
+    /// Checkout the local branch `name`, like `refs/heads/feature`, similar to `git checkout feature`.
+    ///
+    /// All files which differ between the tree of the current `HEAD` commit and the tree of the branch are written to or
+    /// removed from the working tree and the index, whereas all other files, including untracked ones and local or staged
+    /// changes to unaffected files, are left untouched. Afterwards, `HEAD` points to `name` and its reflog records the checkout.
+    ///
+    /// If a file to be changed has local modifications or an untracked file is in the way, nothing is changed and
+    /// [`Error::Conflict`] is returned with all affected paths, unless [`Options::force`] is set.
+    /// Submodules are skipped.
+    ///
+    /// Note that local changes are determined by comparing files in the working tree as well as their entries in the index
+    /// with the tree of the current `HEAD` commit, hence staged changes are conflicts as well.
+    /// If not all files can be changed, the index is still written to record the files changed so far.
+    pub fn checkout_branch(&self, name: &FullNameRef, opts: Options) -> Result<Stats, Error> {
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        if name.category() != Some(Category::LocalBranch) {
+            return Err(Error::NotABranch { name: name.to_owned() });
+        }
+        let target_id = self.find_reference(name)?.peel_to_id_in_place()?.detach();
+        let target_files = self.tree_files::<Error>(target_id)?;
+
+        let head = self.head()?;
+        let head_id = head.id().map(|id| id.detach());
+        let head_name = head.referent_name().map(|name| name.shorten().to_owned());
+        let current_files = match head_id {
+            Some(id) => self.tree_files::<Error>(id)?,
+            None => Files::new(),
+        };
+
+        let changes: Vec<FileChange> = current_files
+            .keys()
+            .chain(target_files.keys().filter(|path| !current_files.contains_key(*path)))
+            .filter_map(|path| {
+                let (current, target) = (current_files.get(path).copied(), target_files.get(path).copied());
+                (current != target).then(|| (path.clone(), current, target))
+            })
+            .collect();
+
+        for (path, _, _) in &changes {
+            git_sec::permission::check_path_traversal(work_dir, &git_path::from_bstr(path.as_bstr()))?;
+        }
+
+        let index_lock = self.lock_index().map_err(crate::worktree::write_index::Error::from)?;
+        let mut index = if self.git_dir().join("index").is_file() {
+            self.open_index()?
+        } else {
+            git_index::File::new(self.git_dir().join("index"), git_index::Version::V2, self.object_hash())
+        };
+        if !opts.force {
+            let mut conflicts = Vec::new();
+            for (path, current, target) in &changes {
+                if self.has_local_changes(work_dir, path, *current, *target, &current_files, &index)? {
+                    conflicts.push(path.clone());
+                }
+            }
+            if !conflicts.is_empty() {
+                return Err(Error::Conflict { paths: conflicts });
+            }
+        }
+
+        let should_interrupt = crate::interrupt::flag(opts.cancellation.as_ref());
+        let mut stats = Stats::default();
+        let res = self.change_files(work_dir, &changes, &mut index, should_interrupt, &mut stats);
+        // Record the files changed so far, even if not all of them could be changed.
+        self.write_index_to_lock(&index, index_lock)?;
+        res?;
+
+        let from = match (&head_name, head_id) {
+            (Some(name), _) => name.to_string(),
+            (None, Some(id)) => id.to_hex().to_string(),
+            (None, None) => unreachable!("HEAD is either symbolic or detached"),
+        };
+        self.edit_reference(
+            RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: format!("checkout: moving from {} to {}", from, name.shorten()).into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Symbolic(name.to_owned()),
+                },
+                name: head.name().to_owned(),
+                deref: false,
+            },
+            git_lock::acquire::Fail::Immediately,
+            None,
+        )?;
+        Ok(stats)
+    }
+
+    /// Remove and write the files of all `changes` in the working tree at `work_dir` and update their entries in `index`,
+    /// counting them in `stats`.
+    fn change_files(
+        &self,
+        work_dir: &Path,
+        changes: &[FileChange],
+        index: &mut git_index::File,
+        should_interrupt: &AtomicBool,
+        stats: &mut Stats,
+    ) -> Result<(), Error> {
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+        for (path, _, _) in changes.iter().filter(|(_, _, target)| target.is_none()) {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let file_path = work_dir.join(git_path::from_bstr(path.as_bstr()));
+            match std::fs::remove_file(&file_path) {
+                Ok(()) => remove_empty_parents(work_dir, &file_path),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(io_err(&file_path)(err)),
+            }
+            for stage in 0..=3 {
+                index.remove_entry(path.as_bstr(), stage);
+            }
+            stats.files_removed += 1;
+        }
+        let mut buf = Vec::new();
+        for (path, current, target) in changes {
+            let (mode, id) = match target {
+                Some(target) => *target,
+                None => continue,
+            };
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let file_path = work_dir.join(git_path::from_bstr(path.as_bstr()));
+            let data = self.objects.find_blob(&id, &mut buf)?.data;
+            write_file(&file_path, mode, data).map_err(io_err(&file_path))?;
+            let metadata = std::fs::symlink_metadata(&file_path).map_err(io_err(&file_path))?;
+            index.add_entry(
+                Entry::new(stat_of(&metadata), id, Flags::empty(), mode),
+                path.as_bstr(),
+                true,
+            )?;
+            if current.is_some() {
+                stats.files_modified += 1;
+            } else {
+                stats.files_added += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return all files reachable from the tree of commit `id`, without submodules.
+    pub(crate) fn tree_files<E>(&self, id: ObjectId) -> Result<Files, E>
+    where
+        E: From<object::find::existing::OdbError>
+            + From<object::peel::to_kind::Error>
+            + From<git_traverse::tree::breadthfirst::Error>,
+    {
+        Ok(self
+            .find_object(id)?
+            .peel_to_kind(git_object::Kind::Tree)?
+            .into_tree()
+            .traverse()
+            .breadthfirst
+            .files()?
+            .into_iter()
+            .filter(|entry| entry.mode.is_no_tree() && entry.mode != git_object::tree::EntryMode::Commit)
+            .map(|entry| (entry.filepath, (Mode::from_bits_truncate(entry.mode as u32), entry.oid)))
+            .collect())
+    }
+
+    /// Return true if the file at `path` in the working tree or its entry in `index` would lose data when changing it from
+    /// `current` to `target`, which is the case if either is neither in its `current` nor in its `target` state, or if an
+    /// untracked file or directory is in the way.
+    fn has_local_changes(
+        &self,
+        work_dir: &Path,
+        path: &BString,
+        current: Option<(Mode, ObjectId)>,
+        target: Option<(Mode, ObjectId)>,
+        current_files: &Files,
+        index: &git_index::File,
+    ) -> Result<bool, Error> {
+        let file_path = work_dir.join(git_path::from_bstr(path.as_bstr()));
+        let io_err = |source| Error::Io {
+            path: file_path.clone(),
+            source,
+        };
+        if target.is_some() {
+            let mut ancestor = path.as_bstr();
+            while let Some(pos) = ancestor.rfind_byte(b'/') {
+                ancestor = ancestor[..pos].as_bstr();
+                let is_file = std::fs::symlink_metadata(work_dir.join(git_path::from_bstr(ancestor)))
+                    .map_or(false, |metadata| !metadata.is_dir());
+                if is_file && !current_files.contains_key(ancestor) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        let staged = index
+            .entry_by_path(path.as_bstr(), 0)
+            .map(|entry| (entry.mode, entry.id));
+        if staged != current && staged != target {
+            return Ok(true);
+        }
+
+        let metadata = match std::fs::symlink_metadata(&file_path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(io_err(err)),
+        };
+        if metadata.is_dir() {
+            return Ok(target.is_some() && contains_untracked_files(&file_path, path, current_files).map_err(io_err)?);
+        }
+        let mode = mode_of(&metadata, current.map_or(Mode::FILE, |(mode, _)| mode));
+        let id = hash_file(&file_path, &metadata, self.object_hash()).map_err(io_err)?;
+        let is_in_state = |state: Option<(Mode, ObjectId)>| state == Some((mode, id));
+        Ok(!is_in_state(current) && !is_in_state(target))
+    }
+}
+
+/// Return true if the directory at `dir` in the working tree, whose path relative to the working tree is `rela_dir`,
+/// contains files that aren't in `current_files`.
+fn contains_untracked_files(dir: &Path, rela_dir: &BString, current_files: &Files) -> std::io::Result<bool> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let mut rela_path = rela_dir.clone();
+        rela_path.push(b'/');
+        rela_path.extend_from_slice(&git_path::into_bstr(PathBuf::from(entry.file_name())));
+        let is_untracked = if entry.file_type()?.is_dir() {
+            contains_untracked_files(&entry.path(), &rela_path, current_files)?
+        } else {
+            !current_files.contains_key(&rela_path)
+        };
+        if is_untracked {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Remove the empty parent directories of the removed `file_path` up to but excluding `work_dir`.
+fn remove_empty_parents(work_dir: &Path, file_path: &Path) {
+    for dir in file_path.ancestors().skip(1).take_while(|dir| *dir != work_dir) {
+        if std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,552 @@
+//! Create a repository from a remote one, similar to `git clone`.
+use std::{
+    convert::TryFrom,
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::progress::AtomicProgress;
+use git_hash::ObjectId;
+use git_index::{
+    entry::{Flags, Mode},
+    Entry,
+};
+use git_object::bstr::{BStr, BString, ByteSlice, ByteVec};
+use git_odb::FindExt;
+use git_protocol::{
+    fetch::{Action, Arguments, LsRefsAction, Ref, Response},
+    transport,
+    transport::client::Capabilities,
+};
+use git_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::{index_diff::stat_of, object, reference, restore::write_file, Progress};
+
+/// The name of the remote the repository is cloned from.
+const REMOTE_NAME: &str = "origin";
+/// The filter specification for partial clones without blobs.
+const BLOBLESS_FILTER: &str = "blob:none";
+
+/// Options for use in [`Repository::clone_into_with_progress()`][crate::Repository::clone_into_with_progress()].
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If true, create a bare repository whose branches are the branches of the remote, similar to `git clone --bare`.
+    pub bare: bool,
+    /// If true, create a partial clone without any blobs, similar to `git clone --filter=blob:none`, which fetches blobs
+    /// from the remote once they are needed. See [`Repository::partial_clone_blobless()`][crate::Repository::partial_clone_blobless()].
+    pub filter_blobs: bool,
+    /// The amount of threads to use at most when indexing the received pack and checking out files, or all logical cores
+    /// if `None`.
+    pub thread_limit: Option<usize>,
+    /// If set, the clone stops with [`Error::Interrupted`] once the token is cancelled, instead of when the
+    /// [global interrupt flag][crate::interrupt::IS_INTERRUPTED] is set.
+    pub cancellation: Option<crate::interrupt::CancellationToken>,
+}
+
+/// The error returned by [`Repository::clone_into_with_progress()`][crate::Repository::clone_into_with_progress()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not create the directory at '{}'", path.display())]
+    CreateDirectory { path: PathBuf, source: io::Error },
+    #[error(transparent)]
+    Init(#[from] crate::init::Error),
+    #[error(transparent)]
+    Connect(#[from] transport::client::connect::Error),
+    #[error(transparent)]
+    Fetch(#[from] git_protocol::fetch::Error),
+    #[error("The reference name {name:?} of the remote is invalid")]
+    InvalidRefName {
+        name: BString,
+        source: git_validate::refname::Error,
+    },
+    #[error(transparent)]
+    EditReferences(#[from] reference::edit::Error),
+    #[error("Could not read the configuration to add the remote to")]
+    ReadConfig(#[from] git_config::parser::ParserOrIoError<'static>),
+    #[error("Could not write the configuration of the remote")]
+    WriteConfig(#[source] io::Error),
+    #[error(transparent)]
+    Open(#[from] crate::open::Error),
+    #[error(transparent)]
+    FetchPromisedObjects(#[from] crate::promisor::Error),
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::OdbError),
+    #[error(transparent)]
+    FindBlob(#[from] git_odb::find::existing_object::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    PeelToTree(#[from] object::peel::to_kind::Error),
+    #[error(transparent)]
+    Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    PathTraversal(#[from] git_sec::permission::TraversalError),
+    #[error("The path {path:?} leads through the symlink {symlink:?} of the same tree")]
+    SymlinkInPath { path: BString, symlink: BString },
+    #[error(transparent)]
+    AddEntry(#[from] git_index::edit::add_entry::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] crate::worktree::write_index::Error),
+    #[error("Could not write '{}' to the working tree", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+impl crate::Repository {
+    /// Clone the repository at `url` into the directory `into`, like
+    /// [`clone_into_with_progress()`][crate::Repository::clone_into_with_progress()] does, but without reporting progress.
+    pub fn clone_into(url: &str, into: &Path, opts: Options) -> Result<crate::Repository, Error> {
+        Self::clone_into_with_progress(url, into, opts, crate::progress::Discard)
+    }
+
+    /// Clone the repository at `url` into the directory `into` like [`clone_into()`][crate::Repository::clone_into()] does,
+    /// but create a partial clone without any blobs, similar to `git clone --filter=blob:none <url> <into>`.
+    ///
+    /// The remote is configured as promisor remote, from which [`find_object()`][crate::Repository::find_object()] fetches
+    /// missing objects on demand. Only the blobs needed for the checkout are fetched right away, in a single request.
+    ///
+    /// Note that the remote must support filters, i.e. have `uploadpack.allowFilter` enabled, or else all blobs are sent
+    /// as in a normal clone.
+    pub fn partial_clone_blobless(url: &str, into: &Path, opts: Options) -> Result<crate::Repository, Error> {
+        Self::clone_into(
+            url,
+            into,
+            Options {
+                filter_blobs: true,
+                ..opts
+            },
+        )
+    }
+
+    /// Clone the repository at `url` into the directory `into`, which is created if needed, similar to `git clone <url> <into>`,
+    /// and return the new repository.
+    ///
+    /// All branches and tags of the remote are fetched into a single pack, with branches becoming remote tracking branches
+    /// below `refs/remotes/origin/`. The branch the `HEAD` of the remote points to is created locally and checked out by
+    /// writing its files to the working tree along with a new index. The remote is configured as `origin`, with the checked
+    /// out branch tracking its remote counterpart.
+    ///
+    /// `progress` receives the messages of the remote while it counts and compresses objects, the amount of bytes received
+    /// and objects indexed while the pack is written, as well as the amount of files written during the checkout.
+    ///
+    /// Note that the checkout doesn't apply filters like `core.autocrlf`, and that the protocol version 2 is requested
+    /// even though the remote may only support version 1.
+    pub fn clone_into_with_progress(
+        url: &str,
+        into: &Path,
+        opts: Options,
+        mut progress: impl Progress,
+    ) -> Result<crate::Repository, Error> {
+        let repo = if opts.bare {
+            std::fs::create_dir_all(into).map_err(|source| Error::CreateDirectory {
+                path: into.to_owned(),
+                source,
+            })?;
+            crate::init_bare(into)?
+        } else {
+            crate::init(into)?
+        };
+
+        let transport = transport::connect(url.as_bytes(), transport::Protocol::V2)?;
+        let mut delegate = Delegate {
+            pack_dir: repo.objects.store_ref().path().join("pack"),
+            cancellation: opts.cancellation.clone(),
+            thread_limit: opts.thread_limit,
+            object_hash: repo.object_hash(),
+            filter: opts.filter_blobs.then(|| BLOBLESS_FILTER),
+            refs: Vec::new(),
+        };
+        git_protocol::fetch(
+            transport,
+            &mut delegate,
+            git_protocol::credentials::helper,
+            progress.add_child("fetch"),
+            git_protocol::FetchConnection::TerminateOnSuccessfulCompletion,
+        )?;
+
+        let head = repo.write_cloned_references(url, &delegate.refs, opts.bare)?;
+        write_remote_config(
+            repo.git_dir(),
+            url,
+            head.as_ref()
+                .and_then(|(branch, _)| branch.as_ref())
+                .map(|branch| branch.as_bstr()),
+            opts.bare,
+            opts.filter_blobs,
+        )?;
+
+        // reopen the repository to pick up the configuration of the remote.
+        let repo = crate::open(into)?;
+        if let (false, Some((_, id))) = (opts.bare, head) {
+            repo.checkout_cloned_tree(
+                id,
+                opts.thread_limit,
+                crate::interrupt::flag(opts.cancellation.as_ref()),
+                progress.add_child("checkout"),
+            )?;
+        }
+        Ok(repo)
+    }
+
+    /// Create references for all `refs` of the remote at `url` and point `HEAD` to the branch the remote `HEAD` points to.
+    ///
+    /// Return the short name of that branch, if `HEAD` isn't detached, along with the commit it points to, if the remote
+    /// has a `HEAD`.
+    fn write_cloned_references(
+        &self,
+        url: &str,
+        refs: &[Ref],
+        bare: bool,
+    ) -> Result<Option<(Option<BString>, ObjectId)>, Error> {
+        let message: BString = format!("clone: from {}", url).into();
+        let update = |name: FullName, new: Target| RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: message.clone(),
+                },
+                expected: PreviousValue::Any,
+                new,
+            },
+            name,
+            deref: false,
+        };
+
+        let mut edits = Vec::with_capacity(refs.len() + 2);
+        let mut head = None;
+        for remote_ref in refs {
+            match remote_ref {
+                Ref::Symbolic { path, target, object } if *path == "HEAD" => {
+                    head = Some((target.strip_prefix(b"refs/heads/").map(BString::from), *object))
+                }
+                Ref::Direct { path, object } if *path == "HEAD" => head = Some((None, *object)),
+                _ => {
+                    let (path, id) = remote_ref.unpack();
+                    let name = match path.strip_prefix(b"refs/heads/") {
+                        Some(branch) if !bare => remote_tracking_name(branch.as_bstr()),
+                        _ => path.clone(),
+                    };
+                    edits.push(update(full_name(name)?, Target::Peeled(*id)));
+                }
+            }
+        }
+
+        let head_name = FullName::try_from("HEAD").expect("valid");
+        match &head {
+            Some((Some(branch), id)) => {
+                let mut branch_name = BString::from("refs/heads/");
+                branch_name.push_str(branch);
+                let branch_name = full_name(branch_name)?;
+                if !bare {
+                    edits.push(update(
+                        full_name(remote_tracking_name("HEAD".into()))?,
+                        Target::Symbolic(full_name(remote_tracking_name(branch.as_bstr()))?),
+                    ));
+                    edits.push(update(branch_name.clone(), Target::Peeled(*id)));
+                }
+                // `HEAD` comes last so the branch it points to exists when its reflog is written.
+                edits.push(update(head_name, Target::Symbolic(branch_name)));
+                self.edit_references(edits, git_lock::acquire::Fail::Immediately, None)?;
+            }
+            Some((None, id)) => {
+                edits.push(update(head_name, Target::Peeled(*id)));
+                self.edit_references(edits, git_lock::acquire::Fail::Immediately, None)?;
+            }
+            None => {
+                self.edit_references(edits, git_lock::acquire::Fail::Immediately, None)?;
+            }
+        }
+        Ok(head)
+    }
+
+    /// Write all files of the tree of commit `id` into the empty working tree using up to `thread_limit` threads, and create
+    /// an index for them unless `should_interrupt` is set in the meantime.
+    ///
+    /// Symlinks are created only after all other files were written, like `git` does, and trees with entries below one of
+    /// their symlinks are rejected before anything is written as these would be written wherever the symlink points to.
+    fn checkout_cloned_tree(
+        &self,
+        id: ObjectId,
+        thread_limit: Option<usize>,
+        should_interrupt: &AtomicBool,
+        progress: impl Progress,
+    ) -> Result<(), Error> {
+        let work_dir = self.work_dir().expect("non-bare repositories have a working tree");
+        let (files, symlinks): (Vec<_>, Vec<_>) = self
+            .tree_files::<Error>(id)?
+            .into_iter()
+            .partition(|(_, (mode, _))| *mode != Mode::SYMLINK);
+        for (path, _) in files.iter().chain(&symlinks) {
+            if let Some(symlink) = symlink_in_path(path.as_bstr(), &symlinks) {
+                return Err(Error::SymlinkInPath {
+                    path: path.clone(),
+                    symlink: symlink.to_owned(),
+                });
+            }
+        }
+        if self.promisor_remote().is_some() {
+            self.fetch_promised_objects(files.iter().chain(&symlinks).map(|(_, (_, id))| *id))?;
+        }
+        let mut progress = AtomicProgress::new(progress);
+        progress.init(
+            Some(files.len() + symlinks.len()),
+            git_features::progress::count("files"),
+        );
+
+        let objects = self.objects.clone().into_inner();
+        let states = git_features::parallel::in_parallel_with_slice(
+            &files,
+            thread_limit,
+            {
+                let objects = objects.clone();
+                let progress = progress.clone();
+                move |_| (objects.clone(), progress.clone(), Vec::new(), Vec::new())
+            },
+            |(path, (mode, id)), (objects, progress, buf, entries)| -> Result<(), Error> {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                entries.push((path, checkout_file(objects, work_dir, path, *mode, id, buf)?));
+                progress.inc();
+                Ok(())
+            },
+            || Some(std::time::Duration::from_millis(10)),
+        )?;
+
+        let mut entries: Vec<_> = states.into_iter().flat_map(|(_, _, _, entries)| entries).collect();
+        let mut buf = Vec::new();
+        for (path, (mode, id)) in &symlinks {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            entries.push((path, checkout_file(&objects, work_dir, path, *mode, id, &mut buf)?));
+            progress.inc();
+        }
+        entries.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        let mut index = git_index::File::new(self.git_dir().join("index"), git_index::Version::V2, self.object_hash());
+        for (path, entry) in entries {
+            index.add_entry(entry, path.as_bstr(), false)?;
+        }
+        self.write_index(&index)?;
+        Ok(())
+    }
+}
+
+/// Receives a pack with the objects of all branches and tags of the remote, and keeps the references that were advertised
+/// for them.
+struct Delegate {
+    pack_dir: PathBuf,
+    cancellation: Option<crate::interrupt::CancellationToken>,
+    thread_limit: Option<usize>,
+    object_hash: git_hash::Kind,
+    /// The filter specification to send, if the clone is partial.
+    filter: Option<&'static str>,
+    refs: Vec<Ref>,
+}
+
+impl git_protocol::fetch::DelegateBlocking for Delegate {
+    fn prepare_ls_refs(
+        &mut self,
+        _server: &Capabilities,
+        arguments: &mut Vec<BString>,
+        _features: &mut Vec<(&str, Option<&str>)>,
+    ) -> io::Result<LsRefsAction> {
+        arguments.extend(
+            ["HEAD", "refs/heads/", "refs/tags/"]
+                .iter()
+                .map(|prefix| format!("ref-prefix {}", prefix).into()),
+        );
+        Ok(LsRefsAction::Continue)
+    }
+
+    fn prepare_fetch(
+        &mut self,
+        _version: transport::Protocol,
+        _server: &Capabilities,
+        _features: &mut Vec<(&str, Option<&str>)>,
+        refs: &[Ref],
+    ) -> io::Result<Action> {
+        // protocol version 1 advertises all references, not only the ones we asked for.
+        self.refs = refs
+            .iter()
+            .filter(|remote_ref| {
+                let path = remote_ref.unpack().0;
+                *path == "HEAD" || path.starts_with(b"refs/heads/") || path.starts_with(b"refs/tags/")
+            })
+            .cloned()
+            .collect();
+        Ok(if self.refs.is_empty() {
+            Action::Cancel
+        } else {
+            Action::Continue
+        })
+    }
+
+    fn negotiate(
+        &mut self,
+        _refs: &[Ref],
+        arguments: &mut Arguments,
+        _previous_response: Option<&Response>,
+    ) -> io::Result<Action> {
+        let mut wants: Vec<_> = self.refs.iter().map(|remote_ref| *remote_ref.unpack().1).collect();
+        wants.sort();
+        wants.dedup();
+        for id in wants {
+            arguments.want(id);
+        }
+        if let Some(filter) = self.filter.filter(|_| arguments.can_use_filter()) {
+            arguments.filter(filter);
+        }
+        // there are no objects yet, so there is nothing in common with the remote.
+        Ok(Action::Cancel)
+    }
+}
+
+impl git_protocol::fetch::Delegate for Delegate {
+    fn receive_pack(
+        &mut self,
+        input: impl io::BufRead,
+        progress: impl Progress,
+        _refs: &[Ref],
+        _previous_response: &Response,
+    ) -> io::Result<()> {
+        let outcome = crate::fetch::write_pack(
+            input,
+            &self.pack_dir,
+            progress,
+            crate::interrupt::flag(self.cancellation.as_ref()),
+            None,
+            self.thread_limit,
+            self.object_hash,
+        )?;
+        match (self.filter, outcome.data_path) {
+            (Some(_), Some(data_path)) => std::fs::write(data_path.with_extension("promisor"), b""),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Return the path of the first of `symlinks` that is a parent directory of `path`, with `symlinks` sorted by path.
+fn symlink_in_path<'a>(path: &BStr, symlinks: &'a [(BString, (Mode, ObjectId))]) -> Option<&'a BStr> {
+    path.find_iter("/").find_map(|pos| {
+        let parent = &path[..pos];
+        symlinks
+            .binary_search_by(|(symlink, _)| symlink.as_bstr().cmp(parent.as_bstr()))
+            .ok()
+            .map(|idx| symlinks[idx].0.as_bstr())
+    })
+}
+
+/// Write the blob `id` to `path` in `work_dir` as file of the given `mode`, and return its index entry.
+fn checkout_file(
+    objects: &impl git_odb::Find<Error = git_odb::store::find::Error>,
+    work_dir: &Path,
+    path: &BString,
+    mode: Mode,
+    id: &ObjectId,
+    buf: &mut Vec<u8>,
+) -> Result<Entry, Error> {
+    let rela_path = git_path::from_bstr(path.as_bstr());
+    git_sec::permission::check_path_traversal(work_dir, &rela_path)?;
+    let file_path = work_dir.join(rela_path);
+    let io_err = |source| Error::Io {
+        path: file_path.clone(),
+        source,
+    };
+    let data = objects.find_blob(id, buf)?.data;
+    write_file(&file_path, mode, data).map_err(io_err)?;
+    let metadata = std::fs::symlink_metadata(&file_path).map_err(io_err)?;
+    Ok(Entry::new(stat_of(&metadata), *id, Flags::empty(), mode))
+}
+
+fn full_name(name: BString) -> Result<FullName, Error> {
+    FullName::try_from(name.as_bstr()).map_err(|source| Error::InvalidRefName { name, source })
+}
+
+/// Return the name of the remote tracking branch for the `branch` of the remote.
+fn remote_tracking_name(branch: &BStr) -> BString {
+    let mut name = BString::from(format!("refs/remotes/{}/", REMOTE_NAME));
+    name.push_str(branch);
+    name
+}
+
+/// Add the configuration of the remote at `url` to the configuration file in `git_dir`, along with the configuration
+/// of the local `branch` to track its remote counterpart.
+///
+/// If `filter_blobs` is true, the remote is also configured as promisor remote of a partial clone, which requires
+/// repository format version 1 for `git` to respect it.
+fn write_remote_config(
+    git_dir: &Path,
+    url: &str,
+    branch: Option<&BStr>,
+    bare: bool,
+    filter_blobs: bool,
+) -> Result<(), Error> {
+    let path = git_dir.join("config");
+    let mut config = git_config::File::open(&path)?;
+    {
+        let mut remote = config.new_section("remote", Some(REMOTE_NAME.into()));
+        remote.push("url".into(), config_value(url).into_bytes().into());
+        if filter_blobs {
+            remote.push("promisor".into(), b"true".to_vec().into());
+            remote.push("partialclonefilter".into(), BLOBLESS_FILTER.as_bytes().to_vec().into());
+        }
+        if !bare {
+            remote.push(
+                "fetch".into(),
+                format!("+refs/heads/*:refs/remotes/{}/*", REMOTE_NAME)
+                    .into_bytes()
+                    .into(),
+            );
+        }
+    }
+    if let (false, Some(branch)) = (bare, branch) {
+        let branch = branch.to_str_lossy();
+        let mut section = config.new_section("branch", Some(escape(&branch).into()));
+        section.push("remote".into(), REMOTE_NAME.as_bytes().to_vec().into());
+        section.push(
+            "merge".into(),
+            config_value(&format!("refs/heads/{}", branch)).into_bytes().into(),
+        );
+    }
+    if filter_blobs {
+        // `git` ignores extensions in repositories of version 0.
+        set_value(&mut config, "core", "repositoryformatversion", "1");
+        set_value(&mut config, "extensions", "partialClone", REMOTE_NAME);
+    }
+    std::fs::write(path, Vec::<u8>::from(&config)).map_err(Error::WriteConfig)
+}
+
+/// Set `key` in `section` of `config` to `value`, adding the key or the section if they don't exist yet.
+fn set_value(config: &mut git_config::File<'static>, section: &'static str, key: &'static str, value: &str) {
+    if config
+        .set_raw_value(section, None, key, value.as_bytes().to_vec())
+        .is_err()
+    {
+        let value = value.as_bytes().to_vec().into();
+        match config.section_mut(section, None) {
+            Ok(mut body) => body.push(key.into(), value),
+            Err(_) => config.new_section(section, None).push(key.into(), value),
+        }
+    }
+}
+
+/// Escape backslashes and double quotes in `value` so it can be used in a quoted string of a git configuration file.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Return `value` in a form that is read back unchanged as value of a git configuration file.
+fn config_value(value: &str) -> String {
+    let needs_quotes = value.contains(|c| c == '#' || c == ';') || value.trim() != value;
+    if needs_quotes {
+        format!("\"{}\"", escape(value))
+    } else {
+        escape(value)
+    }
+}
@@ -13,6 +13,8 @@ pub enum Error {
     WriteObject(#[from] crate::object::write::Error),
     #[error(transparent)]
     ReferenceEdit(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    Sign(#[from] crate::sign::Error),
 }
 
 ///
@@ -214,3 +216,96 @@ pub mod describe {
         }
     }
 }
+
+///
+#[cfg(feature = "regex")]
+pub mod grep {
+    use crate::{bstr::ByteSlice, object, Commit, Id};
+
+    /// Options for use in [`Repository::find_commits_by_message()`][crate::Repository::find_commits_by_message()].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Options {
+        /// If true, the pattern is interpreted as regular expression. Otherwise it is matched literally as substring.
+        pub regex: bool,
+    }
+
+    /// The error returned by [`Repository::find_commits_by_message()`][crate::Repository::find_commits_by_message()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The pattern could not be compiled into a regular expression")]
+        Regex(#[from] regex::Error),
+        #[error(transparent)]
+        Traverse(#[from] git_traverse::commit::ancestors::Error),
+        #[error(transparent)]
+        FindExistingObject(#[from] object::find::existing::OdbError),
+        #[error(transparent)]
+        ObjectKind(#[from] object::try_into::Error),
+        #[error("The commit message could not be decoded")]
+        Decode(#[from] git_object::decode::Error),
+    }
+
+    enum Matcher {
+        Literal(Vec<u8>),
+        Regex(regex::bytes::Regex),
+    }
+
+    impl Matcher {
+        fn is_match(&self, haystack: &[u8]) -> bool {
+            match self {
+                Matcher::Literal(needle) => haystack.find(needle).is_some(),
+                Matcher::Regex(re) => re.is_match(haystack),
+            }
+        }
+    }
+
+    /// The iterator returned by [`Repository::find_commits_by_message()`][crate::Repository::find_commits_by_message()].
+    pub struct Iter<'repo> {
+        ancestors: crate::id::ancestors::Iter<'repo>,
+        matcher: Matcher,
+    }
+
+    impl<'repo> Iter<'repo> {
+        fn matches(&self, id: Id<'repo>) -> Result<Option<Commit<'repo>>, Error> {
+            let commit = id.object()?.try_into_commit()?;
+            let is_match = self.matcher.is_match(commit.message_raw()?);
+            Ok(is_match.then(|| commit))
+        }
+    }
+
+    impl<'repo> Iterator for Iter<'repo> {
+        type Item = Result<Commit<'repo>, Error>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            loop {
+                let id = match self.ancestors.next()? {
+                    Ok(id) => id,
+                    Err(err) => return Some(Err(err.into())),
+                };
+                match self.matches(id) {
+                    Ok(Some(commit)) => return Some(Ok(commit)),
+                    Ok(None) => continue,
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+        }
+    }
+
+    impl crate::Repository {
+        /// Traverse the history of the commit at `tip` and return an iterator over all commits whose message matches `pattern`,
+        /// similar to `git log --grep=<pattern>`.
+        ///
+        /// The `pattern` is a regular expression if [`Options::regex`] is set, or a literal substring otherwise.
+        pub fn find_commits_by_message(&self, pattern: &str, tip: Id<'_>, opts: Options) -> Result<Iter<'_>, Error> {
+            let matcher = if opts.regex {
+                Matcher::Regex(regex::bytes::Regex::new(pattern)?)
+            } else {
+                Matcher::Literal(pattern.as_bytes().to_owned())
+            };
+            Ok(Iter {
+                ancestors: Id::from_id(tip.detach(), self).ancestors().all()?,
+                matcher,
+            })
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Format commits into a single line with placeholders similar to those of `git log --format`.
+use git_object::bstr::{BString, ByteSlice, ByteVec};
+use git_ref::{Category, FullName};
+
+use crate::{date, reference, Commit};
+
+/// The error returned by [`Repository::format_commit_summary()`][crate::Repository::format_commit_summary()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error(transparent)]
+    ShortId(#[from] crate::id::shorten::Error),
+    #[error(transparent)]
+    ReferencesByTarget(#[from] git_ref::file::by_target::Error),
+    #[error(transparent)]
+    FindHead(#[from] reference::find::existing::Error),
+}
+
+/// A placeholder or literal text in a [`CommitFormat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Literal(BString),
+    /// `%H`
+    Hash,
+    /// `%h`
+    ShortHash,
+    /// `%an`
+    AuthorName,
+    /// `%ae`
+    AuthorEmail,
+    /// `%ai`
+    AuthorDateIso,
+    /// `%s`
+    Subject,
+    /// `%b`
+    Body,
+    /// `%d`
+    RefNames,
+}
+
+/// A format string like `%h %s%d`, parsed once to format any amount of commits with
+/// [`Repository::format_commit_summary()`][crate::Repository::format_commit_summary()].
+///
+/// The following placeholders are supported:
+///
+/// * `%H` - the commit hash
+/// * `%h` - the abbreviated commit hash
+/// * `%an` - the author name
+/// * `%ae` - the author email
+/// * `%ai` - the author date in an ISO 8601-like format, like `2005-04-07 15:13:13 -0700`
+/// * `%s` - the subject, i.e. the first paragraph of the message with its lines joined
+/// * `%b` - the body, i.e. everything after the subject
+/// * `%d` - the names of references pointing to the commit, like ` (HEAD -> main, origin/main, tag: v1.0)`, with `HEAD`
+///   first and all others sorted by their full name
+/// * `%%` - a literal `%`
+///
+/// Like in `git`, unknown placeholders are retained as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitFormat {
+    tokens: Vec<Token>,
+}
+
+impl CommitFormat {
+    /// Parse `format` into a sequence of placeholders and literal text.
+    pub fn new(format: impl AsRef<[u8]>) -> Self {
+        let mut tokens = Vec::new();
+        let mut literal = BString::default();
+        let mut rest = format.as_ref();
+        while let Some(pos) = rest.find_byte(b'%') {
+            literal.push_str(&rest[..pos]);
+            rest = &rest[pos + 1..];
+            let (token, consumed) = match rest {
+                [b'%', ..] => {
+                    literal.push_byte(b'%');
+                    rest = &rest[1..];
+                    continue;
+                }
+                [b'H', ..] => (Token::Hash, 1),
+                [b'h', ..] => (Token::ShortHash, 1),
+                [b'a', b'n', ..] => (Token::AuthorName, 2),
+                [b'a', b'e', ..] => (Token::AuthorEmail, 2),
+                [b'a', b'i', ..] => (Token::AuthorDateIso, 2),
+                [b's', ..] => (Token::Subject, 1),
+                [b'b', ..] => (Token::Body, 1),
+                [b'd', ..] => (Token::RefNames, 1),
+                _ => {
+                    literal.push_byte(b'%');
+                    continue;
+                }
+            };
+            if !literal.is_empty() {
+                tokens.push(Token::Literal(std::mem::take(&mut literal)));
+            }
+            tokens.push(token);
+            rest = &rest[consumed..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+        CommitFormat { tokens }
+    }
+}
+
+impl crate::Repository {
+    /// Format `commit` according to `format`, similar to `git log --format=<format>`, but without a trailing newline.
+    ///
+    /// Note that `%d` only shows references pointing to `commit` directly, so annotated tags are not included.
+    pub fn format_commit_summary(&self, commit: &Commit<'_>, format: &CommitFormat) -> Result<BString, Error> {
+        let mut out = BString::default();
+        for token in &format.tokens {
+            match token {
+                Token::Literal(text) => out.push_str(text),
+                Token::Hash => out.push_str(commit.id.to_hex().to_string()),
+                Token::ShortHash => out.push_str(commit.short_id()?.to_string()),
+                Token::AuthorName => out.push_str(commit.author()?.name),
+                Token::AuthorEmail => out.push_str(commit.author()?.email),
+                Token::AuthorDateIso => out.push_str(date::format_iso(&commit.author()?.time)),
+                Token::Subject => out.push_str(commit.message()?.summary().as_bytes()),
+                Token::Body => out.push_str(commit.message()?.body.map(|body| body.as_bytes()).unwrap_or_default()),
+                Token::RefNames => {
+                    let names = self.ref_names_for_decoration(commit)?;
+                    if names.is_empty() {
+                        continue;
+                    }
+                    out.push_str(" (");
+                    for (index, name) in names.iter().enumerate() {
+                        if index != 0 {
+                            out.push_str(", ");
+                        }
+                        out.push_str(name);
+                    }
+                    out.push_byte(b')');
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Return the names of references pointing to `commit` like `git log --decorate` displays them, with `HEAD` first.
+    fn ref_names_for_decoration(&self, commit: &Commit<'_>) -> Result<Vec<BString>, Error> {
+        let mut names = self
+            .refs
+            .iter_references_by_target(&commit.id)?
+            .map(|r| r.map(|r| r.name))
+            .collect::<Result<Vec<_>, _>>()?;
+        names.sort();
+
+        let mut out = Vec::with_capacity(names.len() + 1);
+        let head = self.head()?;
+        if head.is_detached() {
+            if head.id().map_or(false, |id| id.detach() == commit.id) {
+                out.push("HEAD".into());
+            }
+        } else if let Some(pos) = head
+            .referent_name()
+            .and_then(|referent| names.iter().position(|name| name.as_bstr() == referent.as_bstr()))
+        {
+            let mut name = BString::from("HEAD -> ");
+            name.push_str(short_name(&names.remove(pos)));
+            out.push(name);
+        }
+        out.extend(names.iter().map(short_name));
+        Ok(out)
+    }
+}
+
+/// Shorten `name` like `git log --decorate` does.
+fn short_name(name: &FullName) -> BString {
+    match name.category_and_short_name() {
+        Some((Category::Tag, short)) => {
+            let mut out = BString::from("tag: ");
+            out.push_str(short);
+            out
+        }
+        Some((Category::LocalBranch, short)) | Some((Category::RemoteBranch, short)) => short.to_owned(),
+        _ => name.as_bstr().to_owned(),
+    }
+}
@@ -0,0 +1,61 @@
+//! Read generation numbers from the commit-graph to quickly rule out ancestry between commits.
+use git_hash::ObjectId;
+
+/// The error returned by [`Repository::commit_generation()`][crate::Repository::commit_generation()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The commit-graph could not be opened")]
+    Open(#[from] git_commitgraph::graph::Error),
+}
+
+impl crate::Repository {
+    /// Return the generation number of the commit with `id` as stored in the commit-graph, or `None` if there is no
+    /// commit-graph, the commit isn't in it or its generation number wasn't computed by the `git` version that wrote it.
+    ///
+    /// Commits without parents have generation 1, all others have a generation one greater than the greatest generation of
+    /// their parents.
+    ///
+    /// Note that the commit-graph is opened on each call.
+    pub fn commit_generation(&self, id: impl Into<ObjectId>) -> Result<Option<u64>, Error> {
+        Ok(self.commit_generations(&[id.into()])?[0])
+    }
+
+    /// Return `Some(false)` if `ancestor` can't be an ancestor of `descendant` as judged by their generation numbers in the
+    /// commit-graph, `Some(true)` if both are the same commit, or `None` if a traversal is needed to find out, which is also
+    /// the case if the commit-graph can't be read or doesn't contain one of the commits.
+    pub fn is_ancestor_via_generation(
+        &self,
+        ancestor: impl Into<ObjectId>,
+        descendant: impl Into<ObjectId>,
+    ) -> Option<bool> {
+        let (ancestor, descendant) = (ancestor.into(), descendant.into());
+        if ancestor == descendant {
+            return Some(true);
+        }
+        match self.commit_generations(&[ancestor, descendant]).ok()?[..] {
+            // distinct commits with the same generation can't reach each other either.
+            [Some(ancestor), Some(descendant)] if ancestor >= descendant => Some(false),
+            _ => None,
+        }
+    }
+
+    fn commit_generations(&self, ids: &[ObjectId]) -> Result<Vec<Option<u64>>, Error> {
+        let info_dir = self.objects.store_ref().path().join("info");
+        if !info_dir.join("commit-graph").is_file() && !info_dir.join("commit-graphs").is_dir() {
+            return Ok(vec![None; ids.len()]);
+        }
+        let graph = git_commitgraph::Graph::from_info_dir(info_dir)?;
+        Ok(ids
+            .iter()
+            .map(|id| {
+                graph
+                    .commit_by_id(id)
+                    .map(|commit| commit.generation())
+                    // a generation of zero is written by older versions of git that didn't compute them.
+                    .filter(|generation| *generation != 0 && *generation != git_commitgraph::GENERATION_NUMBER_INFINITY)
+                    .map(u64::from)
+            })
+            .collect())
+    }
+}
@@ -14,6 +14,28 @@ pub enum Error {
     DecodeBoolean { key: String, value: BString },
     #[error(transparent)]
     PathInterpolation(#[from] git_config::values::path::interpolate::Error),
+    #[error("'{}' is not a configuration override of the form section[.subsection].key[=value]", .input)]
+    InvalidOverride { input: String },
+}
+
+mod snapshot {
+    use std::borrow::Cow;
+
+    use crate::ConfigSnapshot;
+
+    impl ConfigSnapshot {
+        /// Return the value of `key` in `section`, or `None` if it isn't set. If there are multiple values, the last one wins.
+        ///
+        /// `section` is the name of the section optionally followed by its subsection, separated by a dot, like `core` or
+        /// `remote.origin`.
+        pub fn value(&self, section: &str, key: &str) -> Option<Cow<'_, [u8]>> {
+            let (section, subsection) = match section.split_once('.') {
+                Some((section, subsection)) => (section, Some(subsection)),
+                None => (section, None),
+            };
+            self.resolved.raw_value(section, subsection, key).ok()
+        }
+    }
 }
 
 /// Utility type to keep pre-obtained configuration values.
@@ -47,6 +69,7 @@ mod cache {
     use std::{convert::TryFrom, path::PathBuf};
 
     use git_config::{
+        file::Origin,
         values::{Boolean, Integer},
         File,
     };
@@ -61,8 +84,34 @@ mod cache {
             home_env: permission::env_var::Resource,
             git_install_dir: Option<&std::path::Path>,
         ) -> Result<Self, Error> {
-            let config = File::open(git_dir.join("config"))?;
+            let mut config = File::open(git_dir.join("config"))?;
+            config.set_origin(Origin::Repository);
+            Self::from_file(config, xdg_config_home_env, home_env, git_install_dir)
+        }
 
+        /// Append `layer` to the configuration so its values take precedence, and re-read all values derived from it.
+        pub fn append_layer(
+            &mut self,
+            layer: File<'static>,
+            git_install_dir: Option<&std::path::Path>,
+        ) -> Result<(), Error> {
+            let mut config = (*self.resolved).clone();
+            config.append(layer);
+            *self = Self::from_file(
+                config,
+                self.xdg_config_home_env.clone(),
+                self.home_env.clone(),
+                git_install_dir,
+            )?;
+            Ok(())
+        }
+
+        fn from_file(
+            config: File<'static>,
+            xdg_config_home_env: permission::env_var::Resource,
+            home_env: permission::env_var::Resource,
+            git_install_dir: Option<&std::path::Path>,
+        ) -> Result<Self, Error> {
             let is_bare = config_bool(&config, "core.bare", false)?;
             let use_multi_pack_index = config_bool(&config, "core.multiPackIndex", true)?;
             let ignore_case = config_bool(&config, "core.ignorecase", false)?;
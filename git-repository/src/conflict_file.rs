@@ -0,0 +1,221 @@
+//! Write files with conflict markers for conflicting changes during a merge.
+use std::path::Path;
+
+use git_object::bstr::{BString, ByteSlice};
+
+use crate::blob_diff::{lines, myers, Edit};
+
+/// The way conflicts are presented in files written by [`Repository::write_conflict_file()`][crate::Repository::write_conflict_file()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Style {
+    /// Use the style configured in `merge.conflictStyle`, or [`Style::Merge`] if it's unset.
+    Default,
+    /// Show our and their version of conflicting lines, without lines that are the same in both.
+    Merge,
+    /// Show our, the ancestor's and their version of conflicting lines.
+    Diff3,
+    /// Like [`Style::Diff3`], but without lines that are the same in our and their version, like [`Style::Merge`].
+    ZDiff3,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style::Default
+    }
+}
+
+/// Options for use in [`Repository::write_conflict_file()`][crate::Repository::write_conflict_file()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// How to present conflicts.
+    pub style: Style,
+    /// The label after the `<<<<<<<` marker, preceding our version.
+    pub ours_label: BString,
+    /// The label after the `|||||||` marker, preceding the ancestor's version.
+    pub ancestor_label: BString,
+    /// The label after the `>>>>>>>` marker, following their version.
+    pub theirs_label: BString,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            style: Style::Default,
+            ours_label: "ours".into(),
+            ancestor_label: "base".into(),
+            theirs_label: "theirs".into(),
+        }
+    }
+}
+
+/// The error returned by [`Repository::write_conflict_file()`][crate::Repository::write_conflict_file()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to write conflict files into")]
+    MissingWorkTree,
+    #[error("The value '{value}' of 'merge.conflictStyle' isn't one of 'merge', 'diff3' or 'zdiff3'")]
+    InvalidConflictStyle { value: BString },
+    #[error(transparent)]
+    PathTraversal(#[from] git_sec::permission::TraversalError),
+    #[error("Could not write '{}' in the working tree", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl crate::Repository {
+    /// Merge the lines of `ours` and `theirs` with their common `ancestor` and write the result to the file at `path`,
+    /// relative to the root of the working tree, with conflicting changes enclosed in conflict markers in the given style,
+    /// similar to what `git merge` does for conflicting files.
+    ///
+    /// Changes made on only one side or identically on both sides are taken as is. If there is no `ancestor`, like when
+    /// both sides added the file, it's treated as empty.
+    pub fn write_conflict_file(
+        &self,
+        path: &Path,
+        ancestor: Option<&[u8]>,
+        ours: &[u8],
+        theirs: &[u8],
+        opts: Options,
+    ) -> Result<(), Error> {
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        git_sec::permission::check_path_traversal(work_dir, path)?;
+        let style = match opts.style {
+            Style::Default => match self.config.resolved.string("merge", None, "conflictStyle") {
+                Some(value) => match value.as_bytes() {
+                    b"merge" => Style::Merge,
+                    b"diff3" => Style::Diff3,
+                    b"zdiff3" => Style::ZDiff3,
+                    _ => {
+                        return Err(Error::InvalidConflictStyle {
+                            value: value.into_owned(),
+                        })
+                    }
+                },
+                None => Style::Merge,
+            },
+            style => style,
+        };
+
+        let (merged, _has_conflicts) = merge(ancestor.unwrap_or_default(), ours, theirs, style, &opts);
+        let file_path = work_dir.join(path);
+        std::fs::write(&file_path, merged).map_err(|source| Error::Io {
+            path: file_path,
+            source,
+        })
+    }
+}
+
+/// Merge `ours` and `theirs` line by line with the diff3 algorithm, presenting conflicts in `style`, and return the
+/// merged content along with `true` if there were conflicts.
+pub(crate) fn merge(ancestor: &[u8], ours: &[u8], theirs: &[u8], style: Style, opts: &Options) -> (Vec<u8>, bool) {
+    let (ancestor, ours, theirs) = (lines(ancestor), lines(ours), lines(theirs));
+    let (ours_at, theirs_at) = (matching_lines(&ancestor, &ours), matching_lines(&ancestor, &theirs));
+    let mut out = Vec::new();
+    let mut has_conflicts = false;
+    let (mut o, mut a, mut b) = (0, 0, 0);
+    loop {
+        // lines that are unchanged on both sides.
+        while o < ancestor.len() && ours_at[o] == Some(a) && theirs_at[o] == Some(b) {
+            out.extend_from_slice(ancestor[o]);
+            o += 1;
+            a += 1;
+            b += 1;
+        }
+        let next_stable = (o..ancestor.len()).find(|&idx| ours_at[idx].is_some() && theirs_at[idx].is_some());
+        let (o_end, a_end, b_end) = match next_stable {
+            Some(idx) => (idx, ours_at[idx].expect("checked"), theirs_at[idx].expect("checked")),
+            None => (ancestor.len(), ours.len(), theirs.len()),
+        };
+        let (base_chunk, ours_chunk, theirs_chunk) = (&ancestor[o..o_end], &ours[a..a_end], &theirs[b..b_end]);
+        if ours_chunk == base_chunk {
+            theirs_chunk.iter().for_each(|line| out.extend_from_slice(line));
+        } else if theirs_chunk == base_chunk || ours_chunk == theirs_chunk {
+            ours_chunk.iter().for_each(|line| out.extend_from_slice(line));
+        } else {
+            write_conflict(&mut out, base_chunk, ours_chunk, theirs_chunk, style, opts);
+            has_conflicts = true;
+        }
+        if next_stable.is_none() {
+            break;
+        }
+        o = o_end;
+        a = a_end;
+        b = b_end;
+    }
+    (out, has_conflicts)
+}
+
+/// Return the index of the line in `other` for each line in `ancestor` that remained unchanged.
+fn matching_lines(ancestor: &[&[u8]], other: &[&[u8]]) -> Vec<Option<usize>> {
+    let mut out = vec![None; ancestor.len()];
+    let (mut o, mut x) = (0, 0);
+    for edit in myers(ancestor, other) {
+        match edit {
+            Edit::Equal => {
+                out[o] = Some(x);
+                o += 1;
+                x += 1;
+            }
+            Edit::Delete => o += 1,
+            Edit::Insert => x += 1,
+        }
+    }
+    out
+}
+
+fn write_conflict(
+    out: &mut Vec<u8>,
+    ancestor: &[&[u8]],
+    mut ours: &[&[u8]],
+    mut theirs: &[&[u8]],
+    style: Style,
+    opts: &Options,
+) {
+    let mut suffix: &[&[u8]] = &[];
+    if style != Style::Diff3 {
+        let prefix_len = ours.iter().zip(theirs).take_while(|(a, b)| a == b).count();
+        ours[..prefix_len].iter().for_each(|line| out.extend_from_slice(line));
+        ours = &ours[prefix_len..];
+        theirs = &theirs[prefix_len..];
+        let suffix_len = ours
+            .iter()
+            .rev()
+            .zip(theirs.iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        suffix = &ours[ours.len() - suffix_len..];
+        ours = &ours[..ours.len() - suffix_len];
+        theirs = &theirs[..theirs.len() - suffix_len];
+    }
+
+    push_marker(out, "<<<<<<<", &opts.ours_label);
+    push_lines(out, ours);
+    if style != Style::Merge {
+        push_marker(out, "|||||||", &opts.ancestor_label);
+        push_lines(out, ancestor);
+    }
+    push_marker(out, "=======", "");
+    push_lines(out, theirs);
+    push_marker(out, ">>>>>>>", &opts.theirs_label);
+    suffix.iter().for_each(|line| out.extend_from_slice(line));
+}
+
+fn push_marker(out: &mut Vec<u8>, marker: &str, label: impl AsRef<[u8]>) {
+    out.extend_from_slice(marker.as_bytes());
+    if !label.as_ref().is_empty() {
+        out.push(b' ');
+        out.extend_from_slice(label.as_ref());
+    }
+    out.push(b'\n');
+}
+
+/// Append `lines` to `out`, making sure they end with a newline to keep the following conflict marker on its own line.
+fn push_lines(out: &mut Vec<u8>, lines: &[&[u8]]) {
+    lines.iter().for_each(|line| out.extend_from_slice(line));
+    if !lines.is_empty() && !out.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+}
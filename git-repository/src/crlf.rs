@@ -0,0 +1,166 @@
+//! Convert line endings of blobs between their form in the index and in the working tree.
+use std::{borrow::Cow, path::Path};
+
+use git_attributes::State;
+use git_object::bstr::{BString, ByteSlice};
+
+/// The error returned by [`Repository::normalize_blob_to_index()`][crate::Repository::normalize_blob_to_index()] and
+/// [`Repository::normalize_blob_to_worktree()`][crate::Repository::normalize_blob_to_worktree()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Attributes(#[from] crate::attributes::Error),
+    #[error("Invalid value for '{key}' = '{value}'")]
+    InvalidConfig { key: &'static str, value: BString },
+}
+
+/// The line ending to use in the working tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Eol {
+    Lf,
+    Crlf,
+}
+
+/// How line endings of a blob are to be converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Conversion {
+    /// The blob is binary and must not be converted.
+    None,
+    /// The blob is text, with its line endings in the working tree set to `Eol`.
+    Text(Eol),
+    /// The blob is converted like [`Conversion::Text`] unless its content looks binary.
+    Auto(Eol),
+}
+
+impl crate::Repository {
+    /// Convert `data` of the file at `path`, relative to the root of the working tree, from its working tree form into
+    /// the form to be stored in the index by turning `CRLF` line endings into `LF`, similar to what git does when staging files.
+    ///
+    /// Whether and how to convert is determined by the `text`, `eol` and `binary` attributes of `path` as well as the
+    /// `core.autocrlf` and `core.eol` configuration. Unless `path` is explicitly marked as text, `data` that looks binary
+    /// is never converted.
+    pub fn normalize_blob_to_index<'a>(&self, path: &Path, data: &'a [u8]) -> Result<Cow<'a, [u8]>, Error> {
+        let convert = match self.crlf_conversion(path)? {
+            Conversion::None => false,
+            Conversion::Text(_) => true,
+            Conversion::Auto(_) => !is_binary(data),
+        };
+        if !convert || data.find(b"\r\n").is_none() {
+            return Ok(Cow::Borrowed(data));
+        }
+        Ok(Cow::Owned(data.replace(b"\r\n", b"\n")))
+    }
+
+    /// Convert `data` of the file at `path`, relative to the root of the working tree, from its form in the index into
+    /// the form to be written into the working tree by turning `LF` line endings into `CRLF` if configured, similar to what
+    /// git does when checking out files.
+    ///
+    /// Line endings are converted if the `eol` attribute is `crlf` or, in its absence, if `core.autocrlf` is `true` or
+    /// `core.eol` is `crlf`, or `native` on Windows, which also is its default.
+    /// See [`normalize_blob_to_index()`][crate::Repository::normalize_blob_to_index()] for how the decision is made
+    /// whether `path` is text at all.
+    pub fn normalize_blob_to_worktree<'a>(&self, path: &Path, data: &'a [u8]) -> Result<Cow<'a, [u8]>, Error> {
+        let convert = match self.crlf_conversion(path)? {
+            Conversion::None | Conversion::Text(Eol::Lf) | Conversion::Auto(Eol::Lf) => false,
+            Conversion::Text(Eol::Crlf) => true,
+            // blobs that already have CRLF line endings were committed that way on purpose.
+            Conversion::Auto(Eol::Crlf) => !is_binary(data) && !data.contains(&b'\r'),
+        };
+        if !convert || !data.contains(&b'\n') {
+            return Ok(Cow::Borrowed(data));
+        }
+        let mut out = Vec::with_capacity(data.len() + data.len() / 16);
+        let mut previous = None;
+        for &byte in data {
+            if byte == b'\n' && previous != Some(b'\r') {
+                out.push(b'\r');
+            }
+            out.push(byte);
+            previous = Some(byte);
+        }
+        Ok(Cow::Owned(out))
+    }
+
+    fn crlf_conversion(&self, path: &Path) -> Result<Conversion, Error> {
+        let path = git_path::to_unix_separators(git_path::into_bstr(path));
+        let case = self
+            .config
+            .ignore_case
+            .then(|| git_glob::pattern::Case::Fold)
+            .unwrap_or_default();
+        let attributes = self.attributes(&path)?;
+        let attribute = |name: &str| {
+            attributes
+                .attribute_matching_relative_path(&*path, name, Some(false), case)
+                .cloned()
+                .unwrap_or(State::Unspecified)
+        };
+
+        let config = &self.config.resolved;
+        let autocrlf = match config.string("core", None, "autocrlf") {
+            Some(value) if value.as_ref() == "input" => Some(Eol::Lf),
+            Some(_) => config
+                .boolean("core", None, "autocrlf")
+                .transpose()
+                .map_err(|err| Error::InvalidConfig {
+                    key: "core.autocrlf",
+                    value: err.input,
+                })?
+                .and_then(|autocrlf| autocrlf.then(|| Eol::Crlf)),
+            None => None,
+        };
+        let eol_attribute = match attribute("eol") {
+            State::Value(eol) if &*eol == "lf" => Some(Eol::Lf),
+            State::Value(eol) if &*eol == "crlf" => Some(Eol::Crlf),
+            _ => None,
+        };
+        let eol = match eol_attribute.or(autocrlf) {
+            Some(eol) => eol,
+            None => match config.string("core", None, "eol") {
+                Some(value) if value.as_ref() == "lf" => Eol::Lf,
+                Some(value) if value.as_ref() == "crlf" => Eol::Crlf,
+                Some(value) if value.as_ref() != "native" => {
+                    return Err(Error::InvalidConfig {
+                        key: "core.eol",
+                        value: value.into_owned().into(),
+                    })
+                }
+                _ if cfg!(windows) => Eol::Crlf,
+                _ => Eol::Lf,
+            },
+        };
+
+        // `binary` is a macro for `-text`, which isn't expanded when matching attributes.
+        if attribute("binary") == State::Set {
+            return Ok(Conversion::None);
+        }
+        Ok(match attribute("text") {
+            State::Unset => Conversion::None,
+            State::Set => Conversion::Text(eol),
+            State::Value(value) if &*value == "auto" => Conversion::Auto(eol),
+            _ if eol_attribute.is_some() => Conversion::Text(eol),
+            _ if autocrlf.is_some() => Conversion::Auto(eol),
+            _ => Conversion::None,
+        })
+    }
+}
+
+/// Return true if `data` looks like binary data, using the same heuristic as git.
+fn is_binary(data: &[u8]) -> bool {
+    let (mut printable, mut non_printable) = (0usize, 0usize);
+    let mut bytes = data.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            b'\r' if bytes.peek() == Some(&&b'\n') => {}
+            // a lone carriage return can't be restored after conversion.
+            b'\r' | 0 => return true,
+            b'\n' | b'\t' | 0x08 | 0x0c | 0x1b => printable += 1,
+            // a trailing end-of-file character, as used by DOS.
+            0x1a if bytes.peek().is_none() => {}
+            0..=0x1f | 0x7f => non_printable += 1,
+            _ => printable += 1,
+        }
+    }
+    (printable >> 7) < non_printable
+}
@@ -0,0 +1,68 @@
+//! Utilities to deal with dates, as needed when formatting them.
+
+/// Compute the proleptic gregorian `(year, month, day)` for the given amount of `days` since the unix epoch.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Format `time` like `2005-04-07 15:13:13 -0700` in its own time zone, similar to `git log --date=iso`.
+pub(crate) fn format_iso(time: &git_actor::Time) -> String {
+    let local = i64::from(time.seconds_since_unix_epoch) + i64::from(time.offset_in_seconds);
+    let (year, month, day) = civil_from_days(local.div_euclid(86400));
+    let secs_of_day = local.rem_euclid(86400);
+    let offset_minutes = time.offset_in_seconds.abs() / 60;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} {}{:02}{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60,
+        secs_of_day % 60,
+        match time.sign {
+            git_actor::Sign::Plus => '+',
+            git_actor::Sign::Minus => '-',
+        },
+        offset_minutes / 60,
+        offset_minutes % 60
+    )
+}
+
+/// Format `time` like `Thu, 7 Apr 2005 15:13:13 -0700` in its own time zone, as used in the `Date` header of emails.
+pub(crate) fn format_rfc2822(time: &git_actor::Time) -> String {
+    const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let local = i64::from(time.seconds_since_unix_epoch) + i64::from(time.offset_in_seconds);
+    let days = local.div_euclid(86400);
+    let (year, month, day) = civil_from_days(days);
+    let secs_of_day = local.rem_euclid(86400);
+    let offset_minutes = time.offset_in_seconds.abs() / 60;
+    format!(
+        "{}, {} {} {} {:02}:{:02}:{:02} {}{:02}{:02}",
+        // the unix epoch was a Thursday
+        WEEKDAYS[(days + 4).rem_euclid(7) as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60,
+        secs_of_day % 60,
+        match time.sign {
+            git_actor::Sign::Plus => '+',
+            git_actor::Sign::Minus => '-',
+        },
+        offset_minutes / 60,
+        offset_minutes % 60
+    )
+}
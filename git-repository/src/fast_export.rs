@@ -0,0 +1,316 @@
+//! Produce a stream in the format understood by `git fast-import`, as documented in `git-fast-import(1)`.
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+};
+
+use git_hash::ObjectId;
+use git_object::{
+    bstr::{BStr, ByteSlice},
+    tree::EntryMode,
+};
+use git_odb::FindExt;
+
+use crate::{object, reference, Id};
+
+/// Options for use in [`Repository::export_fast()`][crate::Repository::export_fast()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If true, signatures of annotated tags are exported as part of their message. Otherwise they are stripped.
+    pub signed_tags: bool,
+    /// If true, tags whose target wasn't exported refer to it by its object id. Otherwise these tags are dropped.
+    pub tag_of_filtered_object: bool,
+    /// If true, collect the [mark][Stats::marks] of each exported object to allow writing a marks file.
+    pub mark_map: bool,
+}
+
+/// Statistics about the stream produced by [`Repository::export_fast()`][crate::Repository::export_fast()].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// The amount of `blob` records written.
+    pub blobs: usize,
+    /// The amount of `commit` records written.
+    pub commits: usize,
+    /// The amount of `tag` records written.
+    pub tags: usize,
+    /// The amount of `reset` records written.
+    pub resets: usize,
+    /// All marks along with the id of the object they represent, in the order they were assigned.
+    ///
+    /// It's only filled if [`Options::mark_map`] is set, and can be used to write a file suitable for `git fast-import --import-marks`.
+    pub marks: Vec<(usize, ObjectId)>,
+}
+
+/// The error returned by [`Repository::export_fast()`][crate::Repository::export_fast()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    ReferenceIterInit(#[from] reference::iter::init::Error),
+    #[error(transparent)]
+    ReferenceIter(#[from] reference::iter::Error),
+    #[error("A reference could not be read or peeled")]
+    Reference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::OdbError),
+    #[error(transparent)]
+    FindCommit(#[from] git_odb::find::existing_iter::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error(transparent)]
+    Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+    #[error("Could not write the fast-import stream")]
+    Io(#[from] std::io::Error),
+}
+
+/// A reference pointing to an exported tip.
+struct Ref {
+    name: git_ref::FullName,
+    /// The id of the annotated tag object if the reference points to one.
+    tag: Option<ObjectId>,
+}
+
+struct State<W> {
+    out: W,
+    marks: HashMap<ObjectId, usize>,
+    stats: Stats,
+    opts: Options,
+}
+
+impl<W: Write> State<W> {
+    fn assign_mark(&mut self, id: ObjectId) -> usize {
+        let mark = self.marks.len() + 1;
+        self.marks.insert(id, mark);
+        if self.opts.mark_map {
+            self.stats.marks.push((mark, id));
+        }
+        mark
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> std::io::Result<()> {
+        writeln!(self.out, "data {}", data.len())?;
+        self.out.write_all(data)?;
+        self.out.write_all(b"\n")
+    }
+
+    fn write_commitish(&mut self, keyword: &str, id: &ObjectId) -> std::io::Result<()> {
+        match self.marks.get(id) {
+            Some(mark) => writeln!(self.out, "{} :{}", keyword, mark),
+            None => writeln!(self.out, "{} {}", keyword, id),
+        }
+    }
+}
+
+impl crate::Repository {
+    /// Write the history of all commits reachable from `tips` into `out` as a stream suitable for `git fast-import`,
+    /// similar to `git fast-export`.
+    ///
+    /// Commits are written after their parents, each with a full listing of their tree. All references pointing to the
+    /// `tips` are recreated with `reset` records, or `tag` records for annotated tags.
+    /// Use `opts` to control how tags are treated and to obtain all marks.
+    pub fn export_fast(&self, tips: &[Id<'_>], out: impl Write, opts: Options) -> Result<Stats, Error> {
+        let refs = refs_by_peeled_id(self)?;
+        let mut state = State {
+            out,
+            marks: HashMap::new(),
+            stats: Stats::default(),
+            opts,
+        };
+
+        for tip in tips {
+            let object = self.find_object(tip.detach())?.peel_tags_to_end()?;
+            if object.kind != git_object::Kind::Commit {
+                continue;
+            }
+            let tip = object.id;
+            drop(object);
+
+            let ref_name = refs
+                .get(&tip)
+                .and_then(|refs| refs.iter().find(|r| r.tag.is_none()))
+                .map_or_else(|| "HEAD".into(), |r| r.name.as_bstr().to_owned());
+            for id in commits_parents_first(self, tip, &state.marks)? {
+                export_commit(self, id, ref_name.as_ref(), &mut state)?;
+            }
+        }
+
+        let mut exported_refs = HashSet::new();
+        for tip in tips {
+            let object = self.find_object(tip.detach())?.peel_tags_to_end()?;
+            for r in refs.get(&object.id).into_iter().flatten() {
+                if !exported_refs.insert(r.name.as_bstr()) {
+                    continue;
+                }
+                match r.tag {
+                    Some(tag_id) => export_tag(self, tag_id, &mut state)?,
+                    None => {
+                        if !state.marks.contains_key(&object.id) {
+                            continue;
+                        }
+                        writeln!(state.out, "reset {}", r.name.as_bstr())?;
+                        state.write_commitish("from", &object.id)?;
+                        state.out.write_all(b"\n")?;
+                        state.stats.resets += 1;
+                    }
+                }
+            }
+        }
+        state.out.flush()?;
+        Ok(state.stats)
+    }
+}
+
+fn refs_by_peeled_id(repo: &crate::Repository) -> Result<HashMap<ObjectId, Vec<Ref>>, Error> {
+    let mut out = HashMap::<_, Vec<_>>::new();
+    let platform = repo.references()?;
+    for r in platform.all()?.peeled() {
+        let r = r.map_err(Error::Reference)?.detach();
+        let target = match r.target.try_id() {
+            Some(id) => id.to_owned(),
+            None => continue,
+        };
+        let peeled = r.peeled.unwrap_or(target);
+        out.entry(peeled).or_default().push(Ref {
+            name: r.name,
+            tag: (peeled != target).then(|| target),
+        });
+    }
+    Ok(out)
+}
+
+/// Return all commits reachable from `tip` which aren't yet `exported`, ordered so that parents come before their children.
+fn commits_parents_first(
+    repo: &crate::Repository,
+    tip: ObjectId,
+    exported: &HashMap<ObjectId, usize>,
+) -> Result<Vec<ObjectId>, Error> {
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![(tip, false)];
+    let mut buf = Vec::new();
+    while let Some((id, parents_are_done)) = stack.pop() {
+        if parents_are_done {
+            out.push(id);
+            continue;
+        }
+        if exported.contains_key(&id) || !seen.insert(id) {
+            continue;
+        }
+        stack.push((id, true));
+        let parents: Vec<_> = repo.objects.find_commit_iter(id, &mut buf)?.parent_ids().collect();
+        stack.extend(
+            parents
+                .into_iter()
+                .rev()
+                .filter(|id| !exported.contains_key(id) && !seen.contains(id))
+                .map(|id| (id, false)),
+        );
+    }
+    Ok(out)
+}
+
+fn export_commit<W: Write>(
+    repo: &crate::Repository,
+    id: ObjectId,
+    ref_name: &BStr,
+    state: &mut State<W>,
+) -> Result<(), Error> {
+    let commit = repo.find_object(id)?;
+    let commit = git_object::CommitRef::from_bytes(&commit.data)?;
+    let mut files = repo
+        .find_object(commit.tree())?
+        .into_tree()
+        .traverse()
+        .breadthfirst
+        .files()?;
+    files.retain(|entry| entry.mode.is_no_tree());
+    files.sort_by(|a, b| a.filepath.cmp(&b.filepath));
+
+    let mut buf = Vec::new();
+    for entry in files.iter().filter(|e| e.mode != EntryMode::Commit) {
+        if state.marks.contains_key(&entry.oid) {
+            continue;
+        }
+        let blob = repo.objects.find(&entry.oid, &mut buf)?;
+        state.out.write_all(b"blob\n")?;
+        let mark = state.assign_mark(entry.oid);
+        writeln!(state.out, "mark :{}", mark)?;
+        state.write_data(blob.data)?;
+        state.stats.blobs += 1;
+    }
+
+    writeln!(state.out, "commit {}", ref_name)?;
+    let mark = state.assign_mark(id);
+    writeln!(state.out, "mark :{}", mark)?;
+    state.out.write_all(b"author ")?;
+    commit.author.write_to(&mut state.out)?;
+    state.out.write_all(b"\ncommitter ")?;
+    commit.committer.write_to(&mut state.out)?;
+    state.out.write_all(b"\n")?;
+    if let Some(encoding) = commit.encoding {
+        writeln!(state.out, "encoding {}", encoding)?;
+    }
+    state.write_data(commit.message)?;
+    for (index, parent) in commit.parents().enumerate() {
+        state.write_commitish(if index == 0 { "from" } else { "merge" }, &parent)?;
+    }
+    state.out.write_all(b"deleteall\n")?;
+    for entry in files {
+        state.out.write_all(b"M ")?;
+        state.out.write_all(entry.mode.as_bytes())?;
+        match state.marks.get(&entry.oid) {
+            Some(mark) if entry.mode != EntryMode::Commit => write!(state.out, " :{} ", mark)?,
+            _ => write!(state.out, " {} ", entry.oid)?,
+        }
+        write_path(&mut state.out, entry.filepath.as_ref())?;
+        state.out.write_all(b"\n")?;
+    }
+    state.out.write_all(b"\n")?;
+    state.stats.commits += 1;
+    Ok(())
+}
+
+fn export_tag<W: Write>(repo: &crate::Repository, tag_id: ObjectId, state: &mut State<W>) -> Result<(), Error> {
+    let tag = repo.find_object(tag_id)?;
+    let tag = git_object::TagRef::from_bytes(&tag.data)?;
+    let target = tag.target();
+    if !state.marks.contains_key(&target) && !state.opts.tag_of_filtered_object {
+        return Ok(());
+    }
+    writeln!(state.out, "tag {}", tag.name)?;
+    state.write_commitish("from", &target)?;
+    if let Some(tagger) = tag.tagger {
+        state.out.write_all(b"tagger ")?;
+        tagger.write_to(&mut state.out)?;
+        state.out.write_all(b"\n")?;
+    }
+    match tag.pgp_signature.filter(|_| state.opts.signed_tags) {
+        Some(signature) => {
+            let mut message = tag.message.to_owned();
+            message.push(b'\n');
+            message.extend_from_slice(signature);
+            state.write_data(&message)?;
+        }
+        None => state.write_data(tag.message)?,
+    }
+    state.out.write_all(b"\n")?;
+    state.stats.tags += 1;
+    Ok(())
+}
+
+/// Write `path`, quoting it if it would otherwise be ambiguous to the parser.
+fn write_path(mut out: impl Write, path: &BStr) -> std::io::Result<()> {
+    if !path.starts_with(b"\"") && path.find_byteset(b"\n\\").is_none() {
+        return out.write_all(path);
+    }
+    out.write_all(b"\"")?;
+    for byte in path.iter() {
+        match byte {
+            b'"' => out.write_all(b"\\\"")?,
+            b'\\' => out.write_all(b"\\\\")?,
+            b'\n' => out.write_all(b"\\n")?,
+            byte => out.write_all(&[*byte])?,
+        }
+    }
+    out.write_all(b"\"")
+}
@@ -0,0 +1,783 @@
+//! Consume a stream in the format understood by `git fast-import`, as documented in `git-fast-import(1)`.
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::{Infallible, TryFrom},
+    io::{BufRead, Read},
+};
+
+use git_features::progress::Progress;
+use git_hash::ObjectId;
+use git_object::{
+    bstr::{BStr, BString, ByteSlice},
+    tree::EntryMode,
+};
+use git_odb::{pack::data::output, Find};
+use git_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use git_tempfile::{handle::Writable, AutoRemove, ContainingDirectory};
+
+use crate::{ext::ObjectIdExt, index_pack, object, reference};
+
+/// Options for use in [`Repository::import_fast()`][crate::Repository::import_fast()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If true, references may be updated even if their new value isn't a descendant of their previous value.
+    pub force: bool,
+    /// The maximum size of each pack to write in bytes, after which objects are written into a new pack, similar to
+    /// `git fast-import --max-pack-size`.
+    ///
+    /// If `None`, packs are only completed on `checkpoint` commands and at the end of the stream.
+    pub max_pack_size: Option<u64>,
+}
+
+/// Statistics about the stream consumed by [`Repository::import_fast()`][crate::Repository::import_fast()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The amount of blobs written.
+    pub blobs: usize,
+    /// The amount of trees written.
+    pub trees: usize,
+    /// The amount of commits written.
+    pub commits: usize,
+    /// The amount of annotated tags written.
+    pub tags: usize,
+    /// The amount of references that were created or updated.
+    pub refs: usize,
+}
+
+/// The error returned by [`Repository::import_fast()`][crate::Repository::import_fast()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the fast-import stream")]
+    Io(#[from] std::io::Error),
+    #[error("{message} in line {line_number}")]
+    Parse { line_number: usize, message: &'static str },
+    #[error("The command {command:?} is not supported")]
+    UnsupportedCommand { command: BString },
+    #[error("Mark :{mark} was used before it was defined")]
+    UnknownMark { mark: usize },
+    #[error("Could not resolve {spec:?} to an object")]
+    UnresolvedCommitish { spec: BString },
+    #[error("The path {path:?} doesn't exist in the tree")]
+    PathNotFound { path: BString },
+    #[error("Refusing to update reference '{}' as the update isn't a fast-forward", name.as_bstr())]
+    NonFastForward { name: FullName },
+    #[error(transparent)]
+    ReferenceName(#[from] git_validate::refname::Error),
+    #[error(transparent)]
+    FindReference(#[from] reference::find::Error),
+    #[error(transparent)]
+    PeelReference(#[from] reference::peel::Error),
+    #[error(transparent)]
+    EditReferences(#[from] reference::edit::Error),
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::OdbError),
+    #[error(transparent)]
+    PeelToKind(#[from] object::peel::to_kind::Error),
+    #[error(transparent)]
+    CompressObject(#[from] output::entry::Error),
+    #[error(transparent)]
+    DecompressObject(#[from] git_features::zlib::inflate::Error),
+    #[error("Could not create a pack")]
+    CreatePack(#[source] std::io::Error),
+    #[error(transparent)]
+    WritePack(#[from] output::bytes::Error<Infallible>),
+    #[error("Could not move the new pack into place")]
+    PersistPack(#[from] git_tempfile::handle::persist::Error<Writable>),
+    #[error(transparent)]
+    IndexPack(#[from] index_pack::Error),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error(transparent)]
+    Traverse(#[from] git_traverse::commit::ancestors::Error),
+}
+
+impl crate::Repository {
+    /// Read a stream in the format of `git fast-import` from `input`, write all blobs, trees, commits and tags it describes into
+    /// the object database and finally create or update all references it mentions, similar to `git fast-import`.
+    ///
+    /// Like `git fast-import`, new objects are written into a pack, which is completed along with its index on `checkpoint`
+    /// commands, at the end of the stream and whenever it would grow beyond [`Options::max_pack_size`], to continue with
+    /// a new pack.
+    ///
+    /// References are only updated if their new value is a descendant of the previous one, unless [`Options::force`] is set.
+    /// `progress` is informed about each written object.
+    ///
+    /// Commands to query the state of the import, like `ls`, `cat-blob` or `get-mark`, as well as notes, are not supported.
+    pub fn import_fast(&self, input: impl Read, mut progress: impl Progress, opts: Options) -> Result<Stats, Error> {
+        let start = std::time::Instant::now();
+        progress.init(None, git_features::progress::count("objects"));
+        let mut import = Import {
+            repo: self,
+            input: Input::new(std::io::BufReader::new(input)),
+            marks: HashMap::new(),
+            branches: BTreeMap::new(),
+            pack: Pack::default(),
+            max_pack_size: opts.max_pack_size,
+            stats: Stats::default(),
+            progress: &mut progress,
+        };
+
+        while let Some(line) = import.input.next_line()? {
+            let (command, args) = split_command(&line);
+            match command {
+                b"blob" => import.blob()?,
+                b"commit" => import.commit(args.into())?,
+                b"tag" => import.tag(args.into())?,
+                b"reset" => import.reset(args.into())?,
+                b"checkpoint" => import.write_pack()?,
+                b"done" => break,
+                b"" | b"progress" | b"feature" | b"option" => {}
+                _ => {
+                    return Err(Error::UnsupportedCommand {
+                        command: command.into(),
+                    })
+                }
+            }
+        }
+
+        import.write_pack()?;
+        let branches = std::mem::take(&mut import.branches);
+        let mut stats = import.stats;
+        let mut edits = Vec::with_capacity(branches.len());
+        for (name, id) in branches.into_iter().filter_map(|(name, id)| id.map(|id| (name, id))) {
+            let name = FullName::try_from(name)?;
+            let previous = match self.try_find_reference(name.as_bstr())? {
+                Some(mut r) => Some(r.peel_to_id_in_place()?.detach()),
+                None => None,
+            };
+            if previous == Some(id) {
+                continue;
+            }
+            if let Some(previous) = previous.filter(|_| !opts.force) {
+                let mut ancestors = id.attach(self).ancestors().all()?;
+                if !ancestors.any(|ancestor| ancestor.map_or(false, |ancestor| ancestor == previous)) {
+                    return Err(Error::NonFastForward { name });
+                }
+            }
+            edits.push(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "fast-import".into(),
+                    },
+                    expected: PreviousValue::Any,
+                    new: Target::Peeled(id),
+                },
+                name,
+                deref: false,
+            });
+        }
+        stats.refs = edits.len();
+        self.edit_references(edits, git_lock::acquire::Fail::Immediately, None)?;
+        progress.show_throughput(start);
+        Ok(stats)
+    }
+}
+
+struct Import<'repo, 'a, R, P> {
+    repo: &'repo crate::Repository,
+    input: Input<R>,
+    marks: HashMap<usize, ObjectId>,
+    /// Branches and tags created or updated by the stream, along with their most recent value, or `None` if they were reset.
+    branches: BTreeMap<BString, Option<ObjectId>>,
+    /// The objects written by the stream which are yet to be written into a pack.
+    pack: Pack,
+    max_pack_size: Option<u64>,
+    stats: Stats,
+    progress: &'a mut P,
+}
+
+/// The compressed entries of a pack which is yet to be written.
+#[derive(Default)]
+struct Pack {
+    entries: Vec<output::Entry>,
+    /// The index into `entries` for each object id.
+    entry_by_id: HashMap<ObjectId, usize>,
+    /// The size of all `entries` in the pack, excluding its header and trailer.
+    size: u64,
+}
+
+impl<'repo, 'a, R: BufRead, P: Progress> Import<'repo, 'a, R, P> {
+    fn blob(&mut self) -> Result<(), Error> {
+        let mark = self.mark()?;
+        self.input.skip("original-oid")?;
+        let data = self.input.data()?;
+        let id = self.write_blob(&data)?;
+        if let Some(mark) = mark {
+            self.marks.insert(mark, id);
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self, branch: BString) -> Result<(), Error> {
+        let mark = self.mark()?;
+        self.input.skip("original-oid")?;
+        let author = self.input.signature("author")?;
+        let committer = self
+            .input
+            .signature("committer")?
+            .ok_or_else(|| self.input.error("Expected 'committer'"))?;
+        let encoding = self.input.argument("encoding")?;
+        let message = self.input.data()?;
+
+        let mut parents = Vec::new();
+        match self.input.argument("from")? {
+            Some(from) => parents.push(self.resolve(from.as_ref())?),
+            None => {
+                if let Some(tip) = self.branch_tip(branch.as_ref())? {
+                    parents.push(tip);
+                }
+            }
+        }
+        while let Some(merge) = self.input.argument("merge")? {
+            parents.push(self.resolve(merge.as_ref())?);
+        }
+
+        let mut tree = match parents.first() {
+            Some(parent) => self.directory(*parent)?,
+            None => Directory::default(),
+        };
+        while let Some(line) = self.input.next_line()? {
+            if line.is_empty() {
+                continue;
+            }
+            let (command, args) = split_command(&line);
+            match command {
+                b"M" => {
+                    let (mode, rest) = split_command(args);
+                    let (dataref, path) = split_command(rest);
+                    let mode = parse_mode(mode).ok_or_else(|| self.input.error("Invalid file mode"))?;
+                    let path = unquote(path);
+                    let id = if dataref == b"inline" {
+                        let data = self.input.data()?;
+                        self.write_blob(&data)?
+                    } else {
+                        self.resolve(dataref.as_bstr())?
+                    };
+                    let node = if mode == EntryMode::Tree {
+                        Node::Tree(self.directory(id)?)
+                    } else {
+                        Node::File { mode, id }
+                    };
+                    tree.insert(path.as_ref(), node);
+                }
+                b"D" => {
+                    tree.remove(unquote(args).as_ref());
+                }
+                b"C" | b"R" => {
+                    let (source, destination) = split_path(args);
+                    let node = if command == b"R" {
+                        tree.remove(source.as_ref())
+                    } else {
+                        tree.get(source.as_ref()).cloned()
+                    }
+                    .ok_or(Error::PathNotFound { path: source })?;
+                    tree.insert(unquote(destination).as_ref(), node);
+                }
+                b"deleteall" => tree = Directory::default(),
+                _ => {
+                    self.input.unread(line);
+                    break;
+                }
+            }
+        }
+
+        let tree = self.write_tree(&tree)?;
+        let commit = git_object::Commit {
+            tree,
+            parents: parents.into_iter().collect(),
+            author: author.unwrap_or_else(|| committer.clone()),
+            committer,
+            encoding,
+            message,
+            extra_headers: Vec::new(),
+        };
+        let id = self.write(&commit)?;
+        self.stats.commits += 1;
+        if let Some(mark) = mark {
+            self.marks.insert(mark, id);
+        }
+        self.branches.insert(branch, Some(id));
+        Ok(())
+    }
+
+    fn tag(&mut self, name: BString) -> Result<(), Error> {
+        let mark = self.mark()?;
+        let target = self
+            .input
+            .argument("from")?
+            .ok_or_else(|| self.input.error("Expected 'from'"))?;
+        let target = self.resolve(target.as_ref())?;
+        self.input.skip("original-oid")?;
+        let tagger = self.input.signature("tagger")?;
+        let message = self.input.data()?;
+
+        let (target_kind, _) = self.find(target)?;
+        let tag = git_object::Tag {
+            target,
+            target_kind,
+            name: name.clone(),
+            tagger,
+            message,
+            pgp_signature: None,
+        };
+        let id = self.write(&tag)?;
+        self.stats.tags += 1;
+        if let Some(mark) = mark {
+            self.marks.insert(mark, id);
+        }
+        let mut ref_name = BString::from("refs/tags/");
+        ref_name.extend_from_slice(&name);
+        self.branches.insert(ref_name, Some(id));
+        Ok(())
+    }
+
+    fn reset(&mut self, branch: BString) -> Result<(), Error> {
+        match self.input.argument("from")? {
+            Some(from) => {
+                let id = self.resolve(from.as_ref())?;
+                self.branches.insert(branch, Some(id));
+            }
+            None => {
+                self.branches.insert(branch, None);
+            }
+        }
+        Ok(())
+    }
+
+    fn mark(&mut self) -> Result<Option<usize>, Error> {
+        self.input
+            .argument("mark")?
+            .map(|mark| {
+                parse_mark(mark.as_ref()).ok_or_else(|| self.input.error("Marks must have the form ':<number>'"))
+            })
+            .transpose()
+    }
+
+    fn branch_tip(&self, branch: &BStr) -> Result<Option<ObjectId>, Error> {
+        if let Some(id) = self.branches.get(branch) {
+            return Ok(*id);
+        }
+        Ok(match self.repo.try_find_reference(branch)? {
+            Some(mut r) => Some(r.peel_to_id_in_place()?.detach()),
+            None => None,
+        })
+    }
+
+    /// Turn a mark, hexadecimal object id or reference name into an object id.
+    fn resolve(&self, spec: &BStr) -> Result<ObjectId, Error> {
+        if let Some(mark) = parse_mark(spec) {
+            return self.marks.get(&mark).copied().ok_or(Error::UnknownMark { mark });
+        }
+        if let Ok(id) = ObjectId::from_hex(spec) {
+            return Ok(id);
+        }
+        let name = spec.strip_suffix(b"^0").unwrap_or(spec).as_bstr();
+        self.branch_tip(name)?
+            .ok_or_else(|| Error::UnresolvedCommitish { spec: spec.to_owned() })
+    }
+
+    fn write_blob(&mut self, data: &[u8]) -> Result<ObjectId, Error> {
+        let id = self.add_to_pack(git_object::Kind::Blob, data)?;
+        self.stats.blobs += 1;
+        self.progress.inc();
+        Ok(id)
+    }
+
+    fn write_tree(&mut self, dir: &Directory) -> Result<ObjectId, Error> {
+        let mut entries = Vec::with_capacity(dir.entries.len());
+        for (filename, node) in &dir.entries {
+            let (mode, oid) = match node {
+                Node::File { mode, id } => (*mode, *id),
+                Node::Tree(dir) => (EntryMode::Tree, self.write_tree(dir)?),
+            };
+            entries.push(git_object::tree::Entry {
+                mode,
+                filename: filename.clone(),
+                oid,
+            });
+        }
+        let mut tree = git_object::Tree { entries };
+        tree.sort_and_deduplicate();
+        let id = self.write(&tree)?;
+        self.stats.trees += 1;
+        Ok(id)
+    }
+
+    fn write(&mut self, object: impl git_object::WriteTo) -> Result<ObjectId, Error> {
+        let mut data = Vec::new();
+        object.write_to(&mut data).expect("writing to a vec can't fail");
+        let id = self.add_to_pack(object.kind(), &data)?;
+        self.progress.inc();
+        Ok(id)
+    }
+
+    /// Add the object of `kind` with `data` to the pending pack unless it exists already, and return its id.
+    ///
+    /// If the pack would grow beyond the maximum pack size with it, the pending pack is written first.
+    fn add_to_pack(&mut self, kind: git_object::Kind, data: &[u8]) -> Result<ObjectId, Error> {
+        let mut hasher = git_features::hash::hasher(self.repo.object_hash());
+        hasher.update(&git_object::encode::loose_header(kind, data.len()));
+        hasher.update(data);
+        let id = ObjectId::from(hasher.digest());
+        if self.pack.entry_by_id.contains_key(&id) || self.repo.objects.contains(id) {
+            return Ok(id);
+        }
+
+        let entry = output::Entry::from_data(&output::Count::from_data(id, None), &git_object::Data::new(kind, data))?;
+        let entry_size = (entry
+            .to_entry_header(git_odb::pack::data::Version::V2, |_| 0)
+            .size(entry.decompressed_size as u64)
+            + entry.compressed_data.len()) as u64;
+        if let Some(max_pack_size) = self.max_pack_size {
+            // the pack header and the trailing checksum
+            let overhead = 12 + self.repo.object_hash().len_in_bytes() as u64;
+            if !self.pack.entries.is_empty() && overhead + self.pack.size + entry_size > max_pack_size {
+                self.write_pack()?;
+            }
+        }
+        self.pack.size += entry_size;
+        self.pack.entry_by_id.insert(id, self.pack.entries.len());
+        self.pack.entries.push(entry);
+        Ok(id)
+    }
+
+    /// Write all pending objects into a new pack along with its index, so they can be found in the object database.
+    fn write_pack(&mut self) -> Result<(), Error> {
+        if self.pack.entries.is_empty() {
+            return Ok(());
+        }
+        let entries = std::mem::take(&mut self.pack).entries;
+        let pack_dir = self.repo.objects.store_ref().path().join("pack");
+        let mut pack_file = git_tempfile::new(&pack_dir, ContainingDirectory::Exists, AutoRemove::Tempfile)
+            .map_err(Error::CreatePack)?;
+        let checksum = {
+            let num_entries = entries.len() as u32;
+            let mut pack = output::bytes::FromEntriesIter::new(
+                std::iter::once(Ok::<_, Infallible>(entries)),
+                &mut pack_file,
+                num_entries,
+                git_odb::pack::data::Version::V2,
+                self.repo.object_hash(),
+            );
+            for written in pack.by_ref() {
+                written?;
+            }
+            pack.digest().expect("iteration is done")
+        };
+        let pack_path = pack_dir.join(format!("pack-{}.pack", checksum));
+        pack_file.persist(&pack_path)?;
+        self.repo
+            .index_pack(&pack_path, self.progress.add_child("index pack"))?;
+        Ok(())
+    }
+
+    /// Return the kind and data of the object `id`, which may still be pending in the current pack.
+    fn find(&self, id: ObjectId) -> Result<(git_object::Kind, Vec<u8>), Error> {
+        match self.pack.entry_by_id.get(&id) {
+            Some(&index) => {
+                let entry = &self.pack.entries[index];
+                let kind = match entry.kind {
+                    output::entry::Kind::Base(kind) => kind,
+                    _ => unreachable!("pending objects are never deltas"),
+                };
+                let mut data = vec![0; entry.decompressed_size];
+                git_features::zlib::Inflate::default().once(&entry.compressed_data, &mut data)?;
+                Ok((kind, data))
+            }
+            None => {
+                let object = self.repo.find_object(id)?.detach();
+                Ok((object.kind, object.data))
+            }
+        }
+    }
+
+    /// Read the tree `id` points to, following tags and commits, into an editable `Directory`.
+    fn directory(&self, mut id: ObjectId) -> Result<Directory, Error> {
+        let data = loop {
+            let (kind, data) = self.find(id)?;
+            id = match kind {
+                git_object::Kind::Tree => break data,
+                git_object::Kind::Commit => git_object::CommitRefIter::from_bytes(&data).tree_id()?,
+                git_object::Kind::Tag => git_object::TagRefIter::from_bytes(&data).target_id()?,
+                git_object::Kind::Blob => {
+                    return Err(object::peel::to_kind::Error::NotFound {
+                        actual: kind,
+                        expected: git_object::Kind::Tree,
+                    }
+                    .into())
+                }
+            };
+        };
+        let children: Vec<_> = git_object::TreeRefIter::from_bytes(&data)
+            .map(|entry| entry.map(git_object::tree::Entry::from))
+            .collect::<Result<_, _>>()?;
+        let mut entries = BTreeMap::new();
+        for entry in children {
+            let node = if entry.mode.is_tree() {
+                Node::Tree(self.directory(entry.oid)?)
+            } else {
+                Node::File {
+                    mode: entry.mode,
+                    id: entry.oid,
+                }
+            };
+            entries.insert(entry.filename, node);
+        }
+        Ok(Directory { entries })
+    }
+}
+
+/// An in-memory tree which is easy to edit.
+#[derive(Default, Clone)]
+struct Directory {
+    entries: BTreeMap<BString, Node>,
+}
+
+#[derive(Clone)]
+enum Node {
+    File { mode: EntryMode, id: ObjectId },
+    Tree(Directory),
+}
+
+impl Directory {
+    fn get(&self, path: &BStr) -> Option<&Node> {
+        let (dir, name) = match path.rfind_byte(b'/') {
+            Some(pos) => (Some(&path[..pos]), &path[pos + 1..]),
+            None => (None, path.as_bytes()),
+        };
+        let mut current = self;
+        for component in dir.into_iter().flat_map(|dir| dir.split_str("/")) {
+            current = match current.entries.get(component.as_bstr())? {
+                Node::Tree(dir) => dir,
+                Node::File { .. } => return None,
+            };
+        }
+        current.entries.get(name.as_bstr())
+    }
+
+    fn insert(&mut self, path: &BStr, node: Node) {
+        match path.find_byte(b'/') {
+            Some(pos) => {
+                let child = self
+                    .entries
+                    .entry(path[..pos].into())
+                    .or_insert_with(|| Node::Tree(Directory::default()));
+                if let Node::File { .. } = child {
+                    *child = Node::Tree(Directory::default());
+                }
+                if let Node::Tree(dir) = child {
+                    dir.insert(path[pos + 1..].as_bstr(), node);
+                }
+            }
+            None => {
+                self.entries.insert(path.into(), node);
+            }
+        }
+    }
+
+    /// Remove the node at `path` and prune directories that became empty in the process.
+    fn remove(&mut self, path: &BStr) -> Option<Node> {
+        match path.find_byte(b'/') {
+            Some(pos) => {
+                let name = path[..pos].as_bstr();
+                let (removed, is_empty) = match self.entries.get_mut(name)? {
+                    Node::Tree(dir) => {
+                        let removed = dir.remove(path[pos + 1..].as_bstr());
+                        (removed, dir.entries.is_empty())
+                    }
+                    Node::File { .. } => return None,
+                };
+                if is_empty {
+                    self.entries.remove(name);
+                }
+                removed
+            }
+            None => self.entries.remove(path),
+        }
+    }
+}
+
+/// A line-based reader which allows reading raw data as well.
+struct Input<R> {
+    inner: R,
+    unread: Option<BString>,
+    line_number: usize,
+}
+
+impl<R: BufRead> Input<R> {
+    fn new(inner: R) -> Self {
+        Input {
+            inner,
+            unread: None,
+            line_number: 0,
+        }
+    }
+
+    /// Return the next line without its trailing newline, skipping comments.
+    fn next_line(&mut self) -> std::io::Result<Option<BString>> {
+        if let Some(line) = self.unread.take() {
+            return Ok(Some(line));
+        }
+        loop {
+            let mut line = Vec::new();
+            if self.inner.read_until(b'\n', &mut line)? == 0 {
+                return Ok(None);
+            }
+            self.line_number += 1;
+            if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+            if !line.starts_with(b"#") {
+                return Ok(Some(line.into()));
+            }
+        }
+    }
+
+    fn unread(&mut self, line: BString) {
+        debug_assert!(self.unread.is_none(), "only one line can be put back");
+        self.unread = Some(line);
+    }
+
+    /// Return the argument of the next line if it starts with `command`, or put the line back otherwise.
+    fn argument(&mut self, command: &str) -> std::io::Result<Option<BString>> {
+        let line = match self.next_line()? {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+        let (line_command, args) = split_command(&line);
+        if line_command == command.as_bytes() {
+            let args = args.into();
+            Ok(Some(args))
+        } else {
+            self.unread(line);
+            Ok(None)
+        }
+    }
+
+    fn skip(&mut self, command: &str) -> std::io::Result<()> {
+        self.argument(command).map(|_| ())
+    }
+
+    fn signature(&mut self, command: &str) -> Result<Option<git_actor::Signature>, Error> {
+        self.argument(command)?
+            .map(|signature| {
+                git_actor::SignatureRef::from_bytes::<()>(&signature)
+                    .map(|s| s.to_owned())
+                    .map_err(|_| self.error("Invalid signature"))
+            })
+            .transpose()
+    }
+
+    /// Read a `data` command with either an exact byte count or a delimiter.
+    fn data(&mut self) -> Result<BString, Error> {
+        let header = self.argument("data")?.ok_or_else(|| self.error("Expected 'data'"))?;
+        let mut data = Vec::new();
+        match header.strip_prefix(b"<<") {
+            Some(delimiter) => loop {
+                let line = self
+                    .next_line()?
+                    .ok_or_else(|| self.error("Unexpected end of stream in delimited data"))?;
+                if line.as_slice() == delimiter {
+                    break;
+                }
+                data.extend_from_slice(&line);
+                data.push(b'\n');
+            },
+            None => {
+                let len: u64 = header
+                    .to_str()
+                    .ok()
+                    .and_then(|len| len.parse().ok())
+                    .ok_or_else(|| self.error("Invalid data length"))?;
+                (&mut self.inner).take(len).read_to_end(&mut data)?;
+                if data.len() as u64 != len {
+                    return Err(self.error("Unexpected end of stream in data"));
+                }
+                self.line_number += data.iter().filter(|b| **b == b'\n').count();
+                // like `git fast-import`, consume the optional newline following the data.
+                if self.inner.fill_buf()?.first() == Some(&b'\n') {
+                    self.inner.consume(1);
+                    self.line_number += 1;
+                }
+            }
+        }
+        Ok(data.into())
+    }
+
+    fn error(&self, message: &'static str) -> Error {
+        Error::Parse {
+            line_number: self.line_number,
+            message,
+        }
+    }
+}
+
+fn split_command(line: &[u8]) -> (&[u8], &[u8]) {
+    match line.find_byte(b' ') {
+        Some(pos) => (&line[..pos], &line[pos + 1..]),
+        None => (line, &[]),
+    }
+}
+
+fn parse_mark(spec: &[u8]) -> Option<usize> {
+    spec.strip_prefix(b":")?.to_str().ok()?.parse().ok()
+}
+
+fn parse_mode(mode: &[u8]) -> Option<EntryMode> {
+    Some(match mode {
+        b"644" | b"100644" => EntryMode::Blob,
+        b"755" | b"100755" => EntryMode::BlobExecutable,
+        b"120000" => EntryMode::Link,
+        b"160000" => EntryMode::Commit,
+        b"040000" | b"40000" => EntryMode::Tree,
+        _ => return None,
+    })
+}
+
+/// Split the arguments of a copy or rename command into source and destination, unquoting the source.
+fn split_path(args: &[u8]) -> (BString, &[u8]) {
+    if args.starts_with(b"\"") {
+        let mut escaped = false;
+        for (pos, byte) in args.iter().enumerate().skip(1) {
+            match byte {
+                b'\\' if !escaped => escaped = true,
+                b'"' if !escaped => return (unquote(&args[..=pos]), args.get(pos + 2..).unwrap_or_default()),
+                _ => escaped = false,
+            }
+        }
+        (unquote(args), &[])
+    } else {
+        let (source, destination) = split_command(args);
+        (source.into(), destination)
+    }
+}
+
+/// Remove C-style quotes from `path` if present.
+fn unquote(path: &[u8]) -> BString {
+    let inner = match path.strip_prefix(b"\"").and_then(|p| p.strip_suffix(b"\"")) {
+        Some(inner) => inner,
+        None => return path.into(),
+    };
+    let mut out = Vec::with_capacity(inner.len());
+    let mut bytes = inner.iter().copied();
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            out.push(byte);
+            continue;
+        }
+        match bytes.next() {
+            Some(b'n') => out.push(b'\n'),
+            Some(b't') => out.push(b'\t'),
+            Some(escaped) => out.push(escaped),
+            None => {}
+        }
+    }
+    out.into()
+}
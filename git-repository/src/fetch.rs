@@ -0,0 +1,506 @@
+//! Fetch objects and references from a remote, similar to `git fetch <remote>`.
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::progress::ThroughputTracker;
+use git_hash::ObjectId;
+use git_object::bstr::{BStr, BString, ByteSlice};
+use git_odb::pack::{self, Find};
+use git_protocol::{
+    fetch::{Action, Arguments, LsRefsAction, Ref, Response},
+    transport,
+    transport::client::Capabilities,
+};
+use git_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    FullName, Target,
+};
+
+use crate::{ahead_behind, reference, remote::map_with_fetch_refspec, Progress};
+
+/// The width of the summary column in the lines describing reference updates, wide enough for `abc1234...def5678`.
+const SUMMARY_WIDTH: usize = 2 * ABBREV_LEN + 3;
+/// The amount of hex characters of abbreviated object ids in the lines describing reference updates.
+const ABBREV_LEN: usize = 7;
+
+/// Options for use in [`Repository::fetch_with_progress()`][crate::Repository::fetch_with_progress()].
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// The amount of threads to use at most when indexing the received pack, or all logical cores if `None`.
+    pub thread_limit: Option<usize>,
+    /// If true, the destinations of all refspecs are moved into `refs/prefetch/` and their updates are forced, similar to
+    /// `git fetch --prefetch`, to receive objects in the background without changing any remote tracking references.
+    ///
+    /// Refspecs without destination or for tags are ignored in this mode.
+    pub prefetch: bool,
+    /// If set, the fetch stops with [`Error::Interrupted`] once the token is cancelled, instead of when the
+    /// [global interrupt flag][crate::interrupt::IS_INTERRUPTED] is set.
+    pub cancellation: Option<crate::interrupt::CancellationToken>,
+}
+
+/// How a local reference was changed by [`Repository::fetch_with_progress()`][crate::Repository::fetch_with_progress()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// The local reference didn't exist and was created.
+    New,
+    /// The local reference already pointed to the object of the remote reference.
+    UpToDate,
+    /// The local reference was updated to a descendant of the commit it pointed to.
+    FastForward,
+    /// The local reference was updated even though the update isn't a fast-forward, as the refspec starts with `+`.
+    Forced,
+    /// The local reference wasn't updated as the update isn't a fast-forward or would change an existing tag.
+    Rejected,
+}
+
+/// A reference of the remote along with the local reference it maps to, as part of an [`Outcome`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Update {
+    /// The name of the reference on the remote, like `refs/heads/main`.
+    pub remote: FullName,
+    /// The name of the local reference it maps to according to the fetch refspecs, like `refs/remotes/origin/main`.
+    pub local: FullName,
+    /// The object the local reference pointed to before the fetch, if it existed.
+    pub previous: Option<ObjectId>,
+    /// The object the remote reference points to.
+    pub new: ObjectId,
+    /// How the local reference was changed.
+    pub mode: Mode,
+}
+
+/// The result of [`Repository::fetch_with_progress()`][crate::Repository::fetch_with_progress()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The URL of the remote that was fetched from.
+    pub url: BString,
+    /// All references of the remote matched by the fetch refspecs, in the order the remote advertised them.
+    pub updates: Vec<Update>,
+    /// Information about the received pack, or `None` if all objects were present already.
+    pub pack: Option<pack::bundle::write::Outcome>,
+}
+
+/// The error returned by [`Repository::fetch_with_progress()`][crate::Repository::fetch_with_progress()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The remote '{name}' has no URL configured in 'remote.{name}.url'")]
+    UnknownRemote { name: String },
+    #[error(transparent)]
+    ReferenceIterInit(#[from] reference::iter::init::Error),
+    #[error(transparent)]
+    ReferenceIter(#[from] reference::iter::Error),
+    #[error("A reference could not be read")]
+    Reference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Connect(#[from] transport::client::connect::Error),
+    #[error(transparent)]
+    Fetch(#[from] git_protocol::fetch::Error),
+    #[error("The reference name {name:?} is invalid")]
+    InvalidRefName {
+        name: BString,
+        source: git_validate::refname::Error,
+    },
+    #[error(transparent)]
+    FindReference(#[from] reference::find::Error),
+    #[error(transparent)]
+    AheadBehind(#[from] ahead_behind::Error),
+    #[error(transparent)]
+    EditReferences(#[from] reference::edit::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+impl crate::Repository {
+    /// Fetch from the remote named `remote_name` like [`fetch_with_progress()`][crate::Repository::fetch_with_progress()]
+    /// does, but without reporting progress.
+    pub fn fetch(&self, remote_name: &str, opts: Options) -> Result<Outcome, Error> {
+        self.fetch_with_progress(remote_name, crate::progress::Discard, opts)
+    }
+
+    /// Fetch all references matched by the `remote.<remote_name>.fetch` refspecs from the remote at `remote.<remote_name>.url`
+    /// along with the objects they need, and update the local references they map to, similar to `git fetch <remote_name>`.
+    ///
+    /// Local references are only updated if the update is a fast-forward or if the refspec starts with `+`, and existing
+    /// tags are never changed without `+`. Updates that aren't performed are returned with [`Mode::Rejected`].
+    ///
+    /// `progress` receives the handshake and negotiation steps, the messages of the remote while it counts and compresses
+    /// objects, the amount of bytes received and objects resolved while the pack is written, and the amount of references
+    /// that were updated. Like `git fetch`, it is informed about the URL that was fetched from and about each changed
+    /// reference with lines like ` * [new branch]      main       -> origin/main`.
+    ///
+    /// Note that tags aren't followed automatically, `FETCH_HEAD` isn't written and that negotiation only sends the tips
+    /// of all local references, which may cause the remote to send more objects than needed.
+    pub fn fetch_with_progress(
+        &self,
+        remote_name: &str,
+        mut progress: impl Progress,
+        opts: Options,
+    ) -> Result<Outcome, Error> {
+        let config = &self.config.resolved;
+        let url = config
+            .string("remote", Some(remote_name), "url")
+            .ok_or_else(|| Error::UnknownRemote {
+                name: remote_name.into(),
+            })?
+            .into_owned();
+        let refspecs: Vec<BString> = config
+            .strings("remote", Some(remote_name), "fetch")
+            .unwrap_or_default()
+            .into_iter()
+            .map(Cow::into_owned)
+            .collect();
+        let refspecs = if opts.prefetch {
+            refspecs
+                .iter()
+                .filter_map(|refspec| prefetch_refspec(refspec.as_bstr()))
+                .collect()
+        } else {
+            refspecs
+        };
+
+        let mut haves = Vec::new();
+        let platform = self.references()?;
+        for r in platform.all()?.peeled() {
+            let r = r.map_err(Error::Reference)?.detach();
+            haves.extend(r.peeled.or_else(|| r.target.try_id().map(ToOwned::to_owned)));
+        }
+        haves.sort();
+        haves.dedup();
+
+        let should_interrupt = crate::interrupt::flag(opts.cancellation.as_ref());
+        let transport = transport::connect(&url, transport::Protocol::V2)?;
+        let mut delegate = Delegate {
+            objects: self.objects.clone(),
+            pack_dir: self.objects.store_ref().path().join("pack"),
+            should_interrupt,
+            thread_limit: opts.thread_limit,
+            object_hash: self.object_hash(),
+            refspecs: &refspecs,
+            haves,
+            refs: Vec::new(),
+            pack: None,
+        };
+        git_protocol::fetch(
+            transport,
+            &mut delegate,
+            git_protocol::credentials::helper,
+            progress.add_child("fetch"),
+            git_protocol::FetchConnection::TerminateOnSuccessfulCompletion,
+        )?;
+        let (refs, pack) = (delegate.refs, delegate.pack);
+
+        let mut update_progress = progress.add_child("update refs");
+        update_progress.init(Some(refs.len()), git_features::progress::count("refs"));
+        let mut updates = Vec::with_capacity(refs.len());
+        let mut edits = Vec::new();
+        for (remote, local, forced) in refs {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let new = *remote.unpack().1;
+            let remote = full_name(remote.unpack().0.clone())?;
+            let local = full_name(local)?;
+            let previous = self
+                .try_find_reference(&local)?
+                .and_then(|r| r.target().try_id().map(ToOwned::to_owned));
+            let mode = match previous {
+                None => Mode::New,
+                Some(previous) if previous == new => Mode::UpToDate,
+                Some(_) if forced => Mode::Forced,
+                Some(_) if local.as_bstr().starts_with(b"refs/tags/") => Mode::Rejected,
+                Some(previous) => match self.compute_ahead_behind(previous, new)? {
+                    (0, _) => Mode::FastForward,
+                    _ => Mode::Rejected,
+                },
+            };
+            let message = match mode {
+                Mode::New if local.as_bstr().starts_with(b"refs/tags/") => Some("storing tag"),
+                Mode::New => Some("storing head"),
+                Mode::FastForward => Some("fast-forward"),
+                Mode::Forced => Some("forced-update"),
+                Mode::UpToDate | Mode::Rejected => None,
+            };
+            if let Some(message) = message {
+                edits.push(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: format!("fetch {}: {}", remote_name, message).into(),
+                        },
+                        expected: match previous {
+                            Some(previous) => PreviousValue::MustExistAndMatch(Target::Peeled(previous)),
+                            None => PreviousValue::MustNotExist,
+                        },
+                        new: Target::Peeled(new),
+                    },
+                    name: local.clone(),
+                    deref: false,
+                });
+            }
+            updates.push(Update {
+                remote,
+                local,
+                previous,
+                new,
+                mode,
+            });
+        }
+        self.edit_references(edits, git_lock::acquire::Fail::Immediately, None)?;
+
+        let remote_width = updates
+            .iter()
+            .map(|update| update.remote.shorten().len())
+            .max()
+            .unwrap_or_default()
+            .max(10);
+        let mut is_first_line = true;
+        for update in &updates {
+            update_progress.inc();
+            let line = match summary_line(update, remote_width) {
+                Some(line) => line,
+                None => continue,
+            };
+            if std::mem::take(&mut is_first_line) {
+                update_progress.info(format!("From {}", url));
+            }
+            update_progress.info(line);
+        }
+        Ok(Outcome { url, updates, pack })
+    }
+}
+
+/// Return the line describing `update` like `git fetch` does, or `None` if nothing changed.
+fn summary_line(update: &Update, remote_width: usize) -> Option<String> {
+    let abbrev = |id: &ObjectId| id.to_hex_with_len(ABBREV_LEN).to_string();
+    let (code, summary, reason) = match (update.mode, update.previous) {
+        (Mode::UpToDate, _) => return None,
+        (Mode::New, _) => {
+            let summary = match update.remote.as_bstr() {
+                name if name.starts_with(b"refs/tags/") => "[new tag]",
+                name if name.starts_with(b"refs/heads/") => "[new branch]",
+                _ => "[new ref]",
+            };
+            ('*', summary.to_owned(), "")
+        }
+        (Mode::FastForward, Some(previous)) => (' ', format!("{}..{}", abbrev(&previous), abbrev(&update.new)), ""),
+        (Mode::Forced, Some(previous)) => (
+            '+',
+            format!("{}...{}", abbrev(&previous), abbrev(&update.new)),
+            "  (forced update)",
+        ),
+        (Mode::Rejected, _) if update.local.as_bstr().starts_with(b"refs/tags/") => {
+            ('!', "[rejected]".to_owned(), "  (would clobber existing tag)")
+        }
+        (Mode::Rejected, _) => ('!', "[rejected]".to_owned(), "  (non-fast-forward)"),
+        (Mode::FastForward | Mode::Forced, None) => unreachable!("updates always have a previous value"),
+    };
+    Some(format!(
+        " {} {:<summary_width$} {:<remote_width$} -> {}{}",
+        code,
+        summary,
+        update.remote.shorten(),
+        update.local.shorten(),
+        reason,
+        summary_width = SUMMARY_WIDTH,
+        remote_width = remote_width,
+    ))
+}
+
+/// Receives a pack with the objects of the references matched by the fetch refspecs that aren't present yet, and keeps
+/// these references along with the local references they map to.
+struct Delegate<'a> {
+    objects: crate::OdbHandle,
+    pack_dir: PathBuf,
+    should_interrupt: &'a AtomicBool,
+    thread_limit: Option<usize>,
+    object_hash: git_hash::Kind,
+    refspecs: &'a [BString],
+    haves: Vec<ObjectId>,
+    /// The references of the remote, the local references they map to and whether their refspec forces the update.
+    refs: Vec<(Ref, BString, bool)>,
+    pack: Option<pack::bundle::write::Outcome>,
+}
+
+impl<'a> git_protocol::fetch::DelegateBlocking for Delegate<'a> {
+    fn prepare_ls_refs(
+        &mut self,
+        _server: &Capabilities,
+        arguments: &mut Vec<BString>,
+        _features: &mut Vec<(&str, Option<&str>)>,
+    ) -> io::Result<LsRefsAction> {
+        for refspec in self.refspecs {
+            let source = refspec_source(refspec.as_bstr());
+            let prefix = source.find_byte(b'*').map_or(source, |star| source[..star].as_bstr());
+            arguments.push(format!("ref-prefix {}", prefix).into());
+        }
+        Ok(LsRefsAction::Continue)
+    }
+
+    fn prepare_fetch(
+        &mut self,
+        _version: transport::Protocol,
+        _server: &Capabilities,
+        _features: &mut Vec<(&str, Option<&str>)>,
+        refs: &[Ref],
+    ) -> io::Result<Action> {
+        self.refs = refs
+            .iter()
+            .filter_map(|remote_ref| {
+                let name = remote_ref.unpack().0.as_bstr();
+                self.refspecs.iter().find_map(|refspec| {
+                    map_with_fetch_refspec(refspec.as_bstr(), name)
+                        .map(|local| (remote_ref.clone(), local, refspec.starts_with(b"+")))
+                })
+            })
+            .collect();
+        let has_all_objects = self
+            .refs
+            .iter()
+            .all(|(remote_ref, _, _)| self.objects.contains(remote_ref.unpack().1));
+        Ok(if has_all_objects {
+            Action::Cancel
+        } else {
+            Action::Continue
+        })
+    }
+
+    fn negotiate(
+        &mut self,
+        _refs: &[Ref],
+        arguments: &mut Arguments,
+        _previous_response: Option<&Response>,
+    ) -> io::Result<Action> {
+        let mut wants: Vec<_> = self
+            .refs
+            .iter()
+            .map(|(remote_ref, _, _)| *remote_ref.unpack().1)
+            .filter(|id| !self.objects.contains(id))
+            .collect();
+        wants.sort();
+        wants.dedup();
+        for id in wants {
+            arguments.want(id);
+        }
+        for id in &self.haves {
+            arguments.have(id);
+        }
+        // all tips are sent at once instead of walking down their history.
+        Ok(Action::Cancel)
+    }
+}
+
+impl<'a> git_protocol::fetch::Delegate for Delegate<'a> {
+    fn receive_pack(
+        &mut self,
+        input: impl io::BufRead,
+        progress: impl Progress,
+        _refs: &[Ref],
+        _previous_response: &Response,
+    ) -> io::Result<()> {
+        let objects = self.objects.clone();
+        self.pack = Some(write_pack(
+            input,
+            &self.pack_dir,
+            progress,
+            self.should_interrupt,
+            Some(Box::new(move |id, buf| {
+                objects.try_find(id, buf).ok().flatten().map(|(data, _)| data)
+            })),
+            self.thread_limit,
+            self.object_hash,
+        )?);
+        Ok(())
+    }
+}
+
+/// Write the pack read from `input` along with an index into `pack_dir`, using `lookup_base` to resolve the bases of thin packs,
+/// and stop once `should_interrupt` is set.
+///
+/// The rate at which the bytes of the pack arrive is shown in the name of a `receiving pack` child of `progress`.
+pub(crate) fn write_pack(
+    input: impl io::BufRead,
+    pack_dir: &Path,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+    lookup_base: Option<Box<dyn for<'b> FnMut(ObjectId, &'b mut Vec<u8>) -> Option<git_object::Data<'b>>>>,
+    thread_limit: Option<usize>,
+    object_hash: git_hash::Kind,
+) -> io::Result<pack::bundle::write::Outcome> {
+    let throughput = ThroughputTracker::new(progress.add_child("receiving pack"));
+    pack::Bundle::write_to_directory(
+        ThroughputRead {
+            inner: input,
+            progress: throughput,
+        },
+        Some(pack_dir),
+        progress,
+        should_interrupt,
+        lookup_base,
+        pack::bundle::write::Options {
+            thread_limit,
+            object_hash,
+            ..Default::default()
+        },
+    )
+    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+}
+
+/// Reports the amount of bytes consumed from `inner` to `progress` to track the rate at which they arrive.
+struct ThroughputRead<R, P: Progress> {
+    inner: R,
+    progress: ThroughputTracker<P>,
+}
+
+impl<R: io::BufRead, P: Progress> io::Read for ThroughputRead<R, P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        self.progress.report_throughput(bytes_read as u64);
+        Ok(bytes_read)
+    }
+}
+
+impl<R: io::BufRead, P: Progress> io::BufRead for ThroughputRead<R, P> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.progress.report_throughput(amt as u64);
+    }
+}
+
+/// Return the source of `refspec`, like `refs/heads/*` for `+refs/heads/*:refs/remotes/origin/*`.
+fn refspec_source(refspec: &BStr) -> &BStr {
+    let refspec = refspec.strip_prefix(b"+").unwrap_or(refspec.as_bytes());
+    refspec
+        .find_byte(b':')
+        .map_or(refspec, |colon| &refspec[..colon])
+        .as_bstr()
+}
+
+/// Return `refspec` forced and with its destination moved into `refs/prefetch/`, like `+refs/heads/*:refs/prefetch/remotes/origin/*`
+/// for `+refs/heads/*:refs/remotes/origin/*`, or `None` if it's ignored when prefetching.
+fn prefetch_refspec(refspec: &BStr) -> Option<BString> {
+    let refspec = refspec.strip_prefix(b"+").unwrap_or(refspec.as_bytes());
+    let colon = refspec.find_byte(b':')?;
+    let (source, destination) = (&refspec[..colon], &refspec[colon + 1..]);
+    if destination.is_empty() || source.starts_with(b"^") || source.starts_with(b"refs/tags/") {
+        return None;
+    }
+    let mut prefetch = BString::from("+");
+    prefetch.extend_from_slice(source);
+    prefetch.extend_from_slice(b":refs/prefetch/");
+    prefetch.extend_from_slice(destination.strip_prefix(b"refs/").unwrap_or(destination));
+    Some(prefetch)
+}
+
+fn full_name(name: BString) -> Result<FullName, Error> {
+    FullName::try_from(name.as_bstr()).map_err(|source| Error::InvalidRefName { name, source })
+}
@@ -0,0 +1,71 @@
+//! Access to the `FETCH_HEAD` file which records what was fetched most recently.
+use git_hash::ObjectId;
+use git_object::bstr::{BString, ByteSlice};
+
+/// A single line of the `FETCH_HEAD` file, as returned by [`Repository::fetch_head()`][crate::Repository::fetch_head()].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The object that was fetched.
+    pub id: ObjectId,
+    /// If true, the entry isn't considered by `git merge FETCH_HEAD` or `git pull`.
+    pub not_for_merge: bool,
+    /// What was fetched, like `branch 'main'` or `tag 'v1.0'`, or empty if the remote's `HEAD` was fetched.
+    pub description: BString,
+    /// The URL of the remote the object was fetched from.
+    pub remote_url: BString,
+}
+
+/// The error returned by [`Repository::fetch_head()`][crate::Repository::fetch_head()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read the FETCH_HEAD file")]
+    Io(#[from] std::io::Error),
+    #[error("Line {line_number} of the FETCH_HEAD file is malformed: {line:?}")]
+    Parse { line_number: usize, line: BString },
+}
+
+impl crate::Repository {
+    /// Return all entries of the `FETCH_HEAD` file in the order they were written by the most recent fetch,
+    /// or an empty list if nothing was fetched yet.
+    pub fn fetch_head(&self) -> Result<Vec<Entry>, Error> {
+        let content = match std::fs::read(self.path().join("FETCH_HEAD")) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(index, line)| {
+                parse_line(line).ok_or_else(|| Error::Parse {
+                    line_number: index + 1,
+                    line: line.into(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse a line of the form `<hex-id>\t[not-for-merge]\t[<description> of ]<url>`.
+fn parse_line(line: &[u8]) -> Option<Entry> {
+    let mut tokens = line.splitn(3, |b| *b == b'\t');
+    let id = ObjectId::from_hex(tokens.next()?).ok()?;
+    let not_for_merge = match tokens.next()? {
+        b"" => false,
+        b"not-for-merge" => true,
+        _ => return None,
+    };
+    let note = tokens.next()?;
+    let (description, remote_url) = match note.find(" of ") {
+        Some(pos) => (&note[..pos], &note[pos + " of ".len()..]),
+        None => (&[][..], note),
+    };
+    Some(Entry {
+        id,
+        not_for_merge,
+        description: description.into(),
+        remote_url: remote_url.into(),
+    })
+}
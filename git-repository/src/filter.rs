@@ -0,0 +1,299 @@
+//! Run the `clean` and `smudge` filter drivers configured for paths with the `filter=<driver>` attribute.
+use std::{
+    borrow::Cow,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use git_object::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The direction in which data passes through a filter in [`Repository::apply_filter()`][crate::Repository::apply_filter()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Convert data from the working tree into the form to be stored in the index.
+    Clean,
+    /// Convert data from the index into the form to be written into the working tree.
+    Smudge,
+}
+
+impl Direction {
+    /// Return the name of the direction as used in configuration keys and in the filter process protocol.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Clean => "clean",
+            Direction::Smudge => "smudge",
+        }
+    }
+}
+
+/// The error returned by [`Repository::apply_filter()`][crate::Repository::apply_filter()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Attributes(#[from] crate::attributes::Error),
+    #[error("Value '{}' at key 'filter.{driver}.required' could not be decoded as boolean", .value)]
+    DecodeRequired { driver: String, value: BString },
+    #[error("The required filter '{driver}' has no command to {}", .direction.as_str())]
+    MissingCommand { driver: String, direction: Direction },
+    #[error("Could not run the filter command '{command}'")]
+    Io { command: BString, source: std::io::Error },
+    #[error("The filter command '{command}' failed with {status}")]
+    Failed {
+        command: BString,
+        status: std::process::ExitStatus,
+    },
+    #[error("The filter process '{command}' violated the protocol: {message}")]
+    Protocol { command: BString, message: String },
+    #[error("The filter process '{command}' reported status '{status}' for '{path}'")]
+    ProcessStatus {
+        command: BString,
+        path: BString,
+        status: BString,
+    },
+}
+
+impl crate::Repository {
+    /// Pass `data` of the file at `relative_path`, relative to the root of the working tree, through the filter driver
+    /// configured for it with the `filter` attribute in the given `direction`, similar to what git does when staging
+    /// or checking out files.
+    ///
+    /// A driver is configured with `filter.<driver>.clean` and `filter.<driver>.smudge`, which are shell commands in which
+    /// `%f` is replaced with the quoted path, or with `filter.<driver>.process` which names a long-running process
+    /// speaking the filter protocol version 2. The latter takes precedence, and is started anew for each invocation.
+    ///
+    /// `data` is returned unchanged if no filter applies. Unless `filter.<driver>.required` is set, this is also the case
+    /// if the driver has no command for `direction` or if the filter fails.
+    pub fn apply_filter<'a>(
+        &self,
+        relative_path: &BStr,
+        data: &'a [u8],
+        direction: Direction,
+    ) -> Result<Cow<'a, [u8]>, Error> {
+        let case = self
+            .config
+            .ignore_case
+            .then(|| git_glob::pattern::Case::Fold)
+            .unwrap_or_default();
+        let driver = match self.attributes(relative_path)?.attribute_matching_relative_path(
+            relative_path,
+            "filter",
+            Some(false),
+            case,
+        ) {
+            Some(git_attributes::State::Value(driver)) => driver.to_string(),
+            _ => return Ok(Cow::Borrowed(data)),
+        };
+
+        let config = &self.config.resolved;
+        let required = config
+            .boolean("filter", Some(&driver), "required")
+            .transpose()
+            .map_err(|err| Error::DecodeRequired {
+                driver: driver.clone(),
+                value: err.input,
+            })?
+            .unwrap_or(false);
+        let cwd = self.work_dir().unwrap_or_else(|| self.git_dir());
+        let res = match config.string("filter", Some(&driver), "process") {
+            Some(command) => process::apply(command.as_ref(), cwd, relative_path, data, direction),
+            None => match config.string("filter", Some(&driver), direction.as_str()) {
+                Some(command) => run_command(command.as_ref(), cwd, relative_path, data),
+                None if required => return Err(Error::MissingCommand { driver, direction }),
+                None => return Ok(Cow::Borrowed(data)),
+            },
+        };
+        match res {
+            Ok(Some(filtered)) => Ok(Cow::Owned(filtered)),
+            Ok(None) => Ok(Cow::Borrowed(data)),
+            Err(err) if required => Err(err),
+            Err(_) => Ok(Cow::Borrowed(data)),
+        }
+    }
+}
+
+/// Run the shell `command` with `%f` replaced by `relative_path`, feeding it `data` and returning its output.
+fn run_command(command: &BStr, cwd: &Path, relative_path: &BStr, data: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+    let command = command.replace("%f", quote(relative_path));
+    let io_err = |source| Error::Io {
+        command: command.as_bstr().to_owned(),
+        source,
+    };
+    let mut child = spawn(command.as_bstr(), cwd).map_err(io_err)?;
+
+    let mut stdin = child.stdin.take().expect("configured");
+    let input = data.to_owned();
+    let writer = std::thread::spawn(move || match stdin.write_all(&input) {
+        // filters may choose not to read all of their input.
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        res => res,
+    });
+    let output = child.wait_with_output().map_err(io_err)?;
+    writer.join().expect("no panic").map_err(io_err)?;
+    if !output.status.success() {
+        return Err(Error::Failed {
+            command: command.into(),
+            status: output.status,
+        });
+    }
+    Ok(Some(output.stdout))
+}
+
+fn spawn(command: &BStr, cwd: &Path) -> std::io::Result<std::process::Child> {
+    Command::new("sh")
+        .arg("-c")
+        .arg(git_path::from_bstr(command).into_owned())
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+}
+
+/// Quote `path` for use as a single argument in a shell command.
+fn quote(path: &BStr) -> BString {
+    let mut quoted = BString::from("'");
+    for byte in path.iter() {
+        if *byte == b'\'' {
+            quoted.push_str("'\\''");
+        } else {
+            quoted.push_byte(*byte);
+        }
+    }
+    quoted.push_byte(b'\'');
+    quoted
+}
+
+/// An implementation of the client side of the long-running filter process protocol, version 2.
+mod process {
+    use std::{
+        io::{BufReader, Read, Write},
+        path::Path,
+    };
+
+    use git_object::bstr::{BStr, BString};
+
+    use super::{Direction, Error};
+
+    /// The maximum amount of bytes in a single packet line, including its 4 bytes of length.
+    const MAX_LINE_LEN: usize = 65520;
+
+    /// Start `command`, and have it filter `data` in `direction`. Return `None` if the process doesn't support `direction`.
+    pub fn apply(
+        command: &BStr,
+        cwd: &Path,
+        relative_path: &BStr,
+        data: &[u8],
+        direction: Direction,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let protocol_err = |message: &str| Error::Protocol {
+            command: command.to_owned(),
+            message: message.into(),
+        };
+        let io_err = |source| Error::Io {
+            command: command.to_owned(),
+            source,
+        };
+        let mut child = super::spawn(command, cwd).map_err(io_err)?;
+        let mut stdin = child.stdin.take().expect("configured");
+        let mut stdout = BufReader::new(child.stdout.take().expect("configured"));
+
+        let res = (|| {
+            write_lines(&mut stdin, &["git-filter-client\n", "version=2\n"]).map_err(io_err)?;
+            let server = read_lines(&mut stdout).map_err(io_err)?;
+            if server.first().map(|l| l.as_slice()) != Some(&b"git-filter-server"[..])
+                || !server.iter().any(|l| l == "version=2")
+            {
+                return Err(protocol_err("expected git-filter-server with version 2"));
+            }
+            write_lines(&mut stdin, &["capability=clean\n", "capability=smudge\n"]).map_err(io_err)?;
+            let capabilities = read_lines(&mut stdout).map_err(io_err)?;
+            if !capabilities
+                .iter()
+                .any(|l| l.strip_prefix(b"capability=") == Some(direction.as_str().as_bytes()))
+            {
+                return Ok(None);
+            }
+
+            let mut pathname = BString::from("pathname=");
+            pathname.extend_from_slice(relative_path);
+            pathname.push(b'\n');
+            write_packet(&mut stdin, format!("command={}\n", direction.as_str()).as_bytes()).map_err(io_err)?;
+            write_packet(&mut stdin, &pathname).map_err(io_err)?;
+            write_flush(&mut stdin).map_err(io_err)?;
+            for chunk in data.chunks(MAX_LINE_LEN - 4) {
+                write_packet(&mut stdin, chunk).map_err(io_err)?;
+            }
+            write_flush(&mut stdin).map_err(io_err)?;
+            stdin.flush().map_err(io_err)?;
+
+            let check_status = |lines: Vec<BString>| -> Result<(), Error> {
+                match lines.iter().rev().find_map(|l| l.strip_prefix(b"status=")) {
+                    Some(b"success") | None => Ok(()),
+                    Some(status) => Err(Error::ProcessStatus {
+                        command: command.to_owned(),
+                        path: relative_path.to_owned(),
+                        status: status.into(),
+                    }),
+                }
+            };
+            check_status(read_lines(&mut stdout).map_err(io_err)?)?;
+            let mut filtered = Vec::new();
+            while let Some(packet) = read_packet(&mut stdout).map_err(io_err)? {
+                filtered.extend_from_slice(&packet);
+            }
+            check_status(read_lines(&mut stdout).map_err(io_err)?)?;
+            Ok(Some(filtered))
+        })();
+
+        drop(stdin);
+        child.wait().map_err(io_err)?;
+        res
+    }
+
+    fn write_packet(out: &mut impl Write, data: &[u8]) -> std::io::Result<()> {
+        out.write_all(format!("{:04x}", data.len() + 4).as_bytes())?;
+        out.write_all(data)
+    }
+
+    fn write_flush(out: &mut impl Write) -> std::io::Result<()> {
+        out.write_all(b"0000")
+    }
+
+    fn write_lines(out: &mut impl Write, lines: &[&str]) -> std::io::Result<()> {
+        for line in lines {
+            write_packet(out, line.as_bytes())?;
+        }
+        write_flush(out)?;
+        out.flush()
+    }
+
+    /// Read a single packet, or `None` if a flush packet was encountered.
+    fn read_packet(input: &mut impl Read) -> std::io::Result<Option<Vec<u8>>> {
+        let mut hex_len = [0u8; 4];
+        input.read_exact(&mut hex_len)?;
+        let len = std::str::from_utf8(&hex_len)
+            .ok()
+            .and_then(|hex_len| usize::from_str_radix(hex_len, 16).ok())
+            .filter(|len| *len == 0 || (4..=MAX_LINE_LEN).contains(len))
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid packet line length"))?;
+        if len == 0 {
+            return Ok(None);
+        }
+        let mut data = vec![0; len - 4];
+        input.read_exact(&mut data)?;
+        Ok(Some(data))
+    }
+
+    /// Read all packets up to the next flush packet as lines without their trailing newline.
+    fn read_lines(input: &mut impl Read) -> std::io::Result<Vec<BString>> {
+        let mut lines = Vec::new();
+        while let Some(mut line) = read_packet(input)? {
+            if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+            lines.push(line.into());
+        }
+        Ok(lines)
+    }
+}
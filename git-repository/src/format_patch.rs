@@ -0,0 +1,706 @@
+//! Produce patch emails for commits like `git format-patch` does.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    ops::Range,
+};
+
+use git_hash::ObjectId;
+use git_object::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    tree::EntryMode,
+};
+use git_odb::FindExt;
+
+use crate::{
+    blob_diff::{lines, myers, Edit},
+    date,
+    ext::ObjectIdExt,
+    object, Id,
+};
+
+/// Options for use in [`Repository::generate_patch()`][crate::Repository::generate_patch()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The commit the patch is meant to be applied to, which is mentioned as `base-commit: <base>` at the end of the patch
+    /// similar to `git format-patch --base=<base>`.
+    pub base: Option<ObjectId>,
+    /// The amount of unchanged lines to show before and after each change.
+    pub context_lines: usize,
+    /// The text to show after the `-- ` line at the very end of the patch, which is usually the version of the program
+    /// producing it. If `None`, the signature is omitted like `git format-patch --no-signature` does.
+    pub signature: Option<BString>,
+}
+
+impl Default for Options {
+    /// The same defaults as used by `git format-patch --no-signature`.
+    fn default() -> Self {
+        Options {
+            base: None,
+            context_lines: 3,
+            signature: None,
+        }
+    }
+}
+
+/// The error returned by [`Repository::generate_patch()`][crate::Repository::generate_patch()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::OdbError),
+    #[error(transparent)]
+    NotACommit(#[from] object::try_into::Error),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error(transparent)]
+    FindBlob(#[from] git_odb::find::existing_object::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+    #[error("Could not compress the content of a binary file")]
+    Compress(#[from] std::io::Error),
+}
+
+/// All files of a tree by their path, along with their mode and blob id.
+type Files = BTreeMap<BString, (EntryMode, ObjectId)>;
+
+/// One side of a file change.
+struct Side<'a> {
+    mode: EntryMode,
+    id: ObjectId,
+    data: &'a [u8],
+}
+
+/// The amount of changes to a file, as shown in the diffstat.
+enum Stat {
+    Text { insertions: usize, deletions: usize },
+    Binary { old_size: usize, new_size: usize },
+}
+
+/// The changes to a single file.
+struct FilePatch {
+    path: BString,
+    old_mode: Option<EntryMode>,
+    new_mode: Option<EntryMode>,
+    stat: Stat,
+    /// The diff of the file, starting with its `diff --git` line.
+    diff: BString,
+}
+
+impl crate::Repository {
+    /// Produce a patch email for `commit` like `git format-patch -1 --stdout` does, with the `From`, `Date` and `Subject`
+    /// headers taken from the commit, followed by the body of its message, a diffstat and the differences to its first
+    /// parent as unified diff.
+    ///
+    /// Binary files are written as `GIT binary patch` which can be applied with `git apply`.
+    ///
+    /// Note that unlike `git`, binary files are always included literally instead of as delta, renames aren't detected,
+    /// changes to submodules are omitted and paths aren't quoted. Lines are diffed with the Myers algorithm without the
+    /// heuristics `git` uses to make diffs more readable, so the lines shown as changed may differ if there are multiple
+    /// shortest diffs.
+    pub fn generate_patch(&self, commit: Id<'_>, opts: Options) -> Result<BString, Error> {
+        let commit = commit.object()?.try_into_commit()?;
+        let old_files = match commit.parent_ids().next() {
+            Some(parent) => self.files_of_tree(parent.object()?.try_into_commit()?.tree_id()?)?,
+            None => Files::default(),
+        };
+        let new_files = self.files_of_tree(commit.tree_id()?)?;
+
+        let mut patches = Vec::new();
+        let (mut old_buf, mut new_buf) = (Vec::new(), Vec::new());
+        for path in old_files.keys().chain(new_files.keys()).collect::<BTreeSet<_>>() {
+            let (old, new) = (old_files.get(path), new_files.get(path));
+            if old == new {
+                continue;
+            }
+            let old = match old {
+                Some((mode, id)) => Some(Side {
+                    mode: *mode,
+                    id: *id,
+                    data: self.objects.find_blob(*id, &mut old_buf)?.data,
+                }),
+                None => None,
+            };
+            let new = match new {
+                Some((mode, id)) => Some(Side {
+                    mode: *mode,
+                    id: *id,
+                    data: self.objects.find_blob(*id, &mut new_buf)?.data,
+                }),
+                None => None,
+            };
+            let is_binary = old.iter().chain(new.iter()).any(|side| is_binary(side.data));
+            let mut diff = BString::default();
+            let (insertions, deletions) = match (&old, &new) {
+                // a change between file and symbolic link is shown as deletion followed by creation.
+                (Some(old), Some(new)) if (old.mode == EntryMode::Link) != (new.mode == EntryMode::Link) => {
+                    let (_, deletions) =
+                        self.write_file_diff(&mut diff, path, Some(old), None, is_binary, opts.context_lines)?;
+                    let (insertions, _) =
+                        self.write_file_diff(&mut diff, path, None, Some(new), is_binary, opts.context_lines)?;
+                    (insertions, deletions)
+                }
+                (old, new) => self.write_file_diff(
+                    &mut diff,
+                    path,
+                    old.as_ref(),
+                    new.as_ref(),
+                    is_binary,
+                    opts.context_lines,
+                )?,
+            };
+            let size = |side: &Option<Side<'_>>| side.as_ref().map_or(0, |side| side.data.len());
+            patches.push(FilePatch {
+                path: path.to_owned(),
+                old_mode: old.as_ref().map(|side| side.mode),
+                new_mode: new.as_ref().map(|side| side.mode),
+                stat: if is_binary && old.as_ref().map(|side| side.id) != new.as_ref().map(|side| side.id) {
+                    Stat::Binary {
+                        old_size: size(&old),
+                        new_size: size(&new),
+                    }
+                } else {
+                    Stat::Text { insertions, deletions }
+                },
+                diff,
+            });
+        }
+
+        let mut out = BString::default();
+        out.push_str(format!("From {} Mon Sep 17 00:00:00 2001\n", commit.id));
+        let author = commit.author()?;
+        write_from_header(&mut out, author.name, author.email);
+        out.push_str(format!("Date: {}\n", date::format_rfc2822(&author.time)));
+        let message = commit.message()?;
+        let subject = message.summary();
+        let subject = subject.as_bstr();
+        let body = message.body.map(|body| body.trim_end()).filter(|body| !body.is_empty());
+        out.push_str("Subject: [PATCH] ");
+        if needs_rfc2047_encoding(subject) {
+            write_rfc2047(&mut out, subject, false);
+        } else {
+            write_folded(&mut out, subject);
+        }
+        out.push_byte(b'\n');
+        if !subject.is_ascii() || !body.map_or(true, |body| body.is_ascii()) {
+            out.push_str(
+                "MIME-Version: 1.0\nContent-Type: text/plain; charset=UTF-8\nContent-Transfer-Encoding: 8bit\n",
+            );
+        }
+        out.push_byte(b'\n');
+        if let Some(body) = body {
+            out.push_str(body);
+            out.push_byte(b'\n');
+        }
+        out.push_str("---\n");
+        if !patches.is_empty() {
+            write_stat(&mut out, &patches);
+            write_summary(&mut out, &patches);
+            out.push_byte(b'\n');
+        }
+        for patch in &patches {
+            out.push_str(&patch.diff);
+        }
+        if let Some(base) = opts.base {
+            out.push_str(format!("\nbase-commit: {}\n", base));
+        }
+        if let Some(signature) = opts.signature {
+            out.push_str("-- \n");
+            out.push_str(signature);
+            out.push_str("\n\n");
+        }
+        Ok(out)
+    }
+
+    fn files_of_tree(&self, id: ObjectId) -> Result<Files, Error> {
+        Ok(self
+            .find_object(id)?
+            .into_tree()
+            .traverse()
+            .breadthfirst
+            .files()?
+            .into_iter()
+            .filter(|entry| entry.mode.is_no_tree() && entry.mode != EntryMode::Commit)
+            .map(|entry| (entry.filepath, (entry.mode, entry.oid)))
+            .collect())
+    }
+
+    /// Write the diff from `old` to `new` at `path` to `out`, and return the amount of inserted and deleted lines.
+    fn write_file_diff(
+        &self,
+        out: &mut BString,
+        path: &BStr,
+        old: Option<&Side<'_>>,
+        new: Option<&Side<'_>>,
+        is_binary: bool,
+        context_lines: usize,
+    ) -> Result<(usize, usize), Error> {
+        out.push_str("diff --git a/");
+        out.push_str(path);
+        out.push_str(" b/");
+        out.push_str(path);
+        out.push_byte(b'\n');
+        match (old, new) {
+            (None, Some(new)) => {
+                out.push_str("new file mode ");
+                out.push_str(new.mode.as_bytes());
+                out.push_byte(b'\n');
+            }
+            (Some(old), None) => {
+                out.push_str("deleted file mode ");
+                out.push_str(old.mode.as_bytes());
+                out.push_byte(b'\n');
+            }
+            (Some(old), Some(new)) if old.mode != new.mode => {
+                out.push_str("old mode ");
+                out.push_str(old.mode.as_bytes());
+                out.push_str("\nnew mode ");
+                out.push_str(new.mode.as_bytes());
+                out.push_byte(b'\n');
+            }
+            _ => {}
+        }
+
+        let null = ObjectId::null(self.object_hash());
+        let (old_id, new_id) = (old.map_or(null, |side| side.id), new.map_or(null, |side| side.id));
+        if old_id == new_id {
+            return Ok((0, 0));
+        }
+        out.push_str("index ");
+        if is_binary {
+            // binary patches can't be applied without knowing the full id of the blob they apply to.
+            out.push_str(format!("{}..{}", old_id, new_id));
+        } else {
+            let shorten = |id: ObjectId| -> Result<Option<git_hash::Prefix>, Error> {
+                Ok((!id.is_null()).then(|| id.attach(self).shorten()).transpose()?)
+            };
+            let (old_prefix, new_prefix) = (shorten(old_id)?, shorten(new_id)?);
+            let hex_len = old_prefix.or(new_prefix).map_or(7, |prefix| prefix.hex_len());
+            let hex = |id: ObjectId, prefix: Option<git_hash::Prefix>| {
+                prefix.map_or_else(|| id.to_hex_with_len(hex_len).to_string(), |prefix| prefix.to_string())
+            };
+            out.push_str(format!("{}..{}", hex(old_id, old_prefix), hex(new_id, new_prefix)));
+        }
+        if let (Some(old), Some(new)) = (old, new) {
+            if old.mode == new.mode {
+                out.push_byte(b' ');
+                out.push_str(old.mode.as_bytes());
+            }
+        }
+        out.push_byte(b'\n');
+
+        let (old_data, new_data) = (
+            old.map_or(&[][..], |side| side.data),
+            new.map_or(&[][..], |side| side.data),
+        );
+        if is_binary {
+            out.push_str("GIT binary patch\n");
+            write_binary_literal(out, new_data)?;
+            write_binary_literal(out, old_data)?;
+            return Ok((0, 0));
+        }
+        let mut hunks = BString::default();
+        let counts = write_hunks(&mut hunks, old_data, new_data, context_lines);
+        if !hunks.is_empty() {
+            let mut write_name = |header: &str, prefix: &str, side: Option<&Side<'_>>| {
+                out.push_str(header);
+                match side {
+                    Some(_) => {
+                        out.push_str(prefix);
+                        out.push_str(path);
+                    }
+                    None => out.push_str("/dev/null"),
+                }
+                out.push_byte(b'\n');
+            };
+            write_name("--- ", "a/", old);
+            write_name("+++ ", "b/", new);
+            out.push_str(hunks);
+        }
+        Ok(counts)
+    }
+}
+
+/// Return true if `data` contains a null byte within its first 8000 bytes, which is how `git` detects binary files.
+fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(8000)].contains(&0)
+}
+
+/// Write the hunks of a unified diff from `old` to `new` to `out`, and return the amount of inserted and deleted lines.
+fn write_hunks(out: &mut BString, old: &[u8], new: &[u8], context_lines: usize) -> (usize, usize) {
+    let (old_lines, new_lines) = (lines(old), lines(new));
+    // Each change replaces a range of old lines with a range of new lines.
+    let mut changes: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    let (mut old_pos, mut new_pos, mut in_change) = (0, 0, false);
+    for edit in myers(&old_lines, &new_lines) {
+        if edit == Edit::Equal {
+            old_pos += 1;
+            new_pos += 1;
+            in_change = false;
+            continue;
+        }
+        if !in_change {
+            changes.push((old_pos..old_pos, new_pos..new_pos));
+            in_change = true;
+        }
+        let (old_range, new_range) = changes.last_mut().expect("just pushed");
+        if edit == Edit::Delete {
+            old_pos += 1;
+            old_range.end = old_pos;
+        } else {
+            new_pos += 1;
+            new_range.end = new_pos;
+        }
+    }
+
+    let (mut insertions, mut deletions) = (0, 0);
+    let mut start = 0;
+    while start < changes.len() {
+        // changes which are close enough to share their context lines are shown in the same hunk.
+        let mut end = start + 1;
+        while end < changes.len() && changes[end].0.start - changes[end - 1].0.end <= 2 * context_lines {
+            end += 1;
+        }
+        let hunk = &changes[start..end];
+        let (first, last) = (&hunk[0], &hunk[hunk.len() - 1]);
+        let leading = first.0.start.min(context_lines);
+        let trailing = (old_lines.len() - last.0.end).min(context_lines);
+        let old_range = first.0.start - leading..last.0.end + trailing;
+        let new_range = first.1.start - leading..last.1.end + trailing;
+
+        out.push_str(format!("@@ -{} +{} @@", hunk_range(&old_range), hunk_range(&new_range)));
+        if let Some(line) = old_lines[..old_range.start]
+            .iter()
+            .rev()
+            .find(|line| is_function_line(line))
+        {
+            out.push_byte(b' ');
+            out.push_str(line[..line.len().min(80)].trim_end());
+        }
+        out.push_byte(b'\n');
+
+        let mut pos = old_range.start;
+        for (old, new) in hunk {
+            write_lines(out, b' ', &old_lines[pos..old.start]);
+            write_lines(out, b'-', &old_lines[old.clone()]);
+            write_lines(out, b'+', &new_lines[new.clone()]);
+            deletions += old.len();
+            insertions += new.len();
+            pos = old.end;
+        }
+        write_lines(out, b' ', &old_lines[pos..old_range.end]);
+        start = end;
+    }
+    (insertions, deletions)
+}
+
+/// Format a range of lines like `git` does in hunk headers, with 1-based line numbers.
+fn hunk_range(range: &Range<usize>) -> String {
+    match range.len() {
+        0 => format!("{},0", range.start),
+        1 => format!("{}", range.start + 1),
+        len => format!("{},{}", range.start + 1, len),
+    }
+}
+
+/// Return true if `line` looks like the start of a function, similar to the default of `git` if there is no `diff` driver.
+fn is_function_line(line: &[u8]) -> bool {
+    line.first()
+        .map_or(false, |b| b.is_ascii_alphabetic() || *b == b'_' || *b == b'$')
+}
+
+fn write_lines(out: &mut BString, prefix: u8, lines: &[&[u8]]) {
+    for line in lines {
+        out.push_byte(prefix);
+        out.push_str(line);
+        if !line.ends_with(b"\n") {
+            out.push_str("\n\\ No newline at end of file\n");
+        }
+    }
+}
+
+/// The digits of the base85 encoding used in binary patches.
+pub(crate) const BASE85_ALPHABET: &[u8; 85] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+/// Write `data` zlib-compressed and encoded in base85 as used in binary patches.
+fn write_binary_literal(out: &mut BString, data: &[u8]) -> std::io::Result<()> {
+    let mut deflate = git_features::zlib::stream::deflate::Write::new(Vec::new());
+    deflate.write_all(data)?;
+    deflate.flush()?;
+    let compressed = deflate.into_inner();
+
+    out.push_str(format!("literal {}\n", data.len()));
+    for line in compressed.chunks(52) {
+        // the amount of bytes on each line is encoded as `A-Z` for 1 to 26 and `a-z` for 27 to 52.
+        out.push_byte(match line.len() {
+            len @ 1..=26 => b'A' + len as u8 - 1,
+            len => b'a' + len as u8 - 27,
+        });
+        for group in line.chunks(4) {
+            let mut value = (0..4).fold(0u32, |value, index| {
+                value << 8 | u32::from(*group.get(index).unwrap_or(&0))
+            });
+            let mut digits = [0u8; 5];
+            for digit in digits.iter_mut().rev() {
+                *digit = BASE85_ALPHABET[(value % 85) as usize];
+                value /= 85;
+            }
+            out.extend_from_slice(&digits);
+        }
+        out.push_byte(b'\n');
+    }
+    out.push_byte(b'\n');
+    Ok(())
+}
+
+/// Write a diffstat like `git format-patch` does, which assumes lines to be at most 72 characters wide.
+fn write_stat(out: &mut BString, patches: &[FilePatch]) {
+    const WIDTH: usize = 72;
+    let (mut max_change, mut number_width, mut bin_width) = (0, 0, 0);
+    for patch in patches {
+        match patch.stat {
+            Stat::Text { insertions, deletions } => max_change = max_change.max(insertions + deletions),
+            Stat::Binary { old_size, new_size } => {
+                // `Bin` is as wide as three digits.
+                number_width = 3;
+                bin_width = bin_width.max(14 + decimal_width(old_size) + decimal_width(new_size));
+            }
+        }
+    }
+    number_width = number_width.max(decimal_width(max_change));
+    let mut graph_width = if max_change + 4 > bin_width {
+        max_change
+    } else {
+        bin_width - 4
+    };
+    let mut name_width = patches
+        .iter()
+        .map(|patch| patch.path.chars().count())
+        .max()
+        .unwrap_or_default();
+    if name_width + number_width + 6 + graph_width > WIDTH {
+        let max_graph_width = (WIDTH * 3 / 8).saturating_sub(number_width + 6);
+        if graph_width > max_graph_width {
+            graph_width = max_graph_width.max(6);
+        }
+        if name_width > WIDTH - number_width - 6 - graph_width {
+            name_width = WIDTH - number_width - 6 - graph_width;
+        } else {
+            graph_width = WIDTH - number_width - 6 - name_width;
+        }
+    }
+
+    let (mut insertions_total, mut deletions_total) = (0, 0);
+    for patch in patches {
+        let (prefix, name, len) = shorten_name(patch.path.as_bstr(), name_width);
+        out.push_byte(b' ');
+        out.push_str(prefix);
+        out.push_str(name);
+        out.push_str(" ".repeat(len.saturating_sub(name.chars().count())));
+        out.push_str(" | ");
+        match patch.stat {
+            Stat::Binary { old_size, new_size } => {
+                out.push_str(format!("{:>width$}", "Bin", width = number_width));
+                if old_size != 0 || new_size != 0 {
+                    out.push_str(format!(" {} -> {} bytes", old_size, new_size));
+                }
+            }
+            Stat::Text { insertions, deletions } => {
+                insertions_total += insertions;
+                deletions_total += deletions;
+                let changes = insertions + deletions;
+                out.push_str(format!("{:>width$}", changes, width = number_width));
+                if changes != 0 {
+                    out.push_byte(b' ');
+                }
+                let (mut plus, mut minus) = (insertions, deletions);
+                if graph_width <= max_change {
+                    let mut total = scale_linear(changes, graph_width, max_change);
+                    if total < 2 && insertions != 0 && deletions != 0 {
+                        total = 2;
+                    }
+                    if insertions < deletions {
+                        plus = scale_linear(insertions, graph_width, max_change);
+                        minus = total - plus;
+                    } else {
+                        minus = scale_linear(deletions, graph_width, max_change);
+                        plus = total - minus;
+                    }
+                }
+                out.push_str("+".repeat(plus));
+                out.push_str("-".repeat(minus));
+            }
+        }
+        out.push_byte(b'\n');
+    }
+
+    let plural = |count: usize| if count == 1 { "" } else { "s" };
+    out.push_str(format!(" {} file{} changed", patches.len(), plural(patches.len())));
+    if insertions_total != 0 || deletions_total == 0 {
+        out.push_str(format!(
+            ", {} insertion{}(+)",
+            insertions_total,
+            plural(insertions_total)
+        ));
+    }
+    if deletions_total != 0 || insertions_total == 0 {
+        out.push_str(format!(", {} deletion{}(-)", deletions_total, plural(deletions_total)));
+    }
+    out.push_byte(b'\n');
+}
+
+/// Return the prefix and the part of `name` to show in a column of `width` characters, along with the width left for the
+/// name itself. Names that are too long lose leading characters up to the next slash, and are prefixed with `...`.
+fn shorten_name(name: &BStr, width: usize) -> (&'static str, &BStr, usize) {
+    let name_len = name.chars().count();
+    if name_len <= width {
+        return ("", name, width);
+    }
+    let len = width.saturating_sub(3);
+    let start = name
+        .char_indices()
+        .nth(name_len - len)
+        .map_or(name.len(), |(start, _, _)| start);
+    let name = name[start..].as_bstr();
+    let name = name.find_byte(b'/').map_or(name, |slash| name[slash..].as_bstr());
+    ("...", name, len)
+}
+
+/// Scale `value` from the range up to `max` to the range up to `width`, while assuring that non-zero values stay non-zero.
+fn scale_linear(value: usize, width: usize, max: usize) -> usize {
+    if value == 0 {
+        0
+    } else {
+        1 + value * (width - 1) / max
+    }
+}
+
+fn decimal_width(mut value: usize) -> usize {
+    let mut width = 1;
+    while value >= 10 {
+        value /= 10;
+        width += 1;
+    }
+    width
+}
+
+/// Write the creation, deletion and mode changes of files like `git diff --summary` does.
+fn write_summary(out: &mut BString, patches: &[FilePatch]) {
+    for patch in patches {
+        match (patch.old_mode, patch.new_mode) {
+            (None, Some(mode)) => {
+                out.push_str(" create mode ");
+                out.push_str(mode.as_bytes());
+            }
+            (Some(mode), None) => {
+                out.push_str(" delete mode ");
+                out.push_str(mode.as_bytes());
+            }
+            (Some(old), Some(new)) if old != new => {
+                out.push_str(" mode change ");
+                out.push_str(old.as_bytes());
+                out.push_str(" => ");
+                out.push_str(new.as_bytes());
+            }
+            _ => continue,
+        }
+        out.push_byte(b' ');
+        out.push_str(&patch.path);
+        out.push_byte(b'\n');
+    }
+}
+
+/// Write the `From` header with the author `name` and `email`, quoting or encoding the name as needed.
+fn write_from_header(out: &mut BString, name: &BStr, email: &BStr) {
+    out.push_str("From: ");
+    let mut max_line_len = MAX_LINE_LEN;
+    if needs_rfc2047_encoding(name) {
+        write_rfc2047(out, name, true);
+        max_line_len = MAX_ENCODED_LINE_LEN;
+    } else if name.iter().any(|b| b"()<>[]:;@,.\"\\".contains(b)) {
+        let mut quoted = BString::from("\"");
+        for b in name.iter() {
+            if *b == b'"' || *b == b'\\' {
+                quoted.push_byte(b'\\');
+            }
+            quoted.push_byte(*b);
+        }
+        quoted.push_byte(b'"');
+        write_folded(out, quoted.as_bstr());
+    } else {
+        write_folded(out, name);
+    }
+    if last_line_len(out) + email.len() + 3 > max_line_len {
+        out.push_byte(b'\n');
+    }
+    out.push_str(" <");
+    out.push_str(email);
+    out.push_str(">\n");
+}
+
+/// The maximum length of header lines.
+const MAX_LINE_LEN: usize = 78;
+/// The maximum length of header lines with encoded words, as defined in RFC 2047.
+const MAX_ENCODED_LINE_LEN: usize = 76;
+
+fn last_line_len(out: &BString) -> usize {
+    out.len() - out.rfind_byte(b'\n').map_or(0, |pos| pos + 1)
+}
+
+/// Append `text` to `out` and continue it on the next line whenever a word wouldn't fit into the current one anymore.
+fn write_folded(out: &mut BString, text: &BStr) {
+    let mut line_len = last_line_len(out);
+    for (index, word) in text.split_str(" ").enumerate() {
+        let separator_len = usize::from(index != 0);
+        let word_len = word.chars().count();
+        if line_len + separator_len + word_len > MAX_LINE_LEN {
+            out.push_str("\n ");
+            line_len = 1;
+        } else if separator_len != 0 {
+            out.push_byte(b' ');
+            line_len += 1;
+        }
+        out.push_str(word);
+        line_len += word_len;
+    }
+}
+
+/// Return true if `text` can't be used in a header as is.
+fn needs_rfc2047_encoding(text: &BStr) -> bool {
+    !text.is_ascii() || text.contains(&b'\n') || text.contains_str("=?")
+}
+
+/// Append `text` to `out` as encoded words, i.e. `=?UTF-8?q?<text>?=`, whose characters are encoded as needed for an
+/// address if `is_address` is true, or for unstructured text otherwise.
+fn write_rfc2047(out: &mut BString, text: &BStr, is_address: bool) {
+    const START: &str = "=?UTF-8?q?";
+    let is_special = |b: u8| {
+        // this includes whitespace and non-ASCII bytes.
+        if !b.is_ascii_graphic() || b"=?_".contains(&b) {
+            return true;
+        }
+        is_address && !(b.is_ascii_alphanumeric() || b"!\"#*+-/".contains(&b))
+    };
+    out.push_str(START);
+    let mut line_len = last_line_len(out);
+    for (start, end, _) in text.char_indices() {
+        let ch = &text[start..end];
+        let is_special = ch.len() > 1 || is_special(ch[0]);
+        let encoded_len = if is_special { 3 * ch.len() } else { 1 };
+        if line_len + encoded_len + 2 > MAX_ENCODED_LINE_LEN {
+            out.push_str("?=\n ");
+            out.push_str(START);
+            line_len = START.len() + 1;
+        }
+        if is_special {
+            for b in ch.iter() {
+                out.push_str(format!("={:02X}", b));
+            }
+        } else {
+            out.push_str(ch);
+        }
+        line_len += encoded_len;
+    }
+    out.push_str("?=");
+}
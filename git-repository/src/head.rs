@@ -109,7 +109,8 @@ pub mod peel {
     mod error {
         use crate::{object, reference};
 
-        /// The error returned by [Head::peel_to_id_in_place()][super::Head::peel_to_id_in_place()] and [Head::into_fully_peeled_id()][super::Head::into_fully_peeled_id()].
+        /// The error returned by [Head::peel_to_id_in_place()][super::Head::peel_to_id_in_place()], [Head::peel_to_kind_in_place()][super::Head::peel_to_kind_in_place()]
+        /// and [Head::into_fully_peeled_id()][super::Head::into_fully_peeled_id()].
         #[derive(Debug, thiserror::Error)]
         #[allow(missing_docs)]
         pub enum Error {
@@ -117,6 +118,8 @@ pub mod peel {
             FindExistingObject(#[from] object::find::existing::OdbError),
             #[error(transparent)]
             PeelReference(#[from] reference::peel::Error),
+            #[error(transparent)]
+            PeelObject(#[from] object::peel::to_kind::Error),
         }
     }
     pub use error::Error;
@@ -150,11 +153,19 @@ pub mod peel {
 
         // TODO: tests
         /// Follow the symbolic reference of this head until its target object and peel it by following tag objects until there is no
-        /// more object to follow, and return that object id.
+        /// more object to follow, and return that object id, which must be a commit like for all valid `HEAD` references.
         ///
         /// Returns `None` if the head is unborn.
         pub fn peel_to_id_in_place(&mut self) -> Option<Result<crate::Id<'repo>, Error>> {
-            Some(match &mut self.kind {
+            self.peel_to_kind_in_place(git_object::Kind::Commit)
+        }
+
+        /// Follow the symbolic reference of this head until its target object and peel it by following tag objects and
+        /// commits to their trees until an object of the given `kind` is encountered, and return its id.
+        ///
+        /// Returns `None` if the head is unborn.
+        pub fn peel_to_kind_in_place(&mut self, kind: git_object::Kind) -> Option<Result<crate::Id<'repo>, Error>> {
+            let peeled: Result<crate::Id<'repo>, Error> = match &mut self.kind {
                 Kind::Unborn(_name) => return None,
                 Kind::Detached {
                     peeled: Some(peeled), ..
@@ -183,7 +194,9 @@ pub mod peel {
                     *r = nr.detach();
                     peeled
                 }
-            })
+            };
+            let repo = self.repo;
+            Some(peeled.and_then(|id| Ok(id.object()?.peel_to_kind(kind)?.id.attach(repo))))
         }
 
         // TODO: tests
@@ -193,12 +206,12 @@ pub mod peel {
         ///
         /// Returns an error if the head is unborn or if it doesn't point to a commit.
         pub fn peel_to_commit_in_place(&mut self) -> Result<crate::Commit<'repo>, to_commit::Error> {
-            let id = self.peel_to_id_in_place().ok_or_else(|| to_commit::Error::Unborn {
-                name: self.referent_name().expect("unborn").to_owned(),
-            })??;
-            id.object()
-                .map_err(|err| to_commit::Error::Peel(Error::FindExistingObject(err)))
-                .and_then(|object| object.try_into_commit().map_err(Into::into))
+            let id =
+                self.peel_to_kind_in_place(git_object::Kind::Commit)
+                    .ok_or_else(|| to_commit::Error::Unborn {
+                        name: self.referent_name().expect("unborn").to_owned(),
+                    })??;
+            Ok(id.object().map_err(Error::from)?.into_commit())
         }
 
         /// Consume this instance and transform it into the final object that it points to, or `None` if the `HEAD`
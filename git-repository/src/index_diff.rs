@@ -0,0 +1,382 @@
+//! Compare the index with the working tree or with a tree.
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use git_hash::ObjectId;
+use git_index::entry::{Flags, Mode};
+use git_object::bstr::{BStr, BString, ByteSlice};
+use git_odb::FindExt;
+
+/// A difference between an entry in the index and its counterpart.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// The entry at `path` doesn't exist on the side compared to.
+    Added {
+        /// The path of the entry, relative to the root of the working tree.
+        path: BString,
+        /// The mode of the new entry.
+        mode: Mode,
+        /// The id of the new entry's content.
+        id: ObjectId,
+    },
+    /// The entry at `path` was removed.
+    Deleted {
+        /// The path of the entry, relative to the root of the working tree.
+        path: BString,
+        /// The mode of the removed entry.
+        mode: Mode,
+        /// The id of the removed entry's content.
+        id: ObjectId,
+    },
+    /// The entry at `path` exists on both sides, but its content or mode differs.
+    Modified {
+        /// The path of the entry, relative to the root of the working tree.
+        path: BString,
+        /// The mode before the change.
+        previous_mode: Mode,
+        /// The id of the content before the change.
+        previous_id: ObjectId,
+        /// The mode after the change.
+        mode: Mode,
+        /// The id of the content after the change.
+        id: ObjectId,
+    },
+}
+
+impl Change {
+    /// Return the path of the changed entry.
+    pub fn path(&self) -> &BStr {
+        match self {
+            Change::Added { path, .. } | Change::Deleted { path, .. } | Change::Modified { path, .. } => path.as_ref(),
+        }
+    }
+}
+
+///
+pub mod to_workdir {
+    use std::path::PathBuf;
+
+    /// Options for use in [`Repository::diff_index_to_workdir()`][crate::Repository::diff_index_to_workdir()].
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Options {
+        /// If true, files that aren't tracked by the index and aren't excluded are reported as [added][super::Change::Added].
+        pub include_untracked: bool,
+    }
+
+    /// The error returned by [`Repository::diff_index_to_workdir()`][crate::Repository::diff_index_to_workdir()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A working tree is required to compare the index with")]
+        MissingWorkTree,
+        #[error("Could not read '{}' in the working tree", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+        #[error(transparent)]
+        Excludes(#[from] crate::worktree::excludes::Error),
+        #[error("The value of configuration key '{key}' could not be interpreted: {value:?}")]
+        InvalidConfig { key: &'static str, value: BString },
+    }
+}
+
+///
+pub mod from_tree {
+    use crate::object;
+
+    /// The error returned by [`Repository::diff_tree_to_index()`][crate::Repository::diff_tree_to_index()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindExistingObject(#[from] object::find::existing::OdbError),
+        #[error(transparent)]
+        PeelToTree(#[from] object::peel::to_kind::Error),
+        #[error(transparent)]
+        Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+    }
+}
+
+impl crate::Repository {
+    /// Compare all files reachable from `tree`, which may also be a commit, with the entries of `index`, similar to
+    /// `git diff --cached`, and return all changes sorted by path as seen from `tree`.
+    ///
+    /// Paths with unmerged entries in the index are not considered.
+    pub fn diff_tree_to_index(
+        &self,
+        tree: crate::Id<'_>,
+        index: &git_index::State,
+    ) -> Result<Vec<Change>, from_tree::Error> {
+        let files = self
+            .find_object(tree.detach())?
+            .peel_to_kind(git_object::Kind::Tree)?
+            .into_tree()
+            .traverse()
+            .breadthfirst
+            .files()?;
+        let mut tree_entries: BTreeMap<_, _> = files
+            .into_iter()
+            .filter(|entry| entry.mode.is_no_tree())
+            .map(|entry| (entry.filepath, (Mode::from_bits_truncate(entry.mode as u32), entry.oid)))
+            .collect();
+
+        let mut changes = Vec::new();
+        let unmerged: HashSet<_> = index
+            .entries()
+            .iter()
+            .filter(|entry| entry.stage() != 0)
+            .map(|entry| entry.path(index))
+            .collect();
+        for entry in index.entries().iter().filter(|entry| entry.stage() == 0) {
+            let path = entry.path(index);
+            match tree_entries.remove(path) {
+                Some((previous_mode, previous_id)) => {
+                    if previous_mode != entry.mode || previous_id != entry.id {
+                        changes.push(Change::Modified {
+                            path: path.to_owned(),
+                            previous_mode,
+                            previous_id,
+                            mode: entry.mode,
+                            id: entry.id,
+                        });
+                    }
+                }
+                None => changes.push(Change::Added {
+                    path: path.to_owned(),
+                    mode: entry.mode,
+                    id: entry.id,
+                }),
+            }
+        }
+        changes.extend(
+            tree_entries
+                .into_iter()
+                .filter(|(path, _)| !unmerged.contains(path.as_bstr()))
+                .map(|(path, (mode, id))| Change::Deleted { path, mode, id }),
+        );
+        changes.sort_by(|a, b| a.path().cmp(b.path()));
+        Ok(changes)
+    }
+
+    /// Compare all entries of the `index` with the files in the working tree, similar to `git diff`, and return all changes
+    /// sorted by path.
+    ///
+    /// The content of files is only hashed if their mode or `stat` information differs from what's recorded in the index.
+    /// Entries marked as `assume-unchanged` or `skip-worktree`, unmerged entries and submodules are not considered.
+    /// Untracked files are listed individually if [`to_workdir::Options::include_untracked`] is set.
+    /// Changes to the executable bit of files are ignored if `core.fileMode` is `false`, and paths that can't be accessed
+    /// are reported as [deleted][Change::Deleted].
+    pub fn diff_index_to_workdir(
+        &self,
+        index: &git_index::State,
+        opts: to_workdir::Options,
+    ) -> Result<Vec<Change>, to_workdir::Error> {
+        use to_workdir::Error;
+        let worktree = self.worktree().ok_or(Error::MissingWorkTree)?;
+        let work_dir = self.work_dir().expect("present if there is a worktree");
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+        let trust_filemode = self
+            .config
+            .resolved
+            .boolean("core", None, "fileMode")
+            .transpose()
+            .map_err(|err| Error::InvalidConfig {
+                key: "core.fileMode",
+                value: err.input,
+            })?
+            .unwrap_or(true);
+
+        let mut changes = Vec::new();
+        for entry in index.entries() {
+            if entry.stage() != 0
+                || entry.flags.intersects(Flags::ASSUME_VALID | Flags::SKIP_WORKTREE)
+                || entry.mode == Mode::COMMIT
+            {
+                continue;
+            }
+            let path = entry.path(index);
+            let file_path = work_dir.join(git_path::from_bstr(path));
+            // like `git`, anything that can't be stat'ed, for instance because a leading path component became a file,
+            // counts as deleted.
+            let metadata = match std::fs::symlink_metadata(&file_path) {
+                Ok(metadata) if !metadata.is_dir() => Some(metadata),
+                Ok(_) | Err(_) => None,
+            };
+            let metadata = match metadata {
+                Some(metadata) => metadata,
+                None => {
+                    changes.push(Change::Deleted {
+                        path: path.to_owned(),
+                        mode: entry.mode,
+                        id: entry.id,
+                    });
+                    continue;
+                }
+            };
+            let mode = match mode_of(&metadata, entry.mode) {
+                Mode::FILE | Mode::FILE_EXECUTABLE
+                    if !trust_filemode && matches!(entry.mode, Mode::FILE | Mode::FILE_EXECUTABLE) =>
+                {
+                    entry.mode
+                }
+                mode => mode,
+            };
+            if mode == entry.mode && stat_matches(&entry.stat, &metadata) {
+                continue;
+            }
+            let id = hash_file(&file_path, &metadata, self.object_hash()).map_err(io_err(&file_path))?;
+            if id != entry.id || mode != entry.mode {
+                changes.push(Change::Modified {
+                    path: path.to_owned(),
+                    previous_mode: entry.mode,
+                    previous_id: entry.id,
+                    mode,
+                    id,
+                });
+            }
+        }
+
+        if opts.include_untracked {
+            let tracked: HashSet<_> = index.entries().iter().map(|entry| entry.path(index)).collect();
+            let mut excludes = worktree.excludes(index, None)?;
+            let mut directories = vec![PathBuf::new()];
+            while let Some(directory) = directories.pop() {
+                let abs_directory = work_dir.join(&directory);
+                for dir_entry in std::fs::read_dir(&abs_directory).map_err(io_err(&abs_directory))? {
+                    let dir_entry = dir_entry.map_err(io_err(&abs_directory))?;
+                    if dir_entry.file_name() == ".git" {
+                        continue;
+                    }
+                    let rela_path = directory.join(dir_entry.file_name());
+                    let file_path = dir_entry.path();
+                    let metadata = std::fs::symlink_metadata(&file_path).map_err(io_err(&file_path))?;
+                    let is_dir = metadata.is_dir();
+                    if is_dir && file_path.join(".git").exists() {
+                        continue;
+                    }
+                    let path = match git_path::try_into_bstr(rela_path.as_path()) {
+                        Ok(path) => git_path::to_unix_separators_on_windows(path).into_owned(),
+                        Err(_) => continue,
+                    };
+                    let is_excluded = excludes
+                        .at_entry(path.as_bstr(), Some(is_dir), |oid, buf| {
+                            self.objects.find_blob(oid, buf)
+                        })
+                        .map_err(io_err(&file_path))?
+                        .is_excluded();
+                    if is_excluded || tracked.contains(path.as_bstr()) {
+                        continue;
+                    }
+                    if is_dir {
+                        directories.push(rela_path);
+                    } else {
+                        let id = hash_file(&file_path, &metadata, self.object_hash()).map_err(io_err(&file_path))?;
+                        changes.push(Change::Added {
+                            path,
+                            mode: mode_of(&metadata, Mode::FILE),
+                            id,
+                        });
+                    }
+                }
+            }
+        }
+        changes.sort_by(|a, b| a.path().cmp(b.path()));
+        Ok(changes)
+    }
+}
+
+/// Hash the file or symlink at `path` as blob without writing it to the object database.
+pub(crate) fn hash_file(
+    path: &Path,
+    metadata: &std::fs::Metadata,
+    object_hash: git_hash::Kind,
+) -> std::io::Result<ObjectId> {
+    use git_odb::Write;
+    let data = if metadata.file_type().is_symlink() {
+        git_path::into_bstr(std::fs::read_link(path)?).into_owned().into()
+    } else {
+        std::fs::read(path)?
+    };
+    git_odb::sink(object_hash).write_buf(git_object::Kind::Blob, &data)
+}
+
+/// Determine the mode of a file in the working tree, falling back to `index_mode` where the filesystem can't tell.
+#[cfg_attr(unix, allow(unused_variables))]
+pub(crate) fn mode_of(metadata: &std::fs::Metadata, index_mode: Mode) -> Mode {
+    if metadata.file_type().is_symlink() {
+        return Mode::SYMLINK;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o100 != 0 {
+            Mode::FILE_EXECUTABLE
+        } else {
+            Mode::FILE
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        if index_mode == Mode::FILE_EXECUTABLE {
+            index_mode
+        } else {
+            Mode::FILE
+        }
+    }
+}
+
+/// Return true if the size and modification time of the file described by `metadata` are the same as recorded in `stat`.
+fn stat_matches(stat: &git_index::entry::Stat, metadata: &std::fs::Metadata) -> bool {
+    let mtime = match metadata
+        .modified()
+        .ok()
+        .and_then(|mtime| mtime.duration_since(std::time::UNIX_EPOCH).ok())
+    {
+        Some(mtime) => mtime,
+        None => return false,
+    };
+    stat.size as u64 == metadata.len()
+        && stat.mtime.secs as u64 == mtime.as_secs()
+        && stat.mtime.nsecs == mtime.subsec_nanos()
+}
+
+/// Return the stat information of the file described by `metadata` as it's recorded in the index.
+pub(crate) fn stat_of(metadata: &std::fs::Metadata) -> git_index::entry::Stat {
+    use git_index::entry::Time;
+    let time = |time: std::io::Result<std::time::SystemTime>| {
+        time.ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(Time { secs: 0, nsecs: 0 }, |time| Time {
+                secs: time.as_secs() as u32,
+                nsecs: time.subsec_nanos(),
+            })
+    };
+    #[cfg(unix)]
+    let (ctime, dev, ino, uid, gid) = {
+        use std::os::unix::fs::MetadataExt;
+        (
+            Time {
+                secs: metadata.ctime() as u32,
+                nsecs: metadata.ctime_nsec() as u32,
+            },
+            metadata.dev() as u32,
+            metadata.ino() as u32,
+            metadata.uid(),
+            metadata.gid(),
+        )
+    };
+    #[cfg(not(unix))]
+    let (ctime, dev, ino, uid, gid) = (time(metadata.created()), 0, 0, 0, 0);
+    git_index::entry::Stat {
+        mtime: time(metadata.modified()),
+        ctime,
+        dev,
+        ino,
+        uid,
+        gid,
+        size: metadata.len() as u32,
+    }
+}
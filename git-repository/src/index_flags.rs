@@ -0,0 +1,60 @@
+//! Set the `assume-unchanged` and `skip-worktree` flags of index entries.
+use std::path::Path;
+
+use git_index::entry::Flags;
+use git_object::bstr::{BString, ByteSlice};
+
+/// The error returned by [`Repository::assume_unchanged()`][crate::Repository::assume_unchanged()] and
+/// [`Repository::skip_worktree()`][crate::Repository::skip_worktree()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to change flags of index entries")]
+    MissingWorkTree,
+    #[error("The path '{path}' isn't tracked by the index or has unresolved conflicts")]
+    NotInIndex { path: BString },
+    #[error(transparent)]
+    PathTraversal(#[from] git_sec::permission::TraversalError),
+    #[error("Could not lock the index")]
+    LockIndex(#[from] git_lock::acquire::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] crate::worktree::write_index::Error),
+}
+
+impl crate::Repository {
+    /// Set the `assume-unchanged` flag of the index entry at `path` if `value` is true or clear it otherwise, similar to
+    /// `git update-index --[no-]assume-unchanged <path>`, to let changes to the file in the working tree go unnoticed.
+    ///
+    /// `path` is relative to the root of the working tree. The index is locked while it is read and changed.
+    pub fn assume_unchanged(&self, path: &Path, value: bool) -> Result<(), Error> {
+        self.set_index_entry_flag(path, Flags::ASSUME_VALID, value)
+    }
+
+    /// Set the `skip-worktree` flag of the index entry at `path` if `value` is true or clear it otherwise, similar to
+    /// `git update-index --[no-]skip-worktree <path>`, to ignore the file in the working tree as used by sparse checkouts.
+    ///
+    /// `path` is relative to the root of the working tree. The index is locked while it is read and changed, and
+    /// it's written in version 3 at least if any entry has this flag set.
+    pub fn skip_worktree(&self, path: &Path, value: bool) -> Result<(), Error> {
+        self.set_index_entry_flag(path, Flags::SKIP_WORKTREE, value)
+    }
+
+    fn set_index_entry_flag(&self, path: &Path, flag: Flags, value: bool) -> Result<(), Error> {
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        git_sec::permission::check_path_traversal(work_dir, path)?;
+        let rela_path = git_path::to_unix_separators(git_path::into_bstr(path)).into_owned();
+
+        let lock = self.lock_index()?;
+        let mut index = self.open_index()?;
+        let entry = index
+            .entry_mut_by_path(rela_path.as_bstr(), 0)
+            .ok_or_else(|| Error::NotInIndex {
+                path: rela_path.clone(),
+            })?;
+        entry.flags.set(flag, value);
+        self.write_index_to_lock(&index, lock)?;
+        Ok(())
+    }
+}
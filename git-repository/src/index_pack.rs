@@ -0,0 +1,63 @@
+//! Create an index for a pack that lacks one, similar to `git index-pack`.
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use git_odb::pack;
+use git_tempfile::{handle::Writable, AutoRemove, ContainingDirectory};
+
+use crate::Progress;
+
+/// The error returned by [`Repository::index_pack()`][crate::Repository::index_pack()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    OpenPack(#[from] pack::data::header::decode::Error),
+    #[error("Could not read the entries of the pack")]
+    Iter(#[from] pack::data::input::Error),
+    #[error("Could not create the temporary index file")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] pack::index::write::Error),
+    #[error("Could not move the index into place")]
+    Persist(#[from] git_tempfile::handle::persist::Error<Writable>),
+}
+
+impl crate::Repository {
+    /// Create an index file for the pack at `pack_path` and place it next to the pack with the `.idx` extension, similar to
+    /// `git index-pack <pack_path>`, and return the path to the index.
+    ///
+    /// All entries of the pack are read and resolved to compute their object ids, which are written into a version 2 index along
+    /// with their offsets and CRC32 checksums. If the pack is located in the object database of this repository, its objects
+    /// can be looked up right away as the object database picks up new packs when objects can't be found.
+    pub fn index_pack(&self, pack_path: &Path, progress: impl Progress) -> Result<PathBuf, Error> {
+        let pack = Arc::new(pack::data::File::at(pack_path, self.object_hash())?);
+        let entries = pack.streaming_iter()?;
+
+        let index_path = pack_path.with_extension("idx");
+        let directory = index_path.parent().filter(|dir| !dir.as_os_str().is_empty());
+        let mut index_file = git_tempfile::new(
+            directory.unwrap_or_else(|| Path::new(".")),
+            ContainingDirectory::Exists,
+            AutoRemove::Tempfile,
+        )?;
+        pack::index::File::write_data_iter_to_stream(
+            pack::index::Version::default(),
+            move || {
+                Ok(move |range: pack::data::EntryRange, out: &mut Vec<u8>| {
+                    pack.entry_slice(range).map(|entry| out.copy_from_slice(entry))
+                })
+            },
+            entries,
+            None,
+            progress,
+            &mut index_file,
+            &crate::interrupt::IS_INTERRUPTED,
+            self.object_hash(),
+        )?;
+        index_file.persist(&index_path)?;
+        Ok(index_path)
+    }
+}
@@ -149,6 +149,13 @@ where
 /// The flag behind all utility functions in this module.
 pub static IS_INTERRUPTED: AtomicBool = AtomicBool::new(false);
 
+pub use git_features::interrupt::CancellationToken;
+
+/// Return the flag set once `token` is cancelled, or the process-global [`IS_INTERRUPTED`] flag if there is no token.
+pub(crate) fn flag(token: Option<&CancellationToken>) -> &AtomicBool {
+    token.map_or(&IS_INTERRUPTED, CancellationToken::should_interrupt)
+}
+
 /// Returns true if an interrupt is requested.
 pub fn is_triggered() -> bool {
     IS_INTERRUPTED.load(Ordering::Relaxed)
@@ -183,18 +183,76 @@ pub type OdbHandle = git_odb::Handle;
 pub(crate) type Config = OwnShared<git_config::File<'static>>;
 
 ///
+mod date;
 mod types;
 pub use types::{
-    Commit, DetachedObject, Head, Id, Object, Reference, Repository, Tag, ThreadSafeRepository, Tree, Worktree,
+    Commit, ConfigSnapshot, DetachedObject, Head, Id, Object, Reference, Repository, Tag, ThreadSafeRepository, Tree,
+    Worktree,
 };
 
+pub mod ahead_behind;
+#[cfg(feature = "git-index")]
+pub mod apply;
+///
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "git-attributes")]
+pub mod attributes;
+pub mod blob_diff;
+pub mod bundle;
+#[cfg(feature = "git-index")]
+pub mod checkout_branch;
+#[cfg(all(feature = "blocking-network-client", feature = "git-index"))]
+pub mod clone;
 pub mod commit;
+pub mod commit_format;
+#[cfg(feature = "git-commitgraph")]
+pub mod commit_graph;
+pub mod conflict_file;
+#[cfg(feature = "git-attributes")]
+pub mod crlf;
+pub mod fast_export;
+pub mod fast_import;
+#[cfg(feature = "blocking-network-client")]
+pub mod fetch;
+pub mod fetch_head;
+#[cfg(feature = "git-attributes")]
+pub mod filter;
+pub mod format_patch;
 pub mod head;
 pub mod id;
+#[cfg(feature = "git-index")]
+pub mod index_diff;
+#[cfg(feature = "git-index")]
+pub mod index_flags;
+pub mod index_pack;
+pub mod maintenance;
+#[cfg(feature = "git-index")]
+pub mod mark_resolved;
+pub mod merge_base;
+#[cfg(feature = "git-attributes")]
+pub mod merge_strategy;
+pub mod notes;
 pub mod object;
+pub mod pack_objects;
+#[cfg(feature = "git-glob")]
+pub mod pathspec;
+#[cfg(feature = "blocking-network-client")]
+pub mod promisor;
+#[cfg(feature = "blocking-network-client")]
+pub mod push;
 pub mod reference;
+pub mod reflog_expire;
+pub mod remote;
+pub mod repack;
 mod repository;
+#[cfg(feature = "git-index")]
+pub mod restore;
+pub mod sign;
 pub mod tag;
+pub mod topo;
+#[cfg(feature = "git-index")]
+pub mod untracked;
 
 /// The kind of repository path.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -374,6 +432,22 @@ pub mod state {
         /// A revert operation with multiple commits pending.
         RevertSequence,
     }
+
+    ///
+    pub mod merge_heads {
+        /// The error returned by [`Repository::merge_heads()`][crate::Repository::merge_heads()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error("Could not read the MERGE_HEAD file")]
+            Io(#[from] std::io::Error),
+            #[error("Line {line_number} of the MERGE_HEAD file isn't a valid object id")]
+            Decode {
+                line_number: usize,
+                source: git_hash::decode::Error,
+            },
+        }
+    }
 }
 
 ///
@@ -0,0 +1,187 @@
+//! Write a commit-graph file with all commits reachable from references.
+use std::{collections::HashMap, io::Write};
+
+use git_hash::ObjectId;
+use git_odb::FindExt;
+
+use super::Error;
+
+const SIGNATURE: &[u8] = b"CGPH";
+const HEADER_LEN: usize = 8;
+const CHUNK_TABLE_ENTRY_LEN: usize = 12;
+const FAN_LEN: usize = 256;
+
+const NO_PARENT: u32 = 0x7000_0000;
+const EXTENDED_EDGES_MASK: u32 = 0x8000_0000;
+const LAST_EXTENDED_EDGE_MASK: u32 = 0x8000_0000;
+/// The largest commit time that can be stored, as 34 bits are available for it.
+const MAX_COMMIT_TIME: u64 = (1 << 34) - 1;
+
+struct Commit {
+    tree: ObjectId,
+    parents: Vec<ObjectId>,
+    time: u64,
+    /// The topological level of the commit, or 0 if it wasn't computed yet.
+    generation: u32,
+}
+
+impl crate::Repository {
+    /// Write `objects/info/commit-graph` with all commits reachable from references and return the amount of commits in it.
+    ///
+    /// Nothing is written in shallow repositories, as their commit-graph would lack parents, just like `git` does.
+    pub(super) fn write_commit_graph(&self) -> Result<usize, Error> {
+        if self.git_dir().join("shallow").is_file() {
+            return Ok(0);
+        }
+
+        let mut commits = HashMap::<ObjectId, Commit>::new();
+        let mut queue = self.commit_tips()?;
+        let mut buf = Vec::new();
+        while let Some(id) = queue.pop() {
+            if commits.contains_key(&id) {
+                continue;
+            }
+            let mut commit = self.objects.find_commit_iter(&id, &mut buf)?;
+            let tree = commit.tree_id()?;
+            let parents: Vec<_> = commit.parent_ids().collect();
+            let time = u64::from(commit.committer()?.time.seconds_since_unix_epoch);
+            queue.extend(parents.iter().filter(|parent| !commits.contains_key(*parent)));
+            commits.insert(
+                id,
+                Commit {
+                    tree,
+                    parents,
+                    time,
+                    generation: 0,
+                },
+            );
+        }
+        compute_generations(&mut commits);
+
+        let mut ids: Vec<_> = commits.keys().copied().collect();
+        ids.sort();
+        let positions: HashMap<_, _> = ids.iter().enumerate().map(|(pos, id)| (*id, pos as u32)).collect();
+        let mut commit_data = Vec::with_capacity(ids.len() * (self.object_hash().len_in_bytes() + 16));
+        let mut extra_edges = Vec::<u32>::new();
+        for id in &ids {
+            let commit = &commits[id];
+            let parent = |nth: usize| commit.parents.get(nth).map_or(NO_PARENT, |parent| positions[parent]);
+            let second_parent = if commit.parents.len() > 2 {
+                let first_edge = extra_edges.len() as u32;
+                extra_edges.extend(commit.parents[1..].iter().map(|parent| positions[parent]));
+                *extra_edges.last_mut().expect("more than one edge") |= LAST_EXTENDED_EDGE_MASK;
+                EXTENDED_EDGES_MASK | first_edge
+            } else {
+                parent(1)
+            };
+            let time = commit.time.min(MAX_COMMIT_TIME);
+            commit_data.extend_from_slice(commit.tree.as_slice());
+            commit_data.extend_from_slice(&parent(0).to_be_bytes());
+            commit_data.extend_from_slice(&second_parent.to_be_bytes());
+            commit_data.extend_from_slice(&((commit.generation << 2) | (time >> 32) as u32).to_be_bytes());
+            commit_data.extend_from_slice(&(time as u32).to_be_bytes());
+        }
+
+        let mut fan = [0u32; FAN_LEN];
+        for id in &ids {
+            fan[usize::from(id.first_byte())] += 1;
+        }
+        let mut oid_fan = Vec::with_capacity(FAN_LEN * 4);
+        let mut total = 0;
+        for count in fan {
+            total += count;
+            oid_fan.extend_from_slice(&total.to_be_bytes());
+        }
+        let oid_lookup: Vec<u8> = ids.iter().flat_map(|id| id.as_slice().iter().copied()).collect();
+        let extra_edges: Vec<u8> = extra_edges.iter().flat_map(|edge| edge.to_be_bytes()).collect();
+
+        let mut chunks = vec![(*b"OIDF", oid_fan), (*b"OIDL", oid_lookup), (*b"CDAT", commit_data)];
+        if !extra_edges.is_empty() {
+            chunks.push((*b"EDGE", extra_edges));
+        }
+
+        let info_dir = self.objects.store_ref().path().join("info");
+        std::fs::create_dir_all(&info_dir)?;
+        let mut lock = git_lock::File::acquire_to_update_resource(
+            info_dir.join("commit-graph"),
+            git_lock::acquire::Fail::Immediately,
+            None,
+        )?;
+        let mut out = git_features::hash::Write::new(&mut lock, self.object_hash());
+        out.write_all(SIGNATURE)?;
+        out.write_all(&[1, self.object_hash() as u8, chunks.len() as u8, 0])?;
+        let mut offset = (HEADER_LEN + (chunks.len() + 1) * CHUNK_TABLE_ENTRY_LEN) as u64;
+        for (id, data) in &chunks {
+            out.write_all(id)?;
+            out.write_all(&offset.to_be_bytes())?;
+            offset += data.len() as u64;
+        }
+        out.write_all(&[0; 4])?;
+        out.write_all(&offset.to_be_bytes())?;
+        for (_, data) in &chunks {
+            out.write_all(data)?;
+        }
+        let checksum: ObjectId = out.hash.digest().into();
+        lock.write_all(checksum.as_slice())?;
+        lock.commit().map_err(|err| err.error)?;
+        Ok(ids.len())
+    }
+
+    /// Return the commits all references point to, after peeling them.
+    fn commit_tips(&self) -> Result<Vec<ObjectId>, Error> {
+        let mut tips = Vec::new();
+        let platform = self.references()?;
+        for r in platform.all()?.peeled() {
+            let r = r.map_err(Error::Reference)?.detach();
+            if let Some(tip) = r.peeled.or_else(|| r.target.try_id().map(ToOwned::to_owned)) {
+                if self.find_object(tip)?.kind == git_object::Kind::Commit {
+                    tips.push(tip);
+                }
+            }
+        }
+        tips.sort();
+        tips.dedup();
+        Ok(tips)
+    }
+}
+
+/// The greatest generation number that can be written, the same as `git_commitgraph::GENERATION_NUMBER_MAX`
+/// which isn't available if the commit-graph isn't read.
+const GENERATION_NUMBER_MAX: u32 = 0x3fff_ffff;
+
+/// Set the generation of each commit to its topological level, which is one greater than the greatest level of its parents,
+/// capped at [`GENERATION_NUMBER_MAX`].
+fn compute_generations(commits: &mut HashMap<ObjectId, Commit>) {
+    let ids: Vec<_> = commits.keys().copied().collect();
+    let mut stack = Vec::new();
+    for id in ids {
+        stack.push(id);
+        while let Some(id) = stack.last().copied() {
+            let commit = &commits[&id];
+            if commit.generation != 0 {
+                stack.pop();
+                continue;
+            }
+            let pending: Vec<_> = commit
+                .parents
+                .iter()
+                .filter(|parent| commits[*parent].generation == 0)
+                .copied()
+                .collect();
+            if pending.is_empty() {
+                let generation = commit
+                    .parents
+                    .iter()
+                    .map(|parent| commits[parent].generation)
+                    .max()
+                    .unwrap_or(0)
+                    .saturating_add(1)
+                    .min(GENERATION_NUMBER_MAX);
+                commits.get_mut(&id).expect("present").generation = generation;
+                stack.pop();
+            } else {
+                stack.extend(pending);
+            }
+        }
+    }
+}
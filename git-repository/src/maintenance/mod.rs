@@ -0,0 +1,408 @@
+//! Run housekeeping tasks to keep the repository fast, similar to `git maintenance run`.
+use git_object::bstr::BString;
+use git_odb::pack::Find;
+use git_ref::{
+    file::transaction::PackedRefs,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{index_pack, pack_objects, progress, reference};
+
+mod commit_graph;
+
+/// A task to run with [`Repository::maintenance_run()`][crate::Repository::maintenance_run()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Task {
+    /// Write a commit-graph file with all commits reachable from references, like the `commit-graph` task.
+    CommitGraph,
+    /// Move all loose references into the `packed-refs` file, like the `pack-refs` task.
+    PackRefs,
+    /// Fetch from all remotes into references below `refs/prefetch/` without changing any remote tracking references,
+    /// like the `prefetch` task.
+    ///
+    /// Running it fails with [`Error::PrefetchUnsupported`] if the `blocking-network-client` feature is disabled,
+    /// in which case it only runs if it is passed explicitly.
+    Prefetch,
+    /// Write all loose objects into a new pack and remove them, like the `loose-objects` task.
+    LooseObjects,
+    /// Consolidate small packs like the `incremental-repack` task, leaving the largest pack, typically the one received
+    /// when cloning, untouched.
+    ///
+    /// Note that as multi-pack indices can't be written yet, all packs not larger than the second-largest pack, but at most
+    /// 2GB in size, are consolidated into one with [`Repository::repack()`][crate::Repository::repack()].
+    IncrementalRepack,
+}
+
+impl Task {
+    /// Return the name of the task as used in `git maintenance run --task=<name>` and in `maintenance.<name>.*` configuration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Task::CommitGraph => "commit-graph",
+            Task::PackRefs => "pack-refs",
+            Task::Prefetch => "prefetch",
+            Task::LooseObjects => "loose-objects",
+            Task::IncrementalRepack => "incremental-repack",
+        }
+    }
+
+    /// All tasks in the order `git` runs them.
+    fn all() -> Vec<Task> {
+        let mut tasks = Vec::new();
+        #[cfg(feature = "blocking-network-client")]
+        tasks.push(Task::Prefetch);
+        tasks.extend([
+            Task::LooseObjects,
+            Task::IncrementalRepack,
+            Task::CommitGraph,
+            Task::PackRefs,
+        ]);
+        tasks
+    }
+
+    /// The schedule of this task if `maintenance.strategy` is `incremental`, or `None` if it isn't part of this strategy.
+    fn incremental_schedule(&self) -> Option<Schedule> {
+        match self {
+            Task::Prefetch | Task::CommitGraph => Some(Schedule::Hourly),
+            Task::LooseObjects | Task::IncrementalRepack => Some(Schedule::Daily),
+            Task::PackRefs => None,
+        }
+    }
+}
+
+/// How often a task is meant to run as configured with `maintenance.<task>.schedule`, for use in
+/// [`Repository::maintenance_run_scheduled()`][crate::Repository::maintenance_run_scheduled()].
+///
+/// Schedules are ordered by frequency, with [`Schedule::Hourly`] being the greatest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Schedule {
+    /// Run once a week.
+    Weekly,
+    /// Run once a day.
+    Daily,
+    /// Run once an hour.
+    Hourly,
+}
+
+/// The largest pack considered by [`Task::IncrementalRepack`], like the default batch size of `git`.
+const MAX_INCREMENTAL_REPACK_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Statistics about the work done by [`Repository::maintenance_run()`][crate::Repository::maintenance_run()].
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The tasks that were run, in order.
+    pub tasks: Vec<Task>,
+    /// The amount of commits written into the commit-graph.
+    pub commit_graph_commits: usize,
+    /// The amount of loose references that were moved into the `packed-refs` file.
+    pub packed_refs: usize,
+    /// The amount of references below `refs/prefetch/` that were created or updated.
+    pub prefetched_refs: usize,
+    /// The amount of loose objects that were written into a new pack and removed.
+    pub loose_objects: usize,
+    /// The amount of packs that were consolidated.
+    pub repacked_packs: usize,
+}
+
+/// The error returned by [`Repository::maintenance_run()`][crate::Repository::maintenance_run()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not obtain a lock, is another maintenance run in progress?")]
+    Lock(#[from] git_lock::acquire::Error),
+    #[error("Invalid value for '{key}' = '{value}'")]
+    InvalidConfig { key: String, value: BString },
+    #[error("Could not read or write files in the object database")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    ReferenceIterInit(#[from] reference::iter::init::Error),
+    #[error(transparent)]
+    ReferenceIter(#[from] reference::iter::Error),
+    #[error("A reference could not be read")]
+    Reference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    LooseReference(#[from] git_ref::file::iter::loose_then_packed::Error),
+    #[error(transparent)]
+    EditReferences(#[from] reference::edit::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::OdbError),
+    #[error(transparent)]
+    FindCommit(#[from] git_odb::find::existing_iter::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error("Could not list loose objects")]
+    IterLooseObjects(#[from] git_odb::loose::iter::Error),
+    #[error(transparent)]
+    PackObjects(#[from] pack_objects::Error),
+    #[error("Could not move the new pack into place")]
+    Persist(#[from] git_tempfile::handle::persist::Error<git_tempfile::handle::Writable>),
+    #[error(transparent)]
+    IndexPack(#[from] index_pack::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] git_odb::pack::index::init::Error),
+    #[error(transparent)]
+    Repack(#[from] crate::repack::Error),
+    #[cfg(feature = "blocking-network-client")]
+    #[error("Could not prefetch from remote '{remote}'")]
+    Prefetch {
+        remote: String,
+        source: Box<crate::fetch::Error>,
+    },
+    #[error("Prefetching requires the 'blocking-network-client' feature")]
+    PrefetchUnsupported,
+}
+
+impl crate::Repository {
+    /// Run the maintenance `tasks` in order, similar to `git maintenance run --task=<task>…`, while holding the
+    /// `objects/maintenance.lock` lock to prevent concurrent maintenance runs.
+    ///
+    /// If `tasks` is empty, all tasks enabled with `maintenance.<task>.enabled` or by `maintenance.strategy = incremental`
+    /// are run.
+    pub fn maintenance_run(&self, tasks: &[Task]) -> Result<Stats, Error> {
+        let _lock = self.lock_maintenance()?;
+        let tasks = if tasks.is_empty() {
+            self.enabled_maintenance_tasks(None)?
+        } else {
+            tasks.to_vec()
+        };
+        self.run_maintenance_tasks(tasks)
+    }
+
+    /// Run all enabled maintenance tasks whose schedule is at least as frequent as `schedule`, similar to
+    /// `git maintenance run --schedule=<frequency>`, while holding the `objects/maintenance.lock` lock.
+    ///
+    /// This is meant to be called by a scheduler, hourly with [`Schedule::Hourly`], daily with [`Schedule::Daily`] instead
+    /// and weekly with [`Schedule::Weekly`] instead, so that no state needs to be kept about previous runs.
+    /// The schedule of a task is set with `maintenance.<task>.schedule` to `hourly`, `daily` or `weekly`, and defaults to
+    /// the one of `maintenance.strategy = incremental`. Tasks without schedule are never run.
+    pub fn maintenance_run_scheduled(&self, schedule: Schedule) -> Result<Stats, Error> {
+        let _lock = self.lock_maintenance()?;
+        let tasks = self.enabled_maintenance_tasks(Some(schedule))?;
+        self.run_maintenance_tasks(tasks)
+    }
+
+    fn lock_maintenance(&self) -> Result<git_lock::Marker, Error> {
+        Ok(git_lock::Marker::acquire_to_hold_resource(
+            self.objects.store_ref().path().join("maintenance"),
+            git_lock::acquire::Fail::Immediately,
+            None,
+        )?)
+    }
+
+    fn run_maintenance_tasks(&self, tasks: Vec<Task>) -> Result<Stats, Error> {
+        let mut stats = Stats::default();
+        for task in tasks {
+            match task {
+                Task::CommitGraph => stats.commit_graph_commits = self.write_commit_graph()?,
+                Task::PackRefs => stats.packed_refs += self.pack_loose_references()?,
+                Task::Prefetch => stats.prefetched_refs += self.prefetch_remotes()?,
+                Task::LooseObjects => stats.loose_objects += self.pack_loose_objects()?,
+                Task::IncrementalRepack => stats.repacked_packs += self.repack_small_packs()?,
+            }
+            stats.tasks.push(task);
+        }
+        Ok(stats)
+    }
+
+    /// Return all enabled tasks, or only those at least as frequent as `min_schedule` if it is set.
+    fn enabled_maintenance_tasks(&self, min_schedule: Option<Schedule>) -> Result<Vec<Task>, Error> {
+        let config = &self.config.resolved;
+        let is_incremental = config
+            .string("maintenance", None, "strategy")
+            .map_or(false, |strategy| strategy.as_ref() == "incremental");
+        let mut enabled = Vec::new();
+        for task in Task::all() {
+            let name = task.as_str();
+            let strategy_schedule = is_incremental.then(|| task.incremental_schedule()).flatten();
+            let is_enabled = config
+                .boolean("maintenance", Some(name), "enabled")
+                .transpose()
+                .map_err(|err| Error::InvalidConfig {
+                    key: format!("maintenance.{}.enabled", name),
+                    value: err.input,
+                })?
+                .unwrap_or_else(|| strategy_schedule.is_some());
+            if !is_enabled {
+                continue;
+            }
+            let schedule = match config.string("maintenance", Some(name), "schedule") {
+                Some(schedule) if schedule.as_ref() == "hourly" => Some(Schedule::Hourly),
+                Some(schedule) if schedule.as_ref() == "daily" => Some(Schedule::Daily),
+                Some(schedule) if schedule.as_ref() == "weekly" => Some(Schedule::Weekly),
+                Some(schedule) => {
+                    return Err(Error::InvalidConfig {
+                        key: format!("maintenance.{}.schedule", name),
+                        value: schedule.into_owned(),
+                    })
+                }
+                None => strategy_schedule,
+            };
+            let is_scheduled = match min_schedule {
+                Some(min_schedule) => schedule.map_or(false, |schedule| schedule >= min_schedule),
+                None => true,
+            };
+            if is_scheduled {
+                enabled.push(task);
+            }
+        }
+        Ok(enabled)
+    }
+
+    fn pack_loose_references(&self) -> Result<usize, Error> {
+        let mut edits = Vec::new();
+        for r in self.refs.loose_iter()? {
+            let r = r?;
+            let is_worktree_private = ["refs/bisect/", "refs/rewritten/", "refs/worktree/"]
+                .iter()
+                .any(|prefix| r.name.as_bstr().starts_with(prefix.as_bytes()));
+            if !r.name.as_bstr().starts_with(b"refs/") || is_worktree_private {
+                continue;
+            }
+            if let Target::Peeled(id) = r.target {
+                edits.push(RefEdit {
+                    change: Change::Update {
+                        // the reflog isn't written as the reference doesn't change.
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message: "pack-refs".into(),
+                        },
+                        expected: PreviousValue::MustExistAndMatch(Target::Peeled(id)),
+                        new: Target::Peeled(id),
+                    },
+                    name: r.name,
+                    deref: false,
+                });
+            }
+        }
+        if edits.is_empty() {
+            return Ok(0);
+        }
+
+        let objects = self.objects.clone();
+        let num_edits = edits.len();
+        self.refs
+            .transaction()
+            .packed_refs(PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(
+                Box::new(move |id, buf| {
+                    objects
+                        .try_find(id, buf)
+                        .map(|obj| obj.map(|(obj, _)| obj.kind))
+                        .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)
+                }),
+            ))
+            .prepare(edits, git_lock::acquire::Fail::Immediately)
+            .map_err(reference::edit::Error::from)?
+            .commit(self.committer().to_ref())
+            .map_err(reference::edit::Error::from)?;
+        Ok(num_edits)
+    }
+
+    #[cfg(feature = "blocking-network-client")]
+    fn prefetch_remotes(&self) -> Result<usize, Error> {
+        let config = &self.config.resolved;
+        let mut remotes = Vec::<String>::new();
+        for (header, _) in config.sections_by_name_with_header("remote") {
+            if let Some(name) = header.subsection_name.as_ref() {
+                if !remotes.iter().any(|remote| remote == name) {
+                    remotes.push(name.to_string());
+                }
+            }
+        }
+
+        let mut num_updates = 0;
+        for remote in remotes {
+            if config.string("remote", Some(&remote), "url").is_none() {
+                continue;
+            }
+            let outcome = self
+                .fetch(
+                    &remote,
+                    crate::fetch::Options {
+                        prefetch: true,
+                        ..Default::default()
+                    },
+                )
+                .map_err(|err| Error::Prefetch {
+                    remote: remote.clone(),
+                    source: Box::new(err),
+                })?;
+            num_updates += outcome
+                .updates
+                .iter()
+                .filter(|update| !matches!(update.mode, crate::fetch::Mode::UpToDate))
+                .count();
+        }
+        Ok(num_updates)
+    }
+
+    #[cfg(not(feature = "blocking-network-client"))]
+    fn prefetch_remotes(&self) -> Result<usize, Error> {
+        Err(Error::PrefetchUnsupported)
+    }
+
+    fn repack_small_packs(&self) -> Result<usize, Error> {
+        let mut sizes: Vec<_> = self.repackable_packs()?.into_iter().map(|(_, size)| size).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        // like `git`, keep the largest pack and consolidate all packs up to the size of the second-largest one.
+        let max_size = match sizes.get(1) {
+            Some(second_largest) => (*second_largest).min(MAX_INCREMENTAL_REPACK_SIZE),
+            None => return Ok(0),
+        };
+        if sizes.iter().filter(|size| **size <= max_size).count() < 2 {
+            return Ok(0);
+        }
+        Ok(self
+            .repack(
+                progress::Discard,
+                crate::repack::Options {
+                    max_input_pack_size: Some(max_size),
+                    ..Default::default()
+                },
+            )?
+            .packs)
+    }
+
+    fn pack_loose_objects(&self) -> Result<usize, Error> {
+        let objects_dir = self.objects.store_ref().path();
+        let ids = git_odb::loose::Store::at(objects_dir, self.object_hash())
+            .iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let pack_dir = objects_dir.join("pack");
+        std::fs::create_dir_all(&pack_dir)?;
+        let mut pack_file = git_tempfile::new(
+            &pack_dir,
+            git_tempfile::ContainingDirectory::Exists,
+            git_tempfile::AutoRemove::Tempfile,
+        )?;
+        let outcome = self.pack_objects(
+            ids.iter().copied(),
+            &mut pack_file,
+            progress::Discard,
+            pack_objects::Options {
+                expansion: pack_objects::ObjectExpansion::AsIs,
+                thread_limit: None,
+                allow_thin_pack: false,
+                delta_reuse: true,
+                ..Default::default()
+            },
+        )?;
+        let pack_path = pack_dir.join(format!("pack-{}.pack", outcome.checksum));
+        pack_file.persist(&pack_path)?;
+        let index_path = self.index_pack(&pack_path, progress::Discard)?;
+
+        // only objects that made it into the new pack are removed.
+        let index = git_odb::pack::index::File::at(&index_path, self.object_hash())?;
+        let mut num_removed = 0;
+        for id in ids.iter().filter(|id| index.lookup(id).is_some()) {
+            let hex = id.to_hex().to_string();
+            std::fs::remove_file(objects_dir.join(&hex[..2]).join(&hex[2..]))?;
+            num_removed += 1;
+        }
+        Ok(num_removed)
+    }
+}
@@ -0,0 +1,88 @@
+//! Mark conflicts as resolved by adding the resolved files to the index.
+use std::path::Path;
+
+use git_index::{entry::Mode, Entry};
+use git_object::bstr::{BString, ByteSlice};
+
+use crate::index_diff::{mode_of, stat_of};
+
+/// The error returned by [`Repository::mark_resolved()`][crate::Repository::mark_resolved()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to mark conflicts as resolved")]
+    MissingWorkTree,
+    #[error("The path '{path}' has no unresolved conflicts")]
+    NotConflicted { path: BString },
+    #[error("The file at '{path}' still contains conflict markers")]
+    UnresolvedConflictMarkers { path: BString },
+    #[error(transparent)]
+    PathTraversal(#[from] git_sec::permission::TraversalError),
+    #[error(transparent)]
+    LockIndex(#[from] git_lock::acquire::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    AddEntry(#[from] git_index::edit::add_entry::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] crate::worktree::write_index::Error),
+    #[error("Could not read '{}' in the working tree", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl crate::Repository {
+    /// Mark the conflict at `path` as resolved by replacing its entries at stages 1, 2 and 3 in the index with a single entry
+    /// for the file in the working tree, similar to what `git add <path>` does after resolving a conflict manually.
+    ///
+    /// `path` is relative to the root of the working tree. The file is written to the object database as blob, and it's an
+    /// error if it still contains lines starting with a `<<<<<<<` conflict marker.
+    pub fn mark_resolved(&self, path: &Path) -> Result<(), Error> {
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        git_sec::permission::check_path_traversal(work_dir, path)?;
+        let rela_path = git_path::to_unix_separators(git_path::into_bstr(path)).into_owned();
+        let lock = self.lock_index()?;
+        let mut index = self.open_index()?;
+        let entries = index.entries_for_path(rela_path.as_bstr());
+        if !entries.iter().any(|entry| entry.stage() != 0) {
+            return Err(Error::NotConflicted { path: rela_path });
+        }
+        let index_mode = entries
+            .iter()
+            .find(|entry| entry.stage() == 2)
+            .or_else(|| entries.last())
+            .map_or(Mode::FILE, |entry| entry.mode);
+
+        let file_path = work_dir.join(path);
+        let io_err = |source| Error::Io {
+            path: file_path.clone(),
+            source,
+        };
+        let metadata = std::fs::symlink_metadata(&file_path).map_err(io_err)?;
+        let mode = mode_of(&metadata, index_mode);
+        let data = if mode == Mode::SYMLINK {
+            git_path::into_bstr(std::fs::read_link(&file_path).map_err(io_err)?)
+                .into_owned()
+                .into()
+        } else {
+            let data = std::fs::read(&file_path).map_err(io_err)?;
+            if data.lines().any(|line| line.starts_with(b"<<<<<<<")) {
+                return Err(Error::UnresolvedConflictMarkers { path: rela_path });
+            }
+            data
+        };
+        let id = self.write_object(git_object::Blob { data })?.detach();
+
+        index.add_entry(
+            Entry::new(stat_of(&metadata), id, git_index::entry::Flags::empty(), mode),
+            rela_path.as_bstr(),
+            false,
+        )?;
+        self.write_index_to_lock(&index, lock)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+//! Find the best common ancestors of commits.
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use git_hash::ObjectId;
+
+use crate::repository::commits::{Commits, Order};
+
+/// The error returned by [`Repository::merge_base_many()`][crate::Repository::merge_base_many()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindCommit(#[from] git_odb::find::existing_iter::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[cfg(feature = "git-commitgraph")]
+    #[error("The commit-graph could not be read")]
+    CommitGraph(#[from] git_commitgraph::file::commit::Error),
+}
+
+const PARENT1: u8 = 1 << 0;
+const PARENT2: u8 = 1 << 1;
+const STALE: u8 = 1 << 2;
+const RESULT: u8 = 1 << 3;
+
+impl crate::Repository {
+    /// Return the best common ancestors of all `commits`, i.e. all commits reachable from each of them which aren't an ancestor
+    /// of another such commit, similar to `git merge-base --octopus`, as needed for octopus merges.
+    ///
+    /// The merge bases of the first two commits are computed, followed by the merge bases of each of them with the next
+    /// commit until all commits were seen, removing bases which are ancestors of other bases along the way.
+    /// The result is empty if `commits` is empty or if they have no common history, and contains the only commit if there
+    /// is just one.
+    pub fn merge_base_many(&self, commits: &[ObjectId]) -> Result<Vec<ObjectId>, Error> {
+        let (first, rest) = match commits.split_first() {
+            Some(split) => split,
+            None => return Ok(Vec::new()),
+        };
+        let mut graph = self.commits();
+        let mut bases = vec![*first];
+        for next in rest {
+            let mut next_bases = Vec::new();
+            for base in bases {
+                for id in merge_bases(&mut graph, base, *next)? {
+                    if !next_bases.contains(&id) {
+                        next_bases.push(id);
+                    }
+                }
+            }
+            bases = remove_redundant(&mut graph, next_bases)?;
+            if bases.is_empty() {
+                break;
+            }
+        }
+        Ok(bases)
+    }
+}
+
+/// Return the best common ancestors of `one` and `two` by painting their ancestors until only commits reachable from both
+/// remain in the queue.
+fn merge_bases(graph: &mut Commits<'_>, one: ObjectId, two: ObjectId) -> Result<Vec<ObjectId>, Error> {
+    if one == two {
+        return Ok(vec![one]);
+    }
+    let mut commits = HashMap::<ObjectId, (u8, Order, Vec<ObjectId>)>::new();
+    let mut queue = BinaryHeap::new();
+    // the queued commits which aren't stale yet.
+    let mut queued_non_stale = HashSet::new();
+    for (id, flags) in [(one, PARENT1), (two, PARENT2)] {
+        let (order, parents) = graph.lookup::<Error>(&id)?;
+        commits.insert(id, (flags, order, parents));
+        queue.push((order, id));
+        queued_non_stale.insert(id);
+    }
+
+    let mut results = Vec::new();
+    while !queued_non_stale.is_empty() {
+        let (_, id) = queue.pop().expect("non-stale commits are queued");
+        queued_non_stale.remove(&id);
+        let (flags, _, parents) = commits.get_mut(&id).expect("queued commits are known");
+        let mut flags_to_propagate = *flags & (PARENT1 | PARENT2 | STALE);
+        if flags_to_propagate == PARENT1 | PARENT2 {
+            if *flags & RESULT == 0 {
+                *flags |= RESULT;
+                results.push(id);
+            }
+            flags_to_propagate |= STALE;
+        }
+
+        for parent in parents.clone() {
+            match commits.get_mut(&parent) {
+                Some((parent_flags, order, _)) => {
+                    if *parent_flags & flags_to_propagate == flags_to_propagate {
+                        continue;
+                    }
+                    *parent_flags |= flags_to_propagate;
+                    queue.push((*order, parent));
+                    if *parent_flags & STALE == 0 {
+                        queued_non_stale.insert(parent);
+                    } else {
+                        queued_non_stale.remove(&parent);
+                    }
+                }
+                None => {
+                    let (order, grand_parents) = graph.lookup::<Error>(&parent)?;
+                    commits.insert(parent, (flags_to_propagate, order, grand_parents));
+                    queue.push((order, parent));
+                    if flags_to_propagate & STALE == 0 {
+                        queued_non_stale.insert(parent);
+                    }
+                }
+            }
+        }
+    }
+    // results found later may have been reached from previous results, which makes them stale.
+    results.retain(|id| commits[id].0 & STALE == 0);
+    remove_redundant(graph, results)
+}
+
+/// Remove all `commits` which are reachable from another commit in `commits`.
+fn remove_redundant(graph: &mut Commits<'_>, commits: Vec<ObjectId>) -> Result<Vec<ObjectId>, Error> {
+    if commits.len() < 2 {
+        return Ok(commits);
+    }
+    let mut out = Vec::with_capacity(commits.len());
+    for (index, candidate) in commits.iter().enumerate() {
+        let others = commits
+            .iter()
+            .enumerate()
+            .filter_map(|(other_index, id)| (other_index != index).then(|| *id));
+        if !is_reachable_from(graph, *candidate, others)? {
+            out.push(*candidate);
+        }
+    }
+    Ok(out)
+}
+
+/// Return true if `target` is reachable from any of the `tips`, without traversing commits which can't have it as ancestor.
+fn is_reachable_from(
+    graph: &mut Commits<'_>,
+    target: ObjectId,
+    tips: impl IntoIterator<Item = ObjectId>,
+) -> Result<bool, Error> {
+    let (target_order, _) = graph.lookup::<Error>(&target)?;
+    let mut seen = HashSet::new();
+    let mut queue: Vec<_> = tips.into_iter().collect();
+    while let Some(id) = queue.pop() {
+        if id == target {
+            return Ok(true);
+        }
+        if !seen.insert(id) {
+            continue;
+        }
+        let (order, parents) = graph.lookup::<Error>(&id)?;
+        if order.cannot_reach(&target_order) {
+            continue;
+        }
+        queue.extend(parents.into_iter().filter(|parent| !seen.contains(parent)));
+    }
+    Ok(false)
+}
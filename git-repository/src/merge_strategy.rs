@@ -0,0 +1,76 @@
+//! Select how files are merged with the `merge` attribute.
+use std::path::Path;
+
+use git_object::bstr::BString;
+
+/// How a file is merged during a three-way merge, as returned by
+/// [`Repository::merge_strategy_for()`][crate::Repository::merge_strategy_for()].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    /// Merge lines of text and mark conflicting changes with conflict markers, which is the default.
+    Text,
+    /// Keep the version of the current branch and report a conflict if both sides changed, as used for binary files.
+    Binary,
+    /// Merge lines of text, keeping the lines of both sides in case of conflicting changes without conflict markers.
+    Union,
+    /// Run the merge driver configured with `merge.<name>.driver`.
+    Driver {
+        /// The name of the driver.
+        name: String,
+        /// The shell command to run, with `%O`, `%A` and `%B` standing for the paths of the ancestor's, our and
+        /// their version respectively.
+        command: BString,
+    },
+}
+
+/// The error returned by [`Repository::merge_strategy_for()`][crate::Repository::merge_strategy_for()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Attributes(#[from] crate::attributes::Error),
+}
+
+impl crate::Repository {
+    /// Return the strategy to merge the file at `path`, relative to the root of the working tree, as selected by its
+    /// `merge` attribute, similar to what `git merge` does for each file changed on both sides.
+    ///
+    /// `merge` selects [`Strategy::Text`] and `-merge` selects [`Strategy::Binary`]. `merge=<name>` selects the driver
+    /// configured with `merge.<name>.driver` if there is one, like one named `ours` which is commonly configured to run `true`,
+    /// or otherwise the builtin `text`, `binary` or `union` strategy of the same name. Without the attribute, the driver
+    /// named by `merge.default` is used. All unknown names fall back to [`Strategy::Text`].
+    pub fn merge_strategy_for(&self, path: &Path) -> Result<Strategy, Error> {
+        let path = git_path::to_unix_separators(git_path::into_bstr(path));
+        let case = self
+            .config
+            .ignore_case
+            .then(|| git_glob::pattern::Case::Fold)
+            .unwrap_or_default();
+        let name = match self
+            .attributes(&path)?
+            .attribute_matching_relative_path(&*path, "merge", Some(false), case)
+        {
+            Some(git_attributes::State::Set) => return Ok(Strategy::Text),
+            Some(git_attributes::State::Unset) => return Ok(Strategy::Binary),
+            Some(git_attributes::State::Value(name)) => name.to_string(),
+            Some(git_attributes::State::Unspecified) | None => {
+                match self.config.resolved.string("merge", None, "default") {
+                    Some(name) => name.to_string(),
+                    None => return Ok(Strategy::Text),
+                }
+            }
+        };
+
+        if let Some(command) = self.config.resolved.string("merge", Some(&name), "driver") {
+            return Ok(Strategy::Driver {
+                name,
+                command: command.into_owned(),
+            });
+        }
+        Ok(match name.as_str() {
+            "binary" => Strategy::Binary,
+            "union" => Strategy::Union,
+            _ => Strategy::Text,
+        })
+    }
+}
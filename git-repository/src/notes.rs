@@ -0,0 +1,109 @@
+//! Read the notes `git notes` attaches to objects.
+use git_hash::ObjectId;
+use git_object::{bstr::ByteSlice, TreeRefIter};
+
+use crate::{object, reference};
+
+/// The error returned by [`Repository::notes_tree()`][crate::Repository::notes_tree()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindReference(#[from] reference::find::Error),
+    #[error(transparent)]
+    PeelReference(#[from] reference::peel::Error),
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::OdbError),
+    #[error(transparent)]
+    PeelToTree(#[from] object::peel::to_kind::Error),
+}
+
+/// The tree of a notes reference, as returned by [`Repository::notes_tree()`][crate::Repository::notes_tree()].
+#[derive(Debug, Clone, Copy)]
+pub struct NotesTree<'repo> {
+    repo: &'repo crate::Repository,
+    /// The tree of the notes commit, or `None` if the notes reference doesn't exist yet.
+    tree: Option<ObjectId>,
+}
+
+impl<'repo> NotesTree<'repo> {
+    /// Return the id of the tree with all notes, or `None` if the notes reference doesn't exist and there are no notes.
+    pub fn tree_id(&self) -> Option<ObjectId> {
+        self.tree
+    }
+
+    /// Return the note attached to the object with `id`, or `None` if there is none.
+    ///
+    /// Notes are blobs named after the hexadecimal id of the object they are attached to. With many notes, `git` moves them
+    /// into fan-out directories named after the first two hexadecimal characters of the remaining name, possibly across
+    /// multiple levels, which are all searched.
+    pub fn find(
+        &self,
+        id: impl Into<ObjectId>,
+    ) -> Result<Option<crate::Object<'repo>>, object::find::existing::OdbError> {
+        let mut tree = match self.tree {
+            Some(tree_id) => self.repo.find_object(tree_id)?,
+            None => return Ok(None),
+        };
+        let hex = id.into().to_hex().to_string();
+        let mut remaining = hex.as_str();
+        loop {
+            let next = TreeRefIter::from_bytes(&tree.data)
+                .filter_map(Result::ok)
+                .find_map(|entry| {
+                    if entry.mode.is_no_tree() && entry.filename == remaining.as_bytes().as_bstr() {
+                        Some((entry.oid.to_owned(), remaining.len()))
+                    } else if entry.mode.is_tree()
+                        && remaining.len() > 2
+                        && entry.filename == remaining[..2].as_bytes().as_bstr()
+                    {
+                        Some((entry.oid.to_owned(), 2))
+                    } else {
+                        None
+                    }
+                });
+            match next {
+                Some((id, consumed)) if consumed == remaining.len() => return Ok(Some(self.repo.find_object(id)?)),
+                Some((id, consumed)) => {
+                    remaining = &remaining[consumed..];
+                    tree = self.repo.find_object(id)?;
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+impl crate::Repository {
+    /// Return the tree of the notes reference `ref_name` to find notes attached to objects in, similar to what
+    /// `git notes --ref <ref_name> show` uses.
+    ///
+    /// `ref_name` is expanded like `git` does, so `commits` and `notes/commits` both name `refs/notes/commits`. If it's
+    /// `None`, the reference configured in `core.notesRef` is used, or `refs/notes/commits` if it's unset.
+    /// If the reference doesn't exist, the returned tree has no notes.
+    pub fn notes_tree(&self, ref_name: Option<&str>) -> Result<NotesTree<'_>, Error> {
+        let ref_name = match ref_name {
+            Some(name) => name.to_owned(),
+            None => match self.config.resolved.string("core", None, "notesRef") {
+                Some(name) => name.to_string(),
+                None => "refs/notes/commits".into(),
+            },
+        };
+        let ref_name = if ref_name.starts_with("refs/notes/") {
+            ref_name
+        } else if ref_name.starts_with("notes/") {
+            format!("refs/{}", ref_name)
+        } else {
+            format!("refs/notes/{}", ref_name)
+        };
+
+        let tree = match self.try_find_reference(ref_name.as_str())? {
+            Some(mut reference) => {
+                let commit_id = reference.peel_to_id_in_place()?.detach();
+                Some(self.find_object(commit_id)?.peel_to_kind(git_object::Kind::Tree)?.id)
+            }
+            None => None,
+        };
+        Ok(NotesTree { repo: self, tree })
+    }
+}
@@ -0,0 +1,84 @@
+use git_hash::ObjectId;
+use git_odb::pack::{data::entry::Header, Find};
+
+use crate::object;
+
+/// An object in the form it is stored in, as returned by [`Repository::read_object_compressed()`][crate::Repository::read_object_compressed()].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Compressed {
+    /// The kind of the object.
+    pub kind: git_object::Kind,
+    /// The amount of bytes `compressed_data` decompresses to.
+    pub decompressed_size: u64,
+    /// The zlib-compressed data as stored in the object database.
+    ///
+    /// For loose objects, this is the content of the object file which includes the loose object header.
+    /// For packed objects, this is the pack entry without its header, which is a delta if [`pack_header`][Compressed::pack_header]
+    /// says so.
+    pub compressed_data: Vec<u8>,
+    /// The header of the pack entry if the object is packed, or `None` if it is a loose object.
+    pub pack_header: Option<Header>,
+}
+
+/// The error returned by [`Repository::read_object_compressed()`][crate::Repository::read_object_compressed()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("An object with id {id} could not be found")]
+    NotFound { id: ObjectId },
+    #[error(transparent)]
+    FindLoose(#[from] git_odb::loose::find::Error),
+    #[error(transparent)]
+    Alternates(#[from] git_odb::alternate::Error),
+    #[error(transparent)]
+    FindExistingObject(#[from] object::find::existing::OdbError),
+}
+
+impl crate::Repository {
+    /// Return the object with `id` in its compressed form without decompressing its data, which is useful when
+    /// copying objects into another object database or pack.
+    ///
+    /// # Performance Note
+    ///
+    /// To learn the kind of a packed object stored as delta, the object has to be fully decoded.
+    pub fn read_object_compressed(&self, id: impl Into<ObjectId>) -> Result<object::Compressed, Error> {
+        let id = id.into();
+        let mut buf = Vec::new();
+        let mut objects = self.objects.clone();
+        objects.prevent_pack_unload();
+        if let Some(location) = objects.location_by_oid(&id, &mut buf) {
+            let entry = objects
+                .entry_by_location(&location)
+                .expect("pack entries at known locations are available");
+            let header = git_odb::pack::data::Entry::from_bytes(
+                &entry.data,
+                location.pack_offset,
+                self.object_hash().len_in_bytes(),
+            );
+            let kind = match header.header.as_kind() {
+                Some(kind) => kind,
+                None => self.find_object(id)?.kind,
+            };
+            return Ok(object::Compressed {
+                kind,
+                decompressed_size: header.decompressed_size,
+                compressed_data: entry.data[header.header_size()..].to_owned(),
+                pack_header: Some(header.header),
+            });
+        }
+
+        let objects_dir = self.objects.store_ref().path();
+        for objects_dir in std::iter::once(objects_dir.to_owned()).chain(git_odb::alternate::resolve(objects_dir)?) {
+            let loose = git_odb::loose::Store::at(objects_dir, self.object_hash());
+            if let Some((kind, size)) = loose.try_find_compressed(id, &mut buf)? {
+                return Ok(object::Compressed {
+                    kind,
+                    decompressed_size: (git_object::encode::loose_header(kind, size).len() + size) as u64,
+                    compressed_data: buf,
+                    pack_header: None,
+                });
+            }
+        }
+        Err(Error::NotFound { id })
+    }
+}
@@ -23,6 +23,29 @@ pub mod find {
     pub mod existing {
         pub(crate) type OdbError = git_odb::find::existing::Error<git_odb::store::find::Error>;
     }
+
+    ///
+    pub mod unique_prefix {
+        use git_hash::{ObjectId, Prefix};
+
+        /// The error returned by [`Repository::find_unique_prefix()`][crate::Repository::find_unique_prefix()].
+        #[derive(Debug, thiserror::Error)]
+        #[allow(missing_docs)]
+        pub enum Error {
+            #[error(transparent)]
+            InvalidPrefix(#[from] git_hash::prefix::from_hex::Error),
+            #[error(transparent)]
+            Find(#[from] git_odb::store::find::Error),
+            #[error("Could not load all pack indices to list ambiguous objects")]
+            LoadIndex(#[from] git_odb::store::load_index::Error),
+            #[error("Could not list loose objects to find ambiguous ones")]
+            IterLoose(#[from] git_odb::loose::iter::Error),
+            #[error("No object starts with {prefix}")]
+            NotFound { prefix: Prefix },
+            #[error("The short id {prefix} is ambiguous, candidates are {}", .candidates.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+            Ambiguous { prefix: Prefix, candidates: Vec<ObjectId> },
+        }
+    }
 }
 
 ///
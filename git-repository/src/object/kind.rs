@@ -0,0 +1,62 @@
+use std::{io::Read, path::PathBuf};
+
+use git_hash::ObjectId;
+
+use crate::object;
+
+/// The error returned by [`Repository::object_kind()`][crate::Repository::object_kind()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("An object with id {id} could not be found")]
+    NotFound { id: ObjectId },
+    #[error("Could not read loose object at '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Could not decompress the header of the loose object at '{}'", path.display())]
+    Decompress {
+        path: PathBuf,
+        source: git_features::zlib::inflate::Error,
+    },
+    #[error(transparent)]
+    DecodeHeader(#[from] git_object::decode::LooseHeaderDecodeError),
+    #[error(transparent)]
+    FindObject(#[from] object::find::OdbError),
+}
+
+impl crate::Repository {
+    /// Return the kind of the object with `id` without decompressing its data, similar to `git cat-file -t`.
+    ///
+    /// For packed objects, only the headers of its pack entry and those of its delta bases are read, and for loose objects
+    /// only the beginning of the object file is decompressed.
+    ///
+    /// # Performance Note
+    ///
+    /// Loose objects are only looked up in the objects directory of this repository, and objects in alternates are fully
+    /// decoded to learn their kind.
+    pub fn object_kind(&self, id: impl Into<ObjectId>) -> Result<object::Kind, Error> {
+        let id = id.into();
+        if let Some(kind) = self.objects.packed_object_kind(&id) {
+            return Ok(kind);
+        }
+
+        let hex = id.to_hex().to_string();
+        let path = self.objects.store_ref().path().join(&hex[..2]).join(&hex[2..]);
+        let mut compressed = Vec::new();
+        match std::fs::File::open(&path).and_then(|file| file.take(4096).read_to_end(&mut compressed)) {
+            Ok(_) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return match self.try_find_object(id)? {
+                    Some(object) => Ok(object.kind),
+                    None => Err(Error::NotFound { id }),
+                }
+            }
+            Err(source) => return Err(Error::Io { path, source }),
+        }
+        let mut header = [0u8; 64];
+        let (_status, _consumed_in, consumed_out) = git_features::zlib::Inflate::default()
+            .once(&compressed, &mut header)
+            .map_err(|source| Error::Decompress { path, source })?;
+        let (kind, _size, _header_size) = git_object::decode::loose_header(&header[..consumed_out])?;
+        Ok(kind)
+    }
+}
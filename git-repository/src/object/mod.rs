@@ -13,7 +13,12 @@ pub(crate) mod cache {
 pub use errors::{conversion, find, write};
 ///
 pub mod commit;
+///
+pub mod compressed;
+pub use compressed::Compressed;
 mod impls;
+///
+pub mod kind;
 pub mod peel;
 mod tag;
 ///
@@ -57,6 +62,9 @@ impl std::fmt::Debug for DetachedObject {
 }
 
 /// Consuming conversions to attached object kinds.
+///
+/// To get the object back if it is of another kind, use [`try_into_tag()`][Object::try_into_tag()] or
+/// [`TryFrom`][std::convert::TryFrom] conversions like `Commit::try_from(object)`.
 impl<'repo> Object<'repo> {
     pub(crate) fn from_data(
         id: impl Into<ObjectId>,
@@ -97,13 +105,9 @@ impl<'repo> Object<'repo> {
         })
     }
 
-    /// Transform this object into a tag, or return it as part of the `Err` if it is no commit.
-    pub fn try_into_tag(self) -> Result<Tag<'repo>, try_into::Error> {
-        self.try_into().map_err(|this: Self| try_into::Error {
-            id: this.id,
-            actual: this.kind,
-            expected: git_object::Kind::Commit,
-        })
+    /// Transform this object into a tag, or return it unchanged as `Err` if it is no tag.
+    pub fn try_into_tag(self) -> Result<Tag<'repo>, Self> {
+        self.try_into()
     }
 
     /// Transform this object into a tree, or return it as part of the `Err` if it is no tree.
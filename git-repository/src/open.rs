@@ -313,6 +313,8 @@ impl ThreadSafeRepository {
             work_tree: worktree_dir,
             config,
             linked_worktree_options,
+            #[cfg(feature = "blocking-network-client")]
+            promised: Default::default(),
         })
     }
 }
@@ -0,0 +1,280 @@
+//!
+use std::{cmp::Reverse, collections::VecDeque, convert::Infallible, io::Write, sync::atomic::AtomicBool};
+
+use git_features::parallel::{reduce::Finalize, InOrderIter};
+use git_hash::ObjectId;
+use git_odb::pack::{data::output, Find};
+pub use output::count::objects::ObjectExpansion;
+
+use crate::{object::find::existing::OdbError, progress, Progress};
+
+/// Options for use in [`Repository::pack_objects()`][crate::Repository::pack_objects()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Determine which objects reachable from the input objects are added to the pack as well.
+    pub expansion: ObjectExpansion,
+    /// The amount of threads to use at most, or all logical cores if `None`.
+    ///
+    /// Note that counting objects is always single-threaded unless [`expansion`][Options::expansion] is
+    /// [`ObjectExpansion::AsIs`], as tree traversals may otherwise yield many more objects than needed.
+    pub thread_limit: Option<usize>,
+    /// If true, objects may be stored as deltas against base objects which are not part of the pack, making it a _thin_ pack.
+    ///
+    /// This only affects deltas copied from existing packs, as new deltas are only computed against objects in the pack.
+    pub allow_thin_pack: bool,
+    /// If true, objects are copied from existing packs as is, including those stored as deltas, and all other objects are
+    /// compressed as base objects, without computing any new deltas.
+    ///
+    /// Otherwise all objects are recompressed, using new deltas within the [`delta_window`][Options::delta_window].
+    pub delta_reuse: bool,
+    /// The amount of preceding objects of the same kind, ordered by size, to try as base when computing a new delta for
+    /// each object, similar to `git pack-objects --window`, unless [`delta_reuse`][Options::delta_reuse] is set.
+    ///
+    /// If `0`, no new deltas are computed and all objects are stored as base objects.
+    pub delta_window: usize,
+    /// The maximum length of chains of newly computed deltas, similar to `git pack-objects --depth`.
+    pub max_delta_depth: usize,
+}
+
+/// Information about the pack written by [`Repository::pack_objects()`][crate::Repository::pack_objects()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of objects written into the pack.
+    pub num_objects: usize,
+    /// The amount of bytes written, including the header and the trailing checksum.
+    pub pack_size: u64,
+    /// The checksum of the pack, which also serves as its name.
+    pub checksum: ObjectId,
+    /// Statistics about counting the objects to put into the pack.
+    pub counts: output::count::objects::Outcome,
+    /// Statistics about turning counted objects into pack entries.
+    pub entries: output::entry::iter_from_counts::Outcome,
+    /// The amount of objects stored as newly computed deltas, which is always `0` if [`Options::delta_reuse`] is set or
+    /// [`Options::delta_window`] is `0`.
+    pub computed_deltas: usize,
+}
+
+/// The error returned by [`Repository::pack_objects()`][crate::Repository::pack_objects()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not obtain a thread-safe handle to the object database")]
+    OpenObjectDatabase(#[source] std::io::Error),
+    #[error(transparent)]
+    Count(#[from] output::count::objects::Error<git_odb::pack::find::existing::Error<OdbError>, Infallible>),
+    #[error(transparent)]
+    Entries(#[from] output::entry::iter_from_counts::Error<OdbError>),
+    #[error(transparent)]
+    Write(#[from] output::bytes::Error<output::entry::iter_from_counts::Error<OdbError>>),
+    #[error(transparent)]
+    FindObject(#[from] git_odb::store::find::Error),
+    #[error(transparent)]
+    CreateEntry(#[from] output::entry::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+impl crate::Repository {
+    /// Write a pack containing all objects identified by `ids` to `out`, similar to `git pack-objects`, with objects
+    /// reachable from them added according to [`Options::expansion`].
+    ///
+    /// If [`Options::delta_reuse`] is set, objects which are already stored as deltas in existing packs are copied as is,
+    /// and all other objects are compressed as base objects. Otherwise all objects are stored as deltas against other
+    /// objects in the pack if that saves enough space, or as base objects.
+    pub fn pack_objects(
+        &self,
+        ids: impl IntoIterator<Item = ObjectId>,
+        out: impl Write,
+        mut progress: impl Progress,
+        opts: Options,
+    ) -> Result<Outcome, Error> {
+        let mut handle = self.objects.clone().into_arc().map_err(Error::OpenObjectDatabase)?;
+        handle.prevent_pack_unload();
+        handle.ignore_replacements = true;
+
+        let chunk_size = 1000;
+        let should_interrupt = &crate::interrupt::IS_INTERRUPTED;
+        let input = ids.into_iter().map(Ok::<_, Infallible>);
+        let (counts, count_stats) = {
+            let mut progress = progress.add_child("counting");
+            progress.init(None, progress::count("objects"));
+            if matches!(opts.expansion, ObjectExpansion::AsIs) {
+                output::count::objects(
+                    handle.clone(),
+                    input.collect::<Vec<_>>().into_iter(),
+                    progress,
+                    should_interrupt,
+                    output::count::objects::Options {
+                        thread_limit: opts.thread_limit,
+                        chunk_size,
+                        input_object_expansion: opts.expansion,
+                    },
+                )?
+            } else {
+                output::count::objects_unthreaded(handle.clone(), input, progress, should_interrupt, opts.expansion)?
+            }
+        };
+
+        let (num_objects, pack_size, checksum, entry_stats, computed_deltas) = if opts.delta_reuse {
+            let num_objects = counts.len();
+            let mut entries = InOrderIter::from(output::entry::iter_from_counts(
+                counts,
+                handle,
+                progress.add_child("creating entries"),
+                output::entry::iter_from_counts::Options {
+                    thread_limit: opts.thread_limit,
+                    mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                    allow_thin_pack: opts.allow_thin_pack,
+                    chunk_size,
+                    version: Default::default(),
+                },
+            ));
+            let (pack_size, checksum) = write_entries(
+                entries.by_ref(),
+                out,
+                num_objects,
+                self.object_hash(),
+                progress.add_child("writing"),
+            )?;
+            (num_objects, pack_size, checksum, entries.inner.finalize()?, 0)
+        } else {
+            let (entries, entry_stats, computed_deltas) = entries_with_computed_deltas(
+                &handle,
+                &counts,
+                opts,
+                progress.add_child("computing deltas"),
+                should_interrupt,
+            )?;
+            let num_objects = entries.len();
+            let (pack_size, checksum) = write_entries(
+                std::iter::once(Ok(entries)),
+                out,
+                num_objects,
+                self.object_hash(),
+                progress.add_child("writing"),
+            )?;
+            (num_objects, pack_size, checksum, entry_stats, computed_deltas)
+        };
+
+        Ok(Outcome {
+            num_objects,
+            pack_size,
+            checksum,
+            counts: count_stats,
+            entries: entry_stats,
+            computed_deltas,
+        })
+    }
+}
+
+/// Write a pack with `num_objects` `entries` to `out` and return its size and checksum.
+fn write_entries(
+    entries: impl Iterator<Item = Result<Vec<output::Entry>, output::entry::iter_from_counts::Error<OdbError>>>,
+    out: impl Write,
+    num_objects: usize,
+    object_hash: git_hash::Kind,
+    mut progress: impl Progress,
+) -> Result<(u64, ObjectId), Error> {
+    progress.init(None, progress::bytes());
+    let mut pack = output::bytes::FromEntriesIter::new(
+        entries,
+        out,
+        num_objects as u32,
+        git_odb::pack::data::Version::default(),
+        object_hash,
+    );
+    let mut pack_size = 0;
+    for written in pack.by_ref() {
+        let written = written?;
+        pack_size += written;
+        progress.inc_by(written as usize);
+    }
+    Ok((pack_size, pack.digest().expect("iteration is done")))
+}
+
+/// An object which may serve as delta base for the objects following it.
+struct Candidate {
+    kind: git_object::Kind,
+    entry_index: usize,
+    data: Vec<u8>,
+    depth: usize,
+}
+
+/// Turn all `counts` into entries, leaving out missing ones, and store objects as deltas against one of the
+/// [`Options::delta_window`] objects before them if that's at most half their size. Return the entries along with statistics
+/// and the amount of deltas.
+///
+/// Objects are sorted by kind and decreasing size, so that smaller objects tend to become deltas of larger ones, which is
+/// what `git` does as well, minus the sorting by path as counts don't have one.
+fn entries_with_computed_deltas(
+    handle: &git_odb::HandleArc,
+    counts: &[output::Count],
+    opts: Options,
+    mut progress: impl Progress,
+    should_interrupt: &AtomicBool,
+) -> Result<(Vec<output::Entry>, output::entry::iter_from_counts::Outcome, usize), Error> {
+    let mut stats = output::entry::iter_from_counts::Outcome::default();
+    let mut buf = Vec::new();
+    let mut order = Vec::with_capacity(counts.len());
+    for (index, count) in counts.iter().enumerate() {
+        match handle.try_find(count.id, &mut buf)? {
+            Some((obj, _location)) => order.push((obj.kind, Reverse(obj.data.len()), index)),
+            None => stats.missing_objects += 1,
+        }
+    }
+    order.sort();
+
+    progress.init(Some(order.len()), progress::count("objects"));
+    let mut entries = Vec::with_capacity(counts.len());
+    let mut window = VecDeque::<Candidate>::with_capacity(opts.delta_window);
+    let mut computed_deltas = 0;
+    for (kind, _size, index) in order {
+        if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        let count = &counts[index];
+        let data = match handle.try_find(count.id, &mut buf)? {
+            Some((obj, _location)) => obj.data.to_owned(),
+            None => {
+                stats.missing_objects += 1;
+                continue;
+            }
+        };
+        if window.back().map_or(false, |candidate| candidate.kind != kind) {
+            window.clear();
+        }
+
+        let mut best = None;
+        let mut max_delta_size = data.len() / 2;
+        for candidate in window.iter().filter(|candidate| candidate.depth < opts.max_delta_depth) {
+            if let Some(entry) =
+                output::Entry::from_delta(count, &data, candidate.entry_index, &candidate.data, max_delta_size)
+            {
+                let entry = entry?;
+                max_delta_size = entry.decompressed_size.saturating_sub(1);
+                best = Some((entry, candidate.depth + 1));
+            }
+        }
+        let (entry, depth) = match best {
+            Some(delta) => {
+                computed_deltas += 1;
+                delta
+            }
+            None => (output::Entry::from_data(count, &git_object::Data::new(kind, &data))?, 0),
+        };
+        stats.decoded_and_recompressed_objects += 1;
+        entries.push(entry);
+
+        if window.len() == opts.delta_window {
+            window.pop_front();
+        }
+        window.push_back(Candidate {
+            kind,
+            entry_index: entries.len() - 1,
+            data,
+            depth,
+        });
+        progress.inc();
+    }
+    Ok((entries, stats, computed_deltas))
+}
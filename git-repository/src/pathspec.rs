@@ -0,0 +1,133 @@
+//! Filter paths with pathspecs like `git ls-files -- <pathspec>` does.
+use std::path::Path;
+
+use git_object::bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`Repository::pathspec_matches()`][crate::Repository::pathspec_matches()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Unknown pathspec magic '{magic}'")]
+    UnknownMagic { magic: BString },
+    #[error("Missing ')' at the end of the pathspec magic in '{spec}'")]
+    UnterminatedMagic { spec: BString },
+    #[error("The 'glob' and 'literal' pathspec magic can't be used together")]
+    IncompatibleMagic,
+}
+
+/// A parsed pathspec, which is a pattern along with its magic.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Pathspec {
+    pattern: BString,
+    /// Match `*` and `?` like shells do, i.e. not across `/`, with `**` matching any amount of directories.
+    glob: bool,
+    /// Match the pattern literally without treating any character specially.
+    literal: bool,
+    /// Match case-insensitively.
+    icase: bool,
+    /// Exclude matching paths instead of including them.
+    exclude: bool,
+    /// The pattern ended with a `/` and thus only matches directories.
+    must_be_dir: bool,
+}
+
+impl Pathspec {
+    fn parse(spec: &BStr) -> Result<Self, Error> {
+        let mut out = Pathspec::default();
+        let pattern = match spec.strip_prefix(b":") {
+            Some(rest) if rest.starts_with(b"(") => {
+                let end = rest
+                    .find_byte(b')')
+                    .ok_or_else(|| Error::UnterminatedMagic { spec: spec.into() })?;
+                for magic in rest[1..end].split_str(",").filter(|magic| !magic.is_empty()) {
+                    match magic {
+                        b"top" => {}
+                        b"glob" => out.glob = true,
+                        b"literal" => out.literal = true,
+                        b"icase" => out.icase = true,
+                        b"exclude" => out.exclude = true,
+                        _ => return Err(Error::UnknownMagic { magic: magic.into() }),
+                    }
+                }
+                &rest[end + 1..]
+            }
+            Some(mut rest) => {
+                while let Some((&magic, tail)) = rest.split_first() {
+                    match magic {
+                        b'/' => {}
+                        b'!' | b'^' => out.exclude = true,
+                        b':' => {
+                            rest = tail;
+                            break;
+                        }
+                        _ => break,
+                    }
+                    rest = tail;
+                }
+                rest
+            }
+            None => spec.as_bytes(),
+        };
+        if out.glob && out.literal {
+            return Err(Error::IncompatibleMagic);
+        }
+        out.must_be_dir = pattern.ends_with(b"/");
+        out.pattern = pattern.trim_end_with(|c| c == '/').into();
+        Ok(out)
+    }
+
+    /// Return true if `path` or one of its leading directories matches, with `is_dir` telling if `path` is a directory.
+    fn matches(&self, path: &BStr, is_dir: impl FnOnce() -> bool) -> bool {
+        if self.pattern.is_empty() {
+            return true;
+        }
+        let leading_dir_matches = path
+            .find_iter("/")
+            .any(|pos| self.matches_exactly(path[..pos].as_bstr()));
+        leading_dir_matches || (self.matches_exactly(path) && (!self.must_be_dir || is_dir()))
+    }
+
+    fn matches_exactly(&self, path: &BStr) -> bool {
+        let pattern = self.pattern.as_bstr();
+        let is_wildcard = !self.literal && pattern.find_byteset(br"*?[\").is_some();
+        if is_wildcard {
+            let mut mode = git_glob::wildmatch::Mode::empty();
+            if self.glob {
+                mode |= git_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL;
+            }
+            if self.icase {
+                mode |= git_glob::wildmatch::Mode::IGNORE_CASE;
+            }
+            git_glob::wildmatch(pattern, path, mode)
+        } else if self.icase {
+            pattern.eq_ignore_ascii_case(path)
+        } else {
+            pattern == path
+        }
+    }
+}
+
+impl crate::Repository {
+    /// Return all `paths` matching the pathspec `spec`, similar to what `git ls-files -- <spec>` lists, in order.
+    ///
+    /// `paths` are relative to the root of the working tree and match if they or one of their leading directories match.
+    /// Patterns with wildcards like `*.rs` match across directories, so `src/*.rs` matches `src/a/b.rs`, unless the `glob`
+    /// magic is used. A trailing `/` only matches directories, which is determined by looking at the working tree.
+    ///
+    /// Magic is supported in its long form like `:(glob,exclude)*.rs`, with `top`, `glob`, `literal`, `icase` and `exclude`,
+    /// and in its short form like `:!*.rs`, with `/` for `top` as well as `!` and `^` for `exclude`. As `paths` are always
+    /// relative to the root of the working tree, `top` has no effect. If `spec` is excluding, all paths that don't match
+    /// are returned.
+    pub fn pathspec_matches<'a>(&self, spec: &str, paths: &[&'a Path]) -> Result<Vec<&'a Path>, Error> {
+        let spec = Pathspec::parse(spec.into())?;
+        Ok(paths
+            .iter()
+            .filter(|path| {
+                let rela_path = git_path::to_unix_separators(git_path::into_bstr(**path));
+                let is_dir = || self.work_dir().map_or(false, |work_dir| work_dir.join(path).is_dir());
+                spec.matches(rela_path.as_ref(), is_dir) != spec.exclude
+            })
+            .copied()
+            .collect())
+    }
+}
@@ -0,0 +1,317 @@
+//! List and fetch objects omitted by a partial clone, which the remote promised to provide.
+use std::{
+    collections::HashSet,
+    io,
+    path::{Path, PathBuf},
+};
+
+use git_hash::ObjectId;
+use git_object::bstr::{BStr, BString};
+use git_odb::{pack::Find, FindExt};
+use git_protocol::{
+    fetch::{Action, Arguments, LsRefsAction, Ref, Response},
+    transport,
+    transport::client::Capabilities,
+};
+
+use crate::Progress;
+
+/// The error returned by [`Repository::fetch_promised_objects()`][crate::Repository::fetch_promised_objects()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The repository isn't a partial clone as 'extensions.partialClone' isn't set")]
+    NoPromisorRemote,
+    #[error("The promisor remote '{name}' has no URL configured in 'remote.{name}.url'")]
+    UnknownRemote { name: String },
+    #[error(transparent)]
+    Connect(#[from] transport::client::connect::Error),
+    #[error(transparent)]
+    Fetch(#[from] git_protocol::fetch::Error),
+}
+
+///
+pub mod list {
+    /// The error returned by [`Repository::list_promisor_objects()`][crate::Repository::list_promisor_objects()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the pack directory")]
+        ReadPackDirectory(#[from] std::io::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] git_odb::pack::index::init::Error),
+        #[error(transparent)]
+        FindExistingObject(#[from] crate::object::find::existing::OdbError),
+        #[error(transparent)]
+        Decode(#[from] git_object::decode::Error),
+    }
+}
+
+impl crate::Repository {
+    /// Return the ids of all objects which are referenced by objects in promisor packs, i.e. packs marked with a `.promisor`
+    /// file, but which aren't present in the object database. These are the objects the promisor remote promised to
+    /// provide once they are needed.
+    ///
+    /// Note that all promisor packs are fully decoded the first time, after which the result is cached and shared with all
+    /// instances created from the same [`ThreadSafeRepository`][crate::ThreadSafeRepository]. Promisor packs fetched by
+    /// these instances later are added to it.
+    pub fn list_promisor_objects(&self) -> Result<HashSet<ObjectId>, list::Error> {
+        self.with_promised_objects(|promised| {
+            promised
+                .iter()
+                .filter(|id| !self.objects.contains(id))
+                .copied()
+                .collect()
+        })
+    }
+
+    /// Return true if `id` is [promised by the promisor remote][crate::Repository::list_promisor_objects()], i.e.
+    /// referenced by an object in a promisor pack without being present itself.
+    ///
+    /// Errors while listing promised objects are treated as if `id` isn't promised.
+    pub fn is_promised(&self, id: impl Into<ObjectId>) -> bool {
+        let id = id.into();
+        self.with_promised_objects(|promised| promised.contains(&id))
+            .unwrap_or(false)
+            && !self.objects.contains(id)
+    }
+
+    fn with_promised_objects<T>(&self, f: impl FnOnce(&HashSet<ObjectId>) -> T) -> Result<T, list::Error> {
+        let mut promised = self.promised.lock().expect("no panics while holding the lock");
+        if promised.is_none() {
+            *promised = Some(promised_objects(&self.objects, self.object_hash())?);
+        }
+        Ok(f(promised.as_ref().expect("set above")))
+    }
+
+    /// Return the name of the remote configured in `extensions.partialClone`, which promises to provide all objects
+    /// that are missing from this repository as it is a partial clone, or `None` if this isn't a partial clone.
+    pub fn promisor_remote(&self) -> Option<String> {
+        promisor_remote(&self.config)
+    }
+
+    /// Fetch the objects identified by `ids` from the [promisor remote][crate::Repository::promisor_remote()] into a new
+    /// pack, which is marked with a `.promisor` file like `git` does, without following any references.
+    ///
+    /// Only objects which are not present yet are requested, and nothing happens if all of them are present already.
+    /// The remote must allow requesting arbitrary objects, which protocol version 2 does for all reachable objects.
+    ///
+    /// Note that single objects are fetched automatically when they are looked up but missing, this method is useful
+    /// to obtain many objects at once.
+    pub fn fetch_promised_objects(&self, ids: impl IntoIterator<Item = ObjectId>) -> Result<(), Error> {
+        let mut ids: Vec<_> = ids.into_iter().filter(|id| !self.objects.contains(id)).collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+        ids.sort();
+        ids.dedup();
+
+        let remote_name = self.promisor_remote().ok_or(Error::NoPromisorRemote)?;
+        let url = promisor_url(&self.config, &remote_name)?;
+        let index_path = fetch(url.as_ref(), self.objects.store_ref().path(), self.object_hash(), ids)?;
+        add_promises_of_new_pack(&self.promised, &self.objects, index_path.as_deref(), self.object_hash());
+        Ok(())
+    }
+}
+
+/// Previously computed [promised objects][crate::Repository::list_promisor_objects()], shared by all instances created
+/// from the same [`ThreadSafeRepository`][crate::ThreadSafeRepository] and the functions fetching objects on lookup misses.
+pub(crate) type Cache = std::sync::Arc<std::sync::Mutex<Option<HashSet<ObjectId>>>>;
+
+/// Return all objects referenced by objects in promisor packs of `objects` which aren't present themselves.
+fn promised_objects(objects: &git_odb::Handle, object_hash: git_hash::Kind) -> Result<HashSet<ObjectId>, list::Error> {
+    let mut promised = HashSet::new();
+    for entry in std::fs::read_dir(objects.store_ref().path().join("pack"))? {
+        let promisor_path = entry?.path();
+        if promisor_path.extension().map_or(true, |ext| ext != "promisor") {
+            continue;
+        }
+        add_promises_of_pack(
+            objects,
+            &promisor_path.with_extension("idx"),
+            object_hash,
+            &mut promised,
+        )?;
+    }
+    promised.retain(|id| !objects.contains(id));
+    Ok(promised)
+}
+
+/// Add the promises of the promisor pack with the index at `index_path`, if one was written, to the `promised` objects
+/// if these were computed already. If that fails, they are forgotten to be computed from scratch when needed.
+fn add_promises_of_new_pack(
+    promised: &Cache,
+    objects: &git_odb::Handle,
+    index_path: Option<&Path>,
+    object_hash: git_hash::Kind,
+) {
+    let mut cache = promised.lock().expect("no panics while holding the lock");
+    let updated = match (cache.as_mut(), index_path) {
+        (Some(promised), Some(index_path)) => add_promises_of_pack(objects, index_path, object_hash, promised).is_ok(),
+        _ => true,
+    };
+    if !updated {
+        *cache = None;
+    }
+}
+
+/// Add all objects referenced by objects in the pack with the index at `index_path` to `promised`.
+fn add_promises_of_pack(
+    objects: &git_odb::Handle,
+    index_path: &Path,
+    object_hash: git_hash::Kind,
+    promised: &mut HashSet<ObjectId>,
+) -> Result<(), list::Error> {
+    let mut buf = Vec::new();
+    let index = git_odb::pack::index::File::at(index_path, object_hash)?;
+    for entry in index.iter() {
+        let obj = objects.find(&entry.oid, &mut buf)?;
+        match obj.decode()? {
+            git_object::ObjectRef::Commit(commit) => {
+                promised.extend(Some(commit.tree()).into_iter().chain(commit.parents()))
+            }
+            git_object::ObjectRef::Tree(tree) => promised.extend(
+                tree.entries
+                    .iter()
+                    .filter(|entry| entry.mode != git_object::tree::EntryMode::Commit)
+                    .map(|entry| entry.oid.to_owned()),
+            ),
+            git_object::ObjectRef::Tag(tag) => {
+                promised.insert(tag.target());
+            }
+            git_object::ObjectRef::Blob(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn promisor_remote(config: &crate::config::Cache) -> Option<String> {
+    config
+        .resolved
+        .string("extensions", None, "partialClone")
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_string())
+}
+
+fn promisor_url(config: &crate::config::Cache, remote_name: &str) -> Result<BString, Error> {
+    Ok(config
+        .resolved
+        .string("remote", Some(remote_name), "url")
+        .ok_or_else(|| Error::UnknownRemote {
+            name: remote_name.to_owned(),
+        })?
+        .into_owned())
+}
+
+/// Return a function to install as [`fetch_promised_object`][git_odb::store::Handle::fetch_promised_object] if `config`
+/// belongs to a partial clone, so that missing objects are fetched from the promisor remote when looked up.
+///
+/// Like in `git`, all missing objects are requested without checking if they were promised, as that would require
+/// decoding all promisor packs. The promises of each fetched pack are added to the `promised` objects though.
+pub(crate) fn object_fetcher(
+    config: &crate::config::Cache,
+    objects_dir: &Path,
+    promised: Cache,
+) -> Option<git_odb::store::FetchPromisedObject> {
+    let url = promisor_url(config, &promisor_remote(config)?).ok()?;
+    let objects_dir = objects_dir.to_owned();
+    let object_hash = config.object_hash;
+    Some(std::sync::Arc::new(move |id: &git_hash::oid| {
+        let index_path = fetch(url.as_ref(), &objects_dir, object_hash, vec![id.to_owned()])?;
+        if promised.lock().expect("no panics while holding the lock").is_some() {
+            // A separate handle is used as the one we are installed on would call us again for each missing object.
+            add_promises_of_new_pack(
+                &promised,
+                &git_odb::at(&objects_dir)?,
+                index_path.as_deref(),
+                object_hash,
+            );
+        }
+        Ok(())
+    }))
+}
+
+/// Fetch `ids` from the remote at `url` into a new pack in `objects_dir`, and return the path to its index if one was written.
+fn fetch(
+    url: &BStr,
+    objects_dir: &Path,
+    object_hash: git_hash::Kind,
+    ids: Vec<ObjectId>,
+) -> Result<Option<PathBuf>, Error> {
+    let transport = transport::connect(url, transport::Protocol::V2)?;
+    let mut delegate = Delegate {
+        objects_dir: objects_dir.to_owned(),
+        object_hash,
+        ids,
+        index_path: None,
+    };
+    git_protocol::fetch(
+        transport,
+        &mut delegate,
+        git_protocol::credentials::helper,
+        crate::progress::Discard,
+        git_protocol::FetchConnection::TerminateOnSuccessfulCompletion,
+    )?;
+    Ok(delegate.index_path)
+}
+
+/// Receives a pack with the promised objects, without listing or receiving any references.
+struct Delegate {
+    objects_dir: PathBuf,
+    object_hash: git_hash::Kind,
+    ids: Vec<ObjectId>,
+    /// The path to the index of the received pack, if one was written.
+    index_path: Option<PathBuf>,
+}
+
+impl git_protocol::fetch::DelegateBlocking for Delegate {
+    fn prepare_ls_refs(
+        &mut self,
+        _server: &Capabilities,
+        _arguments: &mut Vec<BString>,
+        _features: &mut Vec<(&str, Option<&str>)>,
+    ) -> io::Result<LsRefsAction> {
+        Ok(LsRefsAction::Skip)
+    }
+
+    fn negotiate(
+        &mut self,
+        _refs: &[Ref],
+        arguments: &mut Arguments,
+        _previous_response: Option<&Response>,
+    ) -> io::Result<Action> {
+        for id in &self.ids {
+            arguments.want(id);
+        }
+        Ok(Action::Cancel)
+    }
+}
+
+impl git_protocol::fetch::Delegate for Delegate {
+    fn receive_pack(
+        &mut self,
+        input: impl io::BufRead,
+        progress: impl Progress,
+        _refs: &[Ref],
+        _previous_response: &Response,
+    ) -> io::Result<()> {
+        // A handle without a promised object fetcher is used as base objects must be present already.
+        let objects = git_odb::at(&self.objects_dir)?;
+        let outcome = crate::fetch::write_pack(
+            input,
+            &self.objects_dir.join("pack"),
+            progress,
+            &crate::interrupt::IS_INTERRUPTED,
+            Some(Box::new(move |id, buf| {
+                objects.try_find(id, buf).ok().flatten().map(|(data, _)| data)
+            })),
+            None,
+            self.object_hash,
+        )?;
+        if let Some(data_path) = outcome.data_path {
+            std::fs::write(data_path.with_extension("promisor"), b"")?;
+        }
+        self.index_path = outcome.index_path;
+        Ok(())
+    }
+}
@@ -0,0 +1,467 @@
+//! Send objects to a remote and update its references, similar to `git push <remote> <refspec>…`.
+use std::{
+    borrow::Cow,
+    convert::TryFrom,
+    io::{self, BufRead, Write},
+};
+
+use git_hash::ObjectId;
+use git_object::bstr::{BString, ByteSlice};
+use git_odb::Find;
+use git_protocol::transport::{
+    self,
+    client::{ExtendedBufRead, MessageKind, Transport, TransportWithoutIO, WriteMode},
+};
+use git_ref::{FullName, Target};
+
+use crate::{ahead_behind, object, pack_objects, reference};
+
+/// Options for use in [`Repository::push()`][crate::Repository::push()].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Strings like `key=value` to pass to the hooks of the remote, similar to `git push --push-option=<option>`.
+    ///
+    /// If empty, the options configured in `push.pushOption` are used instead, see
+    /// [`Repository::push_options()`][crate::Repository::push_options()].
+    pub push_options: Vec<BString>,
+    /// The amount of threads to use at most when creating the pack to send, or all logical cores if `None`.
+    pub thread_limit: Option<usize>,
+}
+
+/// How a reference of the remote was changed by [`Repository::push()`][crate::Repository::push()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// The remote reference didn't exist and was created.
+    New,
+    /// The remote reference already pointed to the object that was pushed.
+    UpToDate,
+    /// The remote reference was updated to a descendant of the commit it pointed to.
+    FastForward,
+    /// The remote reference was updated even though the update isn't a fast-forward, as the refspec starts with `+`.
+    Forced,
+    /// The remote reference was deleted, as the source of the refspec is empty.
+    Deleted,
+    /// The update wasn't sent as it isn't a fast-forward or would change an existing tag.
+    Rejected,
+    /// The update was sent but the remote refused it, with the reason available in [`Update::reason`].
+    RemoteRejected,
+}
+
+/// A reference of the remote along with the object it was set to, as part of an [`Outcome`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Update {
+    /// The name of the reference on the remote, like `refs/heads/main`.
+    pub remote: FullName,
+    /// The object the remote reference pointed to before the push, if it existed.
+    pub previous: Option<ObjectId>,
+    /// The object the remote reference was set to, or `None` if it was deleted.
+    pub new: Option<ObjectId>,
+    /// How the remote reference was changed.
+    pub mode: Mode,
+    /// The reason given by the remote if the mode is [`Mode::RemoteRejected`].
+    pub reason: Option<BString>,
+}
+
+/// The result of [`Repository::push()`][crate::Repository::push()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The URL of the remote that was pushed to.
+    pub url: BString,
+    /// All references named by the refspecs, in the order of the refspecs.
+    pub updates: Vec<Update>,
+    /// True if push options were sent, which is only the case if there were any and the remote advertised `push-options`.
+    pub push_options_sent: bool,
+    /// Information about the pack that was sent, or `None` if no pack was needed.
+    pub pack: Option<pack_objects::Outcome>,
+}
+
+/// The error returned by [`Repository::push()`][crate::Repository::push()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The remote '{name}' has no URL configured in 'remote.{name}.url'")]
+    UnknownRemote { name: String },
+    #[error("No refspecs were given and 'remote.{name}.push' isn't configured")]
+    NoRefspecs { name: String },
+    #[error("The refspec {refspec:?} is invalid: {message}")]
+    InvalidRefspec { refspec: BString, message: &'static str },
+    #[error("The source of refspec {refspec:?} didn't match any reference or object")]
+    UnknownSource { refspec: BString },
+    #[error("The reference name {name:?} is invalid")]
+    InvalidRefName {
+        name: BString,
+        source: git_validate::refname::Error,
+    },
+    #[error("The remote doesn't support deleting references")]
+    DeleteUnsupported,
+    #[error("Unable to delete {name:?} as the remote reference doesn't exist")]
+    DeleteNonexistent { name: BString },
+    #[error("The remote advertised a malformed reference line: {line:?}")]
+    MalformedRefLine { line: String },
+    #[error("The remote failed to unpack the objects: {message}")]
+    Unpack { message: BString },
+    #[error(transparent)]
+    Connect(#[from] transport::client::connect::Error),
+    #[error(transparent)]
+    Transport(#[from] transport::client::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    FindReference(#[from] reference::find::Error),
+    #[error(transparent)]
+    FindExistingReference(#[from] reference::find::existing::Error),
+    #[error(transparent)]
+    FindObject(#[from] object::find::existing::OdbError),
+    #[error(transparent)]
+    AheadBehind(#[from] ahead_behind::Error),
+    #[error(transparent)]
+    PackObjects(#[from] pack_objects::Error),
+}
+
+/// A single reference update to send to the remote.
+struct Command {
+    remote: FullName,
+    previous: Option<ObjectId>,
+    new: Option<ObjectId>,
+    forced: bool,
+}
+
+impl crate::Repository {
+    /// Return the push options configured in `push.pushOption`, used by [`push()`][crate::Repository::push()] if no
+    /// options are given explicitly. Like in `git`, an empty value clears all options configured before it.
+    pub fn push_options(&self) -> Vec<BString> {
+        let mut out = Vec::new();
+        for value in self
+            .config
+            .resolved
+            .strings("push", None, "pushOption")
+            .unwrap_or_default()
+        {
+            if value.is_empty() {
+                out.clear();
+            } else {
+                out.push(value.into_owned());
+            }
+        }
+        out
+    }
+
+    /// Update the references of the remote named `remote_name` as described by `refspecs`, like `main`,
+    /// `+HEAD:refs/heads/other` or `:refs/heads/to-delete`, and send the objects they need along with it, similar to
+    /// `git push <remote_name> <refspec>…`. If `refspecs` is empty, the ones configured in `remote.<remote_name>.push`
+    /// are used.
+    ///
+    /// The remote is contacted at `remote.<remote_name>.pushurl`, or `remote.<remote_name>.url` if unset. Updates are
+    /// only sent if they are a fast-forward or if the refspec starts with `+`, and existing tags are never changed without
+    /// `+`. Updates that aren't sent are returned with [`Mode::Rejected`]. Like in `git`, nothing is pushed if a reference
+    /// to delete doesn't exist on the remote.
+    ///
+    /// [`Options::push_options`] are sent to the remote for use by its hooks only if it advertises the `push-options`
+    /// capability, and are silently skipped otherwise, which is reported in [`Outcome::push_options_sent`].
+    ///
+    /// Note that refspecs with wildcards aren't supported and that the pack sent contains all objects of the pushed commits
+    /// which aren't reachable from the references of the remote that are present locally.
+    pub fn push(&self, remote_name: &str, refspecs: &[&str], opts: Options) -> Result<Outcome, Error> {
+        let config = &self.config.resolved;
+        let url = config
+            .string("remote", Some(remote_name), "pushurl")
+            .or_else(|| config.string("remote", Some(remote_name), "url"))
+            .ok_or_else(|| Error::UnknownRemote {
+                name: remote_name.into(),
+            })?
+            .into_owned();
+        let refspecs: Vec<BString> = if refspecs.is_empty() {
+            config
+                .strings("remote", Some(remote_name), "push")
+                .unwrap_or_default()
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect()
+        } else {
+            refspecs.iter().map(|refspec| (*refspec).into()).collect()
+        };
+        if refspecs.is_empty() {
+            return Err(Error::NoRefspecs {
+                name: remote_name.into(),
+            });
+        }
+        let push_options = if opts.push_options.is_empty() {
+            self.push_options()
+        } else {
+            opts.push_options
+        };
+
+        let mut transport = transport::connect(&url, transport::Protocol::V1)?;
+        let (capabilities, remote_refs) = {
+            let response = transport.handshake(transport::Service::ReceivePack, &[])?;
+            let mut remote_refs = Vec::new();
+            if let Some(mut refs) = response.refs {
+                let mut line = String::new();
+                loop {
+                    line.clear();
+                    if refs.read_line(&mut line)? == 0 {
+                        break;
+                    }
+                    if let Some(remote_ref) = parse_ref_line(&line)? {
+                        remote_refs.push(remote_ref);
+                    }
+                }
+            }
+            (response.capabilities, remote_refs)
+        };
+
+        let mut commands = Vec::with_capacity(refspecs.len());
+        for refspec in &refspecs {
+            let (forced, source, destination) = split_refspec(refspec)?;
+            let (new, remote) = match source {
+                None => (
+                    None,
+                    full_name(destination.expect("deletions always have a destination").into())?,
+                ),
+                Some(source) => {
+                    let (id, name) = self.resolve_source(source, refspec)?;
+                    let remote = match destination {
+                        Some(destination) => full_name(destination.into())?,
+                        None => name.ok_or_else(|| Error::InvalidRefspec {
+                            refspec: refspec.clone(),
+                            message: "the destination is required if the source isn't a reference",
+                        })?,
+                    };
+                    (Some(id), remote)
+                }
+            };
+            let previous = remote_refs
+                .iter()
+                .find(|(name, _)| name == remote.as_bstr())
+                .map(|(_, id)| *id);
+            if new.is_none() && previous.is_none() {
+                return Err(Error::DeleteNonexistent {
+                    name: remote.as_bstr().to_owned(),
+                });
+            }
+            commands.push(Command {
+                remote,
+                previous,
+                new,
+                forced,
+            });
+        }
+        if commands.iter().any(|c| c.new.is_none()) && !capabilities.contains("delete-refs") {
+            return Err(Error::DeleteUnsupported);
+        }
+
+        let mut updates = Vec::with_capacity(commands.len());
+        for command in &commands {
+            let mode = match (command.previous, command.new) {
+                (None, None) => unreachable!("deletions of nonexistent references are rejected above"),
+                (Some(_), None) => Mode::Deleted,
+                (None, Some(_)) => Mode::New,
+                (Some(previous), Some(new)) if previous == new => Mode::UpToDate,
+                (Some(_), Some(_)) if command.forced => Mode::Forced,
+                (Some(_), Some(_)) if command.remote.as_bstr().starts_with(b"refs/tags/") => Mode::Rejected,
+                (Some(previous), Some(new)) => {
+                    if self.objects.contains(previous)
+                        && self.compute_ahead_behind(previous, self.peel_tags(new)?)?.0 == 0
+                    {
+                        Mode::FastForward
+                    } else {
+                        Mode::Rejected
+                    }
+                }
+            };
+            updates.push(Update {
+                remote: command.remote.clone(),
+                previous: command.previous,
+                new: command.new,
+                mode,
+                reason: None,
+            });
+        }
+
+        let to_send: Vec<_> = commands
+            .iter()
+            .zip(&updates)
+            .filter(|(_, update)| !matches!(update.mode, Mode::UpToDate | Mode::Rejected))
+            .map(|(command, _)| command)
+            .collect();
+        if to_send.is_empty() {
+            transport.request(WriteMode::Binary, MessageKind::Flush)?.into_read()?;
+            return Ok(Outcome {
+                url,
+                updates,
+                push_options_sent: false,
+                pack: None,
+            });
+        }
+
+        let report_status = capabilities.contains("report-status");
+        let push_options_sent = !push_options.is_empty() && capabilities.contains("push-options");
+        let mut writer = transport.request(WriteMode::OneLfTerminatedLinePerWriteCall, MessageKind::Flush)?;
+        let null = ObjectId::null(self.object_hash());
+        for (index, command) in to_send.iter().enumerate() {
+            let mut line: BString = format!(
+                "{} {} {}",
+                command.previous.unwrap_or(null),
+                command.new.unwrap_or(null),
+                command.remote.as_bstr()
+            )
+            .into();
+            if index == 0 {
+                let (agent_name, agent_version) = git_protocol::fetch::agent();
+                let mut features = vec![format!("{}={}", agent_name, agent_version.unwrap_or_default())];
+                if report_status {
+                    features.push("report-status".into());
+                }
+                if push_options_sent {
+                    features.push("push-options".into());
+                }
+                line.push(0);
+                line.extend_from_slice(features.join(" ").as_bytes());
+            }
+            writer.write_all(&line)?;
+        }
+        writer.write_message(MessageKind::Flush)?;
+        if push_options_sent {
+            for option in &push_options {
+                writer.write_all(option)?;
+            }
+            writer.write_message(MessageKind::Flush)?;
+        }
+
+        let (mut out, mut reader) = writer.into_parts();
+        let pack = if to_send.iter().any(|command| command.new.is_some()) {
+            let mut haves = Vec::new();
+            for id in remote_refs.iter().map(|(_, id)| *id) {
+                if self.objects.contains(id) {
+                    haves.push(self.peel_tags(id)?);
+                }
+            }
+            let mut ids = Vec::new();
+            let mut tips = Vec::new();
+            for new in to_send.iter().filter_map(|command| command.new) {
+                let tip = self.peel_tags(new)?;
+                if tip != new {
+                    ids.push(new);
+                }
+                tips.push(tip);
+            }
+            let (commits, _) = self.commits().left_right::<ahead_behind::Error>(&tips, &haves)?;
+            ids.extend(commits);
+            let outcome = self.pack_objects(
+                ids,
+                &mut out,
+                crate::progress::Discard,
+                pack_objects::Options {
+                    expansion: pack_objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+                    thread_limit: opts.thread_limit,
+                    allow_thin_pack: false,
+                    delta_reuse: true,
+                    ..Default::default()
+                },
+            )?;
+            Some(outcome)
+        } else {
+            None
+        };
+        out.flush()?;
+
+        if report_status {
+            reader.reset(transport::Protocol::V1);
+            let mut line = String::new();
+            loop {
+                line.clear();
+                if reader.read_line(&mut line)? == 0 {
+                    break;
+                }
+                let line = line.trim_end();
+                if let Some(status) = line.strip_prefix("unpack ") {
+                    if status != "ok" {
+                        return Err(Error::Unpack { message: status.into() });
+                    }
+                } else if let Some(rest) = line.strip_prefix("ng ") {
+                    let (name, reason) = rest.split_once(' ').unwrap_or((rest, ""));
+                    if let Some(update) = updates.iter_mut().find(|update| update.remote.as_bstr() == name) {
+                        update.mode = Mode::RemoteRejected;
+                        update.reason = Some(reason.into());
+                    }
+                }
+            }
+        }
+        Ok(Outcome {
+            url,
+            updates,
+            push_options_sent,
+            pack,
+        })
+    }
+
+    /// Return the id `source` refers to along with the name of the reference it is read from, if it is a reference.
+    fn resolve_source(&self, source: &str, refspec: &BString) -> Result<(ObjectId, Option<FullName>), Error> {
+        let unknown = || Error::UnknownSource {
+            refspec: refspec.clone(),
+        };
+        match self.try_find_reference(source).ok().flatten().map(|r| r.detach()) {
+            Some(r) => match r.target {
+                Target::Peeled(id) => Ok((id, Some(r.name))),
+                Target::Symbolic(target) => {
+                    let r = self.find_reference(&target)?.detach();
+                    let id = r.target.try_id().ok_or_else(unknown)?.to_owned();
+                    Ok((id, Some(r.name)))
+                }
+            },
+            None => ObjectId::from_hex(source.as_bytes())
+                .ok()
+                .filter(|id| self.objects.contains(id))
+                .map(|id| (id, None))
+                .ok_or_else(unknown),
+        }
+    }
+
+    /// Follow `id` through tag objects until something that isn't a tag is reached.
+    fn peel_tags(&self, id: ObjectId) -> Result<ObjectId, Error> {
+        Ok(self.find_object(id)?.peel_tags_to_end()?.id)
+    }
+}
+
+/// Split `refspec` into whether it is forced, its source unless it is a deletion, and its destination if given.
+fn split_refspec(refspec: &BString) -> Result<(bool, Option<&str>, Option<&str>), Error> {
+    let invalid = |message| Error::InvalidRefspec {
+        refspec: refspec.clone(),
+        message,
+    };
+    let spec = refspec.to_str().map_err(|_| invalid("it isn't valid UTF-8"))?;
+    if spec.contains('*') {
+        return Err(invalid("wildcards aren't supported"));
+    }
+    let (forced, spec) = match spec.strip_prefix('+') {
+        Some(spec) => (true, spec),
+        None => (false, spec),
+    };
+    let (source, destination) = match spec.split_once(':') {
+        Some((source, destination)) => (source, (!destination.is_empty()).then(|| destination)),
+        None => (spec, None),
+    };
+    match (source.is_empty(), destination) {
+        (true, None) => Err(invalid("the source and destination can't both be empty")),
+        (true, Some(_)) => Ok((forced, None, destination)),
+        (false, _) => Ok((forced, Some(source), destination)),
+    }
+}
+
+/// Parse a line of the reference advertisement of `git-receive-pack`, skipping peeled tags and the placeholder sent
+/// by empty repositories.
+fn parse_ref_line(line: &str) -> Result<Option<(BString, ObjectId)>, Error> {
+    let malformed = || Error::MalformedRefLine {
+        line: line.trim_end().into(),
+    };
+    let (hex, name) = line.trim_end().split_once(' ').ok_or_else(malformed)?;
+    if name.ends_with("^{}") {
+        return Ok(None);
+    }
+    let id = ObjectId::from_hex(hex.as_bytes()).map_err(|_| malformed())?;
+    Ok(Some((name.into(), id)))
+}
+
+fn full_name(name: BString) -> Result<FullName, Error> {
+    FullName::try_from(name.as_bstr()).map_err(|source| Error::InvalidRefName { name, source })
+}
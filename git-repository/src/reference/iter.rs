@@ -11,6 +11,12 @@ pub struct Platform<'r> {
     pub(crate) repo: &'r crate::Repository,
 }
 
+/// A platform to create iterators over references which are sorted by their full name, obtained with [`Platform::sorted()`].
+#[must_use = "Iterators should be obtained from this iterator platform"]
+pub struct SortedPlatform<'p> {
+    platform: &'p Platform<'p>,
+}
+
 /// An iterator over references, with or without filter.
 pub struct Iter<'r> {
     inner: git_ref::file::iter::LooseThenPacked<'r, 'r>,
@@ -19,7 +25,7 @@ pub struct Iter<'r> {
 }
 
 impl<'r> Iter<'r> {
-    fn new(repo: &'r crate::Repository, platform: git_ref::file::iter::LooseThenPacked<'r, 'r>) -> Self {
+    pub(crate) fn new(repo: &'r crate::Repository, platform: git_ref::file::iter::LooseThenPacked<'r, 'r>) -> Self {
         Iter {
             inner: platform,
             peel: false,
@@ -69,6 +75,87 @@ impl<'r> Platform<'r> {
     pub fn remote_branches(&self) -> Result<Iter<'_>, init::Error> {
         Ok(Iter::new(self.repo, self.platform.prefixed("refs/remotes/")?))
     }
+
+    /// Return a platform whose iterators yield references sorted by their full name, independently of the order in which
+    /// the file system lists them, as needed for deterministic output similar to `git for-each-ref`.
+    pub fn sorted(&self) -> SortedPlatform<'_> {
+        SortedPlatform { platform: self }
+    }
+}
+
+/// An iterator over references sorted by their full name, obtained with [`SortedPlatform`].
+///
+/// Errors are yielded after all references, in the order they were encountered.
+pub struct SortedIter<'r> {
+    inner: Option<Iter<'r>>,
+    sorted: std::vec::IntoIter<<Iter<'r> as Iterator>::Item>,
+}
+
+impl<'r> SortedIter<'r> {
+    fn new(inner: Iter<'r>) -> Self {
+        SortedIter {
+            inner: Some(inner),
+            sorted: Vec::new().into_iter(),
+        }
+    }
+
+    /// Automatically peel references before yielding them during iteration.
+    ///
+    /// See [`Iter::peeled()`] for details.
+    pub fn peeled(mut self) -> Self {
+        self.inner = self.inner.map(Iter::peeled);
+        self
+    }
+}
+
+impl<'r> Iterator for SortedIter<'r> {
+    type Item = <Iter<'r> as Iterator>::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(inner) = self.inner.take() {
+            let mut all: Vec<_> = inner.collect();
+            // the sort is stable, so errors keep their order.
+            all.sort_by(|a, b| match (a, b) {
+                (Ok(a), Ok(b)) => a.name().as_bstr().cmp(b.name().as_bstr()),
+                (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+                (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+                (Err(_), Err(_)) => std::cmp::Ordering::Equal,
+            });
+            self.sorted = all.into_iter();
+        }
+        self.sorted.next()
+    }
+}
+
+impl<'p> SortedPlatform<'p> {
+    /// Return an iterator over all references in the repository, sorted by their full name.
+    ///
+    /// See [`Platform::all()`] for details.
+    pub fn all(&self) -> Result<SortedIter<'p>, init::Error> {
+        self.platform.all().map(SortedIter::new)
+    }
+
+    /// Return an iterator over all references that match the given `prefix`, sorted by their full name.
+    ///
+    /// See [`Platform::prefixed()`] for details.
+    pub fn prefixed(&self, prefix: impl AsRef<Path>) -> Result<SortedIter<'p>, init::Error> {
+        self.platform.prefixed(prefix).map(SortedIter::new)
+    }
+
+    /// Return an iterator over all references that are tags, sorted by their full name.
+    pub fn tags(&self) -> Result<SortedIter<'p>, init::Error> {
+        self.platform.tags().map(SortedIter::new)
+    }
+
+    /// Return an iterator over all local branches, sorted by their full name.
+    pub fn local_branches(&self) -> Result<SortedIter<'p>, init::Error> {
+        self.platform.local_branches().map(SortedIter::new)
+    }
+
+    /// Return an iterator over all remote branches, sorted by their full name.
+    pub fn remote_branches(&self) -> Result<SortedIter<'p>, init::Error> {
+        self.platform.remote_branches().map(SortedIter::new)
+    }
 }
 
 impl<'r> Iter<'r> {
@@ -114,7 +201,8 @@ impl<'r> Iterator for Iter<'r> {
 
 ///
 pub mod init {
-    /// The error returned by [`Platform::all()`][super::Platform::all()] or [`Platform::prefixed()`][super::Platform::prefixed()].
+    /// The error returned by [`Platform::all()`][super::Platform::all()], [`Platform::prefixed()`][super::Platform::prefixed()]
+    /// or [`Repository::list_loose_refs()`][crate::Repository::list_loose_refs()].
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
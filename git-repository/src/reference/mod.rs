@@ -3,7 +3,10 @@
 use git_odb::pack::Find;
 use git_ref::file::ReferenceExt;
 
-use crate::{Id, Reference};
+use crate::{
+    bstr::{BStr, ByteSlice},
+    Id, Reference,
+};
 
 pub mod iter;
 
@@ -28,12 +31,50 @@ impl<'repo> Reference<'repo> {
         self.inner.name.as_ref()
     }
 
+    /// Return the reference's name without its `refs/heads/`, `refs/tags/`, `refs/remotes/` or `refs/notes/` prefix, for
+    /// example `main` for `refs/heads/main` or `origin/main` for `refs/remotes/origin/main`, or its full name if it has
+    /// none of these prefixes.
+    ///
+    /// Note that unlike [`FullNameRef::shorten()`][git_ref::FullNameRef::shorten()], other well-known prefixes are kept.
+    pub fn short_name(&self) -> &BStr {
+        let name = self.name().as_bstr();
+        match self.name().category() {
+            Some(category @ (Category::Tag | Category::LocalBranch | Category::RemoteBranch | Category::Note)) => {
+                name[category.prefix().len()..].as_bstr()
+            }
+            _ => name,
+        }
+    }
+
     /// Turn this instances into a stand-alone reference.
     pub fn detach(self) -> git_ref::Reference {
         self.inner
     }
 }
 
+/// Classification
+impl<'repo> Reference<'repo> {
+    /// Return true if this is a tag in `refs/tags/`.
+    pub fn is_tag(&self) -> bool {
+        self.name().category() == Some(Category::Tag)
+    }
+
+    /// Return true if this is a local branch in `refs/heads/`.
+    pub fn is_branch(&self) -> bool {
+        self.name().category() == Some(Category::LocalBranch)
+    }
+
+    /// Return true if this is a remote tracking branch in `refs/remotes/`.
+    pub fn is_remote_tracking(&self) -> bool {
+        self.name().category() == Some(Category::RemoteBranch)
+    }
+
+    /// Return true if this is a note in `refs/notes/`.
+    pub fn is_note(&self) -> bool {
+        self.name().category() == Some(Category::Note)
+    }
+}
+
 impl<'repo> std::fmt::Debug for Reference<'repo> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Debug::fmt(&self.inner, f)
@@ -0,0 +1,203 @@
+//! Remove old entries from reflogs, similar to `git reflog expire`.
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use git_hash::ObjectId;
+use git_odb::FindExt;
+use git_ref::{file::log, FullName};
+
+use crate::reference;
+
+/// Options for use in [`Repository::expire_reflog()`][crate::Repository::expire_reflog()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// Entries older than this are removed.
+    pub expire: Duration,
+    /// If set and different from [`expire`][Options::expire], entries older than this are removed unless the commit they
+    /// point to is reachable from any reference, including the stash. Reachable entries are removed once they are older
+    /// than [`expire`][Options::expire].
+    pub expire_unreachable: Option<Duration>,
+    /// If true, all reflogs are processed instead of only the one of the given reference.
+    pub all: bool,
+}
+
+impl Default for Options {
+    /// Expire all entries older than 90 days, the default of `git reflog expire`, in the reflog of the given reference only.
+    fn default() -> Self {
+        Options {
+            expire: Duration::from_secs(90 * 24 * 60 * 60),
+            expire_unreachable: None,
+            all: false,
+        }
+    }
+}
+
+/// Statistics about the work done by [`Repository::expire_reflog()`][crate::Repository::expire_reflog()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The amount of reflogs that were processed.
+    pub logs: usize,
+    /// The amount of entries which were kept.
+    pub entries_kept: usize,
+    /// The amount of entries which were removed.
+    pub entries_removed: usize,
+}
+
+/// The error returned by [`Repository::expire_reflog()`][crate::Repository::expire_reflog()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    ReferenceName(#[from] git_validate::refname::Error),
+    #[error("Could not list or read reflogs")]
+    Io(#[from] std::io::Error),
+    #[error("Could not lock the reflog at '{}'", path.display())]
+    Lock {
+        path: PathBuf,
+        source: git_lock::acquire::Error,
+    },
+    #[error("Could not parse the reflog at '{}'", path.display())]
+    Decode {
+        path: PathBuf,
+        source: log::iter::decode::Error,
+    },
+    #[error("Could not write the reflog at '{}'", path.display())]
+    Commit {
+        path: PathBuf,
+        source: git_lock::commit::Error<git_lock::File>,
+    },
+    #[error(transparent)]
+    ReferenceIterInit(#[from] reference::iter::init::Error),
+    #[error(transparent)]
+    ReferenceIter(#[from] reference::iter::Error),
+    #[error("A reference could not be read")]
+    Reference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::OdbError),
+    #[error(transparent)]
+    Ancestors(#[from] git_traverse::commit::ancestors::Error),
+}
+
+impl crate::Repository {
+    /// Remove entries older than [`Options::expire`] from the reflog of the reference named `ref_name`, or from all reflogs
+    /// if [`Options::all`] is set in which case `ref_name` is ignored, similar to `git reflog expire --expire=<time>`.
+    ///
+    /// Each reflog is locked while it's rewritten and replaced atomically, and it's left untouched if no entry is removed.
+    /// A reflog which doesn't exist is not an error, and [`Stats::logs`] won't count it.
+    pub fn expire_reflog(&self, ref_name: &str, opts: Options) -> Result<Stats, Error> {
+        let mut stats = Stats::default();
+        let ref_name = (!opts.all).then(|| FullName::try_from(ref_name)).transpose()?;
+        let expire = cutoff_seconds(opts.expire);
+        let expire_unreachable = opts
+            .expire_unreachable
+            .filter(|expire_unreachable| *expire_unreachable != opts.expire)
+            .map(cutoff_seconds);
+        let mut reachable = None;
+
+        for log in self.refs.iter_logs() {
+            let (name, path) = log?;
+            if ref_name.as_ref().map_or(false, |ref_name| *ref_name != name) {
+                continue;
+            }
+            stats.logs += 1;
+            let lock = git_lock::File::acquire_to_update_resource(&path, git_lock::acquire::Fail::Immediately, None)
+                .map_err(|source| Error::Lock {
+                    path: path.clone(),
+                    source,
+                })?;
+
+            let data = std::fs::read(&path)?;
+            let mut kept = Vec::new();
+            let mut removed = 0;
+            for line in log::iter::forward(&data) {
+                let line = line.map_err(|source| Error::Decode {
+                    path: path.clone(),
+                    source,
+                })?;
+                let time = u64::from(line.signature.time.seconds_since_unix_epoch);
+                let is_expired = match expire_unreachable {
+                    Some(expire_unreachable) if is_older(time, expire) != is_older(time, expire_unreachable) => {
+                        if reachable.is_none() {
+                            reachable = Some(self.commits_reachable_from_references()?);
+                        }
+                        let is_reachable = reachable.as_ref().expect("just set").contains(&line.new_oid());
+                        is_older(time, if is_reachable { expire } else { expire_unreachable })
+                    }
+                    _ => is_older(time, expire),
+                };
+                if is_expired {
+                    removed += 1;
+                } else {
+                    kept.push(line.to_owned());
+                }
+            }
+
+            stats.entries_kept += kept.len();
+            stats.entries_removed += removed;
+            if removed != 0 {
+                write_log(lock, &path, &kept)?;
+            }
+        }
+        Ok(stats)
+    }
+
+    fn commits_reachable_from_references(&self) -> Result<HashSet<ObjectId>, Error> {
+        let mut tips = HashSet::new();
+        let platform = self.references()?;
+        for r in platform.all()?.peeled() {
+            let r = r.map_err(Error::Reference)?.detach();
+            let tip = match r.peeled.or_else(|| r.target.try_id().map(ToOwned::to_owned)) {
+                Some(tip) if !tips.contains(&tip) => tip,
+                _ => continue,
+            };
+            if self.find_object(tip)?.kind == git_object::Kind::Commit {
+                tips.insert(tip);
+            }
+        }
+
+        // A single traversal of all tips visits each commit only once, no matter how many references share its history.
+        let mut reachable = HashSet::new();
+        let traversal = git_traverse::commit::Ancestors::new(
+            tips,
+            git_traverse::commit::ancestors::State::default(),
+            |oid, buf| self.objects.find_commit_iter(oid, buf),
+        );
+        for id in traversal {
+            match id {
+                Ok(id) => reachable.insert(id),
+                // commits missing in shallow clones are skipped, but the traversal continues with the remaining ones.
+                Err(git_traverse::commit::ancestors::Error::FindExisting { oid, .. }) => reachable.insert(oid),
+                Err(err) => return Err(err.into()),
+            };
+        }
+        Ok(reachable)
+    }
+}
+
+/// Return the time in seconds since the unix epoch before which entries are older than `age`, or `None` if no entry can be.
+fn cutoff_seconds(age: Duration) -> Option<u64> {
+    SystemTime::now()
+        .checked_sub(age)
+        .and_then(|cutoff| cutoff.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|cutoff| cutoff.as_secs())
+}
+
+fn is_older(time: u64, cutoff: Option<u64>) -> bool {
+    cutoff.map_or(false, |cutoff| time < cutoff)
+}
+
+fn write_log(mut lock: git_lock::File, path: &Path, lines: &[git_ref::log::Line]) -> Result<(), Error> {
+    for line in lines {
+        line.write_to(&mut lock)?;
+    }
+    lock.commit().map_err(|source| Error::Commit {
+        path: path.to_owned(),
+        source,
+    })?;
+    Ok(())
+}
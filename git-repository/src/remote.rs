@@ -0,0 +1,41 @@
+//! Obtain information about remotes as configured for branches.
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`Repository::push_remote_for_branch()`][crate::Repository::push_remote_for_branch()] and
+/// [`Repository::tracking_branch_for()`][crate::Repository::tracking_branch_for()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("'{name}' is not a local branch")]
+    NotALocalBranch { name: BString },
+    #[error("The value '{value}' of '{key}' is not a valid reference name")]
+    InvalidReferenceName {
+        key: String,
+        value: BString,
+        source: git_validate::reference::name::Error,
+    },
+}
+
+/// Map `name` with the source of the fetch `refspec`, like `+refs/heads/*:refs/remotes/origin/*`, to its destination,
+/// or return `None` if the source doesn't match `name`.
+pub(crate) fn map_with_fetch_refspec(refspec: &BStr, name: &BStr) -> Option<BString> {
+    let (refspec, name) = (refspec.as_bytes(), name.as_bytes());
+    let refspec = refspec.strip_prefix(b"+").unwrap_or(refspec);
+    let colon = refspec.find_byte(b':')?;
+    let (source, destination) = (&refspec[..colon], &refspec[colon + 1..]);
+    match (source.find_byte(b'*'), destination.find_byte(b'*')) {
+        (None, _) => (source == name).then(|| destination.into()),
+        (Some(source_star), Some(destination_star)) => {
+            let (prefix, suffix) = (&source[..source_star], &source[source_star + 1..]);
+            if name.len() < prefix.len() + suffix.len() || !name.starts_with(prefix) || !name.ends_with(suffix) {
+                return None;
+            }
+            let matched = &name[prefix.len()..name.len() - suffix.len()];
+            let mut out = BString::from(&destination[..destination_star]);
+            out.extend_from_slice(matched);
+            out.extend_from_slice(&destination[destination_star + 1..]);
+            Some(out)
+        }
+        (Some(_), None) => None,
+    }
+}
@@ -0,0 +1,162 @@
+//! Consolidate all packs of the object database into a single one, similar to `git repack -a -d`.
+use std::path::{Path, PathBuf};
+
+use git_odb::pack;
+use git_tempfile::{handle::Writable, AutoRemove, ContainingDirectory};
+
+use crate::{index_pack, pack_objects, Progress};
+
+/// Options for use in [`Repository::repack()`][crate::Repository::repack()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If true, objects already stored as deltas are copied into the new pack as is, and no new deltas are computed.
+    ///
+    /// Otherwise new deltas are computed for all objects, similar to `git repack -f`.
+    pub delta_reuse: bool,
+    /// The amount of objects to consider as delta base when computing new deltas, similar to `git repack --window`.
+    ///
+    /// If `0` and [`delta_reuse`][Options::delta_reuse] isn't set, all objects are stored as base objects.
+    pub window: usize,
+    /// The maximum length of delta chains when computing new deltas, similar to `git repack --depth`.
+    pub depth: usize,
+    /// If set, packs larger than this amount of bytes are left untouched and only the remaining ones are consolidated.
+    pub max_input_pack_size: Option<u64>,
+}
+
+impl Default for Options {
+    /// The same defaults as used by `git repack`.
+    fn default() -> Self {
+        Options {
+            delta_reuse: true,
+            window: 10,
+            depth: 50,
+            max_input_pack_size: None,
+        }
+    }
+}
+
+/// Statistics about the work done by [`Repository::repack()`][crate::Repository::repack()].
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The amount of packs that were consolidated.
+    pub packs: usize,
+    /// The amount of distinct objects in the new pack.
+    pub objects: usize,
+    /// The path to the new pack, or `None` if there were no packs to consolidate.
+    pub pack_path: Option<PathBuf>,
+}
+
+/// The error returned by [`Repository::repack()`][crate::Repository::repack()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not list or remove packs")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] pack::index::init::Error),
+    #[error(transparent)]
+    PackObjects(#[from] pack_objects::Error),
+    #[error("Could not move the new pack into place")]
+    Persist(#[from] git_tempfile::handle::persist::Error<Writable>),
+    #[error(transparent)]
+    IndexPack(#[from] index_pack::Error),
+    #[error("The new pack lacks {missing} of {expected} objects, keeping all previous packs")]
+    Verify { missing: usize, expected: usize },
+}
+
+impl crate::Repository {
+    /// Write all objects of all packs into a single new pack along with its index, and remove the previous packs once the new
+    /// one was verified to contain all of their objects, similar to `git repack -a -d`.
+    ///
+    /// Packs marked with a `.keep` file are left untouched, as are loose objects and packs larger than
+    /// [`max_input_pack_size`][Options::max_input_pack_size].
+    pub fn repack(&self, mut progress: impl Progress, opts: Options) -> Result<Stats, Error> {
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        let mut index_paths = Vec::new();
+        for (index_path, pack_size) in self.repackable_packs()? {
+            if opts.max_input_pack_size.map_or(true, |max_size| pack_size <= max_size) {
+                index_paths.push(index_path);
+            }
+        }
+        if index_paths.is_empty() {
+            return Ok(Stats::default());
+        }
+        index_paths.sort();
+
+        let mut ids = Vec::new();
+        for index_path in &index_paths {
+            let index = pack::index::File::at(index_path, self.object_hash())?;
+            ids.extend(index.iter().map(|entry| entry.oid));
+        }
+        ids.sort();
+        ids.dedup();
+
+        let mut pack_file = git_tempfile::new(&pack_dir, ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+        let outcome = self.pack_objects(
+            ids.iter().copied(),
+            &mut pack_file,
+            progress.add_child("write pack"),
+            pack_objects::Options {
+                expansion: pack_objects::ObjectExpansion::AsIs,
+                thread_limit: None,
+                allow_thin_pack: false,
+                delta_reuse: opts.delta_reuse,
+                delta_window: opts.window,
+                max_delta_depth: opts.depth,
+            },
+        )?;
+        let pack_path = pack_dir.join(format!("pack-{}.pack", outcome.checksum));
+        pack_file.persist(&pack_path)?;
+        let new_index_path = self.index_pack(&pack_path, progress.add_child("index pack"))?;
+
+        let new_index = pack::index::File::at(&new_index_path, self.object_hash())?;
+        let missing = ids.iter().filter(|id| new_index.lookup(id).is_none()).count();
+        if missing != 0 {
+            return Err(Error::Verify {
+                missing,
+                expected: ids.len(),
+            });
+        }
+
+        for index_path in index_paths.iter().filter(|path| **path != new_index_path) {
+            remove_pack(index_path)?;
+        }
+        Ok(Stats {
+            packs: index_paths.len(),
+            objects: ids.len(),
+            pack_path: Some(pack_path),
+        })
+    }
+}
+
+impl crate::Repository {
+    /// Return the paths to the indices of all packs not marked with a `.keep` file along with the size of their pack in bytes.
+    pub(crate) fn repackable_packs(&self) -> std::io::Result<Vec<(PathBuf, u64)>> {
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        let mut packs = Vec::new();
+        for entry in std::fs::read_dir(&pack_dir)? {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "idx") && !path.with_extension("keep").exists() {
+                match std::fs::metadata(path.with_extension("pack")) {
+                    Ok(metadata) if metadata.is_file() => packs.push((path, metadata.len())),
+                    Ok(_) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+        Ok(packs)
+    }
+}
+
+/// Remove the pack with the index at `index_path`, starting with the index so it isn't used anymore.
+fn remove_pack(index_path: &Path) -> std::io::Result<()> {
+    std::fs::remove_file(index_path)?;
+    for extension in ["rev", "bitmap"] {
+        match std::fs::remove_file(index_path.with_extension(extension)) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            res => res?,
+        }
+    }
+    std::fs::remove_file(index_path.with_extension("pack"))
+}
@@ -18,6 +18,30 @@ impl crate::Repository {
         }
     }
 
+    /// Load all objects identified by `ids` into the object cache to speed up their first access, which is useful in long-running
+    /// processes, and return the amount of objects that are held by the cache afterwards.
+    ///
+    /// Nothing is loaded if no [object cache is configured][crate::Repository::object_cache_size()]. Objects larger than the cache
+    /// are skipped, and objects loaded early may be evicted again if the cache can't hold all of them.
+    pub fn object_cache_warm(
+        &self,
+        ids: impl IntoIterator<Item = git_hash::ObjectId>,
+    ) -> Result<usize, crate::object::find::existing::OdbError> {
+        use git_odb::FindExt;
+        if !self.objects.has_object_cache() {
+            return Ok(0);
+        }
+        let mut ids: Vec<_> = ids.into_iter().collect();
+        ids.sort();
+        ids.dedup();
+        let mut buf = self.free_buf();
+        for id in &ids {
+            self.objects.find(id, &mut buf)?;
+        }
+        self.reuse_buffer(&mut buf);
+        Ok(ids.iter().filter(|id| self.objects.object_cache_contains(id)).count())
+    }
+
     /// Read well-known environment variables related to caches and apply them to this instance, but not to clones of it - each
     /// needs their own configuration.
     ///
@@ -0,0 +1,175 @@
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use git_hash::{oid, ObjectId};
+use git_odb::FindExt;
+
+const LEFT: u8 = 1 << 0;
+const RIGHT: u8 = 1 << 1;
+const BOTH: u8 = LEFT | RIGHT;
+
+/// The generation of commits that aren't in the commit-graph, the same as `git_commitgraph::GENERATION_NUMBER_INFINITY`
+/// which isn't available if the commit-graph isn't read.
+const GENERATION_NUMBER_INFINITY: u32 = 0xffff_ffff;
+
+/// Errors that can be created from errors of the commit-graph, if it's read at all.
+#[cfg(feature = "git-commitgraph")]
+pub(crate) trait FromGraphError: From<git_commitgraph::file::commit::Error> {}
+#[cfg(feature = "git-commitgraph")]
+impl<E: From<git_commitgraph::file::commit::Error>> FromGraphError for E {}
+/// Errors that can be created from errors of the commit-graph, if it's read at all.
+#[cfg(not(feature = "git-commitgraph"))]
+pub(crate) trait FromGraphError {}
+#[cfg(not(feature = "git-commitgraph"))]
+impl<E> FromGraphError for E {}
+
+/// The order in which commits are traversed, newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Order {
+    /// The generation number as stored in the commit-graph, or infinity for commits that aren't in it.
+    pub generation: u32,
+    /// The committer time in seconds since the unix epoch.
+    pub time: u64,
+}
+
+impl Order {
+    /// Return true if the commit with this order can't possibly have the commit with `ancestor` order as its ancestor,
+    /// which can only be known if both are in the commit-graph.
+    pub fn cannot_reach(&self, ancestor: &Order) -> bool {
+        use self::GENERATION_NUMBER_INFINITY as INFINITY;
+        // a generation of zero is written by older versions of git that didn't compute them.
+        let is_known = |generation: u32| generation != 0 && generation != INFINITY;
+        is_known(self.generation)
+            && (ancestor.generation == INFINITY
+                || (is_known(ancestor.generation) && self.generation <= ancestor.generation))
+    }
+}
+
+/// Obtain the parents and the traversal order of commits, from the commit-graph if possible.
+pub(crate) struct Commits<'repo> {
+    repo: &'repo crate::Repository,
+    #[cfg(feature = "git-commitgraph")]
+    graph: Option<git_commitgraph::Graph>,
+    buf: Vec<u8>,
+}
+
+impl crate::Repository {
+    pub(crate) fn commits(&self) -> Commits<'_> {
+        Commits {
+            repo: self,
+            #[cfg(feature = "git-commitgraph")]
+            graph: git_commitgraph::Graph::from_info_dir(self.objects.store_ref().path().join("info")).ok(),
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl<'repo> Commits<'repo> {
+    /// Return the traversal order and the parents of the commit with `id`.
+    pub fn lookup<E>(&mut self, id: &oid) -> Result<(Order, Vec<ObjectId>), E>
+    where
+        E: From<git_odb::find::existing_iter::Error<git_odb::store::find::Error>>
+            + From<git_object::decode::Error>
+            + FromGraphError,
+    {
+        #[cfg(feature = "git-commitgraph")]
+        if let Some((graph, commit)) = self
+            .graph
+            .as_ref()
+            .and_then(|graph| graph.commit_by_id(id).map(|commit| (graph, commit)))
+        {
+            let parents = commit
+                .iter_parents()
+                .map(|parent| parent.map(|parent| graph.id_at(parent).to_owned()))
+                .collect::<Result<_, _>>()?;
+            let order = Order {
+                generation: commit.generation(),
+                time: commit.committer_timestamp(),
+            };
+            return Ok((order, parents));
+        }
+        let commit = self.repo.objects.find_commit_iter(id, &mut self.buf)?;
+        let parents = commit.parent_ids().collect();
+        let order = Order {
+            generation: GENERATION_NUMBER_INFINITY,
+            time: commit.committer()?.time.seconds_since_unix_epoch.into(),
+        };
+        Ok((order, parents))
+    }
+
+    /// Return `(left_only, right_only)`, the commits reachable from the `left` tips but not from the `right` ones and vice versa,
+    /// newest first.
+    ///
+    /// Commits are traversed newest first until only commits reachable from both sides remain. Commits in the commit-graph
+    /// are ordered by their generation number, which makes the result exact, all others by their committer time, making the
+    /// result subject to clock skew like it is in `git`.
+    pub fn left_right<E>(&mut self, left: &[ObjectId], right: &[ObjectId]) -> Result<(Vec<ObjectId>, Vec<ObjectId>), E>
+    where
+        E: From<git_odb::find::existing_iter::Error<git_odb::store::find::Error>>
+            + From<git_object::decode::Error>
+            + FromGraphError,
+    {
+        let mut flags = HashMap::<ObjectId, u8>::new();
+        for (tips, side) in [(left, LEFT), (right, RIGHT)] {
+            for id in tips {
+                *flags.entry(*id).or_default() |= side;
+            }
+        }
+        let mut commits = HashMap::<ObjectId, (Order, Vec<ObjectId>)>::new();
+        let mut queue = BinaryHeap::new();
+        // the queued commits which aren't yet known to be reachable from both sides.
+        let mut queued_one_sided = HashSet::new();
+        for (id, id_flags) in &flags {
+            let (order, parents) = self.lookup::<E>(id)?;
+            commits.insert(*id, (order, parents));
+            queue.push((order, *id));
+            if *id_flags != BOTH {
+                queued_one_sided.insert(*id);
+            }
+        }
+
+        let mut visited = Vec::new();
+        let mut visited_flags = HashMap::<ObjectId, u8>::new();
+        while !queued_one_sided.is_empty() {
+            let (_, id) = queue.pop().expect("one-sided commits are queued");
+            queued_one_sided.remove(&id);
+            let commit_flags = flags[&id];
+            // with clock skew, commits may be visited again after learning they are reachable from the other side.
+            match visited_flags.insert(id, commit_flags) {
+                Some(previous) if previous == commit_flags => continue,
+                Some(_) => {}
+                None => visited.push(id),
+            }
+
+            for parent in commits[&id].1.clone() {
+                let parent_flags = flags.entry(parent).or_default();
+                if *parent_flags | commit_flags == *parent_flags {
+                    continue;
+                }
+                *parent_flags |= commit_flags;
+                if *parent_flags == BOTH {
+                    queued_one_sided.remove(&parent);
+                } else {
+                    queued_one_sided.insert(parent);
+                }
+                let order = match commits.get(&parent) {
+                    Some((order, _)) => *order,
+                    None => {
+                        let (order, parents) = self.lookup::<E>(&parent)?;
+                        commits.insert(parent, (order, parents));
+                        order
+                    }
+                };
+                queue.push((order, parent));
+            }
+        }
+
+        let only = |side: u8| {
+            visited
+                .iter()
+                .filter(|id| flags[*id] == side)
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        Ok((only(LEFT), only(RIGHT)))
+    }
+}
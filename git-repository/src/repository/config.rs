@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+
+use git_config::file::{ConfigSource, Origin};
+
+/// Configuration
+impl crate::Repository {
+    /// Apply a configuration override of the form `section[.subsection].key=value` with the highest precedence, similar
+    /// to `git -c section.key=value`, typically right after opening the repository to apply `--config` flags passed on the
+    /// command-line. If `=value` is omitted, the value is `true`.
+    ///
+    /// Overrides accumulate with later ones taking precedence, and values obtained from the configuration are updated
+    /// accordingly. They only apply to this instance and not to its clones or the [`ThreadSafeRepository`][crate::ThreadSafeRepository]
+    /// it was created from.
+    pub fn load_config_layer(&mut self, key_value: &str) -> Result<(), crate::config::Error> {
+        let invalid = || crate::config::Error::InvalidOverride {
+            input: key_value.into(),
+        };
+        let (key, value) = match key_value.split_once('=') {
+            Some((key, value)) => (key, value),
+            None => (key_value, "true"),
+        };
+        let (section, subsection_and_key) = key.split_once('.').ok_or_else(invalid)?;
+        let (subsection, key) = match subsection_and_key.rsplit_once('.') {
+            Some((subsection, key)) => (Some(subsection), key),
+            None => (None, subsection_and_key),
+        };
+        if section.is_empty() || key.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut layer = git_config::File::new();
+        layer
+            .new_section(
+                section.to_owned(),
+                subsection.map(|subsection| Cow::Owned(subsection.to_owned())),
+            )
+            .push(
+                Cow::<str>::Owned(key.to_owned()).into(),
+                Cow::Owned(value.as_bytes().to_owned()),
+            );
+        layer.set_origin(Origin::CommandLine);
+        self.config
+            .append_layer(layer, crate::path::install_dir().ok().as_deref())
+    }
+
+    /// Return a snapshot of the configuration as it is now to read multiple values from it consistently, unaffected by
+    /// later changes like [`load_config_layer()`][crate::Repository::load_config_layer()].
+    ///
+    /// Obtaining it is cheap as the configuration is shared, not copied.
+    pub fn config_snapshot(&self) -> crate::ConfigSnapshot {
+        crate::ConfigSnapshot {
+            resolved: self.config.resolved.clone(),
+        }
+    }
+
+    /// Return the source of the value of `key` in `section` and `subsection` of the configuration, or `None` if it isn't set.
+    ///
+    /// Values set with [`load_config_layer()`][crate::Repository::load_config_layer()] have the [`Origin::CommandLine`].
+    pub fn config_value_source(&self, section: &str, subsection: Option<&str>, key: &str) -> Option<ConfigSource> {
+        self.config.resolved.value_source(section, subsection, key)
+    }
+}
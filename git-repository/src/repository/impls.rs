@@ -7,6 +7,8 @@ impl Clone for crate::Repository {
             self.common_dir.clone(),
             self.config.clone(),
             self.linked_worktree_options.clone(),
+            #[cfg(feature = "blocking-network-client")]
+            self.promised.clone(),
         )
     }
 }
@@ -38,6 +40,8 @@ impl From<&crate::ThreadSafeRepository> for crate::Repository {
             repo.common_dir.clone(),
             repo.config.clone(),
             repo.linked_worktree_options.clone(),
+            #[cfg(feature = "blocking-network-client")]
+            repo.promised.clone(),
         )
     }
 }
@@ -51,6 +55,8 @@ impl From<crate::ThreadSafeRepository> for crate::Repository {
             repo.common_dir,
             repo.config,
             repo.linked_worktree_options,
+            #[cfg(feature = "blocking-network-client")]
+            repo.promised,
         )
     }
 }
@@ -64,6 +70,8 @@ impl From<crate::Repository> for crate::ThreadSafeRepository {
             common_dir: r.common_dir,
             config: r.config,
             linked_worktree_options: r.linked_worktree_options,
+            #[cfg(feature = "blocking-network-client")]
+            promised: r.promised,
         }
     }
 }
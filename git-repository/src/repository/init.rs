@@ -8,9 +8,20 @@ impl crate::Repository {
         common_dir: Option<std::path::PathBuf>,
         config: crate::config::Cache,
         linked_worktree_options: crate::open::Options,
+        #[cfg(feature = "blocking-network-client")] promised: crate::promisor::Cache,
     ) -> Self {
+        #[cfg(feature = "blocking-network-client")]
+        let objects = {
+            let mut objects = objects;
+            objects.fetch_promised_object =
+                crate::promisor::object_fetcher(&config, objects.store_ref().path(), promised.clone());
+            objects
+        };
         crate::Repository {
             bufs: RefCell::new(Vec::with_capacity(4)),
+            ahead_behind: Default::default(),
+            #[cfg(feature = "blocking-network-client")]
+            promised,
             work_tree,
             common_dir,
             objects: {
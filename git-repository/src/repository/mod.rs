@@ -56,6 +56,10 @@ mod impls;
 
 mod cache;
 
+mod config;
+
+pub(crate) mod commits;
+
 mod reference;
 
 mod object;
@@ -22,6 +22,8 @@ impl crate::Repository {
     ///
     /// There are various legitimate reasons for an object to not be present, which is why
     /// [`try_find_object(…)`][crate::Repository::try_find_object()] might be preferable instead.
+    /// If this repository is a partial clone, missing objects are
+    /// [fetched from the promisor remote][crate::Repository::fetch_promised_objects()] first.
     ///
     /// # Important
     ///
@@ -40,8 +42,32 @@ impl crate::Repository {
         Ok(Object::from_data(id, kind, buf, self))
     }
 
+    /// Like [`find_object()`][crate::Repository::find_object()], but report the amount of deltas applied so far to `progress`
+    /// while decoding a packed object, which helps observing objects at the end of long delta chains.
+    ///
+    /// `progress` is initialized with the length of the delta chain and isn't touched if the object isn't a delta, or if
+    /// it was obtained from a cache.
+    pub fn find_object_with_progress(
+        &self,
+        id: impl Into<ObjectId>,
+        mut progress: impl crate::Progress,
+    ) -> Result<Object<'_>, object::find::existing::OdbError> {
+        let id = id.into();
+        let mut buf = self.free_buf();
+        let kind = self
+            .objects
+            .try_find_with_progress(&id, &mut buf, &mut progress)
+            .map_err(git_odb::find::existing::Error::Find)?
+            .ok_or(git_odb::find::existing::Error::NotFound { oid: id })?
+            .kind;
+        Ok(Object::from_data(id, kind, buf, self))
+    }
+
     /// Try to find the object with `id` or return `None` it it wasn't found.
     ///
+    /// If this repository is a partial clone, missing objects are
+    /// [fetched from the promisor remote][crate::Repository::fetch_promised_objects()] first.
+    ///
     /// # Important
     ///
     /// As a shared buffer is written to back the object data, the returned `ObjectRef` will prevent other
@@ -62,6 +88,32 @@ impl crate::Repository {
         }
     }
 
+    /// Find the only object whose id starts with the hexadecimal `hex_prefix`, which must be at least 4 characters long,
+    /// searching all loose objects and all packs.
+    ///
+    /// If multiple objects match, all of them are listed in the [`Ambiguous`][object::find::unique_prefix::Error::Ambiguous] error,
+    /// similar to the hints `git show` provides.
+    pub fn find_unique_prefix(&self, hex_prefix: &str) -> Result<ObjectId, object::find::unique_prefix::Error> {
+        use object::find::unique_prefix::Error;
+        let prefix = git_hash::Prefix::from_hex(hex_prefix)?;
+        match self.objects.lookup_prefix(prefix)? {
+            Some(Ok(id)) => Ok(id),
+            Some(Err(())) => {
+                // the lookup stops at the first collision, so finding all candidates requires looking at all objects.
+                let mut candidates = Vec::new();
+                for id in self.objects.iter()? {
+                    let id = id?;
+                    if prefix.cmp_oid(&id).is_eq() && !candidates.contains(&id) {
+                        candidates.push(id);
+                    }
+                }
+                candidates.sort();
+                Err(Error::Ambiguous { prefix, candidates })
+            }
+            None => Err(Error::NotFound { prefix }),
+        }
+    }
+
     /// Write the given object into the object database and return its object id.
     pub fn write_object(&self, object: impl git_object::WriteTo) -> Result<Id<'_>, object::write::Error> {
         use git_odb::Write;
@@ -111,6 +163,8 @@ impl crate::Repository {
     /// The first parent id in `parents` is expected to be the current target of `reference` and the operation will fail if it is not.
     /// If there is no parent, the `reference` is expected to not exist yet.
     ///
+    /// If `commit.gpgSign` is true, the commit is signed with [`sign_commit()`][crate::Repository::sign_commit()].
+    ///
     /// The method fails immediately if a `reference` lock can't be acquired.
     pub fn commit<Name, E>(
         &self,
@@ -133,7 +187,7 @@ impl crate::Repository {
         // TODO: possibly use CommitRef to save a few allocations (but will have to allocate for object ids anyway.
         //       This can be made vastly more efficient though if we wanted to, so we lie in the API
         let reference = reference.try_into()?;
-        let commit = git_object::Commit {
+        let mut commit = git_object::Commit {
             message: message.as_ref().into(),
             tree: tree.into(),
             author: author.to_owned(),
@@ -142,6 +196,19 @@ impl crate::Repository {
             parents: parents.into_iter().map(|id| id.into()).collect(),
             extra_headers: Default::default(),
         };
+        let sign = self
+            .config
+            .resolved
+            .boolean("commit", None, "gpgSign")
+            .transpose()
+            .map_err(|err| crate::sign::Error::InvalidConfig {
+                key: "commit.gpgSign",
+                value: err.input,
+            })?
+            .unwrap_or(false);
+        if sign {
+            self.sign_commit(&mut commit)?;
+        }
 
         let commit_id = self.write_object(&commit)?;
         self.edit_reference(
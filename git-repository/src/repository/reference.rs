@@ -205,6 +205,24 @@ impl crate::Repository {
         Ok(self.head()?.referent_name().map(|n| n.to_owned()))
     }
 
+    /// Return the name of the reference the symbolic reference `name` points to without following it, or `None` if `name`
+    /// refers to an object directly.
+    ///
+    /// For example, if `HEAD` points to `refs/heads/main`, `refs/heads/main` is returned even if it doesn't exist yet.
+    pub fn symbolic_ref_target<'a, Name, E>(
+        &self,
+        name: Name,
+    ) -> Result<Option<git_ref::FullName>, reference::find::existing::Error>
+    where
+        Name: TryInto<&'a PartialNameRef, Error = E>,
+        git_ref::file::find::Error: From<E>,
+    {
+        Ok(match self.find_reference(name)?.inner.target {
+            Target::Symbolic(name) => Some(name),
+            Target::Peeled(_) => None,
+        })
+    }
+
     /// Return the commit object the `HEAD` reference currently points to after peeling it fully.
     ///
     /// Note that this may fail for various reasons, most notably because the repository
@@ -239,6 +257,28 @@ impl crate::Repository {
         })
     }
 
+    /// Return an iterator over all references which are stored as files in the `refs/` directory, without consulting
+    /// the `packed-refs` file at all, in lexical order.
+    ///
+    /// In linked worktrees, per-worktree references are listed along with the ones in the common directory.
+    /// References which are both loose and packed are listed as well, with their loose value, whereas references which
+    /// are only packed are not. This is useful to learn which references still need packing.
+    pub fn list_loose_refs(&self) -> Result<crate::reference::iter::Iter<'_>, crate::reference::iter::init::Error> {
+        Ok(crate::reference::iter::Iter::new(self, self.refs.loose_iter()?))
+    }
+
+    /// Return the modification time of the `packed-refs` file, or `None` if it doesn't exist, which allows tools polling for
+    /// reference changes to learn if packed references changed without reading them.
+    ///
+    /// Compare it with [`packed::Buffer::mtime()`][git_ref::packed::Buffer::mtime()] of a previously opened buffer.
+    pub fn packed_refs_mtime(&self) -> std::io::Result<Option<std::time::SystemTime>> {
+        match std::fs::metadata(self.refs.packed_refs_path()) {
+            Ok(metadata) => metadata.modified().map(Some),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Try to find the reference named `name`, like `main`, `heads/branch`, `HEAD` or `origin/other`, and return it.
     ///
     /// Otherwise return `None` if the reference wasn't found.
@@ -1,9 +1,16 @@
-use std::{borrow::Cow, convert::TryInto};
+use std::{
+    borrow::Cow,
+    convert::{TryFrom, TryInto},
+};
 
-use git_ref::FullNameRef;
+use git_ref::{Category, FullName, FullNameRef};
 use git_validate::reference::name::Error as ValidateNameError;
 
-use crate::bstr::BStr;
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote,
+    remote::map_with_fetch_refspec,
+};
 
 impl crate::Repository {
     /// Returns a reference to the remote associated with the given `short_branch_name`, typically `main` instead of `refs/heads/main`.
@@ -25,4 +32,68 @@ impl crate::Repository {
     pub fn branch_remote_name(&self, short_branch_name: &str) -> Option<Cow<'_, BStr>> {
         self.config.resolved.string("branch", Some(short_branch_name), "remote")
     }
+
+    /// Returns the name of the remote to push the local `branch`, like `refs/heads/main`, to, similar to what `git push`
+    /// does without arguments. In some cases, the returned name will be an URL.
+    ///
+    /// It is configured with `branch.<name>.pushRemote`, which overrides `remote.pushDefault`, which in turn overrides
+    /// `branch.<name>.remote`. Returns `None` if neither of these is set.
+    pub fn push_remote_for_branch(&self, branch: &FullNameRef) -> Result<Option<BString>, remote::Error> {
+        let short_name = local_branch_short_name(branch)?;
+        let config = &self.config.resolved;
+        Ok(config
+            .string("branch", Some(&short_name), "pushRemote")
+            .or_else(|| config.string("remote", None, "pushDefault"))
+            .or_else(|| config.string("branch", Some(&short_name), "remote"))
+            .map(Cow::into_owned))
+    }
+
+    /// Returns the name of the remote tracking branch which is the upstream of the `local` branch, like
+    /// `refs/remotes/origin/main` for `refs/heads/main`, similar to `git rev-parse --symbolic-full-name @{u}`.
+    ///
+    /// It is derived from the reference configured in `branch.<name>.merge` by mapping it with the `remote.<remote>.fetch`
+    /// refspecs of the remote configured in `branch.<name>.remote`. If this remote is `.`, the upstream is a local branch
+    /// and the merge reference is returned as is.
+    /// Returns `None` if either of these keys isn't set, or if no refspec maps the merge reference.
+    pub fn tracking_branch_for(&self, local: &FullNameRef) -> Result<Option<FullName>, remote::Error> {
+        let short_name = local_branch_short_name(local)?;
+        let config = &self.config.resolved;
+        let (remote_name, merge) = match (
+            config.string("branch", Some(&short_name), "remote"),
+            config.string("branch", Some(&short_name), "merge"),
+        ) {
+            (Some(remote_name), Some(merge)) => (remote_name, merge),
+            _ => return Ok(None),
+        };
+        let to_full_name = |key: String, name: BString| {
+            FullName::try_from(name.clone()).map_err(|source| remote::Error::InvalidReferenceName {
+                key,
+                value: name,
+                source,
+            })
+        };
+        if remote_name.as_ref() == "." {
+            return to_full_name(format!("branch.{}.merge", short_name), merge.into_owned()).map(Some);
+        }
+
+        let remote_name = remote_name.to_str_lossy();
+        let refspecs = config
+            .strings("remote", Some(&remote_name), "fetch")
+            .unwrap_or_default();
+        for refspec in refspecs.iter().rev() {
+            if let Some(tracking_branch) = map_with_fetch_refspec(refspec.as_ref(), merge.as_ref()) {
+                return to_full_name(format!("remote.{}.fetch", remote_name), tracking_branch).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn local_branch_short_name(branch: &FullNameRef) -> Result<Cow<'_, str>, remote::Error> {
+    match branch.category_and_short_name() {
+        Some((Category::LocalBranch, short_name)) => Ok(short_name.to_str_lossy()),
+        _ => Err(remote::Error::NotALocalBranch {
+            name: branch.as_bstr().to_owned(),
+        }),
+    }
 }
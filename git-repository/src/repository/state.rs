@@ -1,4 +1,9 @@
-use crate::state;
+use git_hash::ObjectId;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    state,
+};
 
 impl crate::Repository {
     /// Returns the status of an in progress operation on a repository or [`None`]
@@ -41,4 +46,35 @@ impl crate::Repository {
             None
         }
     }
+
+    /// Return the ids of all commits that are being merged into `HEAD` while a [merge is in progress][state::InProgress::Merge],
+    /// as read from the `MERGE_HEAD` file, or an empty list if there is no merge in progress.
+    pub fn merge_heads(&self) -> Result<Vec<ObjectId>, state::merge_heads::Error> {
+        let content = match std::fs::read(self.path().join("MERGE_HEAD")) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(index, line)| {
+                ObjectId::from_hex(line).map_err(|source| state::merge_heads::Error::Decode {
+                    line_number: index + 1,
+                    source,
+                })
+            })
+            .collect()
+    }
+
+    /// Return the message prepared for the merge commit of an in-progress merge, as read from the `MERGE_MSG` file,
+    /// or `None` if there is no such file.
+    pub fn merge_message(&self) -> std::io::Result<Option<BString>> {
+        match std::fs::read(self.path().join("MERGE_MSG")) {
+            Ok(content) => Ok(Some(content.into())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
 }
@@ -66,8 +66,8 @@ impl crate::Repository {
     /// Open a new copy of the index file and decode it entirely.
     ///
     /// It will use the `index.threads` configuration key to learn how many threads to use.
+    /// All index versions are supported, including V4 with its prefix-compressed paths as written with `index.version=4`.
     /// Note that it may fail if there is no index.
-    // TODO: test
     #[cfg(feature = "git-index")]
     pub fn open_index(&self) -> Result<git_index::File, crate::worktree::open_index::Error> {
         use std::convert::{TryFrom, TryInto};
@@ -96,4 +96,43 @@ impl crate::Repository {
         )
         .map_err(Into::into)
     }
+
+    /// Write `index` to the index file of this repository in the version it was read in, replacing it atomically.
+    ///
+    /// Note that some extensions are dropped, see [`git_index::File::write_to()`] for details.
+    #[cfg(feature = "git-index")]
+    pub fn write_index(
+        &self,
+        index: &git_index::File,
+    ) -> Result<git_index::file::write::Stats, crate::worktree::write_index::Error> {
+        let lock = self.lock_index()?;
+        self.write_index_to_lock(index, lock)
+    }
+
+    /// Lock the index file for writing, preventing others from changing it until the lock is committed or dropped.
+    #[cfg(feature = "git-index")]
+    pub(crate) fn lock_index(&self) -> Result<git_lock::File, git_lock::acquire::Error> {
+        git_lock::File::acquire_to_update_resource(
+            self.git_dir().join("index"),
+            git_lock::acquire::Fail::Immediately,
+            None,
+        )
+    }
+
+    /// Write `index` into `lock` as obtained by [`lock_index()`][crate::Repository::lock_index()] and commit it.
+    #[cfg(feature = "git-index")]
+    pub(crate) fn write_index_to_lock(
+        &self,
+        index: &git_index::File,
+        mut lock: git_lock::File,
+    ) -> Result<git_index::file::write::Stats, crate::worktree::write_index::Error> {
+        let stats = index.write_to(
+            &mut lock,
+            git_index::file::write::Options {
+                version: index.version(),
+            },
+        )?;
+        lock.commit()?;
+        Ok(stats)
+    }
 }
@@ -0,0 +1,194 @@
+//! Restore files in the working tree or the index from the index or a tree.
+use std::{collections::HashSet, path::Path};
+
+use git_hash::ObjectId;
+use git_index::{
+    entry::{Flags, Mode, Stat, Time},
+    Entry,
+};
+use git_object::bstr::{BStr, BString, ByteSlice};
+use git_odb::FindExt;
+
+/// The place to take the content of restored files from, for use in [`Repository::restore()`][crate::Repository::restore()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// Use the tree of the commit `HEAD` points to.
+    Head,
+    /// Use the given tree, or the tree of the given commit.
+    Tree(ObjectId),
+    /// Use the current index.
+    Index,
+}
+
+/// The error returned by [`Repository::restore()`][crate::Repository::restore()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to restore files into")]
+    MissingWorkTree,
+    #[error("The path '{path}' did not match any file known to the source")]
+    PathNotFound { path: BString },
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    AddEntry(#[from] git_index::edit::add_entry::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] crate::worktree::write_index::Error),
+    #[error(transparent)]
+    FindExistingObject(#[from] crate::object::find::existing::OdbError),
+    #[error(transparent)]
+    FindBlob(#[from] git_odb::find::existing_object::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    Traverse(#[from] git_traverse::tree::breadthfirst::Error),
+    #[error(transparent)]
+    PathTraversal(#[from] git_sec::permission::TraversalError),
+    #[error("Could not write '{}' in the working tree", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl crate::Repository {
+    /// Restore the files at `path` from `source`, similar to `git restore --source=<source> [--staged] <path>`.
+    ///
+    /// If `staged` is false, the files in the working tree are overwritten with their content in `source`.
+    /// If `staged` is true, the entries in the index are replaced with the ones in `source` instead, and index entries
+    /// below `path` which don't exist in `source` are removed, which unstages all changes to `path`. The working tree
+    /// is left untouched in that case, and restoring the index from [`Source::Index`] has no effect.
+    ///
+    /// `path` is relative to the root of the working tree and may name a file or a directory, in which case all files
+    /// below it are restored. An empty `path` restores all files.
+    /// It's an error if `path` doesn't match any file in `source`, and submodules are skipped.
+    pub fn restore(&self, path: &Path, source: Source, staged: bool) -> Result<(), Error> {
+        let work_dir = self.work_dir().ok_or(Error::MissingWorkTree)?;
+        git_sec::permission::check_path_traversal(work_dir, path)?;
+        let path = git_path::to_unix_separators(git_path::into_bstr(path)).into_owned();
+        let path = path.trim_end_with(|c| c == '/').as_bstr();
+        let matches = |entry_path: &BStr| {
+            path.is_empty()
+                || entry_path == path
+                || (entry_path.starts_with(path) && entry_path.get(path.len()) == Some(&b'/'))
+        };
+
+        let entries: Vec<(BString, Mode, ObjectId)> = match source {
+            Source::Index => {
+                let index = self.open_index()?;
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.stage() == 0 && entry.mode != Mode::COMMIT)
+                    .filter(|entry| matches(entry.path(&index)))
+                    .map(|entry| (entry.path(&index).to_owned(), entry.mode, entry.id))
+                    .collect()
+            }
+            Source::Head | Source::Tree(_) => {
+                let id = match source {
+                    Source::Tree(id) => id,
+                    _ => self.head_id()?.detach(),
+                };
+                self.find_object(id)?
+                    .peel_to_kind(git_object::Kind::Tree)?
+                    .into_tree()
+                    .traverse()
+                    .breadthfirst
+                    .files()?
+                    .into_iter()
+                    .filter(|entry| {
+                        entry.mode.is_no_tree()
+                            && entry.mode != git_object::tree::EntryMode::Commit
+                            && matches(entry.filepath.as_ref())
+                    })
+                    .map(|entry| (entry.filepath, Mode::from_bits_truncate(entry.mode as u32), entry.oid))
+                    .collect()
+            }
+        };
+        if entries.is_empty() {
+            return Err(Error::PathNotFound { path: path.into() });
+        }
+
+        if staged {
+            if source == Source::Index {
+                return Ok(());
+            }
+            let mut index = self.open_index()?;
+            let unknown_to_source: Vec<_> = {
+                let source_paths: HashSet<_> = entries.iter().map(|(path, _, _)| path.as_bstr()).collect();
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.mode != Mode::COMMIT)
+                    .map(|entry| (entry.path(&index).to_owned(), entry.stage()))
+                    .filter(|(entry_path, _)| {
+                        matches(entry_path.as_bstr()) && !source_paths.contains(entry_path.as_bstr())
+                    })
+                    .collect()
+            };
+            for (entry_path, stage) in unknown_to_source {
+                index.remove_entry(entry_path.as_bstr(), stage);
+            }
+            for (rela_path, mode, id) in entries {
+                let is_unchanged = index
+                    .entry_by_path(rela_path.as_bstr(), 0)
+                    .map_or(false, |entry| entry.mode == mode && entry.id == id);
+                if !is_unchanged {
+                    // like git, the stat information is left empty so the file will be compared by content.
+                    let stat = Stat {
+                        mtime: Time { secs: 0, nsecs: 0 },
+                        ctime: Time { secs: 0, nsecs: 0 },
+                        dev: 0,
+                        ino: 0,
+                        uid: 0,
+                        gid: 0,
+                        size: 0,
+                    };
+                    index.add_entry(Entry::new(stat, id, Flags::empty(), mode), rela_path.as_bstr(), true)?;
+                }
+            }
+            self.write_index(&index)?;
+            return Ok(());
+        }
+
+        let mut buf = Vec::new();
+        for (rela_path, mode, id) in entries {
+            let dest = work_dir.join(git_path::from_bstr(rela_path.as_bstr()));
+            let data = self.objects.find_blob(&id, &mut buf)?.data;
+            write_file(&dest, mode, data).map_err(|source| Error::Io { path: dest, source })?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `data` to `dest` as file or symlink according to `mode`, replacing whatever was there before.
+#[cfg_attr(not(unix), allow(unused_variables))]
+pub(crate) fn write_file(dest: &Path, mode: Mode, data: &[u8]) -> std::io::Result<()> {
+    match std::fs::symlink_metadata(dest) {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(dest)?,
+        Ok(_) => std::fs::remove_file(dest)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            let parent_dir = dest
+                .parent()
+                .expect("files in the working tree have a parent directory");
+            std::fs::create_dir_all(parent_dir)?;
+        }
+        Err(err) => return Err(err),
+    }
+
+    #[cfg(unix)]
+    if mode == Mode::SYMLINK {
+        let target = git_path::try_from_byte_slice(data)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        return std::os::unix::fs::symlink(target, dest);
+    }
+    std::fs::write(dest, data)?;
+    #[cfg(unix)]
+    if mode == Mode::FILE_EXECUTABLE {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dest, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,453 @@
+//! Sign data and verify signatures with OpenPGP, X.509 or SSH keys, like git does for signed commits.
+use std::{
+    ffi::OsString,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use git_object::{
+    bstr::{BString, ByteSlice},
+    WriteTo,
+};
+use git_tempfile::{AutoRemove, ContainingDirectory};
+
+/// The kind of signature to create, as configured in `gpg.format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Signatures created by `gpg`.
+    OpenPgp,
+    /// Signatures made with X.509 certificates, created by `gpgsm`.
+    X509,
+    /// Signatures created by `ssh-keygen`.
+    Ssh,
+}
+
+impl Format {
+    /// Return the name of the format as used in configuration keys.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::OpenPgp => "openpgp",
+            Format::X509 => "x509",
+            Format::Ssh => "ssh",
+        }
+    }
+
+    fn default_program(&self) -> &'static str {
+        match self {
+            Format::OpenPgp => "gpg",
+            Format::X509 => "gpgsm",
+            Format::Ssh => "ssh-keygen",
+        }
+    }
+
+    /// Determine the format of an armored `signature` by its first line.
+    fn from_signature(signature: &[u8]) -> Option<Self> {
+        let signature = signature.trim_start();
+        if signature.starts_with(b"-----BEGIN PGP SIGNATURE-----")
+            || signature.starts_with(b"-----BEGIN PGP MESSAGE-----")
+        {
+            Some(Format::OpenPgp)
+        } else if signature.starts_with(b"-----BEGIN SIGNED MESSAGE-----") {
+            Some(Format::X509)
+        } else if signature.starts_with(b"-----BEGIN SSH SIGNATURE-----") {
+            Some(Format::Ssh)
+        } else {
+            None
+        }
+    }
+}
+
+/// The key to sign with, as returned by [`Repository::signing_key()`][crate::Repository::signing_key()].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SigningKey {
+    /// The id, fingerprint or user id of a key known to `gpg` or `gpgsm`.
+    KeyId(BString),
+    /// An armored OpenPGP public key, whose private key is known to `gpg`.
+    ArmoredPgp(BString),
+    /// A public SSH key like `ssh-ed25519 AAAA…`, whose private key is held by the `ssh-agent`.
+    SshPublicKey(BString),
+    /// The path to a private SSH key, or to a public key whose private key is held by the `ssh-agent`.
+    SshKeyFile(PathBuf),
+}
+
+/// The result of [`Repository::verify_signature()`][crate::Repository::verify_signature()].
+///
+/// `signer` is the user id of the key for OpenPGP and X.509, or the principal in the allowed signers file for SSH, and
+/// `key` is the id or fingerprint of the key, each as reported by the verifying program.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SignatureStatus {
+    /// The signature is good and was made by a trusted key.
+    Good { signer: BString, key: BString },
+    /// The signature is good, but the validity of the key is unknown, or for SSH, the key isn't in the allowed signers file.
+    GoodWithUnknownValidity { signer: BString, key: BString },
+    /// The signature doesn't match the data.
+    Bad { signer: BString, key: BString },
+    /// The signature is good, but has expired.
+    ExpiredSignature { signer: BString, key: BString },
+    /// The signature is good, but was made by a key that has expired.
+    ExpiredKey { signer: BString, key: BString },
+    /// The signature is good, but was made by a key that was revoked.
+    RevokedKey { signer: BString, key: BString },
+    /// The signature can't be checked, typically as the key isn't known.
+    CannotCheck { key: BString },
+}
+
+/// The error returned by [`Repository::sign_data()`][crate::Repository::sign_data()],
+/// [`Repository::sign_commit()`][crate::Repository::sign_commit()] and
+/// [`Repository::verify_signature()`][crate::Repository::verify_signature()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The value '{value}' of key '{key}' is invalid")]
+    InvalidConfig { key: &'static str, value: BString },
+    #[error("No signing key is configured in 'user.signingKey', and the committer isn't known either")]
+    MissingSigningKey,
+    #[error("'gpg.ssh.allowedSignersFile' needs to be configured to verify SSH signatures")]
+    MissingAllowedSignersFile,
+    #[error("The kind of signature could not be determined")]
+    UnknownSignatureFormat,
+    #[error("Could not run '{}'", program.to_string_lossy())]
+    Io { program: OsString, source: std::io::Error },
+    #[error("'{}' failed to sign the data: {stderr}", program.to_string_lossy())]
+    SigningFailed { program: OsString, stderr: BString },
+    #[error(transparent)]
+    PathInterpolation(#[from] git_config::values::path::interpolate::Error),
+    #[error("Could not determine the fingerprint of the armored key in 'user.signingKey'")]
+    ArmoredKeyFingerprint,
+    #[error("Could not write the signature or the data to be signed to a temporary file")]
+    Tempfile(#[source] std::io::Error),
+}
+
+impl crate::Repository {
+    /// Determine the key to sign with from `user.signingKey`.
+    ///
+    /// Public SSH keys like `ssh-ed25519 AAAA…`, optionally prefixed with `key::`, and armored OpenPGP keys are detected
+    /// by their content. Otherwise the value is the path to an SSH key if `gpg.format` is `ssh`, with `~` expanded to the
+    /// home directory, or the id of the key to pass to `gpg` or `gpgsm`.
+    /// If `user.signingKey` isn't set, the latter defaults to the name and email of the committer like in git.
+    pub fn signing_key(&self) -> Result<SigningKey, Error> {
+        let format = self.signature_format()?;
+        let config = &self.config.resolved;
+        let key = match config.string("user", None, "signingKey") {
+            Some(key) => key,
+            None if format == Format::Ssh => return Err(Error::MissingSigningKey),
+            None => {
+                return match (
+                    config.string("user", None, "name"),
+                    config.string("user", None, "email"),
+                ) {
+                    (Some(name), Some(email)) => Ok(SigningKey::KeyId(format!("{} <{}>", name, email).into())),
+                    _ => Err(Error::MissingSigningKey),
+                }
+            }
+        };
+        Ok(if let Some(literal) = key.strip_prefix(b"key::") {
+            SigningKey::SshPublicKey(literal.into())
+        } else if ["ssh-", "ecdsa-sha2-", "sk-"]
+            .iter()
+            .any(|prefix| key.starts_with(prefix.as_bytes()))
+        {
+            SigningKey::SshPublicKey(key.into_owned())
+        } else if key.starts_with(b"-----BEGIN PGP ") {
+            SigningKey::ArmoredPgp(key.into_owned())
+        } else if format == Format::Ssh {
+            let path = config
+                .path("user", None, "signingKey")
+                .expect("present as it was read before");
+            SigningKey::SshKeyFile(path.interpolate(None)?.into_owned())
+        } else {
+            SigningKey::KeyId(key.into_owned())
+        })
+    }
+
+    /// Sign `data` with the [key in `user.signingKey`][crate::Repository::signing_key()] and return the armored signature,
+    /// similar to what git does when signing commits.
+    ///
+    /// The kind of signature is configured in `gpg.format`, which is `openpgp` by default, and the signing program in
+    /// `gpg.<format>.program`, which defaults to `gpg`, `gpgsm` or `ssh-keygen` respectively. `gpg.program` can be used
+    /// as well for OpenPGP.
+    /// SSH keys are always used with `ssh-keygen -Y sign`, and OpenPGP keys with `gpg`, independently of `gpg.format`.
+    pub fn sign_data(&self, data: &[u8]) -> Result<BString, Error> {
+        let key = self.signing_key()?;
+        let format = match key {
+            SigningKey::SshPublicKey(_) | SigningKey::SshKeyFile(_) => Format::Ssh,
+            SigningKey::ArmoredPgp(_) => Format::OpenPgp,
+            SigningKey::KeyId(_) => self.signature_format()?,
+        };
+        let program = self.signing_program(format);
+
+        let mut args: Vec<OsString> = Vec::new();
+        // keeps the literal public key alive until it was used.
+        let mut _key_file = None;
+        match key {
+            SigningKey::KeyId(key) => {
+                args.extend([
+                    "--status-fd=2".into(),
+                    "-bsau".into(),
+                    git_path::from_bstring(key).into(),
+                ]);
+            }
+            SigningKey::ArmoredPgp(key) => {
+                let fingerprint = pgp_fingerprint(&program, &key)?;
+                args.extend([
+                    "--status-fd=2".into(),
+                    "-bsau".into(),
+                    git_path::from_bstring(fingerprint).into(),
+                ]);
+            }
+            SigningKey::SshPublicKey(key) => {
+                let (file, path) = tempfile_with(&key)?;
+                _key_file = Some(file);
+                args.extend(["-Y".into(), "sign".into(), "-n".into(), "git".into(), "-U".into()]);
+                args.extend(["-f".into(), path.into()]);
+            }
+            SigningKey::SshKeyFile(path) => {
+                args.extend(["-Y".into(), "sign".into(), "-n".into(), "git".into()]);
+                args.extend(["-f".into(), path.into()]);
+            }
+        }
+
+        let output = run(&program, &args, data)?;
+        let created = match format {
+            Format::Ssh => true,
+            Format::OpenPgp | Format::X509 => output.stderr.find("[GNUPG:] SIG_CREATED ").is_some(),
+        };
+        if !output.status.success() || !created || output.stdout.is_empty() {
+            return Err(Error::SigningFailed {
+                program,
+                stderr: output.stderr.into(),
+            });
+        }
+        Ok(output.stdout.into())
+    }
+
+    /// Sign `commit` with [`sign_data()`][crate::Repository::sign_data()], and add the signature to it as `gpgsig` header,
+    /// replacing any previous signature.
+    ///
+    /// [`commit()`][crate::Repository::commit()] does this automatically if `commit.gpgSign` is true.
+    pub fn sign_commit(&self, commit: &mut git_object::Commit) -> Result<(), Error> {
+        commit.extra_headers.retain(|(name, _)| name != "gpgsig");
+        let mut data = Vec::new();
+        commit.write_to(&mut data).expect("writing to a vec can't fail");
+        let signature = self.sign_data(&data)?;
+        let signature = signature.strip_suffix(b"\n").unwrap_or(&signature);
+        commit.extra_headers.push(("gpgsig".into(), signature.into()));
+        Ok(())
+    }
+
+    /// Verify that `signature` was made for `data`, like git does for signed commits.
+    ///
+    /// The kind of signature is determined by its content, and it's verified by the program configured as in
+    /// [`sign_data()`][crate::Repository::sign_data()].
+    /// SSH signatures are trusted if they were made by a key in `gpg.ssh.allowedSignersFile`, which is required.
+    pub fn verify_signature(&self, data: &[u8], signature: &[u8]) -> Result<SignatureStatus, Error> {
+        let format = Format::from_signature(signature).ok_or(Error::UnknownSignatureFormat)?;
+        let program = self.signing_program(format);
+        let (_signature_file, signature_path) = tempfile_with(signature)?;
+        match format {
+            Format::OpenPgp | Format::X509 => {
+                let mut args: Vec<OsString> = Vec::new();
+                if format == Format::OpenPgp {
+                    args.push("--keyid-format=long".into());
+                }
+                args.extend([
+                    "--status-fd=1".into(),
+                    "--verify".into(),
+                    signature_path.into(),
+                    "-".into(),
+                ]);
+                let output = run(&program, &args, data)?;
+                Ok(parse_gpg_status(output.stdout.as_slice()))
+            }
+            Format::Ssh => {
+                let allowed_signers = self
+                    .config
+                    .resolved
+                    .path("gpg", Some("ssh"), "allowedSignersFile")
+                    .ok_or(Error::MissingAllowedSignersFile)?
+                    .interpolate(None)?
+                    .into_owned();
+                let mut args: Vec<OsString> = vec![
+                    "-Y".into(),
+                    "find-principals".into(),
+                    "-f".into(),
+                    allowed_signers.clone().into(),
+                    "-s".into(),
+                    signature_path.clone().into(),
+                ];
+                let output = run(&program, &args, &[])?;
+                let principals = output.status.success().then(|| output.stdout).unwrap_or_default();
+
+                let mut status = None;
+                for principal in principals.lines().filter(|line| !line.is_empty()) {
+                    args = vec![
+                        "-Y".into(),
+                        "verify".into(),
+                        "-n".into(),
+                        "git".into(),
+                        "-f".into(),
+                        allowed_signers.clone().into(),
+                        "-I".into(),
+                        git_path::from_bstr(principal.as_bstr()).into_owned().into(),
+                        "-s".into(),
+                        signature_path.clone().into(),
+                    ];
+                    let output = run(&program, &args, data)?;
+                    let (signer, key) = (principal.into(), ssh_key(&output.stdout));
+                    if output.status.success() {
+                        return Ok(SignatureStatus::Good { signer, key });
+                    }
+                    status = Some(SignatureStatus::Bad { signer, key });
+                }
+                if let Some(status) = status {
+                    return Ok(status);
+                }
+
+                // the key isn't allowed, but the signature itself can still be checked.
+                args = vec![
+                    "-Y".into(),
+                    "check-novalidate".into(),
+                    "-n".into(),
+                    "git".into(),
+                    "-s".into(),
+                    signature_path.into(),
+                ];
+                let output = run(&program, &args, data)?;
+                let (signer, key) = (BString::default(), ssh_key(&output.stdout));
+                Ok(if output.status.success() {
+                    SignatureStatus::GoodWithUnknownValidity { signer, key }
+                } else {
+                    SignatureStatus::Bad { signer, key }
+                })
+            }
+        }
+    }
+
+    fn signature_format(&self) -> Result<Format, Error> {
+        Ok(match self.config.resolved.string("gpg", None, "format") {
+            None => Format::OpenPgp,
+            Some(value) => match value.as_bytes() {
+                b"openpgp" => Format::OpenPgp,
+                b"x509" => Format::X509,
+                b"ssh" => Format::Ssh,
+                _ => {
+                    return Err(Error::InvalidConfig {
+                        key: "gpg.format",
+                        value: value.into_owned(),
+                    })
+                }
+            },
+        })
+    }
+
+    fn signing_program(&self, format: Format) -> OsString {
+        let config = &self.config.resolved;
+        config
+            .string("gpg", Some(format.as_str()), "program")
+            .or_else(|| {
+                (format == Format::OpenPgp)
+                    .then(|| config.string("gpg", None, "program"))
+                    .flatten()
+            })
+            .map(|program| git_path::from_bstring(program.into_owned()).into())
+            .unwrap_or_else(|| format.default_program().into())
+    }
+}
+
+/// Run `program` with `args`, feeding it `input`, and return its output.
+fn run(program: &OsString, args: &[OsString], input: &[u8]) -> Result<std::process::Output, Error> {
+    let io_err = |source| Error::Io {
+        program: program.clone(),
+        source,
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(io_err)?;
+    let mut stdin = child.stdin.take().expect("configured");
+    let input = input.to_owned();
+    let writer = std::thread::spawn(move || match stdin.write_all(&input) {
+        // programs may choose not to read their input, like when failing early.
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        res => res,
+    });
+    let output = child.wait_with_output().map_err(io_err)?;
+    writer.join().expect("no panic").map_err(io_err)?;
+    Ok(output)
+}
+
+/// Return the fingerprint of the armored OpenPGP `key` as determined by the `gpg` `program`.
+fn pgp_fingerprint(program: &OsString, key: &[u8]) -> Result<BString, Error> {
+    let args: Vec<OsString> = vec![
+        "--with-colons".into(),
+        "--import-options".into(),
+        "show-only".into(),
+        "--import".into(),
+    ];
+    let output = run(program, &args, key)?;
+    output
+        .stdout
+        .lines()
+        .find_map(|line| line.strip_prefix(b"fpr:"))
+        .and_then(|fields| fields.split_str(":").find(|field| !field.is_empty()))
+        .map(Into::into)
+        .ok_or(Error::ArmoredKeyFingerprint)
+}
+
+/// Write `data` to a new temporary file and return it along with its path. It's removed once the handle is dropped.
+fn tempfile_with(data: &[u8]) -> Result<(git_tempfile::Handle<git_tempfile::handle::Writable>, PathBuf), Error> {
+    let mut file = git_tempfile::new(std::env::temp_dir(), ContainingDirectory::Exists, AutoRemove::Tempfile)
+        .map_err(Error::Tempfile)?;
+    file.write_all(data).map_err(Error::Tempfile)?;
+    let path = file.with_mut(|file| file.path().to_owned()).map_err(Error::Tempfile)?;
+    Ok((file, path))
+}
+
+/// Interpret the output of `gpg --status-fd` like git does.
+fn parse_gpg_status(status: &[u8]) -> SignatureStatus {
+    let mut result = SignatureStatus::CannotCheck {
+        key: BString::default(),
+    };
+    let mut untrusted = false;
+    for line in status.lines() {
+        let line = match line.strip_prefix(b"[GNUPG:] ") {
+            Some(line) => line,
+            None => continue,
+        };
+        let mut fields = line.splitn_str(3, " ");
+        let (keyword, key, signer) = (
+            fields.next().unwrap_or_default(),
+            BString::from(fields.next().unwrap_or_default()),
+            BString::from(fields.next().unwrap_or_default()),
+        );
+        result = match keyword {
+            b"GOODSIG" => SignatureStatus::Good { signer, key },
+            b"BADSIG" => SignatureStatus::Bad { signer, key },
+            b"EXPSIG" => SignatureStatus::ExpiredSignature { signer, key },
+            b"EXPKEYSIG" => SignatureStatus::ExpiredKey { signer, key },
+            b"REVKEYSIG" => SignatureStatus::RevokedKey { signer, key },
+            b"ERRSIG" => SignatureStatus::CannotCheck { key },
+            b"TRUST_UNDEFINED" | b"TRUST_NEVER" => {
+                untrusted = true;
+                continue;
+            }
+            _ => continue,
+        };
+    }
+    match result {
+        SignatureStatus::Good { signer, key } if untrusted => SignatureStatus::GoodWithUnknownValidity { signer, key },
+        result => result,
+    }
+}
+
+/// Extract the fingerprint from output like `Good "git" signature for <principal> with ED25519 key SHA256:<fingerprint>`.
+fn ssh_key(output: &[u8]) -> BString {
+    let line = output.lines().next().unwrap_or_default();
+    line.rfind(" key ")
+        .map(|pos| line[pos + 5..].into())
+        .unwrap_or_default()
+}
@@ -0,0 +1,83 @@
+//! Iterate commits in topological order.
+use std::collections::HashMap;
+
+use git_hash::ObjectId;
+
+/// The error returned by [`Repository::for_each_commit_topo()`][crate::Repository::for_each_commit_topo()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindCommit(#[from] git_odb::find::existing_iter::Error<git_odb::store::find::Error>),
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[cfg(feature = "git-commitgraph")]
+    #[error("The commit-graph could not be read")]
+    CommitGraph(#[from] git_commitgraph::file::commit::Error),
+}
+
+/// The order in which [`Repository::for_each_commit_topo()`][crate::Repository::for_each_commit_topo()] passes commits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// Pass each commit only after all of its parents, starting with root commits.
+    ParentsFirst,
+    /// Pass each commit only after all of its children, starting with the tips, similar to `git log --topo-order`.
+    ChildrenFirst,
+}
+
+impl crate::Repository {
+    /// Call `f` with the id and the parent ids of each commit reachable from `tips` in topological `order`.
+    ///
+    /// All reachable commits are read before the first call to `f` as their order is only known once all of them were seen.
+    /// They are ordered using Kahn's algorithm, which lets a commit follow once all of its children were passed. Among commits
+    /// which are ready at the same time, the first parent of the most recently passed commit follows first, avoiding to interleave
+    /// lines of history where possible. In [`Order::ParentsFirst`] commits are passed in the reverse order.
+    pub fn for_each_commit_topo(
+        &self,
+        tips: &[ObjectId],
+        order: Order,
+        mut f: impl FnMut(ObjectId, &[ObjectId]),
+    ) -> Result<(), Error> {
+        let mut graph = self.commits();
+        let mut parents_by_id = HashMap::<ObjectId, Vec<ObjectId>>::new();
+        let mut num_children = HashMap::<ObjectId, usize>::new();
+        let mut queue = tips.to_vec();
+        while let Some(id) = queue.pop() {
+            if parents_by_id.contains_key(&id) {
+                continue;
+            }
+            let (_, parents) = graph.lookup::<Error>(&id)?;
+            for parent in &parents {
+                *num_children.entry(*parent).or_default() += 1;
+                queue.push(*parent);
+            }
+            parents_by_id.insert(id, parents);
+        }
+
+        let mut sorted = Vec::with_capacity(parents_by_id.len());
+        let mut ready: Vec<_> = Vec::new();
+        for tip in tips.iter().rev() {
+            if !num_children.contains_key(tip) && !ready.contains(tip) {
+                ready.push(*tip);
+            }
+        }
+        while let Some(id) = ready.pop() {
+            for parent in parents_by_id[&id].iter().rev() {
+                let count = num_children.get_mut(parent).expect("parents have children");
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(*parent);
+                }
+            }
+            sorted.push(id);
+        }
+
+        if order == Order::ParentsFirst {
+            sorted.reverse();
+        }
+        for id in sorted {
+            f(id, &parents_by_id[&id]);
+        }
+        Ok(())
+    }
+}
@@ -113,6 +113,15 @@ pub struct Reference<'r> {
     pub(crate) repo: &'r Repository,
 }
 
+/// A point-in-time view of the configuration of a repository, as obtained by [`Repository::config_snapshot()`].
+///
+/// It isn't affected by later changes to the configuration of the repository it was obtained from, so multiple related
+/// values can be read from it consistently.
+#[derive(Debug, Clone)]
+pub struct ConfigSnapshot {
+    pub(crate) resolved: crate::Config,
+}
+
 /// A thread-local handle to interact with a repository from a single thread.
 ///
 /// It is `Send` but **not** `Sync` - for the latter you can convert it `to_sync()`.
@@ -130,6 +139,12 @@ pub struct Repository {
     pub(crate) common_dir: Option<PathBuf>,
     /// A free-list of re-usable object backing buffers
     pub(crate) bufs: RefCell<Vec<Vec<u8>>>,
+    /// Previously computed results of [`compute_ahead_behind()`][crate::Repository::compute_ahead_behind()].
+    pub(crate) ahead_behind: RefCell<crate::ahead_behind::Cache>,
+    /// Previously computed results of [`list_promisor_objects()`][crate::Repository::list_promisor_objects()], shared
+    /// with the [`ThreadSafeRepository`] this instance was created from.
+    #[cfg(feature = "blocking-network-client")]
+    pub(crate) promised: crate::promisor::Cache,
     /// A pre-assembled selection of often-accessed configuration values for quick access.
     pub(crate) config: crate::config::Cache,
     /// options obtained when instantiating this repository for use when following linked worktrees.
@@ -161,4 +176,8 @@ pub struct ThreadSafeRepository {
     pub(crate) config: crate::config::Cache,
     /// options obtained when instantiating this repository for use when following linked worktrees.
     pub(crate) linked_worktree_options: crate::open::Options,
+    /// Previously computed results of [`list_promisor_objects()`][crate::Repository::list_promisor_objects()], shared
+    /// by all thread-local instances created from it.
+    #[cfg(feature = "blocking-network-client")]
+    pub(crate) promised: crate::promisor::Cache,
 }
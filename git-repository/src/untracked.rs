@@ -0,0 +1,122 @@
+//! List files in the working tree which are neither tracked by the index nor ignored.
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use git_index::entry::Mode;
+use git_object::bstr::ByteSlice;
+use git_odb::FindExt;
+
+/// Options for use in [`Repository::untracked_files()`][crate::Repository::untracked_files()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// If true, the untracked files of initialized submodules are listed as well, otherwise submodules are skipped entirely.
+    pub recurse_submodules: bool,
+}
+
+/// The error returned by [`Repository::untracked_files()`][crate::Repository::untracked_files()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to find untracked files in")]
+    MissingWorkTree,
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Excludes(#[from] crate::worktree::excludes::Error),
+    #[error("Could not read '{}' in the working tree", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Could not open the submodule at '{}'", path.display())]
+    OpenSubmodule {
+        path: PathBuf,
+        source: Box<crate::open::Error>,
+    },
+    #[error("Could not list the untracked files of the submodule at '{}'", path.display())]
+    Submodule { path: PathBuf, source: Box<Error> },
+}
+
+impl crate::Repository {
+    /// Return the paths of all files in the working tree, relative to its root and sorted, which are neither tracked by the index
+    /// nor excluded by `.gitignore` files or the repository's other exclude files, similar to `git ls-files --others --exclude-standard`.
+    ///
+    /// `.git` directories and files are never listed, and nested repositories which aren't tracked as submodules are skipped
+    /// as well. Untracked files in submodules are listed only if [`Options::recurse_submodules`] is set.
+    pub fn untracked_files(&self, opts: Options) -> Result<impl Iterator<Item = PathBuf>, Error> {
+        let mut untracked = self.collect_untracked_files(opts)?;
+        untracked.sort();
+        Ok(untracked.into_iter())
+    }
+
+    fn collect_untracked_files(&self, opts: Options) -> Result<Vec<PathBuf>, Error> {
+        let worktree = self.worktree().ok_or(Error::MissingWorkTree)?;
+        let work_dir = self.work_dir().expect("present if there is a worktree");
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+
+        let index = self.open_index()?;
+        let tracked: HashSet<_> = index.entries().iter().map(|entry| entry.path(&index)).collect();
+        let submodules: HashSet<_> = index
+            .entries()
+            .iter()
+            .filter(|entry| entry.mode == Mode::COMMIT)
+            .map(|entry| entry.path(&index))
+            .collect();
+        let mut excludes = worktree.excludes(&index, None)?;
+
+        let mut untracked = Vec::new();
+        let mut directories = vec![PathBuf::new()];
+        while let Some(directory) = directories.pop() {
+            let abs_directory = work_dir.join(&directory);
+            for dir_entry in std::fs::read_dir(&abs_directory).map_err(io_err(&abs_directory))? {
+                let dir_entry = dir_entry.map_err(io_err(&abs_directory))?;
+                if dir_entry.file_name() == ".git" {
+                    continue;
+                }
+                let rela_path = directory.join(dir_entry.file_name());
+                let file_path = dir_entry.path();
+                let is_dir = dir_entry.file_type().map_err(io_err(&file_path))?.is_dir();
+                let path = match git_path::try_into_bstr(rela_path.as_path()) {
+                    Ok(path) => git_path::to_unix_separators_on_windows(path).into_owned(),
+                    Err(_) => continue,
+                };
+                if is_dir && submodules.contains(path.as_bstr()) {
+                    if opts.recurse_submodules && file_path.join(".git").exists() {
+                        let submodule = crate::open(&file_path).map_err(|err| Error::OpenSubmodule {
+                            path: file_path.clone(),
+                            source: Box::new(err),
+                        })?;
+                        let files = submodule
+                            .collect_untracked_files(opts)
+                            .map_err(|err| Error::Submodule {
+                                path: file_path.clone(),
+                                source: Box::new(err),
+                            })?;
+                        untracked.extend(files.into_iter().map(|file| rela_path.join(file)));
+                    }
+                    continue;
+                }
+                if tracked.contains(path.as_bstr()) || (is_dir && file_path.join(".git").exists()) {
+                    continue;
+                }
+                let is_excluded = excludes
+                    .at_entry(path.as_bstr(), Some(is_dir), |oid, buf| {
+                        self.objects.find_blob(oid, buf)
+                    })
+                    .map_err(io_err(&file_path))?
+                    .is_excluded();
+                if is_excluded {
+                    continue;
+                }
+                if is_dir {
+                    directories.push(rela_path);
+                } else {
+                    untracked.push(rela_path);
+                }
+            }
+        }
+        Ok(untracked)
+    }
+}
@@ -92,6 +92,29 @@ pub mod open_index {
     }
 }
 
+///
+#[cfg(feature = "git-index")]
+pub mod write_index {
+    /// The error returned by [`Worktree::write_index()`][crate::Worktree::write_index()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        AcquireLock(#[from] git_lock::acquire::Error),
+        #[error(transparent)]
+        Write(#[from] git_index::file::write::Error),
+        #[error(transparent)]
+        CommitLock(#[from] git_lock::commit::Error<git_lock::File>),
+    }
+
+    impl<'repo> crate::Worktree<'repo> {
+        /// A shortcut to [`crate::Repository::write_index()`].
+        pub fn write_index(&self, index: &git_index::File) -> Result<git_index::file::write::Stats, Error> {
+            self.parent.write_index(index)
+        }
+    }
+}
+
 ///
 #[cfg(feature = "git-index")]
 pub mod excludes {
@@ -13,3 +13,32 @@ mod describe {
         }
     }
 }
+
+#[cfg(feature = "regex")]
+mod find_commits_by_message {
+    use git_repository::commit::grep::Options;
+
+    use crate::basic_repo;
+
+    #[test]
+    fn literal_and_regex_patterns() -> crate::Result {
+        let repo = basic_repo()?;
+        let tip = repo.head_id()?;
+
+        let messages = |pattern: &str, regex: bool| -> crate::Result<Vec<String>> {
+            Ok(repo
+                .find_commits_by_message(pattern, tip, Options { regex })?
+                .map(|commit| commit.map(|c| c.message_raw().expect("valid").to_string()))
+                .collect::<Result<_, _>>()?)
+        };
+
+        assert_eq!(messages("c1", false)?, vec!["c1\n"]);
+        assert_eq!(
+            messages("c[12]", false)?,
+            Vec::<String>::new(),
+            "literals aren't regexes"
+        );
+        assert_eq!(messages("c[12]", true)?, vec!["c2\n", "c1\n"]);
+        Ok(())
+    }
+}
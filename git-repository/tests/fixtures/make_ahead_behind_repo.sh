@@ -0,0 +1,19 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+git branch feature
+git commit -q --allow-empty -m c2
+git commit -q --allow-empty -m c3
+
+git checkout -q feature
+git commit -q --allow-empty -m f1
+# only some of the commits are in the commit-graph, the others are traversed by their committer time
+git commit-graph write --reachable --no-progress
+git commit -q --allow-empty -m f2
+git commit -q --allow-empty -m f3
+
+git checkout -q -b merged main
+git merge -q --no-ff feature -m "merge feature"
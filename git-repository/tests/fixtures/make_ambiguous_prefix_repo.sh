@@ -0,0 +1,14 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+
+# enough blobs for some of them to share the first four hex characters of their ids
+mkdir blobs
+for i in $(seq 1000); do
+  echo "$i" > "blobs/$i"
+done
+seq 1000 | sed "s|^|blobs/|" | git hash-object -w --stdin-paths | cut -c1-4 | sort | uniq -d | head -n 1 > ambiguous-prefix
+rm -r blobs
@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+seq 1 20 > numbers
+echo deleted > gone
+echo executable > exe
+printf '\000\001\002' > bin.dat
+git add .
+git commit -q -m base
+
+sed -i -e 's/^3$/three/' -e 's/^18$/eighteen/' numbers
+git rm -q gone
+chmod +x exe
+echo new > new
+printf '\000\001\002\003' > bin.dat
+git add -A
+git commit -q -m change
+
+git format-patch -1 --stdout --binary > .git/change.patch
+git reset -q --hard HEAD~1
@@ -0,0 +1,26 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+echo a > a
+seq 1000 > numbers
+git add a numbers && git commit -q -m c1
+git tag base
+
+echo changed > a
+echo b > b
+git add a b && git commit -q -m c2
+git tag -a -m "annotated" v1
+
+echo c > c
+echo 1001 >> numbers
+git add c numbers && git commit -q -m c3
+
+git init -q partial
+(cd partial
+  git fetch -q --update-head-ok .. base:refs/heads/main
+)
+
+# the pack of this bundle is thin and stores 'numbers' as delta against its version in 'base'
+git bundle create -q thin.bundle base..main
@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo unchanged > unchanged
+echo original > changed
+mkdir dir
+echo removed > dir/removed
+git add .
+git commit -q -m "main"
+
+git checkout -q -b feature
+echo modified > changed
+git rm -q dir/removed
+mkdir new
+echo added > new/added
+git add .
+git commit -q -m "feature"
+
+git checkout -q main
+echo untracked > untracked
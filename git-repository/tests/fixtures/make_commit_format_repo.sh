@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+git branch other
+
+GIT_AUTHOR_DATE="2005-04-07 15:13:13 -0700" git commit -q --allow-empty -F - <<MSG
+subject line
+continued
+
+body line 1
+body line 2
+MSG
+git tag v1
+git update-ref refs/remotes/origin/main HEAD
@@ -0,0 +1,20 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo base > file
+echo unchanged > unrelated
+git add .
+git commit -q -m base
+
+git checkout -q -b feature
+echo feature > file
+git commit -q -am feature
+
+git checkout -q main
+echo main > file
+git commit -q -am main
+
+git merge -q feature || :
@@ -0,0 +1,29 @@
+#!/bin/bash
+set -eu -o pipefail
+
+mkdir repo
+(
+  cd repo
+  git init -q
+  git checkout -q -b main
+
+  echo a > a
+  git add a && git commit -q -m c1
+
+  git checkout -q -b feature
+  mkdir dir
+  echo b > dir/b
+  git add dir && git commit -q -m c2
+  echo b2 >> dir/b
+  git commit -q -am c3
+
+  git checkout -q main
+  echo a2 >> a
+  git commit -q -am c4
+  git merge -q --no-ff -m "merge feature" feature
+  git rm -q a && git commit -q -m c5
+  git tag -m "annotated" v1
+
+  git fast-export --all > ../export.stream
+  git for-each-ref --format='%(objectname) %(refname)' > ../refs.baseline
+)
@@ -0,0 +1,35 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  git checkout -q -b main
+  echo a > a
+  git add a && git commit -q -m c1
+  git tag base
+  git tag moved
+  git checkout -q -b rewritten
+  echo b > b
+  git add b && git commit -q -m c2
+  git checkout -q main
+)
+
+git clone -q remote local
+(cd local
+  git config --add remote.origin.fetch 'refs/tags/*:refs/tags/*'
+)
+
+(cd remote
+  echo changed > a
+  git commit -q -am c3
+  git tag v1
+  git tag -f moved >/dev/null
+  git checkout -q -b feature
+  echo f > f
+  git add f && git commit -q -m c4
+  git checkout -q rewritten
+  git reset -q --hard base
+  echo rewritten > b
+  git add b && git commit -q -m c2-rewritten
+  git checkout -q main
+)
@@ -0,0 +1,41 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+printf 'fn main() {\n    one();\n    two();\n    three();\n}\n\nfn other() {\n    a();\n    b();\n    c();\n    d();\n}\n' > code.rs
+echo deleted > gone
+printf 'no newline' > nonl
+echo executable > exe
+printf '\000\001\002' > bin.dat
+git add .
+git commit -q -m base
+git tag base
+
+printf 'fn main() {\n    one();\n    TWO();\n    three();\n}\n\nfn other() {\n    a();\n    b();\n    c();\n    d();\n    e();\n}\n' > code.rs
+git rm -q gone
+printf 'still no newline' > nonl
+chmod +x exe
+echo new > added
+: > empty
+mkdir -p a/very/long/directory/name/that/goes/on/and/on/forever
+echo file > a/very/long/directory/name/that/goes/on/and/on/forever/file.txt
+git add -A
+GIT_AUTHOR_NAME="A. U. Thor" GIT_AUTHOR_DATE="2005-04-07 15:13:13 -0700" git commit -q -F - <<MSG
+Change text files in many different ways to see that each of them is formatted like git does it
+
+The body explains why.
+
+With more than one paragraph.
+MSG
+git tag text
+
+printf '\000\001\002\003' > bin.dat
+printf 'binary\000data' > new.dat
+git add -A
+GIT_AUTHOR_NAME="Jöhn Doe" git commit -q -m "Sübject with ümlauts that is long enough to need more than one line to be encoded"
+git tag binary
+
+git format-patch -1 --stdout --no-signature --no-renames --base=base text > .git/text.patch
+git format-patch -1 --stdout --no-signature --no-renames binary > .git/binary.patch
@@ -0,0 +1,10 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config index.version 4
+
+mkdir -p dir/nested
+touch a dir/b dir/nested/c dir/nested/d
+git add .
+git commit -q -m "initial"
@@ -0,0 +1,22 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+git branch a
+git branch b
+
+git checkout -q a
+git commit -q --allow-empty -m a
+git checkout -q b
+git commit -q --allow-empty -m b
+git checkout -q main
+git commit -q --allow-empty -m c2
+git merge -q --no-ff -m octopus a b >/dev/null
+git tag -m annotated v1
+
+git repack -q -d
+git commit -q --allow-empty -m c3
+git repack -q -d
+git commit -q --allow-empty -m c4
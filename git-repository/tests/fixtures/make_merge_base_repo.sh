@@ -0,0 +1,25 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+git tag c1
+git commit -q --allow-empty -m c2
+git tag c2
+
+git checkout -q -b x c2
+git commit -q --allow-empty -m x1
+git checkout -q -b y c2
+git commit -q --allow-empty -m y1
+git checkout -q -b z c1
+git commit -q --allow-empty -m z1
+
+# a criss-cross merge with two best merge bases, x and y
+git checkout -q -b criss x
+git merge -q --no-ff y -m criss
+git checkout -q -b cross y
+git merge -q --no-ff x -m cross
+
+git checkout -q --orphan unrelated
+git commit -q --allow-empty -m u1
@@ -0,0 +1,20 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo "kept" > kept
+git add kept
+git commit -q -m "kept"
+git repack -q
+for pack in .git/objects/pack/*.pack; do
+  touch "${pack%.pack}.keep"
+done
+
+for i in $(seq 3); do
+  echo "$i" > file
+  git add file
+  git commit -q -m "c$i"
+  git repack -q
+done
@@ -0,0 +1,18 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+touch file
+git add file
+git commit -q -m c1
+git notes add -m "flat note"
+git commit -q --allow-empty -m c2
+
+commit=$(git rev-parse HEAD)
+note=$(echo "fanned out note" | git hash-object -w --stdin)
+inner=$(printf "100644 blob %s\t%s\n" "$note" "${commit:4}" | git mktree)
+middle=$(printf "040000 tree %s\t%s\n" "$inner" "${commit:2:2}" | git mktree)
+root=$(printf "040000 tree %s\t%s\n" "$middle" "${commit:0:2}" | git mktree)
+git update-ref refs/notes/fanout "$(git commit-tree -m "fanned out notes" "$root")"
@@ -0,0 +1,14 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+for i in $(seq 20); do
+  seq 100 > file
+  echo "$i" >> file
+  git add file
+  git commit -q -m "c$i"
+done
+git tag -a -m "annotated" v1
+
+git repack -adfq --depth=50 --window=250
@@ -0,0 +1,15 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config uploadpack.allowFilter true
+git config uploadpack.allowAnySHA1InWant true
+git checkout -q -b main
+echo old > a
+git add a && git commit -q -m c1
+
+echo new > a
+echo b > b
+git add a b && git commit -q -m c2
+
+git rev-parse HEAD~1:a > .git/old-blob
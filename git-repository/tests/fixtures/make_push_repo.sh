@@ -0,0 +1,35 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function remote_with_hook() {
+  git init -q --bare "$1"
+  cat > "$1/hooks/post-receive" <<'HOOK'
+#!/bin/sh
+: > push-options
+i=0
+while [ "$i" -lt "${GIT_PUSH_OPTION_COUNT:-0}" ]; do
+  eval "echo \"\$GIT_PUSH_OPTION_$i\"" >> push-options
+  i=$((i + 1))
+done
+HOOK
+  chmod +x "$1/hooks/post-receive"
+}
+
+remote_with_hook remote.git
+git -C remote.git config receive.advertisePushOptions true
+remote_with_hook plain.git
+
+git init -q local
+(cd local
+  git checkout -q -b main
+  echo a > a
+  git add a && git commit -q -m c1
+  git remote add origin "$PWD/../remote.git"
+  git remote add plain "$PWD/../plain.git"
+  git push -q origin main
+  echo b > b
+  git add b && git commit -q -m c2
+  git config --add push.pushOption ignored
+  git config --add push.pushOption ""
+  git config --add push.pushOption configured=yes
+)
@@ -0,0 +1,12 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+git commit -q --allow-empty -m c1
+git commit -q --allow-empty -m dropped
+git reset -q --hard HEAD~1
+
+# the only reflog entry made now, all others are as old as the fixed committer date
+env -u GIT_COMMITTER_DATE git commit -q --allow-empty -m c2
@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+ssh-keygen -q -t ed25519 -N "" -C author@example.com -f .git/signing_key
+echo "author@example.com $(cat .git/signing_key.pub)" > .git/allowed_signers
+git config gpg.format ssh
+git config user.signingKey "$PWD/.git/signing_key"
+git config gpg.ssh.allowedSignersFile "$PWD/.git/allowed_signers"
+git config commit.gpgSign true
+
+echo content > file
+git add file
+git commit -q -m "signed by git"
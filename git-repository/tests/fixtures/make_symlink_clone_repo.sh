@@ -0,0 +1,13 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+# a tree with a symlink 'a' and a directory 'a', whose file would be written through the symlink
+target=$(printf '../outside' | git hash-object -w --stdin)
+blob=$(echo content | git hash-object -w --stdin)
+subtree=$(printf '100644 blob %s\tfile\n' "$blob" | git mktree)
+tree=$(printf '120000 blob %s\ta\n040000 tree %s\ta\n' "$target" "$subtree" | git mktree)
+commit=$(git commit-tree -m "symlink and directory of the same name" "$tree")
+git update-ref refs/heads/main "$commit"
@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+printf '*.log\nignored-dir/\n' > .gitignore
+touch tracked
+mkdir dir && touch dir/tracked
+git add . && git commit -q -m c1
+
+git init -q submodule
+(cd submodule
+  git checkout -q -b main
+  touch tracked
+  git add tracked && git commit -q -m c1
+  touch untracked
+)
+git add submodule 2>/dev/null && git commit -q -m "add submodule"
+
+touch untracked dir/untracked a.log
+mkdir -p ignored-dir new-dir/sub
+touch ignored-dir/file new-dir/sub/file
+git init -q nested && touch nested/file
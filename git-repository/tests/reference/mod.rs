@@ -63,3 +63,43 @@ mod find {
         Ok(())
     }
 }
+
+mod classification {
+    fn repo() -> crate::Result<git_repository::Repository> {
+        crate::repo("make_references_repo.sh").map(Into::into)
+    }
+
+    #[test]
+    fn by_prefix() -> crate::Result {
+        let repo = repo()?;
+        let tag = repo.find_reference("refs/tags/t1")?;
+        assert!(tag.is_tag());
+        assert!(!tag.is_branch() && !tag.is_remote_tracking() && !tag.is_note());
+
+        let branch = repo.find_reference("refs/heads/main")?;
+        assert!(branch.is_branch());
+        assert!(!branch.is_tag() && !branch.is_remote_tracking() && !branch.is_note());
+
+        let remote = repo.find_reference("refs/remotes/origin/main")?;
+        assert!(remote.is_remote_tracking());
+        assert!(!remote.is_tag() && !remote.is_branch() && !remote.is_note());
+
+        let other = repo.find_reference("refs/d1")?;
+        assert!(!other.is_tag() && !other.is_branch() && !other.is_remote_tracking() && !other.is_note());
+        Ok(())
+    }
+
+    #[test]
+    fn short_name() -> crate::Result {
+        let repo = repo()?;
+        for (name, expected) in [
+            ("refs/tags/t1", "t1"),
+            ("refs/heads/main", "main"),
+            ("refs/remotes/origin/main", "origin/main"),
+            ("refs/d1", "refs/d1"),
+        ] {
+            assert_eq!(repo.find_reference(name)?.short_name(), expected);
+        }
+        Ok(())
+    }
+}
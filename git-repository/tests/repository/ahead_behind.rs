@@ -0,0 +1,35 @@
+use git_repository as git;
+
+use crate::{named_repo, Result};
+
+fn id(repo: &git::Repository, name: &str) -> Result<git::ObjectId> {
+    Ok(repo.find_reference(name)?.peel_to_id_in_place()?.detach())
+}
+
+#[test]
+fn diverged_branches_count_the_commits_past_their_merge_base() -> Result {
+    let repo = named_repo("make_ahead_behind_repo.sh")?;
+    let (main, feature) = (id(&repo, "main")?, id(&repo, "feature")?);
+    assert_eq!(repo.compute_ahead_behind(feature, main)?, (3, 2));
+    assert_eq!(repo.compute_ahead_behind(main, feature)?, (2, 3));
+    assert_eq!(
+        repo.compute_ahead_behind(feature, main)?,
+        (3, 2),
+        "cached results are the same"
+    );
+    Ok(())
+}
+
+#[test]
+fn merged_and_identical_commits() -> Result {
+    let repo = named_repo("make_ahead_behind_repo.sh")?;
+    let (main, merged) = (id(&repo, "main")?, id(&repo, "merged")?);
+    assert_eq!(
+        repo.compute_ahead_behind(merged, main)?,
+        (4, 0),
+        "the merge commit and all commits of the feature branch"
+    );
+    assert_eq!(repo.compute_ahead_behind(main, merged)?, (0, 4));
+    assert_eq!(repo.compute_ahead_behind(main, main)?, (0, 0));
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use git_repository as git;
+
+use crate::{repo_rw, Result};
+
+fn repo_and_patch() -> Result<(git::Repository, Vec<u8>, tempfile::TempDir)> {
+    let (repo, dir) = repo_rw("make_apply_repo.sh")?;
+    let patch = std::fs::read(repo.git_dir().join("change.patch"))?;
+    Ok((repo, patch, dir))
+}
+
+fn numbers(drift: &[(usize, &str)]) -> String {
+    (1..=20)
+        .map(|n| {
+            drift
+                .iter()
+                .find(|(line, _)| *line == n)
+                .map_or_else(|| n.to_string(), |(_, content)| content.to_string())
+                + "\n"
+        })
+        .collect()
+}
+
+#[test]
+fn patches_are_applied_to_the_work_tree() -> Result {
+    let (repo, patch, _keep) = repo_and_patch()?;
+    let work_dir = repo.work_dir().expect("non-bare");
+
+    let outcome = repo.apply_patch(&patch, Default::default())?;
+    assert_eq!(outcome.applied_hunks, 5);
+    assert_eq!(outcome.rejected_hunks, 0);
+    assert_eq!(outcome.modified_files, ["bin.dat", "exe", "gone", "new", "numbers"]);
+    assert!(outcome.conflicting_files.is_empty());
+
+    assert_eq!(
+        std::fs::read_to_string(work_dir.join("numbers"))?,
+        numbers(&[(3, "three"), (18, "eighteen")])
+    );
+    assert_eq!(std::fs::read(work_dir.join("bin.dat"))?, b"\x00\x01\x02\x03");
+    assert_eq!(std::fs::read(work_dir.join("new"))?, b"new\n");
+    assert!(!work_dir.join("gone").exists());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_ne!(std::fs::metadata(work_dir.join("exe"))?.permissions().mode() & 0o111, 0);
+    }
+
+    assert!(
+        matches!(
+            repo.apply_patch(&patch, Default::default()),
+            Err(git::apply::Error::HunkFailed { path, hunk: 1 }) if path == "bin.dat"
+        ),
+        "the same patch can't be applied twice"
+    );
+    Ok(())
+}
+
+#[test]
+fn changed_context_is_merged_with_the_original_file() -> Result {
+    let (repo, patch, _keep) = repo_and_patch()?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    std::fs::write(work_dir.join("numbers"), numbers(&[(5, "five")]))?;
+
+    let outcome = repo.apply_patch(&patch, Default::default())?;
+    assert_eq!(outcome.applied_hunks, 5);
+    assert!(outcome.conflicting_files.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(work_dir.join("numbers"))?,
+        numbers(&[(3, "three"), (5, "five"), (18, "eighteen")])
+    );
+    Ok(())
+}
+
+#[test]
+fn conflicting_changes_leave_conflict_markers() -> Result {
+    let (repo, patch, _keep) = repo_and_patch()?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    std::fs::write(work_dir.join("numbers"), numbers(&[(3, "drei")]))?;
+
+    let outcome = repo.apply_patch(&patch, Default::default())?;
+    assert_eq!(outcome.conflicting_files, ["numbers"]);
+    let content = std::fs::read_to_string(work_dir.join("numbers"))?;
+    assert!(content.contains("<<<<<<<") && content.contains("drei") && content.contains("three"));
+    assert!(content.contains("eighteen"), "non-conflicting changes are merged");
+    Ok(())
+}
+
+#[test]
+fn hunks_without_preimage_are_rejected_or_fail() -> Result {
+    let (repo, patch, _keep) = repo_and_patch()?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    let patch: Vec<u8> = patch
+        .split_inclusive(|b| *b == b'\n')
+        .filter(|line| !line.starts_with(b"index "))
+        .flatten()
+        .copied()
+        .collect();
+    let drifted = numbers(&[(2, "two")]);
+    std::fs::write(work_dir.join("numbers"), &drifted)?;
+
+    assert!(matches!(
+        repo.apply_patch(&patch, Default::default()),
+        Err(git::apply::Error::HunkFailed { path, hunk: 1 }) if path == "numbers"
+    ));
+    assert!(work_dir.join("gone").exists(), "nothing changes on error");
+    assert_eq!(std::fs::read_to_string(work_dir.join("numbers"))?, drifted);
+
+    let outcome = repo.apply_patch(&patch, git::apply::Options { reject: true })?;
+    assert_eq!(outcome.applied_hunks, 4);
+    assert_eq!(outcome.rejected_hunks, 1);
+    assert_eq!(
+        std::fs::read_to_string(work_dir.join("numbers"))?,
+        numbers(&[(2, "two"), (18, "eighteen")])
+    );
+    assert_eq!(
+        std::fs::read_to_string(work_dir.join("numbers.rej"))?,
+        "diff a/numbers b/numbers\t(rejected hunks)\n@@ -1,6 +1,6 @@\n 1\n 2\n-3\n+three\n 4\n 5\n 6\n"
+    );
+    assert!(!work_dir.join("gone").exists());
+    Ok(())
+}
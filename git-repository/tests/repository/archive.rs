@@ -0,0 +1,42 @@
+use git_repository as git;
+
+use crate::{basic_repo, Result};
+
+fn options() -> git::archive::Options {
+    git::archive::Options {
+        prefix: Some("prefix/".into()),
+        mtime: Some(git::actor::Time::new(1_000_000_000, 0)),
+    }
+}
+
+#[test]
+fn tar_contains_all_files_with_prefix() -> Result {
+    let repo = basic_repo()?;
+    let mut buf = Vec::new();
+    let stats = repo.archive(repo.head_id()?, git::archive::Format::Tar, &mut buf, options())?;
+    assert_eq!(stats.entries, 1, "a single file");
+    assert_eq!(stats.bytes, 6, "'hello\\n'");
+
+    let mut archive = tar::Archive::new(buf.as_slice());
+    let paths = archive
+        .entries()?
+        .map(|e| Ok(e?.path()?.into_owned()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    assert_eq!(paths, vec![std::path::PathBuf::from("prefix/this")]);
+    Ok(())
+}
+
+#[test]
+fn zip_has_local_headers_and_central_directory() -> Result {
+    let repo = basic_repo()?;
+    let mut buf = Vec::new();
+    let stats = repo.archive(repo.head_id()?, git::archive::Format::Zip, &mut buf, options())?;
+    assert_eq!(stats.entries, 1);
+    assert_eq!(&buf[..4], b"PK\x03\x04", "it starts with a local file header");
+    assert_eq!(
+        &buf[buf.len() - 22..][..4],
+        b"PK\x05\x06",
+        "it ends with the end of central directory record"
+    );
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use git_repository as git;
+
+use crate::{basic_rw_repo, Result};
+
+#[test]
+fn changed_characters_within_changed_lines() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let old = repo.write_object(git::objs::Blob {
+        data: "hello world\nunchanged\nthird\n".into(),
+    })?;
+    let new = repo.write_object(git::objs::Blob {
+        data: "hello there world\nunchanged\nthirst\nfourth\n".into(),
+    })?;
+
+    let diff = repo.blob_diff_chars(old, new)?;
+    assert!(!diff.is_empty());
+    let hunks: Vec<_> = diff
+        .iter()
+        .map(|hunk| {
+            (
+                hunk.context.to_string(),
+                hunk.deletions.to_string(),
+                hunk.insertions.to_string(),
+            )
+        })
+        .collect();
+    let hunk = |context: &str, deletions: &str, insertions: &str| {
+        (context.to_owned(), deletions.to_owned(), insertions.to_owned())
+    };
+    assert_eq!(
+        hunks,
+        vec![
+            hunk("hello ", "", "there "),
+            hunk("world\nunchanged\nthir", "d", "st"),
+            hunk("\n", "", "fourth\n"),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn equal_blobs_have_only_context() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let id = repo.write_object(git::objs::Blob { data: "a\nb\n".into() })?;
+
+    let diff = repo.blob_diff_chars(id, id)?;
+    assert!(diff.is_empty());
+    assert_eq!(diff.iter().count(), 1, "a single hunk with all content as context");
+    assert_eq!(diff.iter().next().expect("one hunk").context, "a\nb\n");
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use std::convert::TryInto;
+
+use git_repository as git;
+
+use crate::{named_repo, Result};
+
+fn id(repo: &git::Repository, name: &str) -> Result<git::ObjectId> {
+    Ok(repo.find_reference(name)?.target().try_id().expect("peeled").to_owned())
+}
+
+#[test]
+fn header_lists_prerequisites_and_tips_followed_by_a_pack() -> Result {
+    let repo = named_repo("make_bundle_repo.sh")?;
+    let main = id(&repo, "refs/heads/main")?;
+    let tag = id(&repo, "refs/tags/v1")?;
+    let base = id(&repo, "refs/tags/base")?;
+
+    let mut bundle = Vec::new();
+    let outcome = repo.bundle_create(
+        &mut bundle,
+        &[("refs/heads/main", main), ("refs/tags/v1", tag)],
+        &[base],
+        git::progress::Discard,
+        Default::default(),
+    )?;
+    assert_eq!(
+        outcome.num_commits, 2,
+        "c2 and c3 aren't reachable from the prerequisite"
+    );
+
+    let header = format!(
+        "# v2 git bundle\n-{}\n{} refs/heads/main\n{} refs/tags/v1\n\n",
+        base, main, tag
+    );
+    assert_eq!(&bundle[..header.len()], header.as_bytes());
+    let pack = &bundle[header.len()..];
+    assert_eq!(&pack[..4], b"PACK");
+    assert_eq!(outcome.pack.pack_size, pack.len() as u64);
+    assert_eq!(
+        u32::from_be_bytes(pack[8..12].try_into()?) as usize,
+        outcome.pack.num_objects
+    );
+    assert!(
+        outcome.pack.num_objects >= 9,
+        "two commits, their trees, four new blobs and the annotated tag"
+    );
+    Ok(())
+}
+
+#[test]
+fn bundles_without_new_commits_are_refused() -> Result {
+    let repo = named_repo("make_bundle_repo.sh")?;
+    let base = id(&repo, "refs/tags/base")?;
+    let err = repo
+        .bundle_create(
+            Vec::new(),
+            &[("refs/tags/base", base)],
+            &[base],
+            git::progress::Discard,
+            Default::default(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, git::bundle::create::Error::Empty));
+    Ok(())
+}
+
+#[test]
+fn tip_names_must_be_valid_reference_names() -> Result {
+    let repo = named_repo("make_bundle_repo.sh")?;
+    let main = id(&repo, "refs/heads/main")?;
+    let err = repo
+        .bundle_create(
+            Vec::new(),
+            &[("refs/heads/a..b", main)],
+            &[],
+            git::progress::Discard,
+            Default::default(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, git::bundle::create::Error::InvalidRefName { .. }));
+    Ok(())
+}
+
+mod apply {
+    use git_repository as git;
+
+    use super::id;
+    use crate::{repo_rw, Result};
+
+    fn write_bundle(
+        repo: &git::Repository,
+        tips: &[(&str, git::ObjectId)],
+        prereqs: &[git::ObjectId],
+        path: &std::path::Path,
+    ) -> Result {
+        let mut bundle = Vec::new();
+        repo.bundle_create(&mut bundle, tips, prereqs, git::progress::Discard, Default::default())?;
+        std::fs::write(path, bundle)?;
+        Ok(())
+    }
+
+    #[test]
+    fn objects_and_references_are_added_to_a_repository_with_all_prerequisites() -> Result {
+        let (repo, tmp) = repo_rw("make_bundle_repo.sh")?;
+        let main = id(&repo, "refs/heads/main")?;
+        let tag = id(&repo, "refs/tags/v1")?;
+        let base = id(&repo, "refs/tags/base")?;
+        let path = tmp.path().join("repo.bundle");
+        write_bundle(
+            &repo,
+            &[("refs/heads/main", main), ("refs/tags/v1", tag)],
+            &[base],
+            &path,
+        )?;
+
+        let partial = git::open(tmp.path().join("partial"))?;
+        let info = partial.bundle_verify(&path)?;
+        assert_eq!(info.version, 2);
+        assert_eq!(info.prerequisites, vec![base]);
+        assert_eq!(
+            info.references
+                .iter()
+                .map(|(name, id)| (name.as_bstr().to_string(), *id))
+                .collect::<Vec<_>>(),
+            vec![("refs/heads/main".into(), main), ("refs/tags/v1".into(), tag)]
+        );
+
+        let edits = partial.bundle_apply(
+            &path,
+            git::progress::Discard,
+            git::bundle::apply::Options {
+                refname_prefix: Some("refs/bundles/".into()),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(edits.len(), 2);
+        assert_eq!(
+            id(&partial, "refs/bundles/heads/main")?,
+            main,
+            "references are placed below the prefix"
+        );
+        assert_eq!(id(&partial, "refs/bundles/tags/v1")?, tag);
+        assert_eq!(
+            id(&partial, "refs/heads/main")?,
+            base,
+            "existing references are left untouched"
+        );
+        assert_eq!(
+            partial.find_object(main)?.peel_to_kind(git::object::Kind::Tree)?.id,
+            repo.find_object(main)?.peel_to_kind(git::object::Kind::Tree)?.id,
+            "the objects of the pack are available"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn thin_packs_created_by_git_are_completed_with_bases_from_the_prerequisites() -> Result {
+        let (repo, tmp) = repo_rw("make_bundle_repo.sh")?;
+        let main = id(&repo, "refs/heads/main")?;
+        let base = id(&repo, "refs/tags/base")?;
+        let path = tmp.path().join("thin.bundle");
+
+        let partial = git::open(tmp.path().join("partial"))?;
+        assert_eq!(partial.bundle_verify(&path)?.prerequisites, vec![base]);
+        let edits = partial.bundle_apply(&path, git::progress::Discard, Default::default())?;
+        assert_eq!(edits.len(), 1);
+        assert_eq!(id(&partial, "refs/heads/main")?, main);
+
+        let numbers = repo
+            .find_object(main)?
+            .peel_to_kind(git::object::Kind::Tree)?
+            .into_tree()
+            .lookup_path(Some("numbers"))?
+            .expect("present")
+            .oid;
+        assert_eq!(
+            partial.find_object(numbers)?.data,
+            repo.find_object(numbers)?.data,
+            "the blob stored as delta against its version in the prerequisite can be decoded"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_prerequisites_are_reported() -> Result {
+        let (repo, tmp) = repo_rw("make_bundle_repo.sh")?;
+        let main = id(&repo, "refs/heads/main")?;
+        let v1 = repo
+            .find_object(id(&repo, "refs/tags/v1")?)?
+            .peel_to_kind(git::object::Kind::Commit)?
+            .id;
+        let path = tmp.path().join("repo.bundle");
+        write_bundle(&repo, &[("refs/heads/main", main)], &[v1], &path)?;
+
+        let partial = git::open(tmp.path().join("partial"))?;
+        match partial.bundle_verify(&path) {
+            Err(git::bundle::verify::Error::MissingPrerequisites { ids }) => assert_eq!(ids, vec![v1]),
+            res => panic!("expected missing prerequisites, got {:?}", res),
+        }
+        assert!(partial
+            .bundle_apply(&path, git::progress::Discard, Default::default())
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use std::convert::TryInto;
+
+use git_repository as git;
+
+use crate::{repo_rw, Result};
+
+#[test]
+fn files_differing_between_branches_are_changed_and_head_is_updated() -> Result {
+    let (repo, _keep) = repo_rw("make_checkout_branch_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    let feature: &git::refs::FullNameRef = "refs/heads/feature".try_into()?;
+
+    let stats = repo.checkout_branch(feature, Default::default())?;
+    assert_eq!(
+        stats,
+        git::checkout_branch::Stats {
+            files_added: 1,
+            files_modified: 1,
+            files_removed: 1,
+        }
+    );
+    assert_eq!(std::fs::read(work_dir.join("changed"))?, b"modified\n");
+    assert_eq!(std::fs::read(work_dir.join("new/added"))?, b"added\n");
+    assert!(!work_dir.join("dir").exists(), "empty directories are removed");
+    assert_eq!(std::fs::read(work_dir.join("unchanged"))?, b"unchanged\n");
+    assert_eq!(
+        std::fs::read(work_dir.join("untracked"))?,
+        b"untracked\n",
+        "untracked files are kept"
+    );
+
+    let index = repo.open_index()?;
+    let mut index_files: Vec<_> = index
+        .entries()
+        .iter()
+        .map(|entry| (entry.path(&index).to_owned(), entry.id))
+        .collect();
+    index_files.sort();
+    let feature_tree = repo
+        .find_object(repo.find_reference("feature")?.id().object()?.into_commit().tree_id()?)?
+        .into_tree();
+    let mut tree_files: Vec<_> = feature_tree
+        .traverse()
+        .breadthfirst
+        .files()?
+        .into_iter()
+        .map(|entry| (entry.filepath, entry.oid))
+        .collect();
+    tree_files.sort();
+    assert_eq!(index_files, tree_files, "the index matches the tree of the branch");
+
+    let head = repo.head()?;
+    assert_eq!(head.referent_name(), Some(feature));
+    let line = head.log_iter().rev()?.expect("log present").next().expect("one line")?;
+    assert_eq!(line.message, "checkout: moving from main to feature");
+    assert_eq!(line.previous_oid, repo.find_reference("main")?.id().detach());
+    assert_eq!(line.new_oid, repo.find_reference("feature")?.id().detach());
+    Ok(())
+}
+
+#[test]
+fn local_changes_to_files_to_be_changed_are_a_conflict_unless_forced() -> Result {
+    let (repo, _keep) = repo_rw("make_checkout_branch_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    let feature: &git::refs::FullNameRef = "refs/heads/feature".try_into()?;
+    std::fs::write(work_dir.join("changed"), "local change\n")?;
+    std::fs::write(work_dir.join("new"), "untracked file in the way\n")?;
+
+    match repo.checkout_branch(feature, Default::default()) {
+        Err(git::checkout_branch::Error::Conflict { paths }) => {
+            assert_eq!(paths, vec!["changed", "new/added"])
+        }
+        res => panic!("expected a conflict, got {:?}", res),
+    }
+    assert_eq!(
+        std::fs::read(work_dir.join("changed"))?,
+        b"local change\n",
+        "nothing was changed"
+    );
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+
+    std::fs::remove_file(work_dir.join("new"))?;
+    repo.checkout_branch(
+        feature,
+        git::checkout_branch::Options {
+            force: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(std::fs::read(work_dir.join("changed"))?, b"modified\n");
+    Ok(())
+}
+
+#[test]
+fn staged_changes_to_files_to_be_changed_are_a_conflict() -> Result {
+    let (repo, _keep) = repo_rw("make_checkout_branch_repo.sh")?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    let feature: &git::refs::FullNameRef = "refs/heads/feature".try_into()?;
+    std::fs::write(work_dir.join("changed"), "staged change\n")?;
+    assert!(std::process::Command::new("git")
+        .args(["add", "changed"])
+        .current_dir(&work_dir)
+        .status()?
+        .success());
+    std::fs::write(work_dir.join("changed"), "original\n")?;
+
+    match repo.checkout_branch(feature, Default::default()) {
+        Err(git::checkout_branch::Error::Conflict { paths }) => assert_eq!(paths, vec!["changed"]),
+        res => panic!("expected a conflict, got {:?}", res),
+    }
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    Ok(())
+}
+
+#[test]
+fn a_cancelled_token_stops_the_checkout_before_head_changes() -> Result {
+    let (repo, _keep) = repo_rw("make_checkout_branch_repo.sh")?;
+    let feature: &git::refs::FullNameRef = "refs/heads/feature".try_into()?;
+    let token = git::interrupt::CancellationToken::new();
+    token.cancel();
+
+    let res = repo.checkout_branch(
+        feature,
+        git::checkout_branch::Options {
+            cancellation: Some(token),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(res, Err(git::checkout_branch::Error::Interrupted)));
+    assert!(
+        !git::interrupt::is_triggered(),
+        "the global interrupt flag is unaffected"
+    );
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/main");
+    Ok(())
+}
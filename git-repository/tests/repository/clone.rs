@@ -0,0 +1,213 @@
+use std::convert::TryInto;
+
+use git_repository as git;
+
+use crate::{named_repo, Result};
+
+fn id(repo: &git::Repository, name: &str) -> Result<git::ObjectId> {
+    Ok(repo.find_reference(name)?.id().detach())
+}
+
+#[test]
+fn branches_and_tags_are_fetched_and_head_is_checked_out() -> Result {
+    let remote = named_repo("make_bundle_repo.sh")?;
+    let url = remote.work_dir().expect("non-bare").to_str().expect("valid UTF-8");
+    let dir = tempfile::tempdir()?;
+    let repo = git::Repository::clone_into(url, &dir.path().join("clone"), Default::default())?;
+
+    let main_id = id(&remote, "refs/heads/main")?;
+    assert_eq!(id(&repo, "refs/remotes/origin/main")?, main_id);
+    assert_eq!(id(&repo, "refs/heads/main")?, main_id);
+    assert_eq!(
+        id(&repo, "refs/tags/v1")?,
+        id(&remote, "refs/tags/v1")?,
+        "the tag object"
+    );
+    assert_eq!(id(&repo, "refs/tags/base")?, id(&remote, "refs/tags/base")?);
+
+    let main: &git::refs::FullNameRef = "refs/heads/main".try_into()?;
+    let head = repo.head()?;
+    assert_eq!(head.referent_name(), Some(main));
+    let line = head.log_iter().rev()?.expect("log present").next().expect("one line")?;
+    assert_eq!(line.message, format!("clone: from {}", url));
+
+    let work_dir = repo.work_dir().expect("non-bare");
+    assert_eq!(std::fs::read(work_dir.join("a"))?, b"changed\n");
+    assert_eq!(std::fs::read(work_dir.join("c"))?, b"c\n");
+    let index = repo.open_index()?;
+    let paths: Vec<_> = index
+        .entries()
+        .iter()
+        .map(|entry| entry.path(&index).to_string())
+        .collect();
+    assert_eq!(paths, ["a", "b", "c", "numbers"]);
+
+    let config = repo.config_snapshot();
+    assert_eq!(config.value("remote.origin", "url").as_deref(), Some(url.as_bytes()));
+    assert_eq!(
+        config.value("remote.origin", "fetch").as_deref(),
+        Some(&b"+refs/heads/*:refs/remotes/origin/*"[..])
+    );
+    assert_eq!(
+        config.value("branch.main", "merge").as_deref(),
+        Some(&b"refs/heads/main"[..])
+    );
+    Ok(())
+}
+
+#[test]
+fn bare_clones_have_the_branches_of_the_remote() -> Result {
+    let remote = named_repo("make_bundle_repo.sh")?;
+    let url = remote.work_dir().expect("non-bare").to_str().expect("valid UTF-8");
+    let dir = tempfile::tempdir()?;
+    let repo = git::Repository::clone_into_with_progress(
+        url,
+        &dir.path().join("clone.git"),
+        git::clone::Options {
+            bare: true,
+            ..Default::default()
+        },
+        git::progress::Discard,
+    )?;
+
+    assert!(repo.is_bare());
+    assert_eq!(id(&repo, "refs/heads/main")?, id(&remote, "refs/heads/main")?);
+    assert!(repo.try_find_reference("refs/remotes/origin/main")?.is_none());
+    let main: &git::refs::FullNameRef = "refs/heads/main".try_into()?;
+    assert_eq!(repo.head()?.referent_name(), Some(main));
+    Ok(())
+}
+
+#[test]
+fn blobless_partial_clones_fetch_blobs_on_demand() -> Result {
+    use git::{bstr::ByteSlice, odb::Find};
+
+    let remote = named_repo("make_partial_clone_repo.sh")?;
+    let url = remote.work_dir().expect("non-bare").to_str().expect("valid UTF-8");
+    let old_blob = git::ObjectId::from_hex(std::fs::read(remote.git_dir().join("old-blob"))?.trim_end())?;
+    let dir = tempfile::tempdir()?;
+    let repo = git::Repository::partial_clone_blobless(url, &dir.path().join("clone"), Default::default())?;
+
+    assert_eq!(repo.promisor_remote().as_deref(), Some("origin"));
+    let config = repo.config_snapshot();
+    assert_eq!(
+        config.value("core", "repositoryformatversion").as_deref(),
+        Some(&b"1"[..]),
+        "extensions are only respected in repositories of version 1"
+    );
+    assert_eq!(config.value("remote.origin", "promisor").as_deref(), Some(&b"true"[..]));
+    assert_eq!(
+        config.value("remote.origin", "partialclonefilter").as_deref(),
+        Some(&b"blob:none"[..])
+    );
+
+    let work_dir = repo.work_dir().expect("non-bare");
+    assert_eq!(
+        std::fs::read(work_dir.join("a"))?,
+        b"new\n",
+        "blobs of the checkout are fetched"
+    );
+    assert_eq!(std::fs::read(work_dir.join("b"))?, b"b\n");
+
+    assert!(
+        !repo.objects.contains(old_blob),
+        "blobs of previous commits were filtered"
+    );
+    assert_eq!(
+        repo.list_promisor_objects()?,
+        Some(old_blob).into_iter().collect(),
+        "only the filtered blob is promised as all others were fetched for the checkout"
+    );
+    assert!(repo.is_promised(old_blob));
+    assert_eq!(
+        repo.find_object(old_blob)?.data,
+        b"old\n",
+        "missing objects are fetched from the promisor remote"
+    );
+    assert!(repo.objects.contains(old_blob));
+    assert!(!repo.is_promised(old_blob), "fetched objects aren't promised anymore");
+    assert!(repo.list_promisor_objects()?.is_empty());
+
+    let promisor_files = std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "promisor"))
+        .count();
+    assert_eq!(
+        promisor_files, 3,
+        "the clone, the checkout and the lazy fetch each received a pack"
+    );
+    Ok(())
+}
+
+#[test]
+fn blobless_partial_clones_fetch_blobs_when_looked_up_in_the_object_database() -> Result {
+    use git::{bstr::ByteSlice, odb::Find};
+
+    let remote = named_repo("make_partial_clone_repo.sh")?;
+    let url = remote.work_dir().expect("non-bare").to_str().expect("valid UTF-8");
+    let old_blob = git::ObjectId::from_hex(std::fs::read(remote.git_dir().join("old-blob"))?.trim_end())?;
+    let dir = tempfile::tempdir()?;
+    let repo = git::Repository::partial_clone_blobless(url, &dir.path().join("clone"), Default::default())?;
+
+    let mut buf = Vec::new();
+    let obj = repo.objects.try_find(old_blob, &mut buf)?.expect("fetched on miss");
+    assert_eq!(obj.data, b"old\n");
+
+    let unknown = git::ObjectId::from_hex(b"00000000000000000000000000000000000000ff")?;
+    assert!(
+        matches!(
+            repo.objects.try_find(unknown, &mut buf),
+            Err(git::odb::store::find::Error::Promise(
+                git::odb::store::find::PromiseError::FetchFailed { .. }
+            ))
+        ),
+        "objects the remote doesn't have fail to be fetched"
+    );
+    Ok(())
+}
+
+#[test]
+fn a_cancelled_token_stops_the_clone() -> Result {
+    let remote = named_repo("make_bundle_repo.sh")?;
+    let url = remote.work_dir().expect("non-bare").to_str().expect("valid UTF-8");
+    let dir = tempfile::tempdir()?;
+    let token = git::interrupt::CancellationToken::new();
+    token.cancel();
+
+    assert!(git::Repository::clone_into(
+        url,
+        &dir.path().join("clone"),
+        git::clone::Options {
+            cancellation: Some(token),
+            ..Default::default()
+        },
+    )
+    .is_err());
+    assert!(
+        !git::interrupt::is_triggered(),
+        "the global interrupt flag is unaffected"
+    );
+    Ok(())
+}
+
+#[test]
+fn files_below_symlinks_of_the_same_tree_are_rejected() -> Result {
+    let remote = named_repo("make_symlink_clone_repo.sh")?;
+    let url = remote.work_dir().expect("non-bare").to_str().expect("valid UTF-8");
+    let dir = tempfile::tempdir()?;
+    let err = git::Repository::clone_into(url, &dir.path().join("clone"), Default::default())
+        .expect_err("the tree writes through a symlink");
+    assert!(matches!(
+        err,
+        git::clone::Error::SymlinkInPath { path, symlink } if path == "a/file" && symlink == "a"
+    ));
+    assert!(
+        !dir.path().join("outside").exists(),
+        "nothing was written through the symlink"
+    );
+    assert!(
+        std::fs::symlink_metadata(dir.path().join("clone").join("a")).is_err(),
+        "nothing was written at all"
+    );
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use git_repository::commit_format::CommitFormat;
+
+use crate::{named_repo, Result};
+
+#[test]
+fn placeholders() -> Result {
+    let repo = named_repo("make_commit_format_repo.sh")?;
+    let commit = repo.head_commit()?;
+    let format = |format: &str| repo.format_commit_summary(&commit, &CommitFormat::new(format));
+
+    assert_eq!(format("%H")?, commit.id.to_hex().to_string());
+    assert_eq!(format("%h")?, commit.short_id()?.to_string());
+    assert_eq!(
+        format("%an <%ae> %ai")?,
+        "author <author@example.com> 2005-04-07 15:13:13 -0700"
+    );
+    assert_eq!(
+        format("%s")?,
+        "subject line continued",
+        "lines of the subject are joined"
+    );
+    assert_eq!(format("%b")?, "body line 1\nbody line 2\n");
+    assert_eq!(
+        format("[%s]%d")?,
+        "[subject line continued] (HEAD -> main, origin/main, tag: v1)",
+        "HEAD comes first, the other references are sorted by their full name"
+    );
+    Ok(())
+}
+
+#[test]
+fn literals_and_unknown_placeholders() -> Result {
+    let repo = named_repo("make_commit_format_repo.sh")?;
+    let commit = repo.head_commit()?;
+    let other = repo
+        .find_reference("other")?
+        .peel_to_id_in_place()?
+        .object()?
+        .into_commit();
+    let format = CommitFormat::new("100%% %x%d%");
+
+    assert_eq!(
+        repo.format_commit_summary(&commit, &format)?,
+        "100% %x (HEAD -> main, origin/main, tag: v1)%"
+    );
+    assert_eq!(repo.format_commit_summary(&other, &format)?, "100% %x (other)%");
+    assert_eq!(repo.format_commit_summary(&other, &CommitFormat::new(""))?, "");
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use git_repository as git;
+
+use crate::{named_repo, Result};
+
+fn id(repo: &git::Repository, name: &str) -> Result<git::ObjectId> {
+    Ok(repo.find_reference(name)?.peel_to_id_in_place()?.detach())
+}
+
+fn first_parent(repo: &git::Repository, id: git::ObjectId) -> Result<git::ObjectId> {
+    Ok(repo
+        .find_object(id)?
+        .try_into_commit()?
+        .parent_ids()
+        .next()
+        .expect("has parent")
+        .detach())
+}
+
+#[test]
+fn generations_are_read_from_the_commit_graph() -> Result {
+    let repo = named_repo("make_ahead_behind_repo.sh")?;
+    let c3 = id(&repo, "main")?;
+    let c2 = first_parent(&repo, c3)?;
+    let c1 = first_parent(&repo, c2)?;
+    let f3 = id(&repo, "feature")?;
+    let f1 = first_parent(&repo, first_parent(&repo, f3)?)?;
+
+    assert_eq!(repo.commit_generation(c1)?, Some(1));
+    assert_eq!(repo.commit_generation(c3)?, Some(3));
+    assert_eq!(repo.commit_generation(f1)?, Some(2));
+    assert_eq!(
+        repo.commit_generation(f3)?,
+        None,
+        "commits written after the commit-graph aren't in it"
+    );
+    Ok(())
+}
+
+#[test]
+fn ancestry_is_ruled_out_by_generation() -> Result {
+    let repo = named_repo("make_ahead_behind_repo.sh")?;
+    let c3 = id(&repo, "main")?;
+    let c2 = first_parent(&repo, c3)?;
+    let c1 = first_parent(&repo, c2)?;
+    let f3 = id(&repo, "feature")?;
+    let f1 = first_parent(&repo, first_parent(&repo, f3)?)?;
+
+    assert_eq!(repo.is_ancestor_via_generation(c3, c1), Some(false));
+    assert_eq!(
+        repo.is_ancestor_via_generation(f1, c2),
+        Some(false),
+        "the same generation means neither can reach the other"
+    );
+    assert_eq!(repo.is_ancestor_via_generation(c3, c3), Some(true));
+    assert_eq!(
+        repo.is_ancestor_via_generation(c1, c3),
+        None,
+        "a lower generation doesn't imply ancestry"
+    );
+    assert_eq!(
+        repo.is_ancestor_via_generation(c3, f3),
+        None,
+        "commits not in the graph need a traversal"
+    );
+    Ok(())
+}
+
+#[test]
+fn repositories_without_commit_graph_have_no_generations() -> Result {
+    let repo = named_repo("make_basic_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    assert_eq!(repo.commit_generation(head)?, None);
+    assert_eq!(repo.is_ancestor_via_generation(head, head), Some(true));
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use git_config::file::Origin;
+
+use crate::{basic_repo, Result};
+
+#[test]
+fn load_config_layer_overrides_values_and_their_source() -> Result {
+    let mut repo = basic_repo()?;
+    let source = repo.config_value_source("core", None, "bare").expect("set by git init");
+    assert_eq!(source.origin, Origin::Repository);
+    assert_eq!(source.path, Some(repo.git_dir().join("config")));
+    assert!(!repo.is_bare());
+
+    repo.load_config_layer("core.bare=true")?;
+    repo.load_config_layer("remote.with.dots.url=https://example.com")?;
+    assert!(repo.is_bare(), "cached values are updated");
+    let source = repo.config_value_source("core", None, "bare").expect("present");
+    assert_eq!(source.origin, Origin::CommandLine);
+    assert_eq!(source.path, None);
+    assert_eq!(
+        repo.config_value_source("remote", Some("with.dots"), "url")
+            .map(|source| source.origin),
+        Some(Origin::CommandLine)
+    );
+
+    assert!(repo.load_config_layer("nosection").is_err());
+    Ok(())
+}
+
+#[test]
+fn config_snapshot_is_unaffected_by_later_changes() -> Result {
+    let mut repo = basic_repo()?;
+    repo.load_config_layer("remote.origin.url=https://example.com/one")?;
+    let snapshot = repo.config_snapshot();
+    repo.load_config_layer("remote.origin.url=https://example.com/two")?;
+
+    assert_eq!(
+        snapshot.value("remote.origin", "url").as_deref(),
+        Some(&b"https://example.com/one"[..])
+    );
+    assert_eq!(
+        repo.config_snapshot().value("remote.origin", "url").as_deref(),
+        Some(&b"https://example.com/two"[..])
+    );
+    assert_eq!(snapshot.value("core", "bare").as_deref(), Some(&b"false"[..]));
+    assert_eq!(snapshot.value("core", "missing"), None);
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use git_repository as git;
+
+use crate::{basic_rw_repo, Result};
+
+const ANCESTOR: &[u8] = b"a\nb\nc\nd\n";
+const OURS: &[u8] = b"a\n1\n2\n3\nd\n";
+const THEIRS: &[u8] = b"a\n1\nQ\n3\nd\n";
+
+fn write_conflict_file(repo: &git::Repository, style: git::conflict_file::Style) -> Result<String> {
+    repo.write_conflict_file(
+        Path::new("file"),
+        Some(ANCESTOR),
+        OURS,
+        THEIRS,
+        git::conflict_file::Options {
+            style,
+            ..Default::default()
+        },
+    )?;
+    Ok(std::fs::read_to_string(
+        repo.work_dir().expect("non-bare").join("file"),
+    )?)
+}
+
+#[test]
+fn conflicts_are_written_in_the_given_style() -> Result {
+    use git::conflict_file::Style;
+    let (repo, _keep) = basic_rw_repo()?;
+    assert_eq!(
+        write_conflict_file(&repo, Style::Merge)?,
+        "a\n1\n<<<<<<< ours\n2\n=======\nQ\n>>>>>>> theirs\n3\nd\n"
+    );
+    assert_eq!(
+        write_conflict_file(&repo, Style::Diff3)?,
+        "a\n<<<<<<< ours\n1\n2\n3\n||||||| base\nb\nc\n=======\n1\nQ\n3\n>>>>>>> theirs\nd\n"
+    );
+    assert_eq!(
+        write_conflict_file(&repo, Style::ZDiff3)?,
+        "a\n1\n<<<<<<< ours\n2\n||||||| base\nb\nc\n=======\nQ\n>>>>>>> theirs\n3\nd\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn the_configured_style_is_used_by_default() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    let mut config = std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?;
+    std::io::Write::write_all(&mut config, b"[merge]\n\tconflictStyle = diff3\n")?;
+    let repo = git::open(work_dir)?;
+
+    assert!(write_conflict_file(&repo, git::conflict_file::Style::Default)?.contains("||||||| base\n"));
+    Ok(())
+}
+
+#[test]
+fn non_conflicting_changes_are_merged() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    repo.write_conflict_file(
+        Path::new("file"),
+        Some(&b"a\nb\nc\nd\ne\n"[..]),
+        b"a\nB\nc\nd\ne\n",
+        b"a\nb\nc\nD\ne\n",
+        Default::default(),
+    )?;
+    assert_eq!(
+        std::fs::read(repo.work_dir().expect("non-bare").join("file"))?,
+        b"a\nB\nc\nD\ne\n"
+    );
+    Ok(())
+}
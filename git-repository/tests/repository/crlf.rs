@@ -0,0 +1,39 @@
+use std::{borrow::Cow, path::Path};
+
+use crate::{basic_rw_repo, Result};
+
+#[test]
+fn line_endings_follow_the_text_and_eol_attributes() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    std::fs::write(
+        repo.work_dir().expect("non-bare").join(".gitattributes"),
+        "*.txt text eol=crlf\n*.bin binary\n",
+    )?;
+
+    let text = Path::new("dir/file.txt");
+    assert_eq!(repo.normalize_blob_to_index(text, b"a\r\nb\n")?.as_ref(), b"a\nb\n");
+    assert_eq!(
+        repo.normalize_blob_to_worktree(text, b"a\nb\r\n")?.as_ref(),
+        b"a\r\nb\r\n",
+        "existing CRLF line endings are kept"
+    );
+
+    let binary = Path::new("file.bin");
+    assert!(matches!(
+        repo.normalize_blob_to_index(binary, b"a\r\nb")?,
+        Cow::Borrowed(b"a\r\nb")
+    ));
+    assert!(matches!(
+        repo.normalize_blob_to_worktree(binary, b"a\nb")?,
+        Cow::Borrowed(b"a\nb")
+    ));
+
+    assert!(
+        matches!(
+            repo.normalize_blob_to_index(Path::new("file.rs"), b"a\r\nb")?,
+            Cow::Borrowed(b"a\r\nb")
+        ),
+        "without attributes and core.autocrlf, nothing is converted"
+    );
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use git_repository as git;
+use git_repository::bstr::ByteSlice;
+
+use crate::{basic_repo, Result};
+
+#[test]
+fn history_is_exported_parents_first() -> Result {
+    let repo = basic_repo()?;
+    let mut out = Vec::new();
+    let stats = repo.export_fast(
+        &[repo.head_id()?],
+        &mut out,
+        git::fast_export::Options {
+            mark_map: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(stats.commits, 2);
+    assert_eq!(stats.blobs, 2, "an empty file and one with content");
+    assert_eq!(stats.resets, 1, "only main points to the tip");
+    assert_eq!(stats.marks.len(), 4, "every object has a mark");
+
+    let out = git::bstr::BString::from(out);
+    let commits: Vec<_> = out
+        .lines()
+        .filter(|line| line.starts_with(b"commit "))
+        .map(|line| line.as_bstr())
+        .collect();
+    assert_eq!(commits, vec!["commit refs/heads/main", "commit refs/heads/main"]);
+    assert!(out.ends_with(b"reset refs/heads/main\nfrom :4\n\n"));
+    Ok(())
+}
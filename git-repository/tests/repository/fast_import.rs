@@ -0,0 +1,118 @@
+use git_repository as git;
+
+use crate::{basic_repo, Result};
+
+#[test]
+fn exported_history_round_trips() -> Result {
+    let source = basic_repo()?;
+    let mut stream = Vec::new();
+    source.export_fast(&[source.head_id()?], &mut stream, Default::default())?;
+
+    let tmp = tempfile::tempdir()?;
+    let repo = git::init(&tmp)?;
+    let stats = repo.import_fast(stream.as_slice(), git::progress::Discard, Default::default())?;
+    assert_eq!(stats.commits, 2);
+    assert_eq!(stats.blobs, 2);
+    assert_eq!(stats.refs, 1, "only main is recreated");
+
+    assert_eq!(
+        repo.find_reference("refs/heads/main")?.peel_to_id_in_place()?,
+        source.head_id()?,
+        "commits, trees and blobs are reproduced exactly"
+    );
+    Ok(())
+}
+
+#[test]
+fn objects_are_written_into_packs_of_at_most_the_maximum_size() -> Result {
+    let source = basic_repo()?;
+    let mut stream = Vec::new();
+    source.export_fast(&[source.head_id()?], &mut stream, Default::default())?;
+
+    let tmp = tempfile::tempdir()?;
+    let repo = git::init(&tmp)?;
+    let pack_count = |repo: &git::Repository| -> std::io::Result<usize> {
+        let mut count = 0;
+        for entry in std::fs::read_dir(repo.objects.store_ref().path().join("pack"))? {
+            count += usize::from(entry?.path().extension().map_or(false, |ext| ext == "pack"));
+        }
+        Ok(count)
+    };
+    repo.import_fast(stream.as_slice(), git::progress::Discard, Default::default())?;
+    assert_eq!(pack_count(&repo)?, 1, "without limit, all objects go into one pack");
+
+    let tmp = tempfile::tempdir()?;
+    let repo = git::init(&tmp)?;
+    let stats = repo.import_fast(
+        stream.as_slice(),
+        git::progress::Discard,
+        git::fast_import::Options {
+            max_pack_size: Some(1),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        pack_count(&repo)?,
+        stats.blobs + stats.trees + stats.commits,
+        "each object is too large to share a pack with another one"
+    );
+    assert_eq!(
+        repo.find_reference("refs/heads/main")?.peel_to_id_in_place()?,
+        source.head_id()?,
+        "objects of previous packs are found when building trees and checking references"
+    );
+    Ok(())
+}
+
+#[test]
+fn streams_written_by_git_recreate_the_same_history() -> Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_fast_export_repo.sh")?;
+    let stream = std::fs::read(dir.join("export.stream"))?;
+
+    let tmp = tempfile::tempdir()?;
+    let repo = git::init(&tmp)?;
+    let stats = repo.import_fast(stream.as_slice(), git::progress::Discard, Default::default())?;
+    assert_eq!(stats.commits, 6);
+    assert_eq!(stats.tags, 1);
+
+    let baseline = std::fs::read_to_string(dir.join("refs.baseline"))?;
+    for line in baseline.lines() {
+        let (id, name) = line.split_once(' ').expect("id and name");
+        assert_eq!(
+            repo.find_reference(name)?
+                .target()
+                .try_id()
+                .expect("peeled")
+                .to_hex()
+                .to_string(),
+            id,
+            "{} points to the same object, including merges and the tag",
+            name
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn non_fast_forward_updates_are_rejected_unless_forced() -> Result {
+    let (repo, _keep) = crate::basic_rw_repo()?;
+    let stream = "reset refs/heads/main\ncommit refs/heads/main\ncommitter a <a@example.com> 0 +0000\ndata 3\nnew\ndeleteall\n\n";
+    let err = repo
+        .import_fast(stream.as_bytes(), git::progress::Discard, Default::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, git::fast_import::Error::NonFastForward { .. }),
+        "a parentless commit on an existing branch doesn't descend from it"
+    );
+
+    let stats = repo.import_fast(
+        stream.as_bytes(),
+        git::progress::Discard,
+        git::fast_import::Options {
+            force: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(stats.refs, 1);
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use git_repository as git;
+use git_repository::fetch::Mode;
+
+use crate::Result;
+
+fn local_repo() -> Result<(git::Repository, tempfile::TempDir)> {
+    let dir = git_testtools::scripted_fixture_repo_writable_with_args(
+        "make_fetch_repo.sh",
+        None,
+        git_testtools::Creation::ExecuteScript,
+    )?;
+    Ok((git::open(dir.path().join("local"))?, dir))
+}
+
+fn id(repo: &git::Repository, name: &str) -> Result<git::ObjectId> {
+    Ok(repo.find_reference(name)?.id().detach())
+}
+
+#[test]
+fn references_are_updated_according_to_the_fetch_refspecs() -> Result {
+    let (repo, dir) = local_repo()?;
+    let remote = git::open(dir.path().join("remote"))?;
+    let moved_before = id(&repo, "refs/tags/moved")?;
+
+    let outcome = repo.fetch("origin", Default::default())?;
+    assert!(outcome.pack.is_some(), "new commits were received");
+    let mode_of = |local: &str| {
+        outcome
+            .updates
+            .iter()
+            .find(|update| update.local.as_bstr() == local)
+            .map(|update| update.mode)
+    };
+    assert_eq!(mode_of("refs/remotes/origin/main"), Some(Mode::FastForward));
+    assert_eq!(mode_of("refs/remotes/origin/feature"), Some(Mode::New));
+    assert_eq!(mode_of("refs/remotes/origin/rewritten"), Some(Mode::Forced));
+    assert_eq!(mode_of("refs/tags/base"), Some(Mode::UpToDate));
+    assert_eq!(mode_of("refs/tags/v1"), Some(Mode::New));
+    assert_eq!(
+        mode_of("refs/tags/moved"),
+        Some(Mode::Rejected),
+        "the tag refspec isn't forced"
+    );
+
+    for (local, remote_name) in [
+        ("refs/remotes/origin/main", "refs/heads/main"),
+        ("refs/remotes/origin/feature", "refs/heads/feature"),
+        ("refs/remotes/origin/rewritten", "refs/heads/rewritten"),
+        ("refs/tags/v1", "refs/tags/v1"),
+    ] {
+        assert_eq!(id(&repo, local)?, id(&remote, remote_name)?, "{}", local);
+        assert!(repo.find_object(id(&repo, local)?).is_ok(), "objects are present");
+    }
+    assert_eq!(id(&repo, "refs/tags/moved")?, moved_before);
+
+    let outcome = repo.fetch("origin", Default::default())?;
+    assert!(outcome.pack.is_none(), "there is nothing new to receive");
+    assert!(outcome
+        .updates
+        .iter()
+        .all(|update| matches!(update.mode, Mode::UpToDate | Mode::Rejected)));
+    Ok(())
+}
+
+#[test]
+fn unknown_remotes_are_an_error() -> Result {
+    let (repo, _keep) = local_repo()?;
+    assert!(matches!(
+        repo.fetch("unknown", Default::default()),
+        Err(git::fetch::Error::UnknownRemote { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn prefetch_only_updates_references_below_refs_prefetch() -> Result {
+    let (repo, dir) = local_repo()?;
+    let remote = git::open(dir.path().join("remote"))?;
+    let main_before = id(&repo, "refs/remotes/origin/main")?;
+
+    let outcome = repo.fetch(
+        "origin",
+        git::fetch::Options {
+            prefetch: true,
+            ..Default::default()
+        },
+    )?;
+    let mut locals: Vec<_> = outcome
+        .updates
+        .iter()
+        .map(|update| update.local.as_bstr().to_string())
+        .collect();
+    locals.sort();
+    assert_eq!(
+        locals,
+        [
+            "refs/prefetch/remotes/origin/feature",
+            "refs/prefetch/remotes/origin/main",
+            "refs/prefetch/remotes/origin/rewritten"
+        ],
+        "the refspec for tags is ignored"
+    );
+    assert!(outcome.updates.iter().all(|update| update.mode == Mode::New));
+    assert_eq!(
+        id(&repo, "refs/prefetch/remotes/origin/main")?,
+        id(&remote, "refs/heads/main")?
+    );
+    assert_eq!(id(&repo, "refs/remotes/origin/main")?, main_before);
+    assert!(repo.try_find_reference("refs/tags/v1")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn a_cancelled_token_stops_the_fetch_without_updating_references() -> Result {
+    let (repo, _dir) = local_repo()?;
+    let main_before = id(&repo, "refs/remotes/origin/main")?;
+    let token = git::interrupt::CancellationToken::new();
+    token.cancel();
+
+    assert!(repo
+        .fetch(
+            "origin",
+            git::fetch::Options {
+                cancellation: Some(token),
+                ..Default::default()
+            },
+        )
+        .is_err());
+    assert!(
+        !git::interrupt::is_triggered(),
+        "the global interrupt flag is unaffected"
+    );
+    assert_eq!(id(&repo, "refs/remotes/origin/main")?, main_before);
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use git_repository as git;
+use git_testtools::hex_to_id;
+
+use crate::{basic_rw_repo, Result};
+
+#[test]
+fn missing_file_yields_no_entries() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    assert_eq!(repo.fetch_head()?, Vec::new());
+    Ok(())
+}
+
+#[test]
+fn entries_are_parsed_in_order() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    std::fs::write(
+        repo.path().join("FETCH_HEAD"),
+        "3189cd3cb0af8586c39a838aa3e54fd72a872a41\t\tbranch 'main' of https://example.com/repo\n\
+         ff8b5c2ba7a2c8aa9ad6b6e0c5a7e5e3c0df6ab6\tnot-for-merge\ttag 'v1.0' of https://example.com/repo\n\
+         3189cd3cb0af8586c39a838aa3e54fd72a872a41\t\t../other\n",
+    )?;
+    assert_eq!(
+        repo.fetch_head()?,
+        vec![
+            git::fetch_head::Entry {
+                id: hex_to_id("3189cd3cb0af8586c39a838aa3e54fd72a872a41"),
+                not_for_merge: false,
+                description: "branch 'main'".into(),
+                remote_url: "https://example.com/repo".into(),
+            },
+            git::fetch_head::Entry {
+                id: hex_to_id("ff8b5c2ba7a2c8aa9ad6b6e0c5a7e5e3c0df6ab6"),
+                not_for_merge: true,
+                description: "tag 'v1.0'".into(),
+                remote_url: "https://example.com/repo".into(),
+            },
+            git::fetch_head::Entry {
+                id: hex_to_id("3189cd3cb0af8586c39a838aa3e54fd72a872a41"),
+                not_for_merge: false,
+                description: "".into(),
+                remote_url: "../other".into(),
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn malformed_lines_are_reported() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    std::fs::write(repo.path().join("FETCH_HEAD"), "not a hash\t\turl\n")?;
+    assert!(matches!(
+        repo.fetch_head().unwrap_err(),
+        git::fetch_head::Error::Parse { line_number: 1, .. }
+    ));
+    Ok(())
+}
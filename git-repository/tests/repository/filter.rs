@@ -0,0 +1,36 @@
+use std::borrow::Cow;
+
+use git_repository as git;
+
+use crate::{basic_rw_repo, Result};
+
+#[test]
+fn clean_filter_is_applied_to_matching_paths_only() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(work_dir.join(".gitattributes"), "*.txt filter=upper\n")?;
+    let mut config = std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?;
+    std::io::Write::write_all(&mut config, b"[filter \"upper\"]\n\tclean = tr a-z A-Z\n")?;
+    let repo = git::open(work_dir)?;
+
+    let filtered = repo.apply_filter("dir/file.txt".into(), b"hello", git::filter::Direction::Clean)?;
+    assert_eq!(filtered.as_ref(), b"HELLO");
+
+    assert!(
+        matches!(
+            repo.apply_filter("dir/file.txt".into(), b"hello", git::filter::Direction::Smudge)?,
+            Cow::Borrowed(b"hello")
+        ),
+        "drivers without a command for the direction pass data through"
+    );
+    assert!(
+        matches!(
+            repo.apply_filter("file.rs".into(), b"hello", git::filter::Direction::Clean)?,
+            Cow::Borrowed(b"hello")
+        ),
+        "paths without filter attribute are unchanged"
+    );
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use git_repository as git;
+use git_repository::bstr::ByteSlice;
+
+use crate::{named_repo, repo_rw, Result};
+
+fn expected_patch(repo: &git::Repository, name: &str) -> Result<Vec<u8>> {
+    Ok(std::fs::read(repo.git_dir().join(name))?)
+}
+
+#[test]
+fn text_changes_are_formatted_like_git_does() -> Result {
+    let repo = named_repo("make_format_patch_repo.sh")?;
+    let patch = repo.generate_patch(
+        repo.find_reference("refs/tags/text")?.id(),
+        git::format_patch::Options {
+            base: Some(repo.find_reference("refs/tags/base")?.id().detach()),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(patch.as_bstr(), expected_patch(&repo, "text.patch")?.as_bstr());
+    Ok(())
+}
+
+#[test]
+fn binary_changes_can_be_applied_by_git() -> Result {
+    let (repo, _keep) = repo_rw("make_format_patch_repo.sh")?;
+    let patch = repo.generate_patch(
+        repo.find_reference("refs/tags/binary")?.id(),
+        git::format_patch::Options {
+            signature: Some("gitoxide".into()),
+            ..Default::default()
+        },
+    )?;
+    let expected = expected_patch(&repo, "binary.patch")?;
+    let headers_and_stat = |patch: &[u8]| {
+        let end = patch.find("GIT binary patch\n").expect("binary patch present");
+        patch[..end].to_owned()
+    };
+    assert_eq!(
+        headers_and_stat(&patch).as_bstr(),
+        headers_and_stat(&expected).as_bstr(),
+        "the compressed data may differ, but everything else is the same"
+    );
+    assert!(patch.ends_with(b"\n-- \ngitoxide\n\n"));
+
+    let work_dir = repo.work_dir().expect("non-bare");
+    let patch_path = repo.git_dir().join("generated.patch");
+    std::fs::write(&patch_path, &patch)?;
+    let git = |args: &[&str]| -> Result<bool> {
+        Ok(std::process::Command::new("git")
+            .args(args)
+            .current_dir(work_dir)
+            .status()?
+            .success())
+    };
+    assert!(git(&["checkout", "-q", "text"])?);
+    assert!(git(&["apply", "--index", patch_path.to_str().expect("valid UTF-8")])?);
+    assert!(
+        git(&["diff", "--cached", "--quiet", "binary"])?,
+        "applying the patch yields the tree of the commit"
+    );
+    Ok(())
+}
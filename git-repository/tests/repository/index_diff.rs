@@ -0,0 +1,107 @@
+use git_repository as git;
+
+use crate::{basic_rw_repo, Result};
+
+#[test]
+fn index_to_workdir() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let index = repo.open_index()?;
+    assert_eq!(
+        repo.diff_index_to_workdir(&index, Default::default())?,
+        Vec::new(),
+        "a clean checkout has no changes"
+    );
+
+    let work_dir = repo.work_dir().expect("non-bare");
+    std::fs::write(work_dir.join("this"), "changed")?;
+    std::fs::write(work_dir.join("untracked"), "new")?;
+    let changes = repo.diff_index_to_workdir(&index, Default::default())?;
+    assert_eq!(changes.len(), 1, "untracked files aren't listed by default");
+    assert!(matches!(&changes[0], git::index_diff::Change::Modified { path, .. } if path == "this"));
+
+    let changes = repo.diff_index_to_workdir(
+        &index,
+        git::index_diff::to_workdir::Options {
+            include_untracked: true,
+        },
+    )?;
+    assert!(changes
+        .iter()
+        .any(|change| matches!(change, git::index_diff::Change::Added { path, .. } if path == "untracked")));
+
+    std::fs::remove_file(work_dir.join("this"))?;
+    let changes = repo.diff_index_to_workdir(&index, Default::default())?;
+    assert!(matches!(&changes[..], [git::index_diff::Change::Deleted { path, .. }] if path == "this"));
+    Ok(())
+}
+
+#[test]
+fn tree_to_index() -> Result {
+    let repo = crate::basic_repo()?;
+    let index = repo.open_index()?;
+    let head = repo.head_id()?;
+    assert_eq!(
+        repo.diff_tree_to_index(head, &index)?,
+        Vec::new(),
+        "nothing is staged after the last commit"
+    );
+
+    let parent = head.ancestors().all()?.nth(1).expect("two commits")?;
+    let changes = repo.diff_tree_to_index(parent, &index)?;
+    assert!(
+        matches!(&changes[..], [git::index_diff::Change::Modified { path, .. }] if path == "this"),
+        "the second commit changed the only file"
+    );
+    Ok(())
+}
+
+fn git(repo: &git::Repository, args: &[&str]) -> Result {
+    let status = std::process::Command::new("git")
+        .args(args)
+        .current_dir(repo.work_dir().expect("non-bare"))
+        .status()?;
+    assert!(status.success(), "git {:?} failed", args);
+    Ok(())
+}
+
+#[test]
+fn index_to_workdir_reports_paths_below_files_as_deleted() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    std::fs::create_dir(work_dir.join("dir"))?;
+    std::fs::write(work_dir.join("dir").join("file"), "content")?;
+    git(&repo, &["add", "dir/file"])?;
+
+    std::fs::remove_dir_all(work_dir.join("dir"))?;
+    std::fs::write(work_dir.join("dir"), "now a file")?;
+    let index = repo.open_index()?;
+    let changes = repo.diff_index_to_workdir(&index, Default::default())?;
+    assert!(
+        matches!(&changes[..], [git::index_diff::Change::Deleted { path, .. }] if path == "dir/file"),
+        "a leading path component that turned into a file doesn't abort the comparison"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn index_to_workdir_ignores_the_executable_bit_if_core_filemode_is_false() -> Result {
+    use std::os::unix::fs::PermissionsExt;
+    let (repo, keep) = basic_rw_repo()?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    std::fs::set_permissions(work_dir.join("this"), std::fs::Permissions::from_mode(0o755))?;
+    let index = repo.open_index()?;
+    assert!(
+        matches!(&repo.diff_index_to_workdir(&index, Default::default())?[..], [git::index_diff::Change::Modified { path, mode, .. }] if path == "this" && *mode == git::index::entry::Mode::FILE_EXECUTABLE),
+        "by default, the executable bit is a change"
+    );
+
+    git(&repo, &["config", "core.fileMode", "false"])?;
+    let repo = git::open(keep.path())?;
+    assert_eq!(
+        repo.diff_index_to_workdir(&index, Default::default())?,
+        Vec::new(),
+        "the executable bit is ignored if the filesystem can't be trusted"
+    );
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use git_repository as git;
+
+use crate::{repo_rw, Result};
+
+fn ls_files_tag(repo: &git::Repository) -> Result<String> {
+    let out = std::process::Command::new("git")
+        .args(["ls-files", "-v", "this"])
+        .current_dir(repo.work_dir().expect("non-bare"))
+        .output()?;
+    Ok(String::from_utf8(out.stdout)?.trim_end().to_owned())
+}
+
+#[test]
+fn assume_unchanged_is_set_and_cleared() -> Result {
+    let (repo, _keep) = repo_rw("make_basic_repo.sh")?;
+    repo.assume_unchanged(Path::new("this"), true)?;
+    assert_eq!(ls_files_tag(&repo)?, "h this");
+
+    repo.assume_unchanged(Path::new("this"), false)?;
+    assert_eq!(ls_files_tag(&repo)?, "H this");
+    Ok(())
+}
+
+#[test]
+fn skip_worktree_is_set_and_cleared() -> Result {
+    let (repo, _keep) = repo_rw("make_basic_repo.sh")?;
+    repo.skip_worktree(Path::new("this"), true)?;
+    assert_eq!(
+        ls_files_tag(&repo)?,
+        "S this",
+        "the index is upgraded to a version supporting extended flags"
+    );
+
+    repo.skip_worktree(Path::new("this"), false)?;
+    assert_eq!(ls_files_tag(&repo)?, "H this");
+    Ok(())
+}
+
+#[test]
+fn untracked_paths_and_locked_indices_are_an_error() -> Result {
+    let (repo, _keep) = repo_rw("make_basic_repo.sh")?;
+    assert!(matches!(
+        repo.assume_unchanged(Path::new("untracked"), true),
+        Err(git::index_flags::Error::NotInIndex { path }) if path == "untracked"
+    ));
+
+    std::fs::write(repo.git_dir().join("index.lock"), b"")?;
+    assert!(matches!(
+        repo.skip_worktree(Path::new("this"), true),
+        Err(git::index_flags::Error::LockIndex(_))
+    ));
+    assert_eq!(ls_files_tag(&repo)?, "H this", "nothing changed");
+    Ok(())
+}
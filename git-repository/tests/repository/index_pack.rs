@@ -0,0 +1,34 @@
+use git_repository as git;
+
+use crate::{repo_rw, Result};
+
+#[test]
+fn index_is_written_next_to_the_pack_and_makes_its_objects_available() -> Result {
+    let (repo, _tmp) = repo_rw("make_packed_repo.sh")?;
+    let head = repo.head_id()?.detach();
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+    let pack_path = std::fs::read_dir(&pack_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?
+        .into_iter()
+        .find(|path| path.extension().map_or(false, |ext| ext == "pack"))
+        .expect("the fixture has one pack");
+    let index_path = pack_path.with_extension("idx");
+    let expected_index_len = std::fs::metadata(&index_path)?.len();
+    std::fs::remove_file(&index_path)?;
+
+    let repo = git::open(repo.git_dir())?;
+    assert!(
+        repo.try_find_object(head)?.is_none(),
+        "without index the objects of the pack can't be found"
+    );
+
+    assert_eq!(repo.index_pack(&pack_path, git::progress::Discard)?, index_path);
+    assert_eq!(
+        std::fs::metadata(&index_path)?.len(),
+        expected_index_len,
+        "it's a version 2 index just like the one written by git"
+    );
+    assert_eq!(repo.find_object(head)?.kind, git::object::Kind::Commit);
+    Ok(())
+}
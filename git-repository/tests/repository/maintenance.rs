@@ -0,0 +1,162 @@
+use git_repository as git;
+use git_repository::maintenance::{Schedule, Stats, Task};
+
+use crate::{repo_rw, Result};
+
+fn git_output(repo: &git::Repository, args: &[&str]) -> Result<String> {
+    let out = std::process::Command::new("git")
+        .args(args)
+        .current_dir(repo.work_dir().expect("non-bare"))
+        .output()?;
+    assert!(out.status.success(), "{:?} failed", args);
+    Ok(String::from_utf8(out.stdout)?)
+}
+
+#[test]
+fn explicit_tasks_run_in_order() -> Result {
+    let (repo, _keep) = repo_rw("make_maintenance_repo.sh")?;
+    let tasks = [
+        Task::LooseObjects,
+        Task::IncrementalRepack,
+        Task::CommitGraph,
+        Task::PackRefs,
+    ];
+    let stats = repo.maintenance_run(&tasks)?;
+    assert_eq!(
+        stats,
+        Stats {
+            tasks: tasks.to_vec(),
+            commit_graph_commits: 7,
+            packed_refs: 4,
+            prefetched_refs: 0,
+            loose_objects: 1,
+            repacked_packs: 2,
+        }
+    );
+
+    let counts = git_output(&repo, &["count-objects", "-v"])?;
+    assert!(counts.contains("count: 0\n"), "all loose objects were packed");
+    assert!(
+        counts.contains("packs: 2\n"),
+        "the small packs were consolidated, leaving the largest one alone"
+    );
+
+    git_output(&repo, &["commit-graph", "verify"])?;
+    #[cfg(feature = "git-commitgraph")]
+    assert_eq!(
+        repo.commit_generation(repo.head_id()?.detach())?,
+        Some(5),
+        "generations are computed across the octopus merge"
+    );
+
+    assert_eq!(repo.refs.loose_iter()?.count(), 0, "all references are packed");
+    let packed = repo.refs.open_packed_buffer()?.expect("packed-refs were written");
+    for name in ["refs/heads/main", "refs/heads/a", "refs/heads/b", "refs/tags/v1"] {
+        assert!(packed.try_find(name)?.is_some(), "{} was packed", name);
+    }
+    Ok(())
+}
+
+#[test]
+fn enabled_tasks_run_if_none_are_given_and_scheduled_ones_by_frequency() -> Result {
+    let (mut repo, _keep) = repo_rw("make_maintenance_repo.sh")?;
+    assert_eq!(
+        repo.maintenance_run(&[])?,
+        Stats::default(),
+        "nothing is enabled by default"
+    );
+    assert_eq!(repo.maintenance_run_scheduled(Schedule::Weekly)?, Stats::default());
+
+    repo.load_config_layer("maintenance.strategy=incremental")?;
+    let mut hourly = Vec::new();
+    #[cfg(feature = "blocking-network-client")]
+    hourly.push(Task::Prefetch);
+    let mut all = hourly.clone();
+    all.extend([Task::LooseObjects, Task::IncrementalRepack, Task::CommitGraph]);
+    hourly.push(Task::CommitGraph);
+    assert_eq!(repo.maintenance_run(&[])?.tasks, all);
+    assert_eq!(
+        repo.maintenance_run(&[])?.tasks,
+        all,
+        "no state is kept about previous runs"
+    );
+    assert_eq!(repo.maintenance_run_scheduled(Schedule::Hourly)?.tasks, hourly);
+    assert_eq!(
+        repo.maintenance_run_scheduled(Schedule::Daily)?.tasks,
+        all,
+        "more frequent tasks run as well"
+    );
+    assert!(
+        !repo.git_dir().join("objects").join("info").join("maintenance").exists(),
+        "nothing is written to the object database besides objects"
+    );
+
+    repo.load_config_layer("maintenance.pack-refs.enabled")?;
+    assert_eq!(repo.maintenance_run(&[])?.tasks.last(), Some(&Task::PackRefs));
+    assert_eq!(
+        repo.maintenance_run_scheduled(Schedule::Weekly)?.tasks,
+        all,
+        "tasks without schedule never run on a schedule"
+    );
+    repo.load_config_layer("maintenance.pack-refs.schedule=weekly")?;
+    assert_eq!(
+        repo.maintenance_run_scheduled(Schedule::Weekly)?.tasks.last(),
+        Some(&Task::PackRefs)
+    );
+
+    repo.load_config_layer("maintenance.commit-graph.schedule=sometimes")?;
+    assert!(matches!(
+        repo.maintenance_run(&[]),
+        Err(git::maintenance::Error::InvalidConfig { key, .. }) if key == "maintenance.commit-graph.schedule"
+    ));
+    Ok(())
+}
+
+#[test]
+fn concurrent_runs_are_prevented() -> Result {
+    let (repo, _keep) = repo_rw("make_maintenance_repo.sh")?;
+    std::fs::write(repo.git_dir().join("objects").join("maintenance.lock"), b"")?;
+    assert!(matches!(
+        repo.maintenance_run(&[Task::CommitGraph]),
+        Err(git::maintenance::Error::Lock(_))
+    ));
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "blocking-network-client"))]
+fn prefetch_fails_without_network_client() -> Result {
+    let (repo, _keep) = repo_rw("make_maintenance_repo.sh")?;
+    assert!(matches!(
+        repo.maintenance_run(&[Task::Prefetch]),
+        Err(git::maintenance::Error::PrefetchUnsupported)
+    ));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "blocking-network-client")]
+fn prefetch_leaves_remote_tracking_references_alone() -> Result {
+    let dir = git_testtools::scripted_fixture_repo_writable_with_args(
+        "make_fetch_repo.sh",
+        None,
+        git_testtools::Creation::ExecuteScript,
+    )?;
+    let repo = git::open(dir.path().join("local"))?;
+    let tracking_before = repo.find_reference("refs/remotes/origin/main")?.id().detach();
+
+    let stats = repo.maintenance_run(&[Task::Prefetch])?;
+    assert_eq!(stats.prefetched_refs, 3, "main, feature and rewritten");
+    assert_eq!(
+        repo.find_reference("refs/remotes/origin/main")?.id().detach(),
+        tracking_before
+    );
+    let remote = git::open(dir.path().join("remote"))?;
+    assert_eq!(
+        repo.find_reference("refs/prefetch/remotes/origin/feature")?
+            .id()
+            .detach(),
+        remote.find_reference("refs/heads/feature")?.id().detach()
+    );
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use git_repository as git;
+
+use crate::{repo_rw, Result};
+
+#[test]
+fn conflicts_are_replaced_by_the_resolved_file() -> Result {
+    let (repo, _keep) = repo_rw("make_conflict_repo.sh")?;
+    assert!(repo.open_index()?.has_conflicts());
+    let work_dir = repo.work_dir().expect("non-bare");
+
+    assert!(matches!(
+        repo.mark_resolved(Path::new("file")),
+        Err(git::mark_resolved::Error::UnresolvedConflictMarkers { path }) if path == "file"
+    ));
+    assert!(repo.open_index()?.has_conflicts(), "nothing changes on error");
+
+    std::fs::write(work_dir.join("file"), "resolved\n")?;
+    repo.mark_resolved(Path::new("file"))?;
+
+    let index = repo.open_index()?;
+    assert!(!index.has_conflicts());
+    let entry = index.entry_by_path("file".into(), 0).expect("resolved entry");
+    let blob = repo.find_object(entry.id)?;
+    assert_eq!(blob.data, b"resolved\n");
+
+    let status = std::process::Command::new("git")
+        .args(["diff", "--quiet"])
+        .current_dir(work_dir)
+        .status()?;
+    assert!(
+        status.success(),
+        "git sees no changes between the index and the working tree"
+    );
+    Ok(())
+}
+
+#[test]
+fn paths_without_conflicts_are_an_error() -> Result {
+    let (repo, _keep) = repo_rw("make_conflict_repo.sh")?;
+    for path in ["unrelated", "does-not-exist"] {
+        assert!(matches!(
+            repo.mark_resolved(Path::new(path)),
+            Err(git::mark_resolved::Error::NotConflicted { .. })
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use git_repository as git;
+
+use crate::{named_repo, Result};
+
+fn ids(repo: &git::Repository, names: &[&str]) -> Result<Vec<git::ObjectId>> {
+    names
+        .iter()
+        .map(|name| -> Result<git::ObjectId> { Ok(repo.find_reference(*name)?.peel_to_id_in_place()?.detach()) })
+        .collect()
+}
+
+#[test]
+fn two_and_more_commits() -> Result {
+    let repo = named_repo("make_merge_base_repo.sh")?;
+    assert_eq!(repo.merge_base_many(&ids(&repo, &["x", "y"])?)?, ids(&repo, &["c2"])?);
+    assert_eq!(
+        repo.merge_base_many(&ids(&repo, &["x", "y", "z"])?)?,
+        ids(&repo, &["c1"])?,
+        "the merge base of all commits is further in the past"
+    );
+    assert_eq!(
+        repo.merge_base_many(&ids(&repo, &["x", "c2"])?)?,
+        ids(&repo, &["c2"])?,
+        "an ancestor is its own merge base"
+    );
+    Ok(())
+}
+
+#[test]
+fn criss_cross_merges_have_multiple_best_bases() -> Result {
+    let repo = named_repo("make_merge_base_repo.sh")?;
+    let mut bases = repo.merge_base_many(&ids(&repo, &["criss", "cross"])?)?;
+    bases.sort();
+    let mut expected = ids(&repo, &["x", "y"])?;
+    expected.sort();
+    assert_eq!(bases, expected);
+    Ok(())
+}
+
+#[test]
+fn trivial_and_unrelated_inputs() -> Result {
+    let repo = named_repo("make_merge_base_repo.sh")?;
+    assert_eq!(repo.merge_base_many(&[])?, Vec::new());
+    assert_eq!(repo.merge_base_many(&ids(&repo, &["x"])?)?, ids(&repo, &["x"])?);
+    assert_eq!(
+        repo.merge_base_many(&ids(&repo, &["x", "unrelated"])?)?,
+        Vec::new(),
+        "there is no common history"
+    );
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use git_repository as git;
+
+use crate::{basic_rw_repo, Result};
+
+#[test]
+fn the_merge_attribute_selects_the_strategy() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    std::fs::write(
+        work_dir.join(".gitattributes"),
+        "*.txt merge\n*.bin -merge\n*.log merge=union\nCHANGELOG merge=ours\n*.md merge=unknown\n",
+    )?;
+    let mut config = std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?;
+    std::io::Write::write_all(&mut config, b"[merge \"ours\"]\n\tdriver = true\n")?;
+    let repo = git::open(work_dir)?;
+
+    use git::merge_strategy::Strategy;
+    for (path, expected) in [
+        ("dir/a.txt", Strategy::Text),
+        ("a.bin", Strategy::Binary),
+        ("a.log", Strategy::Union),
+        (
+            "CHANGELOG",
+            Strategy::Driver {
+                name: "ours".into(),
+                command: "true".into(),
+            },
+        ),
+        ("README.md", Strategy::Text),
+        ("unmatched", Strategy::Text),
+    ] {
+        assert_eq!(repo.merge_strategy_for(Path::new(path))?, expected, "{}", path);
+    }
+    Ok(())
+}
+
+#[test]
+fn merge_default_is_used_without_attribute() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let work_dir = repo.work_dir().expect("non-bare").to_owned();
+    let mut config = std::fs::OpenOptions::new()
+        .append(true)
+        .open(repo.git_dir().join("config"))?;
+    std::io::Write::write_all(&mut config, b"[merge]\n\tdefault = union\n")?;
+    let repo = git::open(work_dir)?;
+
+    assert_eq!(
+        repo.merge_strategy_for(Path::new("file"))?,
+        git::merge_strategy::Strategy::Union
+    );
+    Ok(())
+}
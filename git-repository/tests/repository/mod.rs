@@ -1,5 +1,56 @@
+mod ahead_behind;
+#[cfg(feature = "git-index")]
+mod apply;
+#[cfg(feature = "archive")]
+mod archive;
+mod blob_diff;
+mod bundle;
+#[cfg(feature = "git-index")]
+mod checkout_branch;
+#[cfg(all(feature = "blocking-network-client", feature = "git-index"))]
+mod clone;
+mod commit_format;
+#[cfg(feature = "git-commitgraph")]
+mod commit_graph;
+mod config;
+mod conflict_file;
+#[cfg(feature = "git-attributes")]
+mod crlf;
+mod fast_export;
+mod fast_import;
+#[cfg(feature = "blocking-network-client")]
+mod fetch;
+mod fetch_head;
+#[cfg(feature = "git-attributes")]
+mod filter;
+mod format_patch;
+#[cfg(feature = "git-index")]
+mod index_diff;
+#[cfg(feature = "git-index")]
+mod index_flags;
+mod index_pack;
+mod maintenance;
+#[cfg(feature = "git-index")]
+mod mark_resolved;
+mod merge_base;
+#[cfg(feature = "git-attributes")]
+mod merge_strategy;
+mod notes;
 mod object;
+mod pack_objects;
+#[cfg(feature = "git-glob")]
+mod pathspec;
+#[cfg(feature = "blocking-network-client")]
+mod push;
 mod reference;
+mod reflog_expire;
 mod remote;
+mod repack;
+#[cfg(feature = "git-index")]
+mod restore;
+mod sign;
 mod state;
+mod topo;
+#[cfg(feature = "git-index")]
+mod untracked;
 mod worktree;
@@ -0,0 +1,32 @@
+use crate::{named_repo, Result};
+
+#[test]
+fn notes_are_found_in_flat_and_fanned_out_trees() -> Result {
+    let repo = named_repo("make_notes_repo.sh")?;
+    let head_commit = repo.head_commit()?;
+    let head = head_commit.id;
+    let parent = head_commit.parent_ids().next().expect("one parent").detach();
+
+    let notes = repo.notes_tree(None)?;
+    assert_eq!(notes.find(parent)?.expect("present").data, b"flat note\n");
+    assert!(notes.find(head)?.is_none());
+    assert_eq!(
+        repo.notes_tree(Some("notes/commits"))?.tree_id(),
+        notes.tree_id(),
+        "names are expanded like git does"
+    );
+
+    let fanned_out = repo.notes_tree(Some("fanout"))?;
+    assert_eq!(fanned_out.find(head)?.expect("present").data, b"fanned out note\n");
+    assert!(fanned_out.find(parent)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn missing_notes_refs_have_no_notes() -> Result {
+    let repo = named_repo("make_notes_repo.sh")?;
+    let notes = repo.notes_tree(Some("refs/notes/does-not-exist"))?;
+    assert!(notes.tree_id().is_none());
+    assert!(notes.find(repo.head_id()?)?.is_none());
+    Ok(())
+}
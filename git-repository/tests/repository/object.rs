@@ -47,6 +47,187 @@ mod find {
     }
 }
 
+mod find_with_progress {
+    use git_repository as git;
+    use git_repository::progress::{AtomicProgress, Progress};
+
+    #[test]
+    fn every_delta_in_the_chain_is_reported() -> crate::Result {
+        let mut repo = crate::named_repo("make_packed_repo.sh")?;
+        repo.objects.unset_pack_cache();
+
+        let mut longest_chain = 0;
+        for id in repo.objects.iter()?.filter_map(Result::ok) {
+            // clones share the step, which is reset when the chain is known and incremented for each applied delta.
+            let progress = AtomicProgress::new(git::progress::Discard);
+            let object = repo.find_object_with_progress(id, progress.clone())?;
+            longest_chain = longest_chain.max(progress.step());
+
+            let expected = repo.find_object(id)?;
+            assert_eq!(object.kind, expected.kind);
+            assert_eq!(
+                object.data, expected.data,
+                "the object is decoded just like without progress"
+            );
+        }
+        assert!(
+            longest_chain > 1,
+            "the fixture packs the versions of a file as delta chain, got {}",
+            longest_chain
+        );
+        Ok(())
+    }
+}
+
+mod object_cache_warm {
+    #[test]
+    fn objects_are_cached_only_if_there_is_a_cache_large_enough() -> crate::Result {
+        let mut repo = crate::basic_repo()?;
+        let ids = {
+            let commit = repo.head_commit()?;
+            [commit.id, commit.tree_id()?, commit.id]
+        };
+
+        assert_eq!(repo.object_cache_warm(ids)?, 0, "there is no object cache by default");
+
+        repo.object_cache_size(128 * 1024);
+        assert_eq!(repo.object_cache_warm(ids)?, 2, "duplicates are counted once");
+
+        repo.object_cache_size(10);
+        assert_eq!(
+            repo.object_cache_warm(ids)?,
+            0,
+            "objects larger than the cache are skipped"
+        );
+        Ok(())
+    }
+}
+
+mod find_unique_prefix {
+    use git_repository as git;
+
+    #[test]
+    fn unique_ambiguous_and_missing_prefixes() -> crate::Result {
+        let repo = crate::named_repo("make_ambiguous_prefix_repo.sh")?;
+        let head = repo.head_id()?.detach();
+        assert_eq!(repo.find_unique_prefix(&head.to_hex_with_len(7).to_string())?, head);
+
+        let ambiguous = std::fs::read_to_string(repo.work_dir().expect("non-bare").join("ambiguous-prefix"))?;
+        let ambiguous = ambiguous.trim();
+        match repo.find_unique_prefix(ambiguous) {
+            Err(git::object::find::unique_prefix::Error::Ambiguous { candidates, .. }) => {
+                assert!(candidates.len() > 1, "all colliding ids are listed");
+                assert!(candidates
+                    .iter()
+                    .all(|id| id.to_hex().to_string().starts_with(ambiguous)));
+            }
+            res => panic!("expected an ambiguous prefix, got {:?}", res),
+        }
+
+        assert!(matches!(
+            repo.find_unique_prefix("deadbeefdeadbeef"),
+            Err(git::object::find::unique_prefix::Error::NotFound { .. })
+        ));
+        assert!(matches!(
+            repo.find_unique_prefix("abc"),
+            Err(git::object::find::unique_prefix::Error::InvalidPrefix(_))
+        ));
+        Ok(())
+    }
+}
+
+mod read_object_compressed {
+    use git_repository as git;
+
+    #[test]
+    fn loose_objects_are_returned_as_stored() -> crate::Result {
+        let repo = crate::basic_repo()?;
+        let head = repo.head_id()?;
+        let object = repo.read_object_compressed(head)?;
+        assert_eq!(object.kind, git_object::Kind::Commit);
+        assert_eq!(object.pack_header, None);
+
+        let data_len = head.object()?.data.len();
+        let header = git_object::encode::loose_header(git_object::Kind::Commit, data_len);
+        assert_eq!(
+            object.decompressed_size as usize,
+            header.len() + data_len,
+            "loose objects decompress to their header and data"
+        );
+        let hex = head.to_hex().to_string();
+        let path = repo.objects.store_ref().path().join(&hex[..2]).join(&hex[2..]);
+        assert_eq!(object.compressed_data, std::fs::read(path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn loose_objects_of_alternates_are_found() -> crate::Result {
+        let repo = crate::basic_repo()?;
+        let head = repo.head_id()?;
+        let tmp = tempfile::tempdir()?;
+        let linked = git::init_bare(tmp.path())?;
+        let objects_dir = linked.objects.store_ref().path().to_owned();
+        std::fs::write(
+            objects_dir.join("info").join("alternates"),
+            repo.objects.store_ref().path().to_string_lossy().as_bytes(),
+        )?;
+
+        let linked = git::open(tmp.path())?;
+        assert_eq!(
+            linked.read_object_compressed(head)?,
+            repo.read_object_compressed(head)?,
+            "the object is read from the alternate as is"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_objects_are_an_error() -> crate::Result {
+        let repo = crate::basic_repo()?;
+        assert!(matches!(
+            repo.read_object_compressed(git::hash::ObjectId::null(repo.object_hash())),
+            Err(git::object::compressed::Error::NotFound { .. })
+        ));
+        Ok(())
+    }
+}
+
+mod object_kind {
+    use git_repository as git;
+    use git_repository::prelude::FindExt;
+
+    #[test]
+    fn loose_objects() -> crate::Result {
+        let repo = crate::basic_repo()?;
+        let head = repo.head_id()?;
+        assert_eq!(repo.object_kind(head)?, git::object::Kind::Commit);
+        let tree_id = head.object()?.into_commit().tree_id()?;
+        assert_eq!(repo.object_kind(tree_id)?, git::object::Kind::Tree);
+        Ok(())
+    }
+
+    #[test]
+    fn packed_objects_including_deltas() -> crate::Result {
+        let repo = crate::named_repo("make_packed_repo.sh")?;
+        let mut buf = Vec::new();
+        for id in repo.objects.iter()?.filter_map(Result::ok) {
+            let expected = repo.objects.find(&id, &mut buf)?.kind;
+            assert_eq!(repo.object_kind(id)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn missing_objects_are_an_error() -> crate::Result {
+        let repo = crate::basic_repo()?;
+        assert!(matches!(
+            repo.object_kind(git::hash::ObjectId::null(repo.object_hash())),
+            Err(git::object::kind::Error::NotFound { .. })
+        ));
+        Ok(())
+    }
+}
+
 mod tag {
     #[test]
     fn simple() -> crate::Result {
@@ -72,6 +253,25 @@ mod tag {
         assert_eq!(tag.message, message);
         Ok(())
     }
+
+    #[test]
+    fn consuming_conversions() -> crate::Result {
+        let repo = crate::named_repo("make_packed_repo.sh")?;
+        let tag = repo
+            .find_reference("v1")?
+            .id()
+            .object()?
+            .try_into_tag()
+            .expect("annotated tag");
+        assert_eq!(tag.target_id()?, repo.head_id()?);
+
+        let commit = repo.head_id()?.object()?;
+        let commit_id = commit.id;
+        let object = commit.try_into_tag().expect_err("not a tag");
+        assert_eq!(object.id, commit_id, "the object is returned unchanged");
+        assert_eq!(object.kind, git_object::Kind::Commit);
+        Ok(())
+    }
 }
 
 mod commit {
@@ -0,0 +1,92 @@
+use git_repository as git;
+
+use crate::{basic_repo, named_repo, Result};
+
+#[test]
+fn commits_can_be_packed_with_all_reachable_objects() -> Result {
+    let repo = basic_repo()?;
+    let head = repo.head_id()?.detach();
+
+    let mut pack = Vec::new();
+    let outcome = repo.pack_objects(Some(head), &mut pack, git::progress::Discard, Default::default())?;
+    assert_eq!(
+        outcome.num_objects, 1,
+        "without expansion, only the input objects are packed"
+    );
+
+    pack.clear();
+    let outcome = repo.pack_objects(
+        Some(head),
+        &mut pack,
+        git::progress::Discard,
+        git::pack_objects::Options {
+            expansion: git::pack_objects::ObjectExpansion::TreeContents,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.num_objects, 3, "the commit, its tree and a blob");
+    assert_eq!(outcome.pack_size, pack.len() as u64);
+    assert_eq!(&pack[..4], b"PACK");
+    assert_eq!(&pack[pack.len() - 20..], outcome.checksum.as_bytes());
+    Ok(())
+}
+
+#[test]
+fn similar_objects_are_stored_as_deltas_within_the_window() -> Result {
+    use git::odb::FindExt;
+
+    let repo = named_repo("make_packed_repo.sh")?;
+    let ids = repo.objects.iter()?.collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let without_deltas = repo.pack_objects(
+        ids.iter().copied(),
+        Vec::new(),
+        git::progress::Discard,
+        Default::default(),
+    )?;
+    assert_eq!(without_deltas.computed_deltas, 0);
+
+    let mut pack = Vec::new();
+    let outcome = repo.pack_objects(
+        ids.iter().copied(),
+        &mut pack,
+        git::progress::Discard,
+        git::pack_objects::Options {
+            delta_window: 10,
+            max_delta_depth: 50,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.num_objects, ids.len());
+    assert!(
+        outcome.computed_deltas >= 3,
+        "the versions of 'file' are deltas of each other, got {}",
+        outcome.computed_deltas
+    );
+    assert!(outcome.pack_size < without_deltas.pack_size);
+
+    let dir = tempfile::tempdir()?;
+    let pack_dir = dir.path().join("pack");
+    std::fs::create_dir(&pack_dir)?;
+    git::odb::pack::Bundle::write_to_directory(
+        pack.as_slice(),
+        Some(&pack_dir),
+        git::progress::Discard,
+        &std::sync::atomic::AtomicBool::new(false),
+        None,
+        git::odb::pack::bundle::write::Options {
+            object_hash: repo.object_hash(),
+            ..Default::default()
+        },
+    )?;
+    let written = git::odb::at(dir.path())?;
+    let mut buf = Vec::new();
+    for id in ids {
+        assert_eq!(
+            written.find(id, &mut buf)?.data,
+            repo.find_object(id)?.data.as_slice(),
+            "deltas resolve to the original objects"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use git_repository as git;
+
+use crate::{named_repo, Result};
+
+fn matches<'a>(
+    repo: &git::Repository,
+    spec: &str,
+    paths: &[&'a str],
+) -> std::result::Result<Vec<&'a str>, git::pathspec::Error> {
+    let paths: Vec<_> = paths.iter().map(Path::new).collect();
+    Ok(repo
+        .pathspec_matches(spec, &paths)?
+        .into_iter()
+        .map(|path| path.to_str().expect("valid UTF-8"))
+        .collect())
+}
+
+const PATHS: &[&str] = &[
+    "a.rs",
+    "src/b.rs",
+    "src/nested/c.rs",
+    "src/d.txt",
+    "some/very/deeply/nested/subdir",
+];
+
+#[test]
+fn plain_patterns_match_paths_and_their_leading_directories() -> Result {
+    let repo = named_repo("make_basic_repo.sh")?;
+    assert_eq!(
+        matches(&repo, "*.rs", PATHS)?,
+        vec!["a.rs", "src/b.rs", "src/nested/c.rs"]
+    );
+    assert_eq!(
+        matches(&repo, "src", PATHS)?,
+        vec!["src/b.rs", "src/nested/c.rs", "src/d.txt"]
+    );
+    assert_eq!(matches(&repo, "src/*.rs", PATHS)?, vec!["src/b.rs", "src/nested/c.rs"]);
+    assert_eq!(matches(&repo, ":(icase)SRC/D.TXT", PATHS)?, vec!["src/d.txt"]);
+    assert_eq!(matches(&repo, ":(literal)*.rs", PATHS)?, Vec::<&str>::new());
+    Ok(())
+}
+
+#[test]
+fn glob_magic_does_not_match_across_directories() -> Result {
+    let repo = named_repo("make_basic_repo.sh")?;
+    assert_eq!(matches(&repo, ":(glob)*.rs", PATHS)?, vec!["a.rs"]);
+    assert_eq!(matches(&repo, ":(glob)src/*.rs", PATHS)?, vec!["src/b.rs"]);
+    assert_eq!(
+        matches(&repo, ":(glob,top)**/*.rs", PATHS)?,
+        vec!["a.rs", "src/b.rs", "src/nested/c.rs"]
+    );
+    Ok(())
+}
+
+#[test]
+fn exclusions_return_all_paths_not_matching() -> Result {
+    let repo = named_repo("make_basic_repo.sh")?;
+    for spec in [":!*.rs", ":^*.rs", ":(exclude)*.rs", ":/!:*.rs"] {
+        assert_eq!(
+            matches(&repo, spec, PATHS)?,
+            vec!["src/d.txt", "some/very/deeply/nested/subdir"],
+            "{}",
+            spec
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn trailing_slashes_only_match_directories() -> Result {
+    let repo = named_repo("make_basic_repo.sh")?;
+    assert_eq!(
+        matches(&repo, "*/subdir/", PATHS)?,
+        vec!["some/very/deeply/nested/subdir"],
+        "the directory exists in the working tree"
+    );
+    assert_eq!(matches(&repo, "a.rs/", PATHS)?, Vec::<&str>::new());
+    assert_eq!(
+        matches(&repo, "src/", PATHS)?,
+        vec!["src/b.rs", "src/nested/c.rs", "src/d.txt"]
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_magic_is_an_error() -> Result {
+    let repo = named_repo("make_basic_repo.sh")?;
+    assert!(matches!(
+        matches(&repo, ":(unknown)a", PATHS),
+        Err(git::pathspec::Error::UnknownMagic { .. })
+    ));
+    assert!(matches!(
+        matches(&repo, ":(glob", PATHS),
+        Err(git::pathspec::Error::UnterminatedMagic { .. })
+    ));
+    assert!(matches!(
+        matches(&repo, ":(glob,literal)a", PATHS),
+        Err(git::pathspec::Error::IncompatibleMagic)
+    ));
+    Ok(())
+}
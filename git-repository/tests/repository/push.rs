@@ -0,0 +1,87 @@
+use git_repository as git;
+use git_repository::push::{Mode, Options};
+
+use crate::Result;
+
+fn local_repo() -> Result<(git::Repository, tempfile::TempDir)> {
+    let dir = git_testtools::scripted_fixture_repo_writable_with_args(
+        "make_push_repo.sh",
+        None,
+        git_testtools::Creation::ExecuteScript,
+    )?;
+    Ok((git::open(dir.path().join("local"))?, dir))
+}
+
+fn id(repo: &git::Repository, name: &str) -> Result<git::ObjectId> {
+    Ok(repo.find_reference(name)?.id().detach())
+}
+
+#[test]
+fn push_options_are_sent_if_the_remote_advertises_them() -> Result {
+    let (repo, dir) = local_repo()?;
+    let options = Options {
+        push_options: vec!["ci.skip".into(), "reason=test".into()],
+        ..Default::default()
+    };
+    let outcome = repo.push("origin", &["main", "main:refs/heads/copy"], options)?;
+    assert!(outcome.push_options_sent);
+    assert!(outcome.pack.is_some());
+    let modes: Vec<_> = outcome.updates.iter().map(|update| update.mode).collect();
+    assert_eq!(modes, vec![Mode::FastForward, Mode::New]);
+
+    let remote = git::open(dir.path().join("remote.git"))?;
+    for name in ["refs/heads/main", "refs/heads/copy"] {
+        assert_eq!(id(&remote, name)?, id(&repo, "refs/heads/main")?, "{}", name);
+    }
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("remote.git").join("push-options"))?,
+        "ci.skip\nreason=test\n"
+    );
+
+    let outcome = repo.push("origin", &["main"], Default::default())?;
+    assert!(
+        !outcome.push_options_sent,
+        "nothing was sent as the remote is up to date"
+    );
+    assert_eq!(outcome.updates[0].mode, Mode::UpToDate);
+    Ok(())
+}
+
+#[test]
+fn push_options_are_skipped_if_the_remote_does_not_advertise_them() -> Result {
+    let (repo, dir) = local_repo()?;
+    let outcome = repo.push("plain", &["main"], Default::default())?;
+    assert!(!outcome.push_options_sent);
+    assert_eq!(outcome.updates[0].mode, Mode::New);
+
+    let remote = git::open(dir.path().join("plain.git"))?;
+    assert_eq!(id(&remote, "refs/heads/main")?, id(&repo, "refs/heads/main")?);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("plain.git").join("push-options"))?,
+        ""
+    );
+    Ok(())
+}
+
+#[test]
+fn deleting_a_nonexistent_remote_reference_is_an_error() -> Result {
+    let (repo, dir) = local_repo()?;
+    assert!(matches!(
+        repo.push("origin", &["main", ":refs/heads/does-not-exist"], Default::default()),
+        Err(git::push::Error::DeleteNonexistent { name }) if name == "refs/heads/does-not-exist"
+    ));
+    let remote = git::open(dir.path().join("remote.git"))?;
+    assert_ne!(
+        id(&remote, "refs/heads/main")?,
+        id(&repo, "refs/heads/main")?,
+        "nothing is pushed"
+    );
+    Ok(())
+}
+
+#[test]
+fn push_options_are_read_from_the_configuration() -> Result {
+    let (repo, _keep) = local_repo()?;
+    assert_eq!(repo.push_options(), vec![git::bstr::BString::from("configured=yes")]);
+    Ok(())
+}
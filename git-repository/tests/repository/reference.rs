@@ -182,6 +182,86 @@ mod iter_references {
         );
         Ok(())
     }
+
+    #[test]
+    fn sorted() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_references_repo.sh")?;
+        for name in ["Z-upper", "a-lower", "main-2"] {
+            std::fs::write(
+                repo.git_dir().join("refs/heads").join(name),
+                "134385f6d781b7e97062102c6a483440bfda2a03\n",
+            )?;
+        }
+        let names = repo
+            .references()?
+            .sorted()
+            .local_branches()?
+            .filter_map(Result::ok)
+            .map(|r| r.name().as_bstr().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "refs/heads/Z-upper",
+                "refs/heads/a-lower",
+                "refs/heads/d1",
+                "refs/heads/dt1",
+                "refs/heads/main",
+                "refs/heads/main-2",
+                "refs/heads/multi-link-target1",
+            ],
+            "names are compared byte-wise, independently of the file system"
+        );
+
+        let all = repo.references()?.sorted().all()?.collect::<Vec<_>>();
+        assert!(all.last().expect("non-empty").is_err(), "errors come last");
+        let names = all
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|r| r.name().as_bstr().to_owned())
+            .collect::<Vec<_>>();
+        let mut expected = names.clone();
+        expected.sort();
+        assert_eq!(names, expected);
+
+        assert_eq!(
+            repo.references()?
+                .sorted()
+                .prefixed("refs/heads/")?
+                .peeled()
+                .filter_map(Result::ok)
+                .map(|r| r.name().as_bstr().to_owned())
+                .collect::<Vec<_>>(),
+            vec![
+                "refs/heads/Z-upper",
+                "refs/heads/a-lower",
+                "refs/heads/d1",
+                "refs/heads/dt1",
+                "refs/heads/main",
+                "refs/heads/main-2",
+                "refs/remotes/origin/multi-link-target3"
+            ],
+            "references are sorted by their name after peeling"
+        );
+        Ok(())
+    }
+}
+
+mod packed_refs_mtime {
+    #[test]
+    fn is_that_of_the_packed_refs_file_if_present() -> crate::Result {
+        let repo = crate::repo("make_references_repo.sh")?.to_thread_local();
+        let expected = std::fs::metadata(repo.git_dir().join("packed-refs"))?.modified()?;
+        assert_eq!(repo.packed_refs_mtime()?, Some(expected));
+        assert_eq!(
+            repo.refs.cached_packed_buffer()?.expect("present").mtime(),
+            Some(expected),
+            "the buffer remembers the time as well"
+        );
+
+        assert_eq!(crate::basic_repo()?.packed_refs_mtime()?, None);
+        Ok(())
+    }
 }
 
 mod head {
@@ -221,6 +301,144 @@ mod head {
         let head = repo.head()?;
         assert!(head.is_detached(), "head is detached");
         assert!(head.referent_name().is_none());
+        assert_eq!(repo.symbolic_ref_target("HEAD")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn peel_to_kind_in_place_follows_tags_and_commits() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_bundle_repo.sh")?;
+        let tag = repo
+            .find_reference("refs/tags/v1")?
+            .target()
+            .try_id()
+            .expect("peeled")
+            .to_owned();
+        repo.reference("HEAD", tag, PreviousValue::Any, "")?;
+
+        let commit = repo.find_object(tag)?.peel_to_kind(git::object::Kind::Commit)?.id;
+        let tree = repo.find_object(commit)?.peel_to_kind(git::object::Kind::Tree)?.id;
+        let mut head = repo.head()?;
+        assert_eq!(
+            head.peel_to_kind_in_place(git::object::Kind::Commit).expect("born")?,
+            commit,
+            "the tag is followed to its commit"
+        );
+        assert_eq!(
+            head.peel_to_kind_in_place(git::object::Kind::Tree).expect("born")?,
+            tree,
+            "the commit is followed to its tree"
+        );
+        assert_eq!(head.peel_to_commit_in_place()?.id, commit);
+
+        let err = head
+            .peel_to_kind_in_place(git::object::Kind::Blob)
+            .expect("born")
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                git::head::peel::Error::PeelObject(git::object::peel::to_kind::Error::NotFound {
+                    actual: git::object::Kind::Tree,
+                    expected: git::object::Kind::Blob,
+                })
+            ),
+            "trees can't be peeled any further"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn peel_to_kind_in_place_on_unborn_head() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo = git::init(tmp.path())?;
+        assert!(repo.head()?.peel_to_kind_in_place(git::object::Kind::Commit).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_ref_target() -> crate::Result {
+        let repo = crate::basic_repo()?;
+        assert_eq!(
+            repo.symbolic_ref_target("HEAD")?.expect("symbolic").as_bstr(),
+            "refs/heads/main"
+        );
+        assert_eq!(
+            repo.symbolic_ref_target("main")?,
+            None,
+            "branches point to commits directly"
+        );
+        assert!(matches!(
+            repo.symbolic_ref_target("does-not-exist"),
+            Err(git::reference::find::existing::Error::NotFound)
+        ));
+        Ok(())
+    }
+}
+
+mod list_loose_refs {
+    use git_repository as git;
+    use git_repository::refs::transaction::PreviousValue;
+
+    #[test]
+    fn packed_references_are_not_listed() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_references_repo.sh")?;
+        let loose_names = |repo: &git::Repository| -> crate::Result<Vec<_>> {
+            Ok(repo
+                .list_loose_refs()?
+                .filter_map(Result::ok)
+                .map(|r| r.name().as_bstr().to_owned())
+                .collect())
+        };
+        assert_eq!(
+            loose_names(&repo)?,
+            vec![
+                "refs/heads/multi-link-target1",
+                "refs/loop-a",
+                "refs/loop-b",
+                "refs/multi-link",
+                "refs/remotes/origin/HEAD",
+                "refs/tags/multi-link-target2",
+            ],
+            "symbolic references can't be packed"
+        );
+        assert_eq!(
+            repo.list_loose_refs()?.filter(Result::is_err).count(),
+            1,
+            "broken references are listed as errors"
+        );
+
+        repo.reference(
+            "refs/heads/main",
+            git::ObjectId::empty_tree(git::hash::Kind::Sha1),
+            PreviousValue::Any,
+            "overwrite packed reference",
+        )?;
+        assert!(
+            loose_names(&repo)?.iter().any(|name| name == "refs/heads/main"),
+            "updating a packed reference makes it loose"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn linked_worktrees_see_references_of_the_common_directory() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_worktree_repo.sh")?;
+        let repo = git::open(dir.join("wt-a"))?;
+        let names: Vec<_> = repo
+            .list_loose_refs()?
+            .filter_map(Result::ok)
+            .map(|r| r.name().as_bstr().to_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "refs/heads/main",
+                "refs/heads/wt-a",
+                "refs/heads/wt-c-locked",
+                "refs/heads/wt-deleted"
+            ]
+        );
         Ok(())
     }
 }
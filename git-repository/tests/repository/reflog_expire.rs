@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use git_repository as git;
+
+use crate::{repo_rw, Result};
+
+fn log_messages(repo: &git::Repository, name: &str) -> Result<Vec<String>> {
+    let mut buf = Vec::new();
+    Ok(repo
+        .refs
+        .reflog_iter(name, &mut buf)?
+        .expect("log exists")
+        .map(|line| line.map(|line| line.message.to_string()))
+        .collect::<std::result::Result<_, _>>()?)
+}
+
+#[test]
+fn entries_older_than_expire_are_removed() -> Result {
+    let (repo, _keep) = repo_rw("make_reflog_expire_repo.sh")?;
+    let stats = repo.expire_reflog("refs/heads/main", Default::default())?;
+    assert_eq!(
+        stats,
+        git::reflog_expire::Stats {
+            logs: 1,
+            entries_kept: 1,
+            entries_removed: 3,
+        }
+    );
+    assert_eq!(log_messages(&repo, "refs/heads/main")?, ["commit: c2"]);
+    assert_eq!(log_messages(&repo, "HEAD")?.len(), 4, "other logs are untouched");
+
+    let stats = repo.expire_reflog("refs/heads/main", Default::default())?;
+    assert_eq!(stats.entries_removed, 0, "nothing else is old enough");
+    Ok(())
+}
+
+#[test]
+fn unreachable_entries_can_expire_sooner() -> Result {
+    let (repo, _keep) = repo_rw("make_reflog_expire_repo.sh")?;
+    let stats = repo.expire_reflog(
+        "ignored",
+        git::reflog_expire::Options {
+            expire: Duration::from_secs(u64::MAX),
+            expire_unreachable: Some(Duration::from_secs(24 * 60 * 60)),
+            all: true,
+        },
+    )?;
+    assert_eq!(
+        stats,
+        git::reflog_expire::Stats {
+            logs: 2,
+            entries_kept: 6,
+            entries_removed: 2,
+        }
+    );
+    for name in ["HEAD", "refs/heads/main"] {
+        assert_eq!(
+            log_messages(&repo, name)?,
+            ["commit (initial): c1", "reset: moving to HEAD~1", "commit: c2"],
+            "only the entry of the dropped commit was unreachable"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn missing_logs_and_invalid_names() -> Result {
+    let (repo, _keep) = repo_rw("make_reflog_expire_repo.sh")?;
+    assert_eq!(
+        repo.expire_reflog("refs/heads/missing", Default::default())?,
+        git::reflog_expire::Stats::default()
+    );
+    assert!(matches!(
+        repo.expire_reflog("refs/heads/in..valid", Default::default()),
+        Err(git::reflog_expire::Error::ReferenceName(_))
+    ));
+    Ok(())
+}
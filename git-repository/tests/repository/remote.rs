@@ -1,3 +1,7 @@
+use std::convert::TryInto;
+
+use git_repository::bstr::ByteSlice;
+
 use crate::{named_repo, Result};
 
 #[test]
@@ -22,3 +26,65 @@ fn simple() -> Result {
 
     Ok(())
 }
+
+#[test]
+fn push_remote_for_branch() -> Result {
+    let mut repo = named_repo("make_remote_repo.sh")?;
+    let main: &git_ref::FullNameRef = "refs/heads/main".try_into()?;
+    assert_eq!(
+        repo.push_remote_for_branch(main)?.expect("set").as_bstr(),
+        "remote_repo",
+        "it falls back to branch.<name>.remote"
+    );
+    assert_eq!(repo.push_remote_for_branch("refs/heads/missing".try_into()?)?, None);
+
+    repo.load_config_layer("remote.pushDefault=default")?;
+    assert_eq!(repo.push_remote_for_branch(main)?.expect("set"), "default");
+    assert_eq!(
+        repo.push_remote_for_branch("refs/heads/missing".try_into()?)?
+            .expect("set"),
+        "default",
+        "the default applies to all branches"
+    );
+
+    repo.load_config_layer("branch.main.pushRemote=push")?;
+    assert_eq!(repo.push_remote_for_branch(main)?.expect("set"), "push");
+
+    assert!(matches!(
+        repo.push_remote_for_branch("refs/tags/main".try_into()?),
+        Err(git_repository::remote::Error::NotALocalBranch { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn tracking_branch_for() -> Result {
+    let mut repo = named_repo("make_remote_repo.sh")?;
+    let main: &git_ref::FullNameRef = "refs/heads/main".try_into()?;
+    assert_eq!(
+        repo.tracking_branch_for(main)?.expect("configured").as_bstr(),
+        "refs/remotes/remote_repo/main",
+        "the merge ref is mapped with the fetch refspec of the remote"
+    );
+    assert_eq!(repo.tracking_branch_for("refs/heads/missing".try_into()?)?, None);
+
+    repo.load_config_layer("branch.main.remote=.")?;
+    assert_eq!(
+        repo.tracking_branch_for(main)?.expect("configured").as_bstr(),
+        "refs/heads/main",
+        "local upstreams are used as is"
+    );
+
+    repo.load_config_layer("branch.main.remote=unknown")?;
+    assert_eq!(
+        repo.tracking_branch_for(main)?,
+        None,
+        "without fetch refspecs there is no tracking branch"
+    );
+
+    assert!(matches!(
+        repo.tracking_branch_for("refs/remotes/remote_repo/main".try_into()?),
+        Err(git_repository::remote::Error::NotALocalBranch { .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::path::{Path, PathBuf};
+
+use git_repository as git;
+
+use crate::{repo_rw, Result};
+
+fn packs(pack_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut packs = std::fs::read_dir(pack_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    packs.retain(|path| path.extension().map_or(false, |ext| ext == "pack"));
+    packs.sort();
+    Ok(packs)
+}
+
+#[test]
+fn all_packs_but_kept_ones_are_consolidated_into_one() -> Result {
+    all_packs_but_kept_ones_are_consolidated_into_one_with(Default::default())
+}
+
+#[test]
+fn all_packs_but_kept_ones_are_consolidated_into_one_with_new_deltas() -> Result {
+    all_packs_but_kept_ones_are_consolidated_into_one_with(git::repack::Options {
+        delta_reuse: false,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn all_packs_but_kept_ones_are_consolidated_into_one_without_deltas() -> Result {
+    all_packs_but_kept_ones_are_consolidated_into_one_with(git::repack::Options {
+        delta_reuse: false,
+        window: 0,
+        depth: 0,
+        ..Default::default()
+    })
+}
+
+fn all_packs_but_kept_ones_are_consolidated_into_one_with(opts: git::repack::Options) -> Result {
+    let (repo, _tmp) = repo_rw("make_multi_pack_repo.sh")?;
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+    let packs_before = packs(&pack_dir)?;
+    assert_eq!(packs_before.len(), 4, "one pack per commit");
+    let kept_pack = packs_before
+        .iter()
+        .find(|path| path.with_extension("keep").exists())
+        .expect("one pack is kept")
+        .to_owned();
+
+    let stats = repo.repack(git::progress::Discard, opts)?;
+    assert_eq!(stats.packs, 3, "the kept pack is ignored");
+    assert_eq!(stats.objects, 9, "three commits, trees and blobs");
+    let new_pack = stats.pack_path.expect("a pack was written");
+
+    let mut expected = vec![kept_pack, new_pack];
+    expected.sort();
+    assert_eq!(packs(&pack_dir)?, expected, "previous packs were removed");
+    assert!(expected.iter().all(|pack| pack.with_extension("idx").is_file()));
+
+    let repo = git::open(repo.git_dir())?;
+    let mut commits = 0;
+    for commit in repo.head_id()?.ancestors().all()? {
+        repo.find_object(commit?)?.peel_to_kind(git::object::Kind::Tree)?;
+        commits += 1;
+    }
+    assert_eq!(commits, 4, "all objects are still available");
+    Ok(())
+}
+
+#[test]
+fn nothing_happens_without_packs() -> Result {
+    let (repo, _tmp) = repo_rw("make_basic_repo.sh")?;
+    assert_eq!(
+        repo.repack(git::progress::Discard, Default::default())?,
+        git::repack::Stats::default()
+    );
+    Ok(())
+}
+
+#[test]
+fn packs_larger_than_the_maximum_input_size_are_left_alone() -> Result {
+    let (repo, _tmp) = repo_rw("make_multi_pack_repo.sh")?;
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+    let packs_before = packs(&pack_dir)?;
+    assert_eq!(
+        repo.repack(
+            git::progress::Discard,
+            git::repack::Options {
+                max_input_pack_size: Some(0),
+                ..Default::default()
+            }
+        )?,
+        git::repack::Stats::default(),
+        "all packs are larger than that"
+    );
+    assert_eq!(packs(&pack_dir)?, packs_before);
+    Ok(())
+}
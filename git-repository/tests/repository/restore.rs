@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use git_repository as git;
+
+use crate::{basic_rw_repo, Result};
+
+#[test]
+fn files_are_restored_from_the_index_or_head() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    let original = std::fs::read(work_dir.join("this"))?;
+
+    std::fs::write(work_dir.join("this"), "changed")?;
+    repo.restore(Path::new("this"), git::restore::Source::Index, false)?;
+    assert_eq!(std::fs::read(work_dir.join("this"))?, original);
+
+    std::fs::remove_file(work_dir.join("this"))?;
+    repo.restore(Path::new(""), git::restore::Source::Head, false)?;
+    assert_eq!(
+        std::fs::read(work_dir.join("this"))?,
+        original,
+        "an empty path restores everything"
+    );
+    Ok(())
+}
+
+#[test]
+fn staged_changes_are_restored_from_head_without_touching_the_working_tree() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    let work_dir = repo.work_dir().expect("non-bare");
+    let head_id = repo.open_index()?.entry_by_path("this".into(), 0).expect("tracked").id;
+
+    std::fs::write(work_dir.join("this"), "staged")?;
+    let staged_id = repo
+        .write_object(git::objs::Blob {
+            data: b"staged".to_vec(),
+        })?
+        .detach();
+    let mut index = repo.open_index()?;
+    for (entry, path) in index.entries_mut_with_paths() {
+        if path == "this" {
+            entry.id = staged_id;
+        }
+    }
+    repo.write_index(&index)?;
+
+    repo.restore(Path::new("this"), git::restore::Source::Head, true)?;
+    assert_eq!(
+        repo.open_index()?
+            .entry_by_path("this".into(), 0)
+            .expect("still tracked")
+            .id,
+        head_id,
+        "the change is unstaged"
+    );
+    assert_eq!(
+        std::fs::read(work_dir.join("this"))?,
+        b"staged",
+        "the working tree is left as is"
+    );
+    Ok(())
+}
+
+#[test]
+fn unknown_paths_are_an_error() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    for staged in [false, true] {
+        assert!(matches!(
+            repo.restore(Path::new("does-not-exist"), git::restore::Source::Head, staged),
+            Err(git::restore::Error::PathNotFound { .. })
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn paths_leaving_the_working_tree_are_an_error() -> Result {
+    let (repo, _keep) = basic_rw_repo()?;
+    assert!(matches!(
+        repo.restore(Path::new("../this"), git::restore::Source::Head, false),
+        Err(git::restore::Error::PathTraversal(_))
+    ));
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use git_repository as git;
+use git_repository::{
+    bstr::ByteSlice,
+    sign::{SignatureStatus, SigningKey},
+};
+
+use crate::Result;
+
+fn repo() -> Result<(git::Repository, tempfile::TempDir)> {
+    let dir = git_testtools::scripted_fixture_repo_writable_with_args(
+        "make_sign_repo.sh",
+        None,
+        git_testtools::Creation::ExecuteScript,
+    )?;
+    Ok((git::open(dir.path())?, dir))
+}
+
+#[test]
+fn ssh_signatures_can_be_created_and_verified() -> Result {
+    let (repo, _keep) = repo()?;
+    let signature = repo.sign_data(b"data\n")?;
+    assert!(signature.starts_with(b"-----BEGIN SSH SIGNATURE-----\n"));
+
+    match repo.verify_signature(b"data\n", &signature)? {
+        SignatureStatus::Good { signer, key } => {
+            assert_eq!(signer, "author@example.com");
+            assert!(key.starts_with(b"SHA256:"), "the fingerprint of the key is returned");
+        }
+        status => panic!("unexpected status: {:?}", status),
+    }
+    assert!(matches!(
+        repo.verify_signature(b"tampered\n", &signature)?,
+        SignatureStatus::Bad { signer, .. } if signer == "author@example.com"
+    ));
+    assert!(matches!(
+        repo.verify_signature(b"data\n", b"not a signature"),
+        Err(git::sign::Error::UnknownSignatureFormat)
+    ));
+    Ok(())
+}
+
+#[test]
+fn commits_are_signed_if_configured() -> Result {
+    let (repo, _keep) = repo()?;
+    let parent = repo.head_id()?;
+    let tree = parent.object()?.to_commit_ref_iter().tree_id().expect("tree to be set");
+    let author = git::actor::Signature::empty();
+    let id = repo.commit("HEAD", author.to_ref(), author.to_ref(), "signed", tree, Some(parent))?;
+
+    let commit = repo.find_object(id)?;
+    assert!(commit.data.find(b"\ngpgsig -----BEGIN SSH SIGNATURE-----\n").is_some());
+    let status = std::process::Command::new("git")
+        .args(["verify-commit", &id.to_string()])
+        .current_dir(repo.work_dir().expect("non-bare"))
+        .status()?;
+    assert!(status.success(), "git accepts the signature");
+    Ok(())
+}
+
+#[test]
+fn signing_keys_are_detected_by_their_content_and_format() -> Result {
+    let (_repo, dir) = repo()?;
+    let signing_key = |format: &str, key: &str| -> Result<SigningKey> {
+        for (name, value) in [("gpg.format", format), ("user.signingKey", key)] {
+            let status = std::process::Command::new("git")
+                .args(["config", name, value])
+                .current_dir(dir.path())
+                .status()?;
+            assert!(status.success());
+        }
+        Ok(git::open(dir.path())?.signing_key()?)
+    };
+
+    assert_eq!(
+        signing_key("ssh", "key::ssh-ed25519 AAAA")?,
+        SigningKey::SshPublicKey("ssh-ed25519 AAAA".into())
+    );
+    assert_eq!(
+        signing_key("ssh", "ssh-rsa AAAA")?,
+        SigningKey::SshPublicKey("ssh-rsa AAAA".into())
+    );
+    assert_eq!(
+        signing_key("ssh", "/path/to/key")?,
+        SigningKey::SshKeyFile("/path/to/key".into())
+    );
+    #[cfg(unix)]
+    assert_eq!(
+        signing_key("ssh", "~/.ssh/id_ed25519")?,
+        SigningKey::SshKeyFile(
+            std::path::PathBuf::from(std::env::var_os("HOME").expect("set")).join(".ssh/id_ed25519")
+        )
+    );
+    assert_eq!(
+        signing_key("openpgp", "-----BEGIN PGP PUBLIC KEY BLOCK-----")?,
+        SigningKey::ArmoredPgp("-----BEGIN PGP PUBLIC KEY BLOCK-----".into())
+    );
+    assert_eq!(
+        signing_key("openpgp", "ssh-ed25519 AAAA")?,
+        SigningKey::SshPublicKey("ssh-ed25519 AAAA".into()),
+        "SSH keys are detected independently of the format"
+    );
+    assert_eq!(
+        signing_key("openpgp", "/path/to/key")?,
+        SigningKey::KeyId("/path/to/key".into())
+    );
+    Ok(())
+}
@@ -46,6 +46,14 @@ fn merge() -> Result {
 
     assert_eq!(repo.head_name()?.unwrap().shorten(), "main");
     assert_eq!(repo.state(), Some(git::state::InProgress::Merge));
+    assert_eq!(
+        repo.merge_heads()?,
+        vec![repo.find_reference("other-branch")?.peel_to_id_in_place()?.detach()]
+    );
+    assert!(repo
+        .merge_message()?
+        .expect("present during merge")
+        .starts_with(b"Merge branch 'other-branch'"));
 
     Ok(())
 }
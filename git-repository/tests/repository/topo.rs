@@ -0,0 +1,50 @@
+use git_repository as git;
+
+use crate::{named_repo, Result};
+
+fn ids(repo: &git::Repository, names: &[&str]) -> Result<Vec<git::ObjectId>> {
+    names
+        .iter()
+        .map(|name| -> Result<git::ObjectId> { Ok(repo.find_reference(*name)?.peel_to_id_in_place()?.detach()) })
+        .collect()
+}
+
+fn commits(repo: &git::Repository, tips: &[&str], order: git::topo::Order) -> Result<Vec<git::ObjectId>> {
+    let mut out = Vec::new();
+    repo.for_each_commit_topo(&ids(repo, tips)?, order, |id, parents| {
+        assert!(
+            parents
+                .iter()
+                .all(|parent| out.contains(parent) != (order == git::topo::Order::ChildrenFirst)),
+            "parents are passed before or after their children, depending on the order"
+        );
+        out.push(id);
+    })?;
+    Ok(out)
+}
+
+#[test]
+fn children_first() -> Result {
+    let repo = named_repo("make_merge_base_repo.sh")?;
+    assert_eq!(
+        commits(&repo, &["criss"], git::topo::Order::ChildrenFirst)?,
+        ids(&repo, &["criss", "x", "y", "c2", "c1"])?,
+        "the first parent is followed first"
+    );
+    assert_eq!(
+        commits(&repo, &["z", "x", "c2"], git::topo::Order::ChildrenFirst)?,
+        ids(&repo, &["z", "x", "c2", "c1"])?,
+        "tips reachable from other tips are passed after their children, once"
+    );
+    Ok(())
+}
+
+#[test]
+fn parents_first() -> Result {
+    let repo = named_repo("make_merge_base_repo.sh")?;
+    assert_eq!(
+        commits(&repo, &["criss"], git::topo::Order::ParentsFirst)?,
+        ids(&repo, &["c1", "c2", "y", "x", "criss"])?
+    );
+    Ok(())
+}
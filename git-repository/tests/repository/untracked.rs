@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use git_repository as git;
+
+use crate::{named_repo, Result};
+
+fn untracked(repo: &git::Repository, recurse_submodules: bool) -> Result<Vec<PathBuf>> {
+    Ok(repo
+        .untracked_files(git::untracked::Options { recurse_submodules })?
+        .collect())
+}
+
+#[test]
+fn files_neither_tracked_nor_ignored_are_listed() -> Result {
+    let repo = named_repo("make_untracked_repo.sh")?;
+    assert_eq!(
+        untracked(&repo, false)?,
+        vec![
+            PathBuf::from("dir").join("untracked"),
+            PathBuf::from("new-dir").join("sub").join("file"),
+            PathBuf::from("untracked"),
+        ],
+        "ignored files, nested repositories and submodules are skipped"
+    );
+    Ok(())
+}
+
+#[test]
+fn submodules_are_entered_on_request() -> Result {
+    let repo = named_repo("make_untracked_repo.sh")?;
+    assert_eq!(
+        untracked(&repo, true)?,
+        vec![
+            PathBuf::from("dir").join("untracked"),
+            PathBuf::from("new-dir").join("sub").join("file"),
+            PathBuf::from("submodule").join("untracked"),
+            PathBuf::from("untracked"),
+        ]
+    );
+    Ok(())
+}
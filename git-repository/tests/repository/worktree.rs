@@ -185,3 +185,50 @@ fn run_assertions(main_repo: git::Repository, should_be_bare: bool) {
         );
     }
 }
+
+#[cfg(feature = "unstable")]
+mod open_index {
+    use git_repository as git;
+
+    #[test]
+    fn v4_indices_with_prefix_compressed_paths_are_supported() -> crate::Result {
+        let repo = crate::named_repo("make_index_v4_repo.sh")?;
+        let index = repo.open_index()?;
+        assert_eq!(index.version(), git::index::Version::V4);
+        assert_eq!(
+            index
+                .entries()
+                .iter()
+                .map(|entry| entry.path(&index).to_string())
+                .collect::<Vec<_>>(),
+            vec!["a", "dir/b", "dir/nested/c", "dir/nested/d"]
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "unstable")]
+mod write_index {
+    use git_repository as git;
+
+    #[test]
+    fn the_index_is_replaced_and_readable_by_git() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_index_v4_repo.sh")?;
+        let index = repo.open_index()?;
+        let stats = repo.write_index(&index)?;
+        assert_eq!(stats.version, git::index::Version::V4);
+        assert_eq!(stats.num_entries, 4);
+
+        let written = repo.open_index()?;
+        assert_eq!(written.checksum, stats.checksum);
+        assert_eq!(written.entries().len(), index.entries().len());
+
+        let status = std::process::Command::new("git")
+            .args(["status", "--porcelain"])
+            .current_dir(repo.work_dir().expect("non-bare"))
+            .output()?;
+        assert!(status.status.success());
+        assert_eq!(status.stdout, b"", "git sees the same entries as before");
+        Ok(())
+    }
+}
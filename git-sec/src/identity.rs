@@ -13,6 +13,8 @@ pub struct Account {
 /// Returns true if the given `path` is owned by the user who is executing the current process.
 ///
 /// Note that this method is very specific to avoid having to deal with any operating system types.
+/// On Unix, the owner's uid is compared to the effective uid of the process. On Windows, the owner SID of the
+/// security descriptor is compared to the owner of the current token, see `trust_for_path_windows()` for details.
 pub fn is_path_owned_by_current_user(path: impl AsRef<Path>) -> std::io::Result<bool> {
     impl_::is_path_owned_by_current_user(path)
 }
@@ -72,11 +74,7 @@ mod impl_ {
         // Home is not actually owned by the corresponding user
         // but it can be considered de-facto owned by the user
         // Ignore errors here and just do the regular checks below
-        if std::env::current_dir()
-            .ok()
-            .and_then(|cwd| git_path::realpath(path, cwd).ok())
-            == dirs::home_dir()
-        {
+        if git_path::realpath(path).ok() == dirs::home_dir() {
             return Ok(true);
         }
 
@@ -17,6 +17,21 @@ pub enum Trust {
     Full,
 }
 
+/// Derive `Full` trust if `path` is owned by the current user according to its security descriptor, or `Reduced`
+/// trust otherwise, similar to the checks git performs since CVE-2022-24765.
+///
+/// The owner SID of `path` is compared to the SID of the owner of the current thread's or process's token.
+/// Unlike on Unix, where only the effective user id is compared to the owner's uid, paths owned by the
+/// `BUILTIN\Administrators` group are trusted if the current user is a member of it, and the user's home directory
+/// is always trusted as it usually isn't owned by the user.
+///
+/// As trust can't be established if the owner can't be determined, errors result in `Reduced` trust. Use
+/// [`Trust::from_path_ownership()`] to learn about them instead.
+#[cfg(windows)]
+pub fn trust_for_path_windows(path: &std::path::Path) -> Trust {
+    Trust::from_path_ownership(path).unwrap_or(Trust::Reduced)
+}
+
 ///
 pub mod trust {
     use crate::Trust;
@@ -77,7 +92,10 @@ pub mod trust {
 
 ///
 pub mod permission {
-    use std::fmt::{Debug, Display};
+    use std::{
+        fmt::{Debug, Display},
+        path::{Component, Path, PathBuf},
+    };
 
     use crate::Access;
 
@@ -119,6 +137,57 @@ pub mod permission {
         }
     }
 
+    /// The error returned by [`check_path_traversal()`] if a path would escape its base directory.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct TraversalError {
+        /// The offending path, as passed to [`check_path_traversal()`].
+        pub path: PathBuf,
+    }
+
+    impl Display for TraversalError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "The path {:?} would leave its base directory", self.path)
+        }
+    }
+
+    impl std::error::Error for TraversalError {}
+
+    /// Return an error if joining `base` and `candidate` results in a path that isn't located within `base`, which is the case
+    /// if `candidate` is absolute or if it has more `..` components than it can undo, like `a/../../b`.
+    ///
+    /// Use it to validate user-supplied paths, like pathspecs, before resolving them relative to the root of the working tree.
+    /// Both paths are normalized lexically, which is why symbolic links, whose targets may be located elsewhere, aren't
+    /// followed.
+    pub fn check_path_traversal(base: &Path, candidate: &Path) -> Result<(), TraversalError> {
+        let base = normalize(base);
+        match normalize(&base.join(candidate)).strip_prefix(&base) {
+            // relative bases may normalize to nothing, which any path starts with.
+            Ok(rest) if !rest.components().any(|component| component == Component::ParentDir) => Ok(()),
+            _ => Err(TraversalError {
+                path: candidate.to_owned(),
+            }),
+        }
+    }
+
+    /// Remove `.` components from `path` and resolve `..` components where possible, without accessing the filesystem.
+    fn normalize(path: &Path) -> PathBuf {
+        let mut out = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => match out.components().next_back() {
+                    Some(Component::Normal(_)) => {
+                        out.pop();
+                    }
+                    Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                    Some(Component::CurDir) | Some(Component::ParentDir) | None => out.push(component),
+                },
+                Component::Prefix(_) | Component::RootDir | Component::Normal(_) => out.push(component),
+            }
+        }
+        out
+    }
+
     /// An error to use if an operation cannot proceed due to insufficient permissions.
     ///
     /// It's up to the implementation to decide which permission is required for an operation, and which one
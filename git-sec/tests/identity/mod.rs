@@ -15,3 +15,15 @@ fn windows_home() -> crate::Result {
     assert!(git_sec::identity::is_path_owned_by_current_user(home)?);
     Ok(())
 }
+
+#[test]
+#[cfg(windows)]
+fn trust_for_path_windows() -> crate::Result {
+    let dir = tempfile::tempdir()?;
+    assert_eq!(git_sec::trust_for_path_windows(dir.path()), git_sec::Trust::Full);
+    assert_eq!(
+        git_sec::trust_for_path_windows(&dir.path().join("does-not-exist")),
+        git_sec::Trust::Reduced
+    );
+    Ok(())
+}
@@ -9,4 +9,47 @@ mod trust {
     }
 }
 
+mod permission {
+    use std::path::Path;
+
+    use git_sec::permission::{check_path_traversal, TraversalError};
+
+    #[test]
+    fn paths_within_base_are_allowed() {
+        for candidate in ["file", "dir/file", "./dir/../file", "dir/sub/../../file", ""] {
+            assert_eq!(
+                check_path_traversal(Path::new("repo"), Path::new(candidate)),
+                Ok(()),
+                "{:?}",
+                candidate
+            );
+        }
+        assert_eq!(check_path_traversal(Path::new("/repo/./wt"), Path::new("file")), Ok(()));
+    }
+
+    #[test]
+    fn paths_leaving_base_are_an_error() {
+        for candidate in ["..", "../file", "dir/../../file", "../repo-other/file"] {
+            assert_eq!(
+                check_path_traversal(Path::new("/repo"), Path::new(candidate)),
+                Err(TraversalError { path: candidate.into() }),
+                "{:?}",
+                candidate
+            );
+        }
+        assert!(
+            check_path_traversal(Path::new("/repo"), Path::new("/etc/passwd")).is_err(),
+            "absolute paths replace the base"
+        );
+        assert!(
+            check_path_traversal(Path::new("repo"), Path::new("../repo/file")).is_ok(),
+            "only the resulting path counts"
+        );
+        assert!(
+            check_path_traversal(Path::new("."), Path::new("../file")).is_err(),
+            "bases that normalize to an empty path can be escaped as well"
+        );
+    }
+}
+
 mod identity;
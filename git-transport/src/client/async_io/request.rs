@@ -79,6 +79,15 @@ impl<'a> RequestWriter<'a> {
         }
         .map(|_| ())
     }
+
+    /// Dissolve this instance into the underlying writer and the reader for obtaining the other side's response,
+    /// without writing the message that [`into_read()`][RequestWriter::into_read()] would write.
+    ///
+    /// This is useful to write data that isn't encoded as packet lines, like the pack sent to `git-receive-pack`.
+    pub fn into_parts(self) -> (Box<dyn AsyncWrite + Unpin + 'a>, Box<dyn ExtendedBufRead + Unpin + 'a>) {
+        (self.writer.into_inner(), self.reader)
+    }
+
     /// Discard the ability to write and turn this instance into the reader for obtaining the other side's response.
     pub async fn into_read(mut self) -> std::io::Result<Box<dyn ExtendedBufRead + Unpin + 'a>> {
         self.write_message(self.on_into_read).await?;
@@ -132,7 +132,11 @@ impl client::Transport for SpawnProcessOnDemand {
         if self.ssh_program.is_some() {
             cmd.arg(service.as_str());
         }
-        cmd.arg("--strict").arg("--timeout=0").arg(self.path.to_os_str_lossy());
+        if service == Service::UploadPack {
+            // `git-receive-pack` doesn't know these flags and would refuse to start.
+            cmd.arg("--strict").arg("--timeout=0");
+        }
+        cmd.arg(self.path.to_os_str_lossy());
 
         let mut child = cmd.spawn()?;
         self.connection = Some(git::Connection::new_for_spawned_process(
@@ -5,6 +5,7 @@ pub struct Ancestors<Find, Predicate, StateMut> {
     state: StateMut,
     parents: Parents,
     sorting: Sorting,
+    stop_at: Option<Box<dyn Fn(&git_hash::ObjectId) -> bool + Send + Sync>>,
 }
 
 /// Specify how to handle commit parents during traversal.
@@ -91,6 +92,17 @@ pub mod ancestors {
             self.parents = mode;
             self
         }
+
+        /// End the traversal once all commits queued for traversal match `predicate`, and drop these queued commits.
+        ///
+        /// Until then, commits matching `predicate` are returned and their parents are traversed like those of any other commit,
+        /// so it depends on the [sorting][Ancestors::sorting()] how many of them are returned. Callers which must not see them,
+        /// like `git log a..b` with the commits reachable from `a`, have to filter them out themselves, but avoid traversing
+        /// the history past them.
+        pub fn stop_at(mut self, predicate: impl Fn(&ObjectId) -> bool + Send + Sync + 'static) -> Self {
+            self.stop_at = Some(Box::new(predicate));
+            self
+        }
     }
 
     impl<Find, Predicate, StateMut, E> Ancestors<Find, Predicate, StateMut>
@@ -180,6 +192,7 @@ pub mod ancestors {
                 state,
                 parents: Default::default(),
                 sorting: Default::default(),
+                stop_at: None,
             }
         }
     }
@@ -194,6 +207,13 @@ pub mod ancestors {
         type Item = Result<ObjectId, Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
+            if let Some(stop_at) = &self.stop_at {
+                let state = self.state.borrow_mut();
+                if state.next.iter().all(|(id, _)| stop_at(id)) {
+                    state.next.clear();
+                    return None;
+                }
+            }
             if matches!(self.parents, Parents::First) {
                 self.next_by_topology()
             } else {
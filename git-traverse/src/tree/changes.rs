@@ -0,0 +1,213 @@
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    io::{BufRead, BufReader, Read},
+};
+
+use git_hash::ObjectId;
+use git_object::{
+    bstr::{BString, ByteSlice},
+    tree,
+};
+use quick_error::quick_error;
+
+use crate::tree::Changes;
+
+quick_error! {
+    /// The error returned by the [`Changes`] iterator.
+    #[derive(Debug)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        Io(err: std::io::Error) {
+            display("Could not read tree data")
+            from()
+            source(err)
+        }
+        InvalidMode(mode: BString) {
+            display("Invalid tree entry mode {:?}", mode)
+        }
+        UnexpectedEof {
+            display("The tree data ended in the middle of an entry")
+        }
+    }
+}
+
+/// The way an entry changed between the old and the new tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangeStatus {
+    /// An entry was added.
+    Addition {
+        /// The mode of the added entry.
+        entry_mode: tree::EntryMode,
+        /// The object id of the added entry.
+        oid: ObjectId,
+    },
+    /// An entry was deleted.
+    Deletion {
+        /// The mode of the deleted entry.
+        entry_mode: tree::EntryMode,
+        /// The object id of the deleted entry.
+        oid: ObjectId,
+    },
+    /// An entry was modified, changing its mode, its object id, or both.
+    Modification {
+        /// The mode of the entry before the modification.
+        previous_entry_mode: tree::EntryMode,
+        /// The object id of the entry before the modification.
+        previous_oid: ObjectId,
+        /// The mode of the entry after the modification.
+        entry_mode: tree::EntryMode,
+        /// The object id of the entry after the modification.
+        oid: ObjectId,
+    },
+}
+
+/// A change between two trees as returned by the [`Changes`] iterator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The name of the changed entry in its tree.
+    pub path: BString,
+    /// How the entry changed.
+    pub status: ChangeStatus,
+}
+
+/// Entries parsed one by one from serialized tree data.
+pub(crate) struct Entries<R> {
+    read: BufReader<R>,
+    buf: Vec<u8>,
+}
+
+impl<R: Read> Entries<R> {
+    fn new(read: R) -> Self {
+        Entries {
+            read: BufReader::new(read),
+            buf: Vec::new(),
+        }
+    }
+
+    fn next_entry(&mut self) -> Result<Option<tree::Entry>, Error> {
+        self.buf.clear();
+        if self.read.read_until(b' ', &mut self.buf)? == 0 {
+            return Ok(None);
+        }
+        let mode = self.buf.strip_suffix(b" ").ok_or(Error::UnexpectedEof)?;
+        let mode = tree::EntryMode::try_from(mode).map_err(|mode| Error::InvalidMode(mode.into()))?;
+
+        self.buf.clear();
+        self.read.read_until(0, &mut self.buf)?;
+        let filename = self.buf.strip_suffix(&[0]).ok_or(Error::UnexpectedEof)?.into();
+
+        let mut oid = [0; 20];
+        self.read.read_exact(&mut oid).map_err(|err| match err.kind() {
+            std::io::ErrorKind::UnexpectedEof => Error::UnexpectedEof,
+            _ => Error::Io(err),
+        })?;
+        Ok(Some(tree::Entry {
+            mode,
+            filename,
+            oid: oid.into(),
+        }))
+    }
+}
+
+impl<Old: Read, New: Read> Changes<Old, New> {
+    /// Create a new instance to iterate the changes needed to turn the tree serialized in `old` into the one serialized in `new`.
+    ///
+    /// Both trees are read one entry at a time as they are sorted by name, yielding all changes in the same order.
+    /// Note that trees aren't traversed recursively, and that an entry changing between tree and non-tree is reported as a deletion
+    /// and an addition as both are sorted differently.
+    pub fn new(old: Old, new: New) -> Self {
+        Changes {
+            old: Entries::new(old),
+            new: Entries::new(new),
+            old_next: None,
+            new_next: None,
+            done: false,
+        }
+    }
+
+    fn next_change(&mut self) -> Result<Option<Change>, Error> {
+        loop {
+            if self.old_next.is_none() {
+                self.old_next = self.old.next_entry()?;
+            }
+            if self.new_next.is_none() {
+                self.new_next = self.new.next_entry()?;
+            }
+            let order = match (&self.old_next, &self.new_next) {
+                (None, None) => return Ok(None),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(old), Some(new)) => tree::Entry::cmp_by_name(&entry_ref(old), &entry_ref(new)),
+            };
+            let change = match order {
+                Ordering::Less => {
+                    let old = self.old_next.take().expect("present");
+                    Change {
+                        path: old.filename,
+                        status: ChangeStatus::Deletion {
+                            entry_mode: old.mode,
+                            oid: old.oid,
+                        },
+                    }
+                }
+                Ordering::Greater => {
+                    let new = self.new_next.take().expect("present");
+                    Change {
+                        path: new.filename,
+                        status: ChangeStatus::Addition {
+                            entry_mode: new.mode,
+                            oid: new.oid,
+                        },
+                    }
+                }
+                Ordering::Equal => {
+                    let old = self.old_next.take().expect("present");
+                    let new = self.new_next.take().expect("present");
+                    if old.mode == new.mode && old.oid == new.oid {
+                        continue;
+                    }
+                    Change {
+                        path: new.filename,
+                        status: ChangeStatus::Modification {
+                            previous_entry_mode: old.mode,
+                            previous_oid: old.oid,
+                            entry_mode: new.mode,
+                            oid: new.oid,
+                        },
+                    }
+                }
+            };
+            return Ok(Some(change));
+        }
+    }
+}
+
+fn entry_ref(entry: &tree::Entry) -> tree::EntryRef<'_> {
+    tree::EntryRef {
+        mode: entry.mode,
+        filename: entry.filename.as_bstr(),
+        oid: &entry.oid,
+    }
+}
+
+impl<Old: Read, New: Read> Iterator for Changes<Old, New> {
+    type Item = Result<Change, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.next_change() {
+            Ok(Some(change)) => Some(Ok(change)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
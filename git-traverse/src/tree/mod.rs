@@ -36,6 +36,18 @@ pub struct Recorder {
     pub records: Vec<recorder::Entry>,
 }
 
+/// An iterator over the changes between two trees which reads both of them entry by entry, without the need to hold either of
+/// them in memory.
+///
+/// Only the entries of the given trees are compared, subtrees are not traversed.
+pub struct Changes<Old, New> {
+    old: changes::Entries<Old>,
+    new: changes::Entries<New>,
+    old_next: Option<git_object::tree::Entry>,
+    new_next: Option<git_object::tree::Entry>,
+    done: bool,
+}
+
 ///
 pub mod visit {
     /// What to do after an entry was [recorded][super::Visit::visit_tree()].
@@ -57,6 +69,9 @@ pub mod visit {
     }
 }
 
+///
+pub mod changes;
+
 ///
 pub mod recorder;
 
@@ -193,6 +193,44 @@ mod ancestor {
         })
     }
 
+    #[test]
+    fn stop_at_ends_traversal_once_only_matching_commits_are_queued() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits.sh")?;
+        let store = git_odb::at(dir.join(".git").join("objects"))?;
+        let stop_ids: Vec<_> = [
+            "9556057aee5abb06912922e9f26c46386a816822",
+            "17d78c64cef6c33a10a604573fd2c429e477fd63",
+        ]
+        .iter()
+        .copied()
+        .map(hex_to_id)
+        .collect();
+
+        let oids: Result<Vec<_>, _> = commit::Ancestors::new(
+            Some(hex_to_id("01ec18a3ebf2855708ad3c9d244306bc1fae3e9b")),
+            commit::ancestors::State::default(),
+            move |oid, buf| store.find_commit_iter(oid, buf).map(|t| t.0),
+        )
+        .stop_at(move |id| stop_ids.contains(id))
+        .collect();
+        assert_eq!(
+            oids?,
+            [
+                "01ec18a3ebf2855708ad3c9d244306bc1fae3e9b",
+                "efd9a841189668f1bab5b8ebade9cd0a1b139a37",
+                "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353",
+                "9556057aee5abb06912922e9f26c46386a816822",
+                "9152eeee2328073cf23dcf8e90c949170b711659",
+            ]
+            .iter()
+            .copied()
+            .map(hex_to_id)
+            .collect::<Vec<_>>(),
+            "the first matching commit is still returned as the other branch is queued, but its parent isn't"
+        );
+        Ok(())
+    }
+
     #[test]
     fn graph_sorted_commits() -> crate::Result {
         TraversalAssertion::new(
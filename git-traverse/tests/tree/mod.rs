@@ -95,3 +95,128 @@ fn basic_nesting() -> crate::Result<()> {
     );
     Ok(())
 }
+
+mod changes {
+    use git_hash::ObjectId;
+    use git_object::{
+        bstr::BString,
+        tree::{Entry, EntryMode, EntryMode::*},
+        Tree, WriteTo,
+    };
+    use git_traverse::tree::{
+        changes::{Change, ChangeStatus},
+        Changes,
+    };
+
+    fn id(byte: u8) -> ObjectId {
+        ObjectId::from([byte; 20])
+    }
+
+    fn tree(entries: &[(&str, EntryMode, u8)]) -> crate::Result<Vec<u8>> {
+        let tree = Tree {
+            entries: entries
+                .iter()
+                .map(|(filename, mode, oid)| Entry {
+                    mode: *mode,
+                    filename: (*filename).into(),
+                    oid: id(*oid),
+                })
+                .collect(),
+        };
+        let mut buf = Vec::new();
+        tree.write_to(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn change(path: &str, status: ChangeStatus) -> Change {
+        Change {
+            path: BString::from(path),
+            status,
+        }
+    }
+
+    #[test]
+    fn additions_deletions_and_modifications_in_tree_order() -> crate::Result {
+        let old = tree(&[
+            ("a", Blob, 1),
+            ("b.txt", Blob, 2),
+            ("d", Tree, 3),
+            ("e", Blob, 4),
+            ("x", Blob, 5),
+        ])?;
+        let new = tree(&[
+            ("a", Blob, 6),
+            ("c", Blob, 7),
+            ("d", Tree, 3),
+            ("e", Tree, 8),
+            ("x", BlobExecutable, 5),
+        ])?;
+
+        let changes = Changes::new(old.as_slice(), new.as_slice()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            changes,
+            vec![
+                change(
+                    "a",
+                    ChangeStatus::Modification {
+                        previous_entry_mode: Blob,
+                        previous_oid: id(1),
+                        entry_mode: Blob,
+                        oid: id(6),
+                    }
+                ),
+                change(
+                    "b.txt",
+                    ChangeStatus::Deletion {
+                        entry_mode: Blob,
+                        oid: id(2),
+                    }
+                ),
+                change(
+                    "c",
+                    ChangeStatus::Addition {
+                        entry_mode: Blob,
+                        oid: id(7),
+                    }
+                ),
+                change(
+                    "e",
+                    ChangeStatus::Deletion {
+                        entry_mode: Blob,
+                        oid: id(4),
+                    }
+                ),
+                change(
+                    "e",
+                    ChangeStatus::Addition {
+                        entry_mode: Tree,
+                        oid: id(8),
+                    }
+                ),
+                change(
+                    "x",
+                    ChangeStatus::Modification {
+                        previous_entry_mode: Blob,
+                        previous_oid: id(5),
+                        entry_mode: BlobExecutable,
+                        oid: id(5),
+                    }
+                ),
+            ],
+            "unchanged entries are skipped, and entries changing between tree and non-tree are deleted and added"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_tree_data_is_an_error() -> crate::Result {
+        let new = tree(&[("a", Blob, 1)])?;
+        let mut changes = Changes::new(&new[..new.len() - 1], &b""[..]);
+        assert!(matches!(
+            changes.next(),
+            Some(Err(git_traverse::tree::changes::Error::UnexpectedEof))
+        ));
+        assert!(changes.next().is_none(), "iteration stops after an error");
+        Ok(())
+    }
+}
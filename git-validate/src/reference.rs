@@ -26,6 +26,9 @@ pub mod name {
             SingleDot {
                 display("Names must not be a single '.', but may contain it.")
             }
+            SlashInComponent {
+                display("A single path component must not contain a slash '/'")
+            }
         }
     }
 